@@ -1,4 +1,6 @@
 // Rust
+#[cfg(feature = "bech32")]
+use alloc::vec::Vec;
 use alloc::{
     boxed::Box,
     string::{String, ToString},
@@ -9,6 +11,8 @@ use core::{
     str::FromStr,
 };
 
+#[cfg(feature = "bech32")]
+use bech32::{FromBase32, ToBase32, Variant};
 use serde_big_array::BigArray;
 
 // IOTA
@@ -77,6 +81,13 @@ pub struct Address {
 }
 
 impl Address {
+    /// Byte separating the `appaddr` and the `msgid` in the bech32 encoding
+    #[cfg(feature = "bech32")]
+    const BECH32_SEPARATOR: u8 = b':';
+    /// Size of the byte string encoded in bech32
+    #[cfg(feature = "bech32")]
+    const BECH32_SIZE: usize = AppAddr::SIZE + 1 + MsgId::SIZE;
+
     /// Creates a new `Address` from an `AppAddr` and a `MsgId`
     ///
     /// # Arguments
@@ -113,6 +124,67 @@ impl Address {
     pub fn to_msg_index(self) -> [u8; 32] {
         self.to_blake2b()
     }
+
+    /// Encode the [`Address`] in bech32, using the provided human-readable part (see
+    /// [`BECH32_HRP`] for the default one). The `appaddr` and `msgid` are encoded together,
+    /// separated by a single `':'` byte.
+    ///
+    /// Errors if `hrp` is not a valid bech32 human-readable part.
+    ///
+    /// # Arguments
+    /// * `hrp`: The human-readable part of the encoding
+    #[cfg(feature = "bech32")]
+    pub fn to_bech32(self, hrp: &str) -> Result<String> {
+        let mut bytes = Vec::with_capacity(Self::BECH32_SIZE);
+        bytes.extend_from_slice(self.appaddr.as_bytes());
+        bytes.push(Self::BECH32_SEPARATOR);
+        bytes.extend_from_slice(self.msgid.as_bytes());
+        Ok(bech32::encode(hrp, bytes.to_base32(), Variant::Bech32)?)
+    }
+
+    /// Decode an [`Address`] out of its bech32 representation, the opposite of
+    /// [`Address::to_bech32()`]. Any human-readable part is accepted.
+    ///
+    /// # Arguments
+    /// * `encoded`: The bech32 encoded [`Address`]
+    #[cfg(feature = "bech32")]
+    pub fn from_bech32(encoded: &str) -> Result<Address> {
+        let bytes = decode_bech32(encoded)?;
+        if bytes.len() != Self::BECH32_SIZE {
+            return Err(Error::InvalidSize(
+                "Address",
+                Self::BECH32_SIZE,
+                bytes.len().try_into().unwrap(),
+            ));
+        }
+        let (appaddr, rest) = bytes.split_at(AppAddr::SIZE);
+        let (separator, msgid) = rest.split_at(1);
+        if separator[0] != Self::BECH32_SEPARATOR {
+            return Err(Error::Malformed("bech32 address", ":", encoded.to_string()));
+        }
+        Ok(Address {
+            appaddr: AppAddr(appaddr.try_into().expect("length has been checked")),
+            msgid: MsgId(msgid.try_into().expect("length has been checked")),
+        })
+    }
+}
+
+/// Default human-readable part of the bech32 encoding of [`Address`] and [`MsgId`]
+#[cfg(feature = "bech32")]
+pub const BECH32_HRP: &str = "streams";
+
+/// Decode a bech32 string (ignoring its human-readable part) into its raw bytes
+#[cfg(feature = "bech32")]
+fn decode_bech32(encoded: &str) -> Result<Vec<u8>> {
+    let (_hrp, data, variant) = bech32::decode(encoded)?;
+    if variant != Variant::Bech32 {
+        return Err(Error::Encoding(
+            "string",
+            "bech32",
+            Box::new(Error::External(anyhow::anyhow!("unexpected bech32m variant"))),
+        ));
+    }
+    Ok(Vec::<u8>::from_base32(&data)?)
 }
 
 /// String representation of a Tangle Link
@@ -155,7 +227,7 @@ impl FromStr for Address {
 pub struct AppAddr(#[serde(with = "BigArray")] [u8; Self::SIZE]);
 
 impl AppAddr {
-    const SIZE: usize = 40;
+    pub(crate) const SIZE: usize = 40;
 
     pub fn new(bytes: [u8; Self::SIZE]) -> Self {
         Self(bytes)
@@ -169,6 +241,21 @@ impl AppAddr {
         spongos.squeeze()
     }
 
+    /// Generate the [`AppAddr`] of a child stream, derived from the [`Address`] of the parent stream
+    /// announcement and the base [`Topic`] of the child stream
+    ///
+    /// # Arguments
+    /// * `parent`: The [`Address`] of the parent stream announcement
+    /// * `base_topic`: The base [`Topic`] of the child stream
+    pub fn gen_child(parent: &Address, base_topic: &Topic) -> AppAddr {
+        let mut spongos = Spongos::<KeccakF1600>::init();
+        spongos.absorb(base_topic);
+        spongos.absorb(parent.base());
+        spongos.absorb(parent.relative());
+        spongos.commit();
+        spongos.squeeze()
+    }
+
     /// Get the hexadecimal representation of the appaddr
     pub fn to_hex_string(self) -> String {
         hex::encode(self.0)
@@ -178,6 +265,44 @@ impl AppAddr {
     pub fn as_bytes(&self) -> &[u8] {
         &self.0
     }
+
+    /// Generate an [`AppAddr`] by hashing an arbitrary seed, for reproducible addresses in tests
+    /// and stubs
+    ///
+    /// # Arguments
+    /// * `seed`: The seed to derive the [`AppAddr`] from
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn from_seed(seed: &[u8]) -> AppAddr {
+        Spongos::<KeccakF1600>::init().sponge(seed)
+    }
+
+    /// Represent the [`AppAddr`] as a libp2p `Multiaddr` protocol component, in the form
+    /// `/<protocol_name>/<hex>`, so it can be embedded in libp2p peer advertisements.
+    ///
+    /// # Arguments
+    /// * `protocol_name`: The name of the protocol component, usually `"streams"`
+    #[cfg(feature = "multiaddr")]
+    pub fn to_multiaddr(self, protocol_name: &str) -> String {
+        alloc::format!("/{}/{}", protocol_name, self.to_hex_string())
+    }
+
+    /// Extract an [`AppAddr`] out of a libp2p `Multiaddr`, the opposite of
+    /// [`AppAddr::to_multiaddr()`]. The [`AppAddr`] is expected in the last protocol component of
+    /// the `Multiaddr`, regardless of the name of the protocol.
+    ///
+    /// # Arguments
+    /// * `ma`: The `Multiaddr` string containing the [`AppAddr`]
+    #[cfg(feature = "multiaddr")]
+    pub fn from_multiaddr(ma: &str) -> Result<AppAddr> {
+        let (protocol, hex) = ma
+            .strip_prefix('/')
+            .and_then(|components| components.rsplit_once('/'))
+            .ok_or_else(|| Error::Malformed("multiaddr", "/", ma.to_string()))?;
+        if protocol.is_empty() {
+            return Err(Error::Malformed("multiaddr", "protocol name", ma.to_string()));
+        }
+        AppAddr::from_str(hex).map_err(|e| Error::Encoding("AppAddr", "hexadecimal", Box::new(e)))
+    }
 }
 
 impl Default for AppAddr {
@@ -239,7 +364,7 @@ impl From<[u8; 40]> for AppAddr {
 pub struct MsgId([u8; Self::SIZE]);
 
 impl MsgId {
-    const SIZE: usize = 12;
+    pub(crate) const SIZE: usize = 12;
 
     pub fn new(bytes: [u8; Self::SIZE]) -> Self {
         Self(bytes)
@@ -264,6 +389,40 @@ impl MsgId {
     pub fn as_bytes(&self) -> &[u8] {
         self.0.as_slice()
     }
+
+    /// Generate a [`MsgId`] by hashing an arbitrary seed, for reproducible addresses in tests and
+    /// stubs
+    ///
+    /// # Arguments
+    /// * `seed`: The seed to derive the [`MsgId`] from
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn gen_deterministic(seed: &[u8]) -> MsgId {
+        Spongos::<KeccakF1600>::init().sponge(seed)
+    }
+
+    /// Encode the [`MsgId`] in bech32, using the provided human-readable part (see
+    /// [`BECH32_HRP`] for the default one). Errors if `hrp` is not a valid bech32 human-readable
+    /// part.
+    ///
+    /// # Arguments
+    /// * `hrp`: The human-readable part of the encoding
+    #[cfg(feature = "bech32")]
+    pub fn to_bech32(self, hrp: &str) -> Result<String> {
+        Ok(bech32::encode(hrp, self.0.to_base32(), Variant::Bech32)?)
+    }
+
+    /// Decode a [`MsgId`] out of its bech32 representation, the opposite of
+    /// [`MsgId::to_bech32()`]. Any human-readable part is accepted.
+    ///
+    /// # Arguments
+    /// * `encoded`: The bech32 encoded [`MsgId`]
+    #[cfg(feature = "bech32")]
+    pub fn from_bech32(encoded: &str) -> Result<MsgId> {
+        decode_bech32(encoded)?
+            .try_into()
+            .map(Self)
+            .map_err(|e: Vec<u8>| Error::InvalidSize("MsgId", Self::SIZE, e.len().try_into().unwrap()))
+    }
 }
 
 impl FromStr for MsgId {
@@ -469,3 +628,90 @@ where
         self.mask(&mut address.appaddr)?.mask(&mut address.msgid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "bech32")]
+    use super::{Address, BECH32_HRP};
+    use super::{AppAddr, MsgId};
+    #[cfg(any(feature = "bech32", feature = "multiaddr"))]
+    use crate::error::Result;
+    #[cfg(feature = "multiaddr")]
+    use crate::{
+        id::{Ed25519, Identity},
+        message::Topic,
+    };
+
+    #[cfg(feature = "bech32")]
+    #[test]
+    fn address_bech32_round_trip() -> Result<()> {
+        let address = Address::new([170; 40], [255; 12]);
+        let encoded = address.to_bech32(BECH32_HRP)?;
+        assert!(encoded.starts_with("streams1"));
+        assert_eq!(Address::from_bech32(&encoded)?, address);
+
+        let encoded = address.to_bech32("custom")?;
+        assert!(encoded.starts_with("custom1"));
+        assert_eq!(Address::from_bech32(&encoded)?, address);
+        Ok(())
+    }
+
+    #[cfg(feature = "bech32")]
+    #[test]
+    fn msgid_bech32_round_trip() -> Result<()> {
+        let msgid = MsgId::new([42; 12]);
+        assert_eq!(MsgId::from_bech32(&msgid.to_bech32(BECH32_HRP)?)?, msgid);
+        Ok(())
+    }
+
+    #[cfg(feature = "bech32")]
+    #[test]
+    fn invalid_bech32_human_readable_parts_are_rejected() {
+        for hrp in ["", "Mixed", "not ascii \u{e9}"].iter() {
+            assert!(Address::new([170; 40], [255; 12]).to_bech32(hrp).is_err());
+            assert!(MsgId::new([42; 12]).to_bech32(hrp).is_err());
+        }
+    }
+
+    #[cfg(feature = "bech32")]
+    #[test]
+    fn corrupted_bech32_checksum_is_rejected() -> Result<()> {
+        let mut encoded = Address::new([170; 40], [255; 12]).to_bech32(BECH32_HRP)?;
+        let last = encoded.pop().unwrap();
+        encoded.push(if last == 'q' { 'p' } else { 'q' });
+        assert!(Address::from_bech32(&encoded).is_err());
+
+        let mut encoded = MsgId::new([42; 12]).to_bech32(BECH32_HRP)?;
+        let last = encoded.pop().unwrap();
+        encoded.push(if last == 'q' { 'p' } else { 'q' });
+        assert!(MsgId::from_bech32(&encoded).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "multiaddr")]
+    #[test]
+    fn appaddr_multiaddr_round_trip() -> Result<()> {
+        let identity = Identity::from(Ed25519::from_seed("multiaddr seed"));
+        let appaddr = AppAddr::gen(identity.identifier(), &Topic::from("BASE_BRANCH"));
+        let multiaddr = appaddr.to_multiaddr("streams");
+        assert!(multiaddr.starts_with("/streams/"));
+        assert_eq!(AppAddr::from_multiaddr(&multiaddr)?, appaddr);
+        assert_eq!(
+            AppAddr::from_multiaddr(&alloc::format!("/ip4/127.0.0.1/tcp/4001{}", multiaddr))?,
+            appaddr
+        );
+        assert!(AppAddr::from_multiaddr("streams").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn seeded_addresses_are_reproducible() {
+        assert_eq!(MsgId::gen_deterministic(b"seed"), MsgId::gen_deterministic(b"seed"));
+        assert_ne!(
+            MsgId::gen_deterministic(b"seed"),
+            MsgId::gen_deterministic(b"other seed")
+        );
+        assert_eq!(AppAddr::from_seed(b"seed"), AppAddr::from_seed(b"seed"));
+        assert_ne!(AppAddr::from_seed(b"seed"), AppAddr::from_seed(b"other seed"));
+    }
+}
@@ -97,6 +97,9 @@ pub enum Error {
     #[error("{0} must be {1} bytes long, but is {2} bytes long instead")]
     InvalidSize(&'static str, usize, u64),
 
+    #[error("Invalid topic '{0}': {1}")]
+    InvalidTopic(String, &'static str),
+
     #[error("Malformed {0}: missing '{1}' for {2}")]
     Malformed(&'static str, &'static str, String),
 
@@ -106,6 +109,9 @@ pub enum Error {
     #[error("Internal Spongos error: {0}")]
     Spongos(SpongosError),
 
+    #[error("Unsupported {0}: {1}")]
+    Unsupported(&'static str, String),
+
     /// Transport
 
     #[error("Transport error for address {1}: {0}")]
@@ -118,12 +124,24 @@ pub enum Error {
     #[error("message '{0}' not found in {1}")]
     MessageMissing(Address, &'static str),
 
+    #[cfg(feature = "nats-client")]
+    #[error("NATS client error while {0}: {1}")]
+    Nats(&'static str, anyhow::Error),
+
     #[error("Nonce is not in the range 0..u32::MAX range for target score: {0}")]
     Nonce(f64),
 
     #[cfg(feature = "utangle-client")]
     #[error("Request HTTP error: {0}")]
     Request(reqwest::Error),
+
+    #[cfg(feature = "timeout")]
+    #[error("Transport request did not complete within {0:?}")]
+    Timeout(core::time::Duration),
+
+    #[cfg(feature = "wasm-client")]
+    #[error("JavaScript transport error while {0}: {1}")]
+    Wasm(&'static str, String),
 }
 
 impl Error {
@@ -155,6 +173,13 @@ impl From<FromHexError> for Error {
     }
 }
 
+#[cfg(feature = "bech32")]
+impl From<bech32::Error> for Error {
+    fn from(error: bech32::Error) -> Self {
+        Self::Encoding("string", "bech32", Box::new(Self::External(anyhow::Error::msg(error))))
+    }
+}
+
 #[cfg(feature = "utangle-client")]
 impl From<reqwest::Error> for Error {
     fn from(error: reqwest::Error) -> Self {
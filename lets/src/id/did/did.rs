@@ -46,7 +46,7 @@ pub(crate) async fn resolve_document(url_info: &DIDUrlInfo) -> Result<ResolvedIo
 }
 
 /// Type of `DID` implementation
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DID {
     // TODO: Add DID Account implementation
     /// Private Key based [`DIDInfo`], manually specifying key pairs
@@ -123,7 +123,7 @@ where
 }
 
 /// Details of a `DID` implementation
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DIDInfo {
     /// Document retrieval information
     url_info: DIDUrlInfo,
@@ -187,6 +187,7 @@ impl DIDInfo {
 }
 
 /// Wrapper for a `DID` based KeyPair
+#[derive(Clone)]
 struct KeyPair(identity_iota::crypto::KeyPair);
 
 impl PartialEq for KeyPair {
@@ -43,6 +43,14 @@ impl Ed25519 {
     }
 }
 
+impl Clone for Ed25519 {
+    fn clone(&self) -> Self {
+        let mut secret_key_bytes = [0; ed25519::SECRET_KEY_LENGTH];
+        secret_key_bytes.copy_from_slice(self.0.as_slice());
+        Self(ed25519::SecretKey::from_bytes(secret_key_bytes))
+    }
+}
+
 impl PartialEq for Ed25519 {
     fn eq(&self, other: &Self) -> bool {
         self.0.as_slice() == other.0.as_slice()
@@ -5,6 +5,8 @@ use spongos::ddml::commands::X25519;
 
 // 3rd-party
 use async_trait::async_trait;
+#[cfg(feature = "jwk")]
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 
 // IOTA
 use crypto::{keys::x25519, signatures::ed25519};
@@ -23,14 +25,13 @@ use spongos::{
 
 // Local
 #[cfg(feature = "did")]
-use crate::{
-    alloc::string::ToString,
-    error::Error,
-    id::did::{resolve_document, DIDUrlInfo},
-};
+use crate::id::did::{resolve_document, DIDUrlInfo};
+#[cfg(any(feature = "did", feature = "jwk"))]
+use crate::{alloc::string::ToString, error::Error};
 
 use crate::{
     error::Result,
+    id::{Identity, Psk},
     message::{ContentEncrypt, ContentEncryptSizeOf, ContentVerify},
 };
 
@@ -105,6 +106,69 @@ impl Identifier {
     }
 }
 
+#[cfg(feature = "jwk")]
+impl Identifier {
+    /// Key type of the JSON Web Key of an Ed25519 public key
+    const JWK_KEY_TYPE: &'static str = "OKP";
+    /// Curve of the JSON Web Key of an Ed25519 public key
+    const JWK_CURVE: &'static str = "Ed25519";
+
+    /// Represent the [`Identifier`] as a JSON Web Key, following
+    /// [RFC 8037](https://www.rfc-editor.org/rfc/rfc8037):
+    /// `{ "kty": "OKP", "crv": "Ed25519", "x": "<base64url public key>" }`.
+    ///
+    /// Only [`Identifier::Ed25519`] identifiers can be represented as a JSON Web Key.
+    pub fn to_jwk(&self) -> Result<serde_json::Value> {
+        match self {
+            Identifier::Ed25519(pk) => Ok(serde_json::json!({
+                "kty": Self::JWK_KEY_TYPE,
+                "crv": Self::JWK_CURVE,
+                "x": URL_SAFE_NO_PAD.encode(pk.as_slice()),
+            })),
+            #[cfg(feature = "did")]
+            Identifier::DID(_) => Err(Error::Unsupported("JWK conversion", "DID identifier".to_string())),
+        }
+    }
+
+    /// Parse an Ed25519 [`Identifier`] out of its JSON Web Key representation, the opposite of
+    /// [`Identifier::to_jwk()`]. Members other than `kty`, `crv` and `x` are ignored.
+    ///
+    /// # Arguments
+    /// * `json`: The JSON Web Key of the Ed25519 public key
+    pub fn from_jwk(json: &serde_json::Value) -> Result<Self> {
+        let member = |name: &'static str| {
+            json.get(name)
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| Error::Malformed("JWK", name, json.to_string()))
+        };
+        let key_type = member("kty")?;
+        if key_type != Self::JWK_KEY_TYPE {
+            return Err(Error::Unsupported("JWK key type", key_type.to_string()));
+        }
+        let curve = member("crv")?;
+        if curve != Self::JWK_CURVE {
+            return Err(Error::Unsupported("JWK curve", curve.to_string()));
+        }
+        let x = URL_SAFE_NO_PAD.decode(member("x")?).map_err(|e| {
+            Error::Encoding(
+                "JWK public key",
+                "base64url",
+                Box::new(Error::External(anyhow::Error::msg(e))),
+            )
+        })?;
+        let bytes: [u8; ed25519::PUBLIC_KEY_LENGTH] = x.as_slice().try_into().map_err(|_| {
+            Error::InvalidSize(
+                "JWK public key",
+                ed25519::PUBLIC_KEY_LENGTH,
+                x.len().try_into().unwrap(),
+            )
+        })?;
+        let pk = ed25519::PublicKey::try_from_bytes(bytes)
+            .map_err(|e| Error::Crypto("create the public key from the JWK", e))?;
+        Ok(Identifier::Ed25519(pk))
+    }
+}
+
 impl Default for Identifier {
     fn default() -> Self {
         let default_public_key = ed25519::PublicKey::try_from_bytes([0; ed25519::PUBLIC_KEY_LENGTH]).unwrap();
@@ -118,6 +182,13 @@ impl From<ed25519::PublicKey> for Identifier {
     }
 }
 
+/// The [`Identifier`] of the [`Identity`] derived out of a [`Psk`]
+impl From<&Psk> for Identifier {
+    fn from(psk: &Psk) -> Self {
+        Identity::from(psk).to_identifier()
+    }
+}
+
 impl AsRef<[u8]> for Identifier {
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
@@ -142,6 +213,25 @@ impl core::fmt::Display for Identifier {
     }
 }
 
+/// Parse an Ed25519 [`Identifier`] out of its hexadecimal representation, the opposite of its
+/// [`Display`](core::fmt::Display) implementation
+impl core::str::FromStr for Identifier {
+    type Err = crate::error::Error;
+    fn from_str(s: &str) -> Result<Self> {
+        let pk_bin = hex::decode(s)?;
+        let bytes: [u8; ed25519::PUBLIC_KEY_LENGTH] = pk_bin.as_slice().try_into().map_err(|_| {
+            crate::error::Error::InvalidSize(
+                "Identifier",
+                ed25519::PUBLIC_KEY_LENGTH,
+                pk_bin.len().try_into().unwrap(),
+            )
+        })?;
+        let pk = ed25519::PublicKey::try_from_bytes(bytes)
+            .map_err(|e| crate::error::Error::Crypto("create the public key from hex", e))?;
+        Ok(Identifier::Ed25519(pk))
+    }
+}
+
 impl Mask<&Identifier> for sizeof::Context {
     fn mask(&mut self, identifier: &Identifier) -> SpongosResult<&mut Self> {
         match identifier {
@@ -354,3 +444,60 @@ where
         }
     }
 }
+
+#[cfg(all(test, feature = "jwk"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use serde_json::json;
+
+    use super::Identifier;
+    use crate::{
+        error::{Error, Result},
+        id::{Ed25519, Identity},
+    };
+
+    #[test]
+    fn ed25519_identifier_jwk_round_trip() -> Result<()> {
+        let identifier = Identity::from(Ed25519::from_seed("jwk round trip")).to_identifier();
+        let jwk = identifier.to_jwk()?;
+        assert_eq!(jwk["kty"], "OKP");
+        assert_eq!(jwk["crv"], "Ed25519");
+        assert_eq!(Identifier::from_jwk(&jwk)?, identifier);
+        Ok(())
+    }
+
+    #[test]
+    fn identifier_from_rfc8037_jwk() -> Result<()> {
+        // RFC 8037, Appendix A.2
+        let jwk = json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+        });
+        let identifier = Identifier::from_jwk(&jwk)?;
+        assert_eq!(
+            identifier.to_string(),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(identifier.to_jwk()?, jwk);
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_jwk_is_rejected() {
+        let jwk = json!({
+            "kty": "OKP",
+            "crv": "X25519",
+            "x": "3p7bfXt9wbTTW2HC7OQ1Nz-DQ8hbeGdNrfx-FG-IK08",
+        });
+        assert!(matches!(
+            Identifier::from_jwk(&jwk),
+            Err(Error::Unsupported("JWK curve", _))
+        ));
+        assert!(matches!(
+            Identifier::from_jwk(&json!({ "kty": "OKP" })),
+            Err(Error::Malformed("JWK", "crv", _))
+        ));
+    }
+}
@@ -38,13 +38,13 @@ use crate::{
 
 use crate::{
     error::Result,
-    id::{ed25519::Ed25519, identifier::Identifier},
+    id::{ed25519::Ed25519, identifier::Identifier, psk::Psk},
     message::{ContentDecrypt, ContentSign, ContentSignSizeof},
 };
 
 /// Wrapper around [`Identifier`], specifying which type of [`Identity`] is being used. An
 /// [`Identity`] is the foundation of message sending and verification.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(clippy::large_enum_variant)]
 pub struct Identity {
     /// Type of User Identity
@@ -97,6 +97,14 @@ impl From<Ed25519> for Identity {
     }
 }
 
+/// Derives an Ed25519 [`Identity`] out of a [`Psk`], so that holders of the same [`Psk`] share
+/// the same [`Identity`]
+impl From<&Psk> for Identity {
+    fn from(psk: &Psk) -> Self {
+        Self::from(Ed25519::from_seed(psk))
+    }
+}
+
 #[cfg(feature = "did")]
 impl From<DID> for Identity {
     fn from(did: DID) -> Self {
@@ -105,7 +113,7 @@ impl From<DID> for Identity {
 }
 
 /// Wrapper for [`Identity`] details
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(clippy::large_enum_variant)]
 pub enum IdentityKind {
     /// An Ed25519 type [`Identity`] using a private key
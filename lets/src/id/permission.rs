@@ -75,8 +75,8 @@ where
             0 => {
                 *duration = PermissionDuration::Perpetual;
             }
-            1 | 2 | 3 => todo!(),
-            o => return Err(SpongosError::InvalidOption("identifier", o)),
+            // Only perpetual durations can be wrapped, anything else is malformed
+            o => return Err(SpongosError::InvalidOption("permission duration", o)),
         }
         Ok(self)
     }
@@ -43,6 +43,14 @@ impl Psk {
         spongos.absorb("PSKID");
         spongos.sponge(self)
     }
+
+    /// Creates the key used for unsubscribing a subscription made with the [`Psk`], by using
+    /// [`Spongos`] to sponge the [`Psk`] into a fixed 32 byte array
+    pub fn to_unsubscribe_key(self) -> [u8; 32] {
+        let mut spongos = Spongos::<KeccakF1600>::init();
+        spongos.absorb("PSKUNSUBSCRIBE");
+        spongos.sponge(self)
+    }
 }
 
 impl AsRef<[u8]> for Psk {
@@ -26,6 +26,25 @@ use crate::{
 /// [`Mac`] for content verification
 const MAC: Mac = Mac::new(32);
 
+/// Encodes a message type into the first byte of the header, leaving the 2 payload length bits
+/// empty. The 4 low bits of the message type take the 4 high bits of the byte, and the 2 high bits
+/// of the message type the 2 bits that follow, so message types below 16 are encoded as before
+/// those bits were used.
+///
+/// # Arguments
+/// * `message_type`: The message type identifier
+fn encode_message_type(message_type: u8) -> u8 {
+    ((message_type & 0b1111) << 4) | ((message_type >> 4) << 2)
+}
+
+/// Decodes the message type from the first byte of the header
+///
+/// # Arguments
+/// * `byte`: The first byte of the header
+fn decode_message_type(byte: u8) -> u8 {
+    (byte >> 4) | ((byte & 0b1100) << 2)
+}
+
 /// The header of a `Streams` message
 #[non_exhaustive]
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -36,7 +55,7 @@ pub struct HDF {
     /// Streams version
     pub version: u8,
     /// Message type identifier
-    // content type is 4 bits
+    // content type is 6 bits
     pub message_type: u8,
     /// Length of the payload of the message (can be set to 0)
     // payload length is 10 bits
@@ -83,8 +102,8 @@ impl HDF {
     /// * `topic`: Reference to branch [`Topic`]
     pub fn new(message_type: u8, sequence: usize, publisher: Identifier, topic: &Topic) -> Self {
         debug_assert!(
-            message_type >> 4 == 0,
-            "invalid content-type '{}': content-type value cannot be greater than 4 bits",
+            message_type >> 6 == 0,
+            "invalid content-type '{}': content-type value cannot be greater than 6 bits",
             message_type
         );
         Self {
@@ -168,7 +187,7 @@ impl ContentSizeof<HDF> for sizeof::Context {
         self.absorb(Uint8::new(hdf.encoding))?
             .absorb(Uint8::new(hdf.version))?
             .skip(message_type_and_payload_length)?
-            .absorb(External::new(Uint8::new(hdf.message_type << 4)))?
+            .absorb(External::new(Uint8::new(encode_message_type(hdf.message_type))))?
             .absorb(Uint8::new(hdf.frame_type))?
             .skip(payload_frame_count)?
             .absorb(Maybe::new(hdf.linked_msg_address.as_ref()))?
@@ -191,7 +210,7 @@ where
     async fn wrap(&mut self, hdf: &mut HDF) -> SpongosResult<&mut Self> {
         let message_type_and_payload_length = {
             let mut nbytes = NBytes::<[u8; 2]>::default();
-            nbytes[0] = (hdf.message_type << 4) | ((hdf.payload_length >> 8) as u8 & 0b0011);
+            nbytes[0] = encode_message_type(hdf.message_type) | ((hdf.payload_length >> 8) as u8 & 0b0011);
             nbytes[1] = hdf.payload_length as u8;
             nbytes
         };
@@ -207,7 +226,7 @@ where
         self.absorb(Uint8::new(hdf.encoding))?
             .absorb(Uint8::new(hdf.version))?
             .skip(message_type_and_payload_length)?
-            .absorb(External::new(Uint8::new(hdf.message_type << 4)))?
+            .absorb(External::new(Uint8::new(encode_message_type(hdf.message_type))))?
             .absorb(Uint8::new(hdf.frame_type))?
             .skip(payload_frame_count)?
             .absorb(Maybe::new(hdf.linked_msg_address.as_ref()))?
@@ -230,7 +249,7 @@ where
     async fn unwrap(&mut self, mut hdf: &mut HDF) -> SpongosResult<&mut Self> {
         let mut encoding = Uint8::default();
         let mut version = Uint8::default();
-        // [message_type x 4][reserved x 2][payload_length x 2]
+        // [message_type low bits x 4][message_type high bits x 2][payload_length x 2]
         // [payload_length x 8 -------------------------------]
         let mut message_type_and_payload_length = NBytes::<[u8; 2]>::default();
         let mut frame_type = Uint8::default();
@@ -244,13 +263,9 @@ where
                 SpongosError::Version("Msg", version.inner()),
             )?
             .skip(message_type_and_payload_length.as_mut())?
-            .guard(
-                0 == message_type_and_payload_length[0] & 0b1100,
-                SpongosError::Reserved("bits 5 and 6 between content-type and payload-length"),
-            )?
             .absorb(External::new(Uint8::new(
                 // Absorb only message_type
-                message_type_and_payload_length[0] & 0b11110000,
+                message_type_and_payload_length[0] & 0b11111100,
             )))?
             .absorb(&mut frame_type)?
             .guard(
@@ -271,7 +286,7 @@ where
 
         hdf.encoding = encoding.inner();
         hdf.version = version.inner();
-        hdf.message_type = message_type_and_payload_length[0] >> 4;
+        hdf.message_type = decode_message_type(message_type_and_payload_length[0]);
         hdf.payload_length =
             (((message_type_and_payload_length[0] & 0b0011) as u16) << 8) | (message_type_and_payload_length[1] as u16);
         hdf.frame_type = frame_type.inner();
@@ -6,6 +6,7 @@ use alloc::{
 use core::{
     convert::{TryFrom, TryInto},
     fmt::Formatter,
+    str::FromStr,
 };
 use spongos::{
     ddml::{
@@ -17,13 +18,16 @@ use spongos::{
     KeccakF1600, Spongos, PRP,
 };
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 /// A wrapper around a `String` used for identifying a branch within a `Stream`
 #[derive(Clone, PartialEq, Eq, Debug, Default, Hash, serde::Serialize)]
 pub struct Topic(String);
 
 impl Topic {
+    /// Maximum length of a [`Topic`] in bytes
+    pub const MAX_LENGTH: usize = 255;
+
     /// Create a new [`Topic`] wrapper for the provided `String`
     ///
     /// # Arguments
@@ -36,6 +40,48 @@ impl Topic {
     pub fn str(&self) -> &str {
         &self.0
     }
+
+    /// Returns true if the provided branch identifier can be used as a [`Topic`]: it must be at
+    /// most [`Topic::MAX_LENGTH`] bytes long and must not contain null bytes.
+    ///
+    /// # Arguments
+    /// * `topic`: The branch identifier to check
+    pub fn is_valid(topic: &str) -> bool {
+        topic.len() <= Self::MAX_LENGTH && !topic.contains('\0')
+    }
+
+    /// Returns true if the [`Topic`] is equal to `ancestor` or nested below it, following the
+    /// slash-separated naming convention (`"sensors/room1"` is a descendant of `"sensors"`, but
+    /// `"sensors2"` is not). The check is purely based on the branch identifiers.
+    ///
+    /// # Arguments
+    /// * `ancestor`: The [`Topic`] to check against
+    pub fn is_descendant_of(&self, ancestor: &Topic) -> bool {
+        match self.0.strip_prefix(ancestor.str()) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/'),
+            None => false,
+        }
+    }
+
+    /// Returns the [`Topic`] with its last slash-separated segment removed, or `None` if it only
+    /// consists of a single segment.
+    pub fn parent(&self) -> Option<Topic> {
+        self.0.rsplit_once('/').map(|(parent, _)| Topic::from(parent))
+    }
+}
+
+/// Create a [`Topic`] out of a branch identifier, validating it according to [`Topic::is_valid`]
+impl FromStr for Topic {
+    type Err = Error;
+    fn from_str(t: &str) -> Result<Self> {
+        if t.len() > Self::MAX_LENGTH {
+            return Err(Error::InvalidTopic(t.to_string(), "topic exceeds the maximum length"));
+        }
+        if t.contains('\0') {
+            return Err(Error::InvalidTopic(t.to_string(), "topic contains a null byte"));
+        }
+        Ok(Self(t.to_string()))
+    }
 }
 
 impl From<&str> for Topic {
@@ -51,7 +97,7 @@ impl From<String> for Topic {
 }
 
 impl TryFrom<&[u8]> for Topic {
-    type Error = crate::error::Error;
+    type Error = Error;
     fn try_from(t: &[u8]) -> Result<Self> {
         let topic = String::from_utf8(t.to_vec())?;
         Ok(Topic(topic))
@@ -59,7 +105,7 @@ impl TryFrom<&[u8]> for Topic {
 }
 
 impl TryFrom<Vec<u8>> for Topic {
-    type Error = crate::error::Error;
+    type Error = Error;
     fn try_from(t: Vec<u8>) -> Result<Self> {
         let topic = String::from_utf8(t)?;
         Ok(Topic(topic))
@@ -116,7 +162,7 @@ where
         self.mask(Bytes::new(&mut topic_bytes))?;
         *topic = topic_bytes
             .try_into()
-            .map_err(|e: crate::error::Error| spongos::error::Error::Context("Mask", e.to_string()))?;
+            .map_err(|e: Error| spongos::error::Error::Context("Mask", e.to_string()))?;
         Ok(self)
     }
 }
@@ -176,3 +222,62 @@ where
         Ok(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::{convert::TryFrom, str::FromStr};
+
+    use super::Topic;
+
+    #[test]
+    fn valid_topics_are_parsed() {
+        assert_eq!(Topic::from_str("BASE_BRANCH").unwrap(), Topic::from("BASE_BRANCH"));
+        assert_eq!("branché".parse::<Topic>().unwrap(), Topic::from("branché"));
+        let longest = "a".repeat(Topic::MAX_LENGTH);
+        assert!(Topic::is_valid(&longest));
+        assert_eq!(longest.parse::<Topic>().unwrap(), Topic::new(longest.clone()));
+    }
+
+    #[test]
+    fn oversized_topics_are_rejected() {
+        let oversized = "a".repeat(Topic::MAX_LENGTH + 1);
+        assert!(!Topic::is_valid(&oversized));
+        assert!(oversized.parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn topics_with_null_bytes_are_rejected() {
+        assert!(!Topic::is_valid("BASE\0BRANCH"));
+        assert!("BASE\0BRANCH".parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn topics_with_invalid_utf8_are_rejected() {
+        assert!(Topic::try_from(&[0xf0, 0x28, 0x8c, 0x28][..]).is_err());
+    }
+
+    #[test]
+    fn descendant_topics_are_detected() {
+        let sensors = Topic::from("sensors");
+        let room = Topic::from("sensors/room1");
+        let temperature = Topic::from("sensors/room1/temperature");
+        assert!(room.is_descendant_of(&sensors));
+        assert!(temperature.is_descendant_of(&room));
+        assert!(temperature.is_descendant_of(&sensors));
+        assert!(sensors.is_descendant_of(&sensors));
+        assert!(!sensors.is_descendant_of(&room));
+        assert!(!Topic::from("sensors2/room1").is_descendant_of(&sensors));
+        assert!(!Topic::from("actuators/room1").is_descendant_of(&sensors));
+    }
+
+    #[test]
+    fn parent_topics_drop_the_last_segment() {
+        let temperature = Topic::from("sensors/room1/temperature");
+        assert_eq!(temperature.parent(), Some(Topic::from("sensors/room1")));
+        assert_eq!(
+            temperature.parent().and_then(|room| room.parent()),
+            Some(Topic::from("sensors"))
+        );
+        assert_eq!(Topic::from("sensors").parent(), None);
+    }
+}
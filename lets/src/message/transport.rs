@@ -1,5 +1,13 @@
 // Rust
+#[cfg(feature = "cbor")]
+use alloc::boxed::Box;
 use alloc::vec::Vec;
+#[cfg(feature = "cbor")]
+use core::{convert::TryInto, fmt::Debug};
+
+// 3rd-party
+#[cfg(feature = "cbor")]
+use ciborium::value::{Integer, Value};
 
 // IOTA
 
@@ -7,6 +15,11 @@ use alloc::vec::Vec;
 use spongos::{ddml::commands::unwrap, PRP};
 
 // Local
+#[cfg(feature = "cbor")]
+use crate::{
+    address::{Address, AppAddr, MsgId},
+    error::Error,
+};
 use crate::{
     error::Result,
     message::{content::ContentUnwrap, hdf::HDF, preparsed::PreparsedMessage},
@@ -55,6 +68,95 @@ impl TransportMessage {
     }
 }
 
+#[cfg(feature = "cbor")]
+impl TransportMessage {
+    /// Version of the CBOR encoding produced by [`TransportMessage::to_cbor()`]
+    pub const CBOR_VERSION: u8 = 1;
+
+    /// Encodes the message and the [`Address`] it is published at into a self-describing CBOR
+    /// array made of the encoding version, the address bytes and the message body as a byte string.
+    /// This allows relays to inspect the address of a message without parsing its body.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message is published at
+    pub fn to_cbor(&self, address: Address) -> Result<Vec<u8>> {
+        let mut address_bytes = Vec::with_capacity(AppAddr::SIZE + MsgId::SIZE);
+        address_bytes.extend_from_slice(address.base().as_bytes());
+        address_bytes.extend_from_slice(address.relative().as_bytes());
+        let value = Value::Array(vec![
+            Value::Integer(Self::CBOR_VERSION.into()),
+            Value::Bytes(address_bytes),
+            Value::Bytes(self.body().clone()),
+        ]);
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&value, &mut bytes).map_err(cbor_error)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a message and the [`Address`] it is published at out of their CBOR encoding, the
+    /// opposite of [`TransportMessage::to_cbor()`]
+    ///
+    /// # Arguments
+    /// * `bytes`: The CBOR encoded message
+    pub fn from_cbor(bytes: &[u8]) -> Result<(Address, Self)> {
+        let value: Value = ciborium::de::from_reader(bytes).map_err(cbor_error)?;
+        let (version, address, body) = match value {
+            Value::Array(fields) => {
+                let mut fields = fields.into_iter();
+                match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                    (Some(Value::Integer(version)), Some(Value::Bytes(address)), Some(Value::Bytes(body)), None) => {
+                        (version, address, body)
+                    }
+                    (version, address, body, _) => {
+                        return Err(Error::Malformed(
+                            "CBOR transport message",
+                            "[version, address, body]",
+                            format!("{:?}", (version, address, body)),
+                        ))
+                    }
+                }
+            }
+            other => {
+                return Err(Error::Malformed(
+                    "CBOR transport message",
+                    "[version, address, body]",
+                    format!("{:?}", other),
+                ))
+            }
+        };
+        if version != Integer::from(Self::CBOR_VERSION) {
+            return Err(Error::Malformed(
+                "CBOR transport message",
+                "supported version",
+                format!("{:?}", version),
+            ));
+        }
+        if address.len() != AppAddr::SIZE + MsgId::SIZE {
+            return Err(Error::InvalidSize(
+                "Address",
+                AppAddr::SIZE + MsgId::SIZE,
+                address.len().try_into().unwrap(),
+            ));
+        }
+        let (appaddr, msgid) = address.split_at(AppAddr::SIZE);
+        let address = Address::new(
+            AppAddr::new(appaddr.try_into().expect("length has been checked")),
+            MsgId::new(msgid.try_into().expect("length has been checked")),
+        );
+        Ok((address, Self::new(body)))
+    }
+}
+
+/// Wraps a CBOR (de)serialization failure into an [`Error`]
+#[cfg(feature = "cbor")]
+fn cbor_error<E: Debug>(error: E) -> Error {
+    Error::Encoding(
+        "TransportMessage",
+        "CBOR",
+        Box::new(Error::External(anyhow::Error::msg(format!("{:?}", error)))),
+    )
+}
+
 impl From<TransportMessage> for Vec<u8> {
     fn from(message: TransportMessage) -> Self {
         message.into_body()
@@ -30,6 +30,26 @@ impl<Msg> Client<Msg> {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns the total number of messages stored in the bucket, across all addresses
+    pub fn message_count(&self) -> usize {
+        self.bucket.values().map(Vec::len).sum()
+    }
+
+    /// Returns the number of distinct [Addresses](`Address`) stored in the bucket
+    pub fn address_count(&self) -> usize {
+        self.bucket.len()
+    }
+
+    /// Returns an iterator over the [Addresses](`Address`) stored in the bucket
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.bucket.keys()
+    }
+
+    /// Removes all the messages stored in the bucket
+    pub fn clear(&mut self) {
+        self.bucket.clear()
+    }
 }
 
 impl<Msg> Default for Client<Msg> {
@@ -1,9 +1,13 @@
 // Rust
+#[cfg(feature = "shared-transport")]
+use alloc::sync::Arc;
 use alloc::{boxed::Box, rc::Rc, vec::Vec};
 use core::cell::RefCell;
 
 // 3rd-party
 use async_trait::async_trait;
+#[cfg(feature = "shared-transport")]
+use tokio::sync::RwLock;
 
 // IOTA
 
@@ -44,6 +48,21 @@ pub trait Transport<'a> {
             Err(Error::AddressError("not found in transport", address))
         }
     }
+
+    /// Send several messages, in order. By default messages are sent one after the other, stopping
+    /// at the first failure. Transports supporting native batching should override it to send the
+    /// whole batch in fewer round-trips.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Self::Msg)>) -> Result<Vec<Self::SendResponse>>
+    where
+        'a: 'async_trait,
+        Self::Msg: 'async_trait,
+    {
+        let mut responses = Vec::with_capacity(messages.len());
+        for (address, msg) in messages {
+            responses.push(self.send_message(address, msg).await?);
+        }
+        Ok(responses)
+    }
 }
 
 #[async_trait(?Send)]
@@ -59,12 +78,91 @@ impl<'a, Tsp: Transport<'a>> Transport<'a> for Rc<RefCell<Tsp>> {
         self.borrow_mut().send_message(address, msg).await
     }
 
+    /// Send several messages, in order.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Tsp::Msg)>) -> Result<Vec<Tsp::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.borrow_mut().send_messages_batch(messages).await
+    }
+
     /// Receive messages with default options.
     async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
         self.borrow_mut().recv_messages(address).await
     }
 }
 
+#[async_trait(?Send)]
+impl<'a, Tsp: Transport<'a>> Transport<'a> for &mut Tsp {
+    type Msg = Tsp::Msg;
+    type SendResponse = Tsp::SendResponse;
+
+    /// Send a message.
+    async fn send_message(&mut self, address: Address, msg: Tsp::Msg) -> Result<Tsp::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        (**self).send_message(address, msg).await
+    }
+
+    /// Send several messages, in order.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Tsp::Msg)>) -> Result<Vec<Tsp::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        (**self).send_messages_batch(messages).await
+    }
+
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
+        (**self).recv_messages(address).await
+    }
+
+    /// Receive a single message with default options.
+    async fn recv_message(&mut self, address: Address) -> Result<Tsp::Msg> {
+        (**self).recv_message(address).await
+    }
+}
+
+/// Shared transport allowing many concurrent receivers with exclusive senders.
+///
+/// The transport is guarded by an asynchronous [`RwLock`], so waiting for the lock yields to the
+/// executor instead of blocking its thread. Sending a message holds the write lock for the whole
+/// operation. Receiving messages only holds the read lock while taking a copy of the transport,
+/// which then performs the request, so receivers never block each other. This is intended for
+/// transports that are cheap to clone, such as HTTP clients.
+#[cfg(feature = "shared-transport")]
+#[async_trait(?Send)]
+impl<'a, Tsp> Transport<'a> for Arc<RwLock<Tsp>>
+where
+    Tsp: Transport<'a> + Clone,
+{
+    type Msg = Tsp::Msg;
+    type SendResponse = Tsp::SendResponse;
+
+    /// Send a message.
+    async fn send_message(&mut self, address: Address, msg: Tsp::Msg) -> Result<Tsp::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.write().await.send_message(address, msg).await
+    }
+
+    /// Send several messages, in order, holding the write lock for the whole batch.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Tsp::Msg)>) -> Result<Vec<Tsp::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.write().await.send_messages_batch(messages).await
+    }
+
+    /// Receive messages with default options.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
+        let mut transport = self.read().await.clone();
+        transport.recv_messages(address).await
+    }
+}
+
 /// Localised mapping for tests and simulations
 pub mod bucket;
 /// `iota.rs` based tangle client
@@ -73,3 +171,174 @@ pub mod tangle;
 /// Localised micro tangle client
 #[cfg(feature = "utangle-client")]
 pub mod utangle;
+/// `NATS` pub/sub client
+#[cfg(feature = "nats-client")]
+pub mod nats;
+/// Rate limited transport wrapper
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+/// Retrying transport wrapper
+#[cfg(feature = "retry")]
+pub mod retry;
+/// Timed out transport wrapper
+#[cfg(feature = "timeout")]
+pub mod timeout;
+/// Transport client calling JavaScript functions, for WebAssembly deployments
+#[cfg(feature = "wasm-client")]
+pub mod wasm;
+
+#[cfg(all(test, feature = "shared-transport"))]
+mod shared_transport_tests {
+    extern crate std;
+
+    use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+    use std::time::{Duration, Instant};
+
+    use async_trait::async_trait;
+    use tokio::sync::RwLock;
+
+    use crate::{
+        address::Address,
+        error::Result,
+        message::TransportMessage,
+        transport::{bucket, Transport},
+    };
+
+    /// Transport taking a fixed time to answer every request
+    #[derive(Clone)]
+    struct SlowTransport {
+        inner: bucket::Client,
+        delay: Duration,
+    }
+
+    #[async_trait(?Send)]
+    impl Transport<'_> for SlowTransport {
+        type Msg = TransportMessage;
+        type SendResponse = TransportMessage;
+
+        async fn send_message(&mut self, address: Address, msg: TransportMessage) -> Result<TransportMessage>
+        where
+            Self::Msg: 'async_trait,
+        {
+            tokio::time::sleep(self.delay).await;
+            self.inner.send_message(address, msg).await
+        }
+
+        async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.recv_messages(address).await
+        }
+    }
+
+    type SharedTransport = Arc<RwLock<SlowTransport>>;
+
+    async fn shared_transport_fixture() -> Result<(SharedTransport, Address)> {
+        let mut transport = Arc::new(RwLock::new(SlowTransport {
+            inner: bucket::Client::new(),
+            delay: Duration::from_millis(200),
+        }));
+        let address = Address::new([1; 40], [1; 12]);
+        transport
+            .send_message(address, TransportMessage::new(vec![1; 32]))
+            .await?;
+        Ok((transport, address))
+    }
+
+    #[tokio::test]
+    async fn concurrent_receivers_do_not_block_each_other() -> Result<()> {
+        let (transport, address) = shared_transport_fixture().await?;
+        let _reader = transport.read().await;
+        let (mut first, mut second) = (transport.clone(), transport.clone());
+        let start = Instant::now();
+        let (first, second) = tokio::join!(first.recv_messages(address), second.recv_messages(address));
+        assert_eq!(first?.len(), 1);
+        assert_eq!(second?.len(), 1);
+        // Both requests waited for the transport at the same time
+        assert!(start.elapsed() < Duration::from_millis(350));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn senders_block_receivers() -> Result<()> {
+        let (transport, _) = shared_transport_fixture().await?;
+        let (mut sender, mut receiver) = (transport.clone(), transport.clone());
+        let address = Address::new([2; 40], [2; 12]);
+        let start = Instant::now();
+        let (sent, received) = tokio::join!(
+            sender.send_message(address, TransportMessage::new(vec![2; 32])),
+            receiver.recv_messages(address)
+        );
+        sent?;
+        // The receiver only copied the transport once the message was sent
+        assert_eq!(received?.len(), 1);
+        assert!(start.elapsed() >= Duration::from_millis(400));
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use async_trait::async_trait;
+
+    use crate::{
+        address::Address,
+        error::{Error, Result},
+        message::TransportMessage,
+        transport::Transport,
+    };
+
+    /// Transport recording the order in which messages are sent, relying on the default batch
+    /// implementation
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: Vec<Address>,
+    }
+
+    #[async_trait(?Send)]
+    impl Transport<'_> for RecordingTransport {
+        type Msg = TransportMessage;
+        type SendResponse = usize;
+
+        async fn send_message(&mut self, address: Address, _msg: TransportMessage) -> Result<usize>
+        where
+            Self::Msg: 'async_trait,
+        {
+            if address == Address::default() {
+                return Err(Error::AddressError("rejected by transport", address));
+            }
+            self.sent.push(address);
+            Ok(self.sent.len())
+        }
+
+        async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+            Err(Error::AddressError("not found in transport", address))
+        }
+    }
+
+    #[tokio::test]
+    async fn send_messages_batch_defaults_to_sequential_sends() -> Result<()> {
+        let mut transport = RecordingTransport::default();
+        let addresses: Vec<Address> = (1..=3).map(|i| Address::new([i; 40], [i; 12])).collect();
+        let responses = transport
+            .send_messages_batch(
+                addresses
+                    .iter()
+                    .map(|address| (*address, TransportMessage::new(vec![1; 32])))
+                    .collect(),
+            )
+            .await?;
+        assert_eq!(responses, vec![1, 2, 3]);
+        assert_eq!(transport.sent, addresses);
+
+        let batch = vec![
+            (Address::new([4; 40], [4; 12]), TransportMessage::new(vec![1; 32])),
+            (Address::default(), TransportMessage::new(vec![1; 32])),
+            (Address::new([5; 40], [5; 12]), TransportMessage::new(vec![1; 32])),
+        ];
+        assert!(transport.send_messages_batch(batch).await.is_err());
+        assert_eq!(transport.sent.len(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+// Rust
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::time::Duration;
+
+// 3rd-party
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::time::{timeout, Instant};
+
+// IOTA
+
+// Streams
+
+// Local
+use crate::{
+    address::Address,
+    error::{Error, Result},
+    message::TransportMessage,
+    transport::Transport,
+};
+
+/// Default time [`Client::recv_messages`](`Transport::recv_messages`) keeps draining a subject
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Default time [`Client::recv_message`](`Transport::recv_message`) waits for a message to be
+/// published to a subject
+const DEFAULT_RECV_MESSAGE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A [`Transport`] Client for publishing and receiving binary messages through a `NATS` server.
+///
+/// Each message is published to the subject `{prefix}.{address}`, where the address is the
+/// hex-encoded `AppAddr` followed by the hex-encoded `MsgId`.
+///
+/// # Limitations
+///
+/// **Core NATS does not persist messages.** Only the messages published while a receiver is
+/// subscribed to their subject are delivered, and a message published before is never received.
+/// Streams users fetch every message by its address after it has been published, when receiving
+/// an announcement, syncing or checking that an address is unused before sending, so **this
+/// transport cannot serve the fetches of a Streams user**: they fail with a message not found once
+/// the receive timeout elapses, and every send waits for that timeout on its unused address check.
+/// Fetches need a backend that persists messages, such as `NATS` JetStream or another
+/// [`Transport`]. Use this one to fan out the messages of a stream to live subscribers, alongside a
+/// transport that persists them.
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// `NATS` client
+    client: async_nats::Client,
+    /// Prefix of the subjects messages are published to
+    prefix: String,
+    /// Time receiving messages waits for messages to be published to the subject
+    drain_timeout: Duration,
+    /// Time receiving a single message waits for a message to be published to the subject
+    recv_message_timeout: Duration,
+}
+
+impl Client {
+    /// Creates a new `NATS` [`Client`] from an already connected [`async_nats::Client`]
+    ///
+    /// # Arguments
+    /// * `client`: The `NATS` client
+    /// * `prefix`: The prefix of the subjects messages are published to
+    pub fn new<P>(client: async_nats::Client, prefix: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Self {
+            client,
+            prefix: prefix.into(),
+            drain_timeout: DEFAULT_DRAIN_TIMEOUT,
+            recv_message_timeout: DEFAULT_RECV_MESSAGE_TIMEOUT,
+        }
+    }
+
+    /// Connects to the `NATS` server at the provided URL
+    ///
+    /// # Arguments
+    /// * `url`: `NATS` server URL
+    /// * `prefix`: The prefix of the subjects messages are published to
+    pub async fn connect(url: &str, prefix: &str) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| Error::Nats("connecting to server", anyhow::Error::msg(e)))?;
+        Ok(Self::new(client, prefix))
+    }
+
+    /// Sets the time receiving messages waits for messages to be published to the subject
+    ///
+    /// # Arguments
+    /// * `drain_timeout`: The time to wait for
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Sets the time receiving a single message waits for a message to be published to the
+    /// subject
+    ///
+    /// # Arguments
+    /// * `recv_message_timeout`: The time to wait for
+    pub fn with_recv_message_timeout(mut self, recv_message_timeout: Duration) -> Self {
+        self.recv_message_timeout = recv_message_timeout;
+        self
+    }
+
+    /// Returns a reference to the [`async_nats::Client`]
+    pub fn client(&self) -> &async_nats::Client {
+        &self.client
+    }
+
+    /// Returns the subject messages with the provided [`Address`] are published to
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the messages
+    pub fn subject(&self, address: Address) -> String {
+        format!("{}.{:x}{:x}", self.prefix, address.base(), address.relative())
+    }
+
+    /// Subscribes to the subject of the provided [`Address`]
+    async fn subscribe(&self, address: Address) -> Result<async_nats::Subscriber> {
+        self.client
+            .subscribe(self.subject(address))
+            .await
+            .map_err(|e| Error::Nats("subscribing to subject", anyhow::Error::msg(e)))
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport<'_> for Client {
+    type Msg = TransportMessage;
+    type SendResponse = TransportMessage;
+
+    /// Publishes a message to the subject of the provided [`Address`].
+    ///
+    /// # Arguments
+    /// * `address`: The address of the message.
+    /// * `msg`: The message to publish.
+    async fn send_message(&mut self, address: Address, msg: TransportMessage) -> Result<TransportMessage> {
+        self.client
+            .publish(self.subject(address), msg.as_ref().to_vec().into())
+            .await
+            .map_err(|e| Error::Nats("publishing message", anyhow::Error::msg(e)))?;
+        self.client
+            .flush()
+            .await
+            .map_err(|e| Error::Nats("flushing published message", anyhow::Error::msg(e)))?;
+        Ok(msg)
+    }
+
+    /// Subscribes to the subject of the provided [`Address`], collecting the messages published
+    /// until the drain timeout elapses. Errors if no message was received.
+    ///
+    /// # Arguments
+    /// * `address`: The address of the messages to receive.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+        let mut subscriber = self.subscribe(address).await?;
+        let deadline = Instant::now() + self.drain_timeout;
+        let mut msgs = Vec::new();
+        while let Ok(Some(msg)) = timeout(deadline.saturating_duration_since(Instant::now()), subscriber.next()).await {
+            msgs.push(TransportMessage::new(msg.payload.to_vec()));
+        }
+        // Unsubscribing is best effort, the subscription is dropped regardless
+        let _ = subscriber.unsubscribe().await;
+        if msgs.is_empty() {
+            return Err(Error::AddressError("not found in transport", address));
+        }
+        Ok(msgs)
+    }
+
+    /// Subscribes to the subject of the provided [`Address`] and returns the first message
+    /// published before the single message timeout elapses.
+    ///
+    /// # Arguments
+    /// * `address`: The address of the message to receive.
+    async fn recv_message(&mut self, address: Address) -> Result<TransportMessage> {
+        let mut subscriber = self.subscribe(address).await?;
+        let msg = timeout(self.recv_message_timeout, subscriber.next()).await;
+        let _ = subscriber.unsubscribe().await;
+        match msg {
+            Ok(Some(msg)) => Ok(TransportMessage::new(msg.payload.to_vec())),
+            _ => Err(Error::AddressError("not found in transport", address)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::vec;
+    use std::process::{Child, Command};
+
+    use super::*;
+
+    const NATS_URL: &str = "nats://127.0.0.1:14222";
+
+    /// Kills the spawned `nats-server` when the test finishes
+    struct NatsServer(Child);
+
+    impl Drop for NatsServer {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+        }
+    }
+
+    async fn nats_fixture() -> Result<(NatsServer, Client)> {
+        let server = NatsServer(
+            Command::new("nats-server")
+                .args(["--port", "14222"])
+                .spawn()
+                .expect("nats-server binary should be installed"),
+        );
+        // Give the server some time to start listening
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let client = Client::connect(NATS_URL, "streams").await?;
+        Ok((server, client))
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a nats-server binary"]
+    async fn messages_round_trip_through_nats() -> Result<()> {
+        let (_server, client) = nats_fixture().await?;
+        let address = Address::new([1; 40], [2; 12]);
+        let msg = TransportMessage::new(vec![3; 32]);
+
+        let mut receiver = client.clone();
+        let mut sender = client;
+        let (received, sent) = tokio::join!(receiver.recv_messages(address), async {
+            // Leave time for the receiver to subscribe before publishing
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            sender.send_message(address, msg.clone()).await
+        });
+
+        assert_eq!(sent?, msg);
+        assert_eq!(received?, vec![msg]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a nats-server binary"]
+    async fn receiving_without_publisher_times_out() -> Result<()> {
+        let (_server, client) = nats_fixture().await?;
+        let mut client = client
+            .with_drain_timeout(Duration::from_millis(100))
+            .with_recv_message_timeout(Duration::from_millis(100));
+        let address = Address::new([1; 40], [2; 12]);
+        client.send_message(address, TransportMessage::new(vec![3; 32])).await?;
+
+        // Messages published before subscribing are not delivered
+        assert!(client.recv_messages(address).await.is_err());
+        let start = Instant::now();
+        assert!(client.recv_message(address).await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+// 3rd-party
+use async_trait::async_trait;
+use tokio::time::{sleep, Instant};
+
+// IOTA
+
+// Streams
+
+// Local
+use crate::{address::Address, error::Result, transport::Transport};
+
+/// A [`Transport`] wrapper throttling the messages sent through the inner transport to a maximum
+/// rate, to avoid overloading nodes with high-frequency publishers.
+///
+/// Sending a message consumes a token from a bucket that is refilled at the configured rate. When
+/// the bucket is empty, sending waits until a token is available. The bucket holds a single token,
+/// so messages are spaced evenly and never sent in bursts. Receiving messages is not throttled.
+#[derive(Debug, Clone)]
+pub struct RateLimitTransport<T> {
+    /// Transport the messages are sent through
+    inner: T,
+    /// Maximum number of messages sent per second
+    rate: u32,
+    /// Last time the token bucket was refilled
+    last_send: Instant,
+    /// Tokens available to send messages
+    token_bucket: f64,
+}
+
+impl<T> RateLimitTransport<T> {
+    /// Creates a new [`RateLimitTransport`] sending at most `messages_per_second` messages per
+    /// second through the inner transport. The first message is sent right away.
+    ///
+    /// # Arguments
+    /// * `inner`: The transport the messages are sent through
+    /// * `messages_per_second`: Maximum number of messages sent per second
+    ///
+    /// # Panics
+    /// Panics if `messages_per_second` is zero.
+    pub fn new(inner: T, messages_per_second: u32) -> Self {
+        assert!(
+            messages_per_second > 0,
+            "the rate limit must allow at least one message per second"
+        );
+        Self {
+            inner,
+            rate: messages_per_second,
+            last_send: Instant::now(),
+            token_bucket: 1.0,
+        }
+    }
+
+    /// Returns a reference to the inner transport
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the [`RateLimitTransport`], returning the inner transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Takes a token from the bucket, waiting for the bucket to be refilled if it is empty
+    async fn acquire_token(&mut self) {
+        let now = Instant::now();
+        let refill = now.duration_since(self.last_send).as_secs_f64() * f64::from(self.rate);
+        self.token_bucket = (self.token_bucket + refill).min(1.0);
+        self.last_send = now;
+
+        if self.token_bucket < 1.0 {
+            let missing = 1.0 - self.token_bucket;
+            sleep(Duration::from_secs_f64(missing / f64::from(self.rate))).await;
+            self.last_send = Instant::now();
+            self.token_bucket = 0.0;
+        } else {
+            self.token_bucket -= 1.0;
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, Tsp: Transport<'a>> Transport<'a> for RateLimitTransport<Tsp> {
+    type Msg = Tsp::Msg;
+    type SendResponse = Tsp::SendResponse;
+
+    /// Send a message, waiting for the rate limit to allow it.
+    async fn send_message(&mut self, address: Address, msg: Tsp::Msg) -> Result<Tsp::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.acquire_token().await;
+        self.inner.send_message(address, msg).await
+    }
+
+    /// Receive messages from the inner transport, without rate limit.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
+        self.inner.recv_messages(address).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{message::TransportMessage, transport::bucket};
+
+    #[tokio::test]
+    async fn sent_messages_are_throttled() -> Result<()> {
+        // With the clock paused, the runtime advances it whenever it only waits on timers
+        tokio::time::pause();
+        let mut transport = RateLimitTransport::new(bucket::Client::new(), 5);
+        let start = Instant::now();
+        for i in 0..10 {
+            let address = Address::new([1; 40], [i; 12]);
+            transport
+                .send_message(address, TransportMessage::new(vec![i; 32]))
+                .await?;
+        }
+        // The first message is sent right away, the next 9 are spaced 200ms apart
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(1800) && elapsed < Duration::from_millis(1850));
+        assert_eq!(transport.inner().message_count(), 10);
+
+        // The bucket is refilled while no message is sent, so the next message is not delayed
+        tokio::time::advance(Duration::from_secs(1)).await;
+        let start = Instant::now();
+        transport
+            .send_message(Address::new([1; 40], [10; 12]), TransportMessage::new(vec![10; 32]))
+            .await?;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(transport.inner().message_count(), 11);
+
+        let address = Address::new([1; 40], [0; 12]);
+        assert_eq!(transport.recv_messages(address).await?.len(), 1);
+        Ok(())
+    }
+}
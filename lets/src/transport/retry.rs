@@ -0,0 +1,221 @@
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+// 3rd-party
+use async_trait::async_trait;
+use tokio::time::sleep;
+
+// IOTA
+
+// Streams
+
+// Local
+use crate::{
+    address::Address,
+    error::{Error, Result},
+    transport::Transport,
+};
+
+/// Number of times [`RetryTransport`] repeats a failed receive request, and time waited between
+/// attempts
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct RetryConfig {
+    /// Maximum number of times a failed request is repeated
+    pub max_retries: u32,
+    /// Time waited before repeating a failed request
+    pub delay: Duration,
+}
+
+impl RetryConfig {
+    /// Creates a new [`RetryConfig`]
+    ///
+    /// # Arguments
+    /// * `max_retries`: Maximum number of times a failed request is repeated
+    /// * `delay`: Time waited before repeating a failed request
+    pub fn new(max_retries: u32, delay: Duration) -> Self {
+        Self { max_retries, delay }
+    }
+}
+
+/// A [`Transport`] wrapper repeating the receive requests that fail on the inner transport, to ride
+/// out transient network failures.
+///
+/// Send requests are made only once: a failed send, such as one that timed out, may still have been
+/// delivered, and repeating it would publish the message twice. Errors reporting that no message is
+/// found at an address are returned right away, as repeating the request would most likely not
+/// change its outcome.
+#[derive(Debug, Clone)]
+pub struct RetryTransport<T> {
+    /// Transport the requests are made through
+    inner: T,
+    /// Retry policy of the failed requests
+    config: RetryConfig,
+}
+
+impl<T> RetryTransport<T> {
+    /// Creates a new [`RetryTransport`] repeating failed requests as configured
+    ///
+    /// # Arguments
+    /// * `inner`: The transport the requests are made through
+    /// * `config`: The retry policy of the failed requests
+    pub fn new(inner: T, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Returns a reference to the inner transport
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the [`RetryTransport`], returning the inner transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns true if a request failing with `error` should be repeated after `attempt` attempts
+    ///
+    /// # Arguments
+    /// * `error`: The error the request failed with
+    /// * `attempt`: The number of attempts made so far
+    fn should_retry(&self, error: &Error, attempt: u32) -> bool {
+        let not_found = matches!(error, Error::AddressError(..) | Error::MessageMissing(..));
+        !not_found && attempt <= self.config.max_retries
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, Tsp> Transport<'a> for RetryTransport<Tsp>
+where
+    Tsp: Transport<'a>,
+{
+    type Msg = Tsp::Msg;
+    type SendResponse = Tsp::SendResponse;
+
+    /// Send a message, without repeating the request if it fails.
+    async fn send_message(&mut self, address: Address, msg: Tsp::Msg) -> Result<Tsp::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.inner.send_message(address, msg).await
+    }
+
+    /// Send several messages, in order, without repeating the request if it fails.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Tsp::Msg)>) -> Result<Vec<Tsp::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.inner.send_messages_batch(messages).await
+    }
+
+    /// Receive messages, repeating the request if it fails.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.recv_messages(address).await {
+                Err(e) if self.should_retry(&e, attempt) => {
+                    attempt += 1;
+                    sleep(self.config.delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{message::TransportMessage, transport::bucket};
+
+    /// Transport failing a number of requests before forwarding them to a bucket
+    struct FlakyTransport {
+        inner: bucket::Client,
+        failures: u32,
+        attempts: u32,
+    }
+
+    #[async_trait(?Send)]
+    impl Transport<'_> for FlakyTransport {
+        type Msg = TransportMessage;
+        type SendResponse = TransportMessage;
+
+        async fn send_message(&mut self, address: Address, msg: TransportMessage) -> Result<TransportMessage>
+        where
+            Self::Msg: 'async_trait,
+        {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                return Err(Error::External(anyhow::anyhow!("connection reset")));
+            }
+            self.inner.send_message(address, msg).await
+        }
+
+        async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+            self.attempts += 1;
+            if self.attempts <= self.failures {
+                return Err(Error::External(anyhow::anyhow!("connection reset")));
+            }
+            self.inner.recv_messages(address).await
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_receives_are_repeated() -> Result<()> {
+        let address = Address::new([1; 40], [1; 12]);
+        let msg = TransportMessage::new(vec![1; 32]);
+        let mut inner = bucket::Client::new();
+        inner.send_message(address, msg.clone()).await?;
+        let flaky = FlakyTransport {
+            inner,
+            failures: 2,
+            attempts: 0,
+        };
+        let mut transport = RetryTransport::new(flaky, RetryConfig::new(1, Duration::from_millis(1)));
+        assert!(transport.recv_messages(address).await.is_err());
+        assert_eq!(transport.inner().attempts, 2);
+
+        let mut transport = RetryTransport::new(transport.into_inner(), RetryConfig::new(3, Duration::from_millis(1)));
+        assert_eq!(transport.recv_messages(address).await?, vec![msg]);
+        assert_eq!(transport.inner().attempts, 3);
+
+        let missing = Address::new([1; 40], [2; 12]);
+        assert!(transport.recv_messages(missing).await.is_err());
+        assert_eq!(transport.inner().attempts, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_sends_are_not_repeated() -> Result<()> {
+        let flaky = FlakyTransport {
+            inner: bucket::Client::new(),
+            failures: 1,
+            attempts: 0,
+        };
+        let mut transport = RetryTransport::new(flaky, RetryConfig::new(3, Duration::from_millis(1)));
+        let address = Address::new([1; 40], [1; 12]);
+        let msg = TransportMessage::new(vec![1; 32]);
+        assert!(transport.send_message(address, msg.clone()).await.is_err());
+        assert_eq!(transport.inner().attempts, 1);
+        transport.send_message(address, msg).await?;
+        assert_eq!(transport.inner().attempts, 2);
+
+        let mut transport = RetryTransport::new(
+            FlakyTransport {
+                inner: bucket::Client::new(),
+                failures: 1,
+                attempts: 0,
+            },
+            RetryConfig::new(3, Duration::from_millis(1)),
+        );
+        let batch = vec![
+            (Address::new([1; 40], [1; 12]), TransportMessage::new(vec![1; 32])),
+            (Address::new([1; 40], [2; 12]), TransportMessage::new(vec![2; 32])),
+        ];
+        assert!(transport.send_messages_batch(batch).await.is_err());
+        assert_eq!(transport.inner().attempts, 1);
+        Ok(())
+    }
+}
@@ -95,6 +95,28 @@ where
             .try_into()
     }
 
+    /// Sends several messages indexed at their provided [`Address`]es to the tangle. Messages are
+    /// indexed independently of each other, so they are all submitted to the node concurrently.
+    ///
+    /// # Arguments
+    /// * `messages`: The addresses and messages to send.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Message)>) -> Result<Vec<SendResponse>>
+    where
+        Message: 'async_trait,
+    {
+        let client = self.client();
+        try_join_all(messages.into_iter().map(|(address, msg)| {
+            client
+                .message()
+                .with_index(address.to_msg_index())
+                .with_data(msg.into())
+                .finish()
+                .map_err(|e| Error::IotaClient("sending message", e))
+                .and_then(|iota_message| ready(iota_message.try_into()))
+        }))
+        .await
+    }
+
     /// Retrieves a message indexed at the provided [`Address`] from the tangle. Errors if no
     /// messages are found.
     ///
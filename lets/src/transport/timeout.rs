@@ -0,0 +1,139 @@
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+// 3rd-party
+use async_trait::async_trait;
+use tokio::time::timeout;
+
+// IOTA
+
+// Streams
+
+// Local
+use crate::{
+    address::Address,
+    error::{Error, Result},
+    transport::Transport,
+};
+
+/// A [`Transport`] wrapper bounding the time every request to the inner transport may take.
+///
+/// Requests that do not complete in time are abandoned and fail with [`Error::Timeout`].
+#[derive(Debug, Clone)]
+pub struct TimeoutTransport<T> {
+    /// Transport the requests are made through
+    inner: T,
+    /// Maximum time a request may take
+    timeout: Duration,
+}
+
+impl<T> TimeoutTransport<T> {
+    /// Creates a new [`TimeoutTransport`] failing the requests that take longer than `timeout`
+    ///
+    /// # Arguments
+    /// * `inner`: The transport the requests are made through
+    /// * `timeout`: Maximum time a request may take
+    pub fn new(inner: T, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+
+    /// Returns a reference to the inner transport
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the [`TimeoutTransport`], returning the inner transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, Tsp: Transport<'a>> Transport<'a> for TimeoutTransport<Tsp> {
+    type Msg = Tsp::Msg;
+    type SendResponse = Tsp::SendResponse;
+
+    /// Send a message, failing if it is not sent in time.
+    async fn send_message(&mut self, address: Address, msg: Tsp::Msg) -> Result<Tsp::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        timeout(self.timeout, self.inner.send_message(address, msg))
+            .await
+            .map_err(|_| Error::Timeout(self.timeout))?
+    }
+
+    /// Send several messages, in order, failing if the whole batch is not sent in time.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Tsp::Msg)>) -> Result<Vec<Tsp::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        timeout(self.timeout, self.inner.send_messages_batch(messages))
+            .await
+            .map_err(|_| Error::Timeout(self.timeout))?
+    }
+
+    /// Receive messages, failing if they are not received in time.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<Tsp::Msg>> {
+        timeout(self.timeout, self.inner.recv_messages(address))
+            .await
+            .map_err(|_| Error::Timeout(self.timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{message::TransportMessage, transport::bucket};
+
+    /// Transport taking a fixed time to answer every request
+    struct SlowTransport {
+        inner: bucket::Client,
+        delay: Duration,
+    }
+
+    #[async_trait(?Send)]
+    impl Transport<'_> for SlowTransport {
+        type Msg = TransportMessage;
+        type SendResponse = TransportMessage;
+
+        async fn send_message(&mut self, address: Address, msg: TransportMessage) -> Result<TransportMessage>
+        where
+            Self::Msg: 'async_trait,
+        {
+            tokio::time::sleep(self.delay).await;
+            self.inner.send_message(address, msg).await
+        }
+
+        async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+            tokio::time::sleep(self.delay).await;
+            self.inner.recv_messages(address).await
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_requests_time_out() -> Result<()> {
+        let slow = SlowTransport {
+            inner: bucket::Client::new(),
+            delay: Duration::from_millis(200),
+        };
+        let mut transport = TimeoutTransport::new(slow, Duration::from_millis(50));
+        let address = Address::new([1; 40], [1; 12]);
+        assert!(matches!(
+            transport
+                .send_message(address, TransportMessage::new(vec![1; 32]))
+                .await,
+            Err(Error::Timeout(_))
+        ));
+
+        let mut transport = TimeoutTransport::new(transport.into_inner(), Duration::from_millis(500));
+        transport
+            .send_message(address, TransportMessage::new(vec![1; 32]))
+            .await?;
+        assert_eq!(transport.recv_messages(address).await?.len(), 1);
+        Ok(())
+    }
+}
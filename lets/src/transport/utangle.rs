@@ -7,6 +7,7 @@ use core::{
 
 // 3rd-party
 use async_trait::async_trait;
+use futures::future::try_join_all;
 use rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize};
 
@@ -169,6 +170,43 @@ where
         Ok(response)
     }
 
+    /// Sends several messages indexed at their provided [`Address`]es to the tangle. The network
+    /// details and tips are requested once for the whole batch, and the messages are then posted
+    /// concurrently.
+    ///
+    /// # Arguments
+    /// * `messages`: The addresses and messages to send.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, Message)>) -> Result<Vec<SendResponse>>
+    where
+        Message: 'async_trait,
+    {
+        if messages.is_empty() {
+            return Ok(Vec::new());
+        }
+        let network_info = self.get_network_info().await?;
+        let tips = self.get_tips().await?;
+
+        let messages_bytes = messages
+            .iter()
+            .map(|(address, msg)| self.pack_message(network_info.clone(), tips.clone(), *address, msg.as_ref()))
+            .collect::<Result<Vec<Vec<u8>>>>()?;
+
+        let path = "api/v1/messages";
+        let (client, node_url) = (&self.client, &self.node_url);
+        try_join_all(messages_bytes.into_iter().map(|message_bytes| async move {
+            let response: SendResponse = client
+                .post(format!("{}/{}", node_url, path))
+                .header("Content-Type", "application/octet-stream")
+                .body(message_bytes)
+                .send()
+                .await?
+                .json()
+                .await?;
+            Ok::<SendResponse, Error>(response)
+        }))
+        .await
+    }
+
     /// Retrieves a message indexed at the provided [`Address`] from the tangle. Errors if no
     /// messages are found.
     ///
@@ -230,7 +268,7 @@ fn nonce(data: &[u8], target_score: f64) -> Result<u64> {
         .ok_or(Error::Nonce(target_score))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 struct NetworkInfo {
     #[serde(rename = "networkId")]
     network_id: String,
@@ -238,7 +276,7 @@ struct NetworkInfo {
     min_pow_score: f64,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 struct Tips {
     #[serde(rename = "tipMessageIds")]
     ids: Vec<String>,
@@ -0,0 +1,175 @@
+// Rust
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+use js_sys::{Function, Promise, Uint8Array};
+use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+// IOTA
+
+// Streams
+
+// Local
+use crate::{
+    address::Address,
+    error::{Error, Result},
+    message::TransportMessage,
+    transport::Transport,
+};
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TRANSPORT_FUNCTIONS: &str = r#"
+/** Sends the bytes of a message to the hex-encoded address, as called by the wasm transport client */
+export type WasmTransportSendFn = (address: string, message: Uint8Array) => Promise<void> | void;
+/** Returns the bytes of the message at the hex-encoded address, or `null` if there is none */
+export type WasmTransportRecvFn = (address: string) => Promise<Uint8Array | null> | Uint8Array | null;
+"#;
+
+/// A [`Transport`] Client delegating the delivery of messages to JavaScript functions, for
+/// deployments compiled to WebAssembly where neither `tokio` nor native sockets are available.
+///
+/// Addresses are passed to the functions as the hex-encoded `AppAddr` followed by the hex-encoded
+/// `MsgId`. Both functions may return a value or a `Promise` resolving to it.
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// Function called with the address and the bytes of each message to send
+    send_fn: Function,
+    /// Function called with an address, returning the bytes of the message found at it
+    recv_fn: Function,
+}
+
+impl Client {
+    /// Creates a new [`Client`] calling the provided JavaScript functions
+    ///
+    /// # Arguments
+    /// * `send_fn`: Function with the `WasmTransportSendFn` signature, sending a message
+    /// * `recv_fn`: Function with the `WasmTransportRecvFn` signature, receiving a message
+    pub fn new(send_fn: Function, recv_fn: Function) -> Self {
+        Self { send_fn, recv_fn }
+    }
+
+    /// Returns the hex encoding of the provided [`Address`], as passed to the JavaScript functions
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] to encode
+    pub fn address_hex(address: Address) -> String {
+        format!("{:x}{:x}", address.base(), address.relative())
+    }
+
+    /// Awaits the value returned by a JavaScript function, resolving it first if it is a `Promise`
+    ///
+    /// # Arguments
+    /// * `value`: The value returned by the function
+    async fn resolve(value: JsValue) -> core::result::Result<JsValue, JsValue> {
+        JsFuture::from(Promise::resolve(&value)).await
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport<'_> for Client {
+    type Msg = TransportMessage;
+    type SendResponse = TransportMessage;
+
+    /// Calls the send function with the hex-encoded address and the bytes of the message, waiting
+    /// for its `Promise` to resolve.
+    ///
+    /// # Arguments
+    /// * `address`: The address of the message.
+    /// * `msg`: The message to send.
+    async fn send_message(&mut self, address: Address, msg: TransportMessage) -> Result<TransportMessage> {
+        let address_hex = JsValue::from_str(&Self::address_hex(address));
+        let bytes = Uint8Array::from(msg.as_ref());
+        let sent = self
+            .send_fn
+            .call2(&JsValue::NULL, &address_hex, &bytes)
+            .map_err(|e| Error::Wasm("calling the send function", format!("{:?}", e)))?;
+        Self::resolve(sent)
+            .await
+            .map_err(|e| Error::Wasm("sending message", format!("{:?}", e)))?;
+        Ok(msg)
+    }
+
+    /// Calls the receive function with the hex-encoded address, expecting a `Uint8Array` with the
+    /// bytes of the message. Errors if it resolves to `null` or `undefined`.
+    ///
+    /// # Arguments
+    /// * `address`: The address of the messages to receive.
+    async fn recv_messages(&mut self, address: Address) -> Result<Vec<TransportMessage>> {
+        let address_hex = JsValue::from_str(&Self::address_hex(address));
+        let received = self
+            .recv_fn
+            .call1(&JsValue::NULL, &address_hex)
+            .map_err(|e| Error::Wasm("calling the receive function", format!("{:?}", e)))?;
+        let received = Self::resolve(received)
+            .await
+            .map_err(|e| Error::Wasm("receiving message", format!("{:?}", e)))?;
+        if received.is_null() || received.is_undefined() {
+            return Err(Error::AddressError("not found in transport", address));
+        }
+        if !received.is_instance_of::<Uint8Array>() {
+            return Err(Error::Wasm(
+                "receiving message",
+                String::from("the receive function did not return a Uint8Array"),
+            ));
+        }
+        Ok(vec![TransportMessage::new(Uint8Array::from(received).to_vec())])
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use js_sys::Array;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    /// Creates a [`Client`] backed by an in-memory JavaScript `Map`, mocking a remote node
+    fn map_client_fixture() -> Client {
+        let functions = Function::new_no_args(
+            "const store = new Map();
+            return [
+                async (address, message) => { store.set(address, message); },
+                async (address) => store.get(address) ?? null,
+            ];",
+        )
+        .call0(&JsValue::NULL)
+        .map(Array::from)
+        .expect("mock functions should be created");
+        Client::new(functions.get(0).into(), functions.get(1).into())
+    }
+
+    #[wasm_bindgen_test]
+    async fn messages_round_trip_through_javascript_functions() {
+        let mut client = map_client_fixture();
+        let address = Address::new([1; 40], [2; 12]);
+        let msg = TransportMessage::new(vec![3; 32]);
+
+        assert_eq!(client.send_message(address, msg.clone()).await.unwrap(), msg);
+        assert_eq!(client.recv_message(address).await.unwrap(), msg);
+    }
+
+    #[wasm_bindgen_test]
+    async fn receiving_a_missing_message_fails() {
+        let mut client = map_client_fixture();
+        let address = Address::new([1; 40], [2; 12]);
+
+        assert!(matches!(
+            client.recv_messages(address).await,
+            Err(Error::AddressError(_, _))
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    async fn rejected_sends_are_reported() {
+        let reject = Function::new_no_args("return Promise.reject(new Error('offline'));");
+        let mut client = Client::new(reject.clone(), reject);
+        let address = Address::new([1; 40], [2; 12]);
+
+        assert!(matches!(
+            client.send_message(address, TransportMessage::new(vec![3; 32])).await,
+            Err(Error::Wasm(_, _))
+        ));
+    }
+}
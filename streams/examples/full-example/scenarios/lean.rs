@@ -8,7 +8,7 @@ use lets::id::Identity;
 // Streams
 use streams::{
     id::{Ed25519, Psk},
-    Result, SendResponse, User,
+    NoIdentity, Result, SendResponse, User,
 };
 
 // Local
@@ -93,7 +93,10 @@ pub(crate) async fn example<SR, T: GenericTransport<SR>>(transport: T, author_se
     Ok(())
 }
 
-async fn sync_subs<SR, T: GenericTransport<SR>>(fat: &mut User<T>, lean: &mut User<T>) -> Result<()> {
+async fn sync_subs<SR, T: GenericTransport<SR>>(
+    fat: &mut User<T, NoIdentity>,
+    lean: &mut User<T, NoIdentity>,
+) -> Result<()> {
     print!("\nsubscribers syncing...");
     fat.sync().await?;
     lean.sync().await?;
@@ -109,8 +112,8 @@ async fn sync_subs<SR, T: GenericTransport<SR>>(fat: &mut User<T>, lean: &mut Us
 }
 
 async fn retrieve_messages<SR, T: GenericTransport<SR>, TSR>(
-    fat_subscriber: &mut User<T>,
-    lean_subscriber: &mut User<T>,
+    fat_subscriber: &mut User<T, NoIdentity>,
+    lean_subscriber: &mut User<T, NoIdentity>,
     first_message: SendResponse<TSR>,
     middle_message: SendResponse<TSR>,
     last_message: SendResponse<TSR>,
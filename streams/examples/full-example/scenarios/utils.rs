@@ -10,7 +10,7 @@ use streams::{SendResponse, User};
 
 // Local
 
-pub fn print_user<T>(user_name: &str, user: &User<T>) {
+pub fn print_user<T, I>(user_name: &str, user: &User<T, I>) {
     println!("  {}:\n{}", user_name, indent(&format!("{:?}", user), "\t"));
 }
 
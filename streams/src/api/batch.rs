@@ -0,0 +1,91 @@
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// Streams
+use lets::{
+    address::Address,
+    error::{Error as LetsError, Result as LetsResult},
+    id::{Identifier, Permissioned, PskId},
+    message::{Topic, TransportMessage},
+    transport::Transport,
+};
+
+/// A message to be sent as part of a batch with [`User::send_batch`](`crate::User::send_batch`)
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum BatchItem {
+    /// A Signed Packet message, see [`User::send_signed_packet`](`crate::User::send_signed_packet`)
+    SignedPacket {
+        /// [`Topic`] of the branch to send the message to
+        topic: Topic,
+        /// Unmasked payload of the message
+        public: Vec<u8>,
+        /// Masked payload of the message
+        masked: Vec<u8>,
+    },
+    /// A Tagged Packet message, see [`User::send_tagged_packet`](`crate::User::send_tagged_packet`)
+    TaggedPacket {
+        /// [`Topic`] of the branch to send the message to
+        topic: Topic,
+        /// Unmasked payload of the message
+        public: Vec<u8>,
+        /// Masked payload of the message
+        masked: Vec<u8>,
+    },
+    /// A Keyload message, see [`User::send_keyload`](`crate::User::send_keyload`)
+    Keyload {
+        /// [`Topic`] of the branch the permissions will be updated for
+        topic: Topic,
+        /// [`Permissioned`] subscribers to be included in the keyload
+        subscribers: Vec<Permissioned<Identifier>>,
+        /// Identifiers of the pre shared keys to be included in the keyload
+        psks: Vec<PskId>,
+    },
+}
+
+/// A [`Transport`] holding the messages of a batch in the order they are prepared, so they can be
+/// sent together once the whole batch has been prepared successfully
+#[derive(Default)]
+pub(crate) struct StagingTransport {
+    /// Prepared messages, along with the [`Address`] they must be sent to
+    messages: Vec<(Address, TransportMessage)>,
+}
+
+impl StagingTransport {
+    /// Consumes the [`StagingTransport`], returning the prepared messages in order
+    pub(crate) fn into_messages(self) -> Vec<(Address, TransportMessage)> {
+        self.messages
+    }
+}
+
+#[async_trait(?Send)]
+impl Transport<'_> for StagingTransport {
+    type Msg = TransportMessage;
+    type SendResponse = ();
+
+    /// Holds the message until the batch is sent
+    async fn send_message(&mut self, address: Address, msg: TransportMessage) -> LetsResult<()>
+    where
+        Self::Msg: 'async_trait,
+    {
+        self.messages.push((address, msg));
+        Ok(())
+    }
+
+    /// Returns the messages of the batch prepared for the address, so that messages of the batch
+    /// are not sent twice to the same address
+    async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<TransportMessage>> {
+        let messages: Vec<TransportMessage> = self
+            .messages
+            .iter()
+            .filter(|(message_address, _)| *message_address == address)
+            .map(|(_, msg)| msg.clone())
+            .collect();
+        if messages.is_empty() {
+            return Err(LetsError::AddressError("No message found in batch", address));
+        }
+        Ok(messages)
+    }
+}
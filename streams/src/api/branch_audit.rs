@@ -0,0 +1,27 @@
+use alloc::vec::Vec;
+
+use lets::{address::Address, message::Topic};
+
+/// Result of auditing the chain of messages of a branch, as returned by
+/// [`User::audit_branch`](`crate::User::audit_branch`)
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct BranchAudit {
+    /// [`Topic`] of the audited branch
+    pub topic: Topic,
+    /// Number of messages traversed, from the first message of the chain to the latest link
+    pub chain_length: usize,
+    /// [`Address`] of the first message of the chain, the stream announcement if the chain is intact
+    pub first_address: Address,
+    /// [`Address`] of the latest link of the branch
+    pub last_address: Address,
+    /// [`Address`]es of the messages whose [`Spongos`](spongos::Spongos) state is missing or does
+    /// not match the one it was stored with, in chain order
+    pub broken_links: Vec<Address>,
+}
+
+impl BranchAudit {
+    /// Returns true if no broken link was found in the chain
+    pub fn is_intact(&self) -> bool {
+        self.broken_links.is_empty()
+    }
+}
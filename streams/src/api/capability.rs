@@ -0,0 +1,29 @@
+// Streams
+use lets::id::{Identifier, PermissionDuration, Permissioned};
+
+/// Semantic role assigned to a subscriber at the application layer with
+/// [`User::assign_capability`](`crate::User::assign_capability`). Each capability maps to the
+/// [permission](`Permissioned`) granted to its holders by
+/// [`User::send_keyload_for_capabilities`](`crate::User::send_keyload_for_capabilities`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Capability {
+    /// Publishes data to the branch, granted read and write permissions
+    DataProducer,
+    /// Consumes the data published in the branch, granted read permissions
+    DataConsumer,
+    /// Inspects the data published in the branch, granted read permissions
+    Auditor,
+}
+
+impl Capability {
+    /// Returns the [permission](`Permissioned`) granted to a holder of the capability
+    ///
+    /// # Arguments
+    /// * `identifier`: The [`Identifier`] of the holder
+    pub fn permission<'a>(&self, identifier: &'a Identifier) -> Permissioned<&'a Identifier> {
+        match self {
+            Self::DataProducer => Permissioned::ReadWrite(identifier, PermissionDuration::Perpetual),
+            Self::DataConsumer | Self::Auditor => Permissioned::Read(identifier),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Rust
+use alloc::vec::Vec;
+
+// Local
+use crate::error::{Error, Result};
+
+/// Magic number opening every `zstd` frame, as laid out in the payload
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Compression level used for the masked payloads sent with
+/// [`User::send_tagged_packet_compressed`](`crate::User::send_tagged_packet_compressed`)
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Compresses a payload into a single `zstd` frame
+///
+/// # Arguments
+/// * `payload`: The payload to compress
+pub(crate) fn compress(payload: &[u8]) -> Result<Vec<u8>> {
+    zstd::encode_all(payload, COMPRESSION_LEVEL).map_err(|e| Error::CompressedPayload("compress", e))
+}
+
+/// Decompresses a payload if it starts with the `zstd` magic number, or returns a copy of it as is
+/// otherwise
+///
+/// # Arguments
+/// * `payload`: The payload to decompress
+pub(crate) fn decompress(payload: &[u8]) -> Result<Vec<u8>> {
+    if payload.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(payload).map_err(|e| Error::CompressedPayload("decompress", e))
+    } else {
+        Ok(payload.to_vec())
+    }
+}
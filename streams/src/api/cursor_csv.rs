@@ -0,0 +1,124 @@
+// Rust
+extern crate std;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::mem::take;
+use std::io::{Result as IoResult, Write};
+
+// Streams
+use lets::id::{Identifier, PermissionDuration, Permissioned};
+
+/// Header record of the CSV exported by
+/// [`User::export_cursor_store_csv`](`crate::User::export_cursor_store_csv`)
+pub(crate) const HEADER: [&str; 5] = [
+    "topic",
+    "identifier_hex",
+    "permission_type",
+    "cursor",
+    "latest_link_hex",
+];
+
+/// Returns the `permission_type` column of a [`Permissioned`] [`Identifier`]: `read`, `admin`, or
+/// `read_write` followed by its [`PermissionDuration`] if it is not perpetual, such as
+/// `read_write:unix:1700000000`
+///
+/// # Arguments
+/// * `permission`: The [`Permissioned`] [`Identifier`] of a cursor
+pub(crate) fn permission_type(permission: &Permissioned<Identifier>) -> String {
+    match permission {
+        Permissioned::Read(_) => "read".to_string(),
+        Permissioned::Admin(_) => "admin".to_string(),
+        Permissioned::ReadWrite(_, PermissionDuration::Perpetual) => "read_write".to_string(),
+        Permissioned::ReadWrite(_, PermissionDuration::Unix(timestamp)) => format!("read_write:unix:{}", timestamp),
+        Permissioned::ReadWrite(_, PermissionDuration::NumBranchMsgs(count)) => {
+            format!("read_write:branch_msgs:{}", count)
+        }
+        Permissioned::ReadWrite(_, PermissionDuration::NumPublishedmsgs(count)) => {
+            format!("read_write:published_msgs:{}", count)
+        }
+    }
+}
+
+/// Parses a `permission_type` column, the opposite of [`permission_type`]. Returns `None` if the
+/// permission type is unknown.
+///
+/// # Arguments
+/// * `permission_type`: The `permission_type` column
+/// * `identifier`: The [`Identifier`] the permission is granted to
+pub(crate) fn parse_permission(permission_type: &str, identifier: Identifier) -> Option<Permissioned<Identifier>> {
+    let duration = match permission_type {
+        "read" => return Some(Permissioned::Read(identifier)),
+        "admin" => return Some(Permissioned::Admin(identifier)),
+        "read_write" => PermissionDuration::Perpetual,
+        _ => match permission_type.strip_prefix("read_write:")?.split_once(':')? {
+            ("unix", timestamp) => PermissionDuration::Unix(timestamp.parse().ok()?),
+            ("branch_msgs", count) => PermissionDuration::NumBranchMsgs(count.parse().ok()?),
+            ("published_msgs", count) => PermissionDuration::NumPublishedmsgs(count.parse().ok()?),
+            _ => return None,
+        },
+    };
+    Some(Permissioned::ReadWrite(identifier, duration))
+}
+
+/// Writes a CSV record, quoting the fields that contain a separator, a quote or a line break
+///
+/// # Arguments
+/// * `writer`: The destination of the CSV
+/// * `fields`: The fields of the record
+pub(crate) fn write_record(writer: &mut dyn Write, fields: &[&str]) -> IoResult<()> {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    writer.write_all(fields.join(",").as_bytes())?;
+    writer.write_all(b"\n")
+}
+
+/// Splits a CSV document into records of unquoted fields. Returns `None` if a quoted field is not
+/// terminated.
+///
+/// # Arguments
+/// * `csv`: The CSV document
+pub(crate) fn parse_records(csv: &str) -> Option<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(take(&mut field));
+                records.push(take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Some(records)
+}
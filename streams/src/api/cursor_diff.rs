@@ -0,0 +1,15 @@
+use lets::{id::Identifier, message::Topic};
+
+/// A publisher cursor that differs between two [`User`](`crate::User`)s, as returned by
+/// [`User::cursor_diff`](`crate::User::cursor_diff`)
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct CursorDiff {
+    /// [`Topic`] of the branch the cursor is stored in
+    pub topic: Topic,
+    /// [`Identifier`] of the publisher the cursor belongs to
+    pub identifier: Identifier,
+    /// Cursor stored by the user the comparison is made from, if any
+    pub self_cursor: Option<usize>,
+    /// Cursor stored by the user compared against, if any
+    pub other_cursor: Option<usize>,
+}
@@ -1,4 +1,5 @@
 // Rust
+use alloc::vec::Vec;
 use core::fmt;
 
 // 3rd-party
@@ -13,11 +14,23 @@ use lets::{
     message::Topic,
 };
 
+#[cfg(feature = "serde")]
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Mask},
+        types::Size,
+    },
+    error::Result as SpongosResult,
+};
+
 // Local
 
 /// Mapping of [`Topic`] to [`InnerCursorStore`]
+///
+/// The store can be converted to and from a flat list of cursors with [`CursorStore::to_vec`] and
+/// [`CursorStore::from_vec`], to be persisted by external means.
 #[derive(Default, Clone, PartialEq, Eq)]
-pub(crate) struct CursorStore(HashMap<Topic, InnerCursorStore>);
+pub struct CursorStore(HashMap<Topic, InnerCursorStore>);
 
 impl CursorStore {
     /// Cretes a default mapping
@@ -53,6 +66,30 @@ impl CursorStore {
         removals.count() > 0
     }
 
+    /// Remove the cursors at or below `max_cursor` in every branch, except those whose
+    /// [`Permissioned`] [`Identifier`] must be kept. Returns the removed [`Permissioned`]
+    /// [`Identifier`]s, once per removed cursor.
+    ///
+    /// # Arguments
+    /// * `max_cursor`: The highest cursor to be removed
+    /// * `keep`: Whether the cursor of a [`Permissioned`] [`Identifier`] must be kept regardless
+    pub(crate) fn remove_inactive<F>(&mut self, max_cursor: usize, keep: F) -> Vec<Permissioned<Identifier>>
+    where
+        F: Fn(&Permissioned<Identifier>) -> bool,
+    {
+        let mut removed = Vec::new();
+        for branch in self.0.values_mut() {
+            branch.cursors.retain(|permission, cursor| {
+                let inactive = *cursor <= max_cursor && !keep(permission);
+                if inactive {
+                    removed.push(permission.clone());
+                }
+                !inactive
+            });
+        }
+        removed
+    }
+
     /// Given a [`Topic`] and an [`Identifier`], return the [`Permissioned`] [`Identifier`] if it
     /// exists
     ///
@@ -180,6 +217,168 @@ impl CursorStore {
     pub(crate) fn get_latest_link(&self, topic: &Topic) -> Option<MsgId> {
         self.0.get(topic).map(|branch| branch.latest_link)
     }
+
+    /// Returns all the cursors of the store as `(topic, permission, cursor)` triples, sorted by
+    /// topic and then by identifier. Latest links are not included, see
+    /// [`CursorStore::latest_links`].
+    pub fn to_vec(&self) -> Vec<(Topic, Permissioned<Identifier>, usize)> {
+        let mut entries: Vec<(Topic, Permissioned<Identifier>, usize)> = self
+            .cursors()
+            .map(|(topic, permission, cursor)| (topic.clone(), permission.clone(), cursor))
+            .collect();
+        entries.sort_by(|(topic_a, permission_a, _), (topic_b, permission_b, _)| {
+            topic_a
+                .str()
+                .cmp(topic_b.str())
+                .then_with(|| permission_a.identifier().cmp(permission_b.identifier()))
+        });
+        entries
+    }
+
+    /// Returns the latest link of every branch of the store as `(topic, latest link)` pairs, sorted
+    /// by topic.
+    pub fn latest_links(&self) -> Vec<(Topic, MsgId)> {
+        let mut latest_links: Vec<(Topic, MsgId)> = self
+            .0
+            .iter()
+            .map(|(topic, branch)| (topic.clone(), branch.latest_link))
+            .collect();
+        latest_links.sort_by(|(topic_a, _), (topic_b, _)| topic_a.str().cmp(topic_b.str()));
+        latest_links
+    }
+
+    /// Reconstructs a [`CursorStore`] from the cursors returned by [`CursorStore::to_vec`] and the
+    /// latest links returned by [`CursorStore::latest_links`].
+    ///
+    /// # Arguments
+    /// * `entries`: The `(topic, permission, cursor)` triples of the store.
+    /// * `latest_links`: The `(topic, latest link)` pairs of the store.
+    pub fn from_vec(entries: Vec<(Topic, Permissioned<Identifier>, usize)>, latest_links: Vec<(Topic, MsgId)>) -> Self {
+        let mut store = Self::new();
+        for (topic, permission, cursor) in entries {
+            if !store.0.contains_key(&topic) {
+                store.new_branch(topic.clone());
+            }
+            store.insert_cursor(&topic, permission, cursor);
+        }
+        for (topic, latest_link) in latest_links {
+            store.set_latest_link(topic, latest_link);
+        }
+        store
+    }
+
+    /// Encodes the cursors and latest links of the store into bytes
+    #[cfg(feature = "serde")]
+    fn encode(&self) -> SpongosResult<Vec<u8>> {
+        let entries = self.to_vec();
+        let latest_links = self.latest_links();
+
+        let mut ctx = sizeof::Context::new();
+        ctx.mask(Size::new(entries.len()))?;
+        for (topic, permission, cursor) in &entries {
+            ctx.mask(topic)?.mask(permission)?.mask(Size::new(*cursor))?;
+        }
+        ctx.mask(Size::new(latest_links.len()))?;
+        for (topic, latest_link) in &latest_links {
+            ctx.mask(topic)?.mask(latest_link)?;
+        }
+
+        let mut buf = vec![0; ctx.finalize()];
+        let mut ctx = wrap::Context::new(&mut buf[..]);
+        ctx.mask(Size::new(entries.len()))?;
+        for (topic, permission, cursor) in &entries {
+            ctx.mask(topic)?.mask(permission)?.mask(Size::new(*cursor))?;
+        }
+        ctx.mask(Size::new(latest_links.len()))?;
+        for (topic, latest_link) in &latest_links {
+            ctx.mask(topic)?.mask(latest_link)?;
+        }
+        Ok(buf)
+    }
+
+    /// Decodes a [`CursorStore`] from the bytes produced by [`CursorStore::encode`]
+    ///
+    /// # Arguments
+    /// * `bytes`: The encoded cursors and latest links.
+    #[cfg(feature = "serde")]
+    fn decode(bytes: &[u8]) -> SpongosResult<Self> {
+        let mut ctx = unwrap::Context::new(bytes);
+
+        let mut amount_entries = Size::default();
+        ctx.mask(&mut amount_entries)?;
+        let mut entries = Vec::with_capacity(amount_entries.inner());
+        for _ in 0..amount_entries.inner() {
+            let mut topic = Topic::default();
+            let mut permission = Permissioned::default();
+            let mut cursor = Size::default();
+            ctx.mask(&mut topic)?.mask(&mut permission)?.mask(&mut cursor)?;
+            entries.push((topic, permission, cursor.inner()));
+        }
+
+        let mut amount_latest_links = Size::default();
+        ctx.mask(&mut amount_latest_links)?;
+        let mut latest_links = Vec::with_capacity(amount_latest_links.inner());
+        for _ in 0..amount_latest_links.inner() {
+            let mut topic = Topic::default();
+            let mut latest_link = MsgId::default();
+            ctx.mask(&mut topic)?.mask(&mut latest_link)?;
+            latest_links.push((topic, latest_link));
+        }
+
+        Ok(Self::from_vec(entries, latest_links))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CursorStore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let bytes = self.encode().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CursorStore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        /// Collects the encoded cursor store, whether the format provides it as bytes or as a
+        /// sequence of integers
+        struct BytesVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("the bytes of an encoded cursor store")
+            }
+
+            fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(bytes.to_vec())
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+        Self::decode(&bytes).map_err(serde::de::Error::custom)
+    }
 }
 
 /// Represents a branch, and wraps a map from a [`Permissioned`] [`Identifier`] to a cursor. Also
@@ -216,8 +415,9 @@ impl fmt::Debug for CursorStore {
 #[cfg(test)]
 mod tests {
     use super::CursorStore;
-    use alloc::string::ToString;
+    use alloc::{string::ToString, vec::Vec};
     use lets::{
+        address::MsgId,
         id::{Ed25519, Identity, PermissionDuration, Permissioned},
         message::Topic,
     };
@@ -241,4 +441,56 @@ mod tests {
         assert!(branch_store.get_cursor(&topic_1, &identifier).is_none());
         assert!(branch_store.get_cursor(&topic_2, &identifier).is_none());
     }
+
+    fn populated_store() -> CursorStore {
+        let mut branch_store = CursorStore::new();
+        let identifier_1 = Identity::from(Ed25519::from_seed("identifier 1")).identifier().clone();
+        let identifier_2 = Identity::from(Ed25519::from_seed("identifier 2")).identifier().clone();
+        let topic_1 = Topic::new("topic 1".to_string());
+        let topic_2 = Topic::new("topic 2".to_string());
+
+        branch_store.new_branch(topic_2.clone());
+        branch_store.new_branch(topic_1.clone());
+        branch_store.insert_cursor(&topic_2, Permissioned::Admin(identifier_1.clone()), 7);
+        branch_store.insert_cursor(&topic_1, Permissioned::Read(identifier_2), 3);
+        branch_store.insert_cursor(
+            &topic_1,
+            Permissioned::ReadWrite(identifier_1, PermissionDuration::Perpetual),
+            5,
+        );
+        branch_store.set_latest_link(topic_1, MsgId::from([1; 12]));
+        branch_store.set_latest_link(topic_2, MsgId::from([2; 12]));
+        branch_store
+    }
+
+    #[test]
+    fn branch_store_round_trips_through_vec() {
+        let branch_store = populated_store();
+        let entries = branch_store.to_vec();
+
+        assert_eq!(entries.len(), 3);
+        let topics: Vec<&str> = entries.iter().map(|(topic, _, _)| topic.str()).collect();
+        assert_eq!(topics, ["topic 1", "topic 1", "topic 2"]);
+        assert!(entries[0].1.identifier() < entries[1].1.identifier());
+
+        let restored = CursorStore::from_vec(entries, branch_store.latest_links());
+        assert_eq!(restored, branch_store);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn branch_store_round_trips_through_bytes() {
+        let branch_store = populated_store();
+        let bytes = branch_store.encode().unwrap();
+        assert_eq!(CursorStore::decode(&bytes).unwrap(), branch_store);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn branch_store_round_trips_through_serde() {
+        let branch_store = populated_store();
+        let json = serde_json::to_string(&branch_store).unwrap();
+        let restored: CursorStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, branch_store);
+    }
 }
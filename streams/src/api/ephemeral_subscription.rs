@@ -0,0 +1,70 @@
+// Streams
+use lets::{address::Address, id::Identifier, message::TransportMessage, transport::Transport};
+
+// Local
+use crate::{
+    api::user::User,
+    error::{Error, Result},
+};
+
+/// A subscriber whose subscription expires, as subscribed with
+/// [`User::subscribe_ephemeral`](`crate::User::subscribe_ephemeral`)
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct ExpiringSubscriber {
+    /// [`Identifier`] of the subscriber
+    pub id: Identifier,
+    /// Unix time, in seconds, from which the subscription is expired
+    pub expires_at: u64,
+}
+
+/// Token returned by [`User::subscribe_ephemeral`](`crate::User::subscribe_ephemeral`), used to
+/// end the ephemeral subscription before it expires
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct UnsubscribeToken {
+    /// [`Address`] of the stream subscribed to
+    stream_address: Address,
+    /// [`Identifier`] of the ephemeral subscriber
+    subscriber: Identifier,
+}
+
+impl UnsubscribeToken {
+    /// Creates a new [`UnsubscribeToken`] for an ephemeral subscription
+    ///
+    /// # Arguments
+    /// * `stream_address`: The [`Address`] of the stream subscribed to
+    /// * `subscriber`: The [`Identifier`] of the ephemeral subscriber
+    pub(crate) fn new(stream_address: Address, subscriber: Identifier) -> Self {
+        Self {
+            stream_address,
+            subscriber,
+        }
+    }
+
+    /// Returns the [`Address`] of the stream subscribed to
+    pub fn stream_address(&self) -> Address {
+        self.stream_address
+    }
+
+    /// Returns a reference to the [`Identifier`] of the ephemeral subscriber
+    pub fn subscriber(&self) -> &Identifier {
+        &self.subscriber
+    }
+
+    /// Sends an Unsubscription message on behalf of the ephemeral subscriber, consuming the token.
+    /// Errors if the [`User`] is not the subscriber the token was issued to.
+    ///
+    /// # Arguments
+    /// * `user`: The ephemeral subscriber
+    pub async fn redeem<T, TSR>(self, user: &mut User<T>) -> Result<()>
+    where
+        T: for<'a> Transport<'a, Msg = TransportMessage, SendResponse = TSR>,
+    {
+        if user.identifier() != Some(&self.subscriber) || user.stream_address() != Some(self.stream_address) {
+            return Err(Error::Setup(
+                "an unsubscribe token can only be redeemed by the subscriber it was issued to",
+            ));
+        }
+        user.unsubscribe().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+/// Type-level marker describing whether a [`User`](`crate::User`) or
+/// [`UserBuilder`](`crate::UserBuilder`) holds an [`Identity`](`lets::id::Identity`).
+///
+/// Only users marked with [`HasIdentity`] expose the methods that publish messages, so attempting
+/// to publish from a read-only user is a compile error instead of a runtime one. The only exception
+/// is subscribing with a [`Psk`](`lets::id::Psk`), which derives its own identity from the key.
+pub trait IdentityMarker {
+    /// Whether users with this marker are guaranteed to hold an [`Identity`](`lets::id::Identity`)
+    const HAS_IDENTITY: bool;
+}
+
+/// Marker for users holding an [`Identity`](`lets::id::Identity`), able to publish messages
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct HasIdentity;
+
+/// Marker for users without an [`Identity`](`lets::id::Identity`), only able to read messages and
+/// subscribe with a [`Psk`](`lets::id::Psk`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct NoIdentity;
+
+impl IdentityMarker for HasIdentity {
+    const HAS_IDENTITY: bool = true;
+}
+
+impl IdentityMarker for NoIdentity {
+    const HAS_IDENTITY: bool = false;
+}
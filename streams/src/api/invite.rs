@@ -0,0 +1,85 @@
+// Rust
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+// 3rd-party
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+// Streams
+use lets::{address::Address, id::Identifier};
+
+// Local
+use crate::error::{Error, Result};
+
+/// Details needed to onboard a new subscriber out-of-band, as encoded in the invite links generated
+/// with [`User::generate_invite_link`](`crate::User::generate_invite_link`)
+///
+/// The `announcement_address` can be passed to [`User::receive_message`](`crate::User::receive_message`)
+/// to connect to the stream and start the subscription flow.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct InviteInfo {
+    /// [`Address`] of the stream
+    pub stream_address: Address,
+    /// [`Address`] of the announcement message of the stream
+    pub announcement_address: Address,
+    /// [`Identifier`] of the author of the stream
+    pub author_identifier: Identifier,
+}
+
+impl InviteInfo {
+    /// Scheme and path preceding the encoded details in an invite link
+    pub(crate) const LINK_PREFIX: &'static str = "streams://invite/";
+
+    /// Encodes the [`InviteInfo`] as an invite link: a JSON object encoded in URL-safe base64,
+    /// following [`InviteInfo::LINK_PREFIX`]. The author [`Identifier`] is represented as a JSON Web
+    /// Key, so only Ed25519 identifiers can be encoded.
+    pub(crate) fn to_link(&self) -> Result<String> {
+        let author_identifier = self
+            .author_identifier
+            .to_jwk()
+            .map_err(|e| Error::Wrapped("encode the author identifier of the invite link", e))?;
+        let json = serde_json::json!({
+            "stream_address": self.stream_address.to_string(),
+            "announcement_address": self.announcement_address.to_string(),
+            "author_identifier": author_identifier,
+        });
+        Ok(format!(
+            "{}{}",
+            Self::LINK_PREFIX,
+            URL_SAFE_NO_PAD.encode(json.to_string())
+        ))
+    }
+
+    /// Decodes an invite link, the opposite of [`InviteInfo::to_link`]
+    ///
+    /// # Arguments
+    /// * `link`: The invite link
+    pub(crate) fn from_link(link: &str) -> Result<Self> {
+        let invalid = |cause: &'static str| Error::InvalidInviteLink(link.to_string(), cause);
+        let blob = link
+            .strip_prefix(Self::LINK_PREFIX)
+            .ok_or_else(|| invalid("missing streams://invite/ prefix"))?;
+        let json = URL_SAFE_NO_PAD
+            .decode(blob)
+            .map_err(|_| invalid("not encoded in URL-safe base64"))?;
+        let json: serde_json::Value = serde_json::from_slice(&json).map_err(|_| invalid("not a JSON object"))?;
+        let address = |name: &'static str| -> Result<Address> {
+            json.get(name)
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| invalid(name))?
+                .parse()
+                .map_err(|_| invalid(name))
+        };
+        let author_identifier = json
+            .get("author_identifier")
+            .ok_or_else(|| invalid("author_identifier"))
+            .and_then(|jwk| Identifier::from_jwk(jwk).map_err(|_| invalid("author_identifier")))?;
+        Ok(Self {
+            stream_address: address("stream_address")?,
+            announcement_address: address("announcement_address")?,
+            author_identifier,
+        })
+    }
+}
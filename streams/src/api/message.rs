@@ -1,5 +1,5 @@
 // Rust
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
 // 3rd-party
 
@@ -13,8 +13,14 @@ use lets::{
 };
 
 // Local
+#[cfg(feature = "compressed-payloads")]
+use crate::api::compressed_payload;
+#[cfg(feature = "timestamped-payloads")]
+use crate::api::timestamped_payload::TimestampedPublicPayload;
 use crate::message::{
-    announcement, branch_announcement, keyload, signed_packet, subscription, tagged_packet, unsubscription,
+    announcement, announcement_ack, branch_announcement, direct_message, heartbeat, keyload, multipart_manifest,
+    proof_of_inclusion, signed_packet, stream_catalog, stream_link, stream_metadata, stream_metadata_update,
+    subscription, tagged_packet, unsubscription,
 };
 
 /// A processed Streams message
@@ -125,6 +131,51 @@ impl Message {
         matches!(self.content, MessageContent::Unsubscription { .. })
     }
 
+    /// Returns true if the message is a [`MessageContent`]`::Heartbeat`
+    pub fn is_heartbeat(&self) -> bool {
+        matches!(self.content, MessageContent::Heartbeat { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::StreamCatalog`
+    pub fn is_stream_catalog(&self) -> bool {
+        matches!(self.content, MessageContent::StreamCatalog { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::DirectMessage`
+    pub fn is_direct_message(&self) -> bool {
+        matches!(self.content, MessageContent::DirectMessage { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::StreamMetadata`
+    pub fn is_stream_metadata(&self) -> bool {
+        matches!(self.content, MessageContent::StreamMetadata { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::StreamMetadataUpdate`
+    pub fn is_stream_metadata_update(&self) -> bool {
+        matches!(self.content, MessageContent::StreamMetadataUpdate { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::ProofOfInclusion`
+    pub fn is_proof_of_inclusion(&self) -> bool {
+        matches!(self.content, MessageContent::ProofOfInclusion { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::MultipartManifest`
+    pub fn is_multipart_manifest(&self) -> bool {
+        matches!(self.content, MessageContent::MultipartManifest { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::AnnouncementAck`
+    pub fn is_announcement_ack(&self) -> bool {
+        matches!(self.content, MessageContent::AnnouncementAck { .. })
+    }
+
+    /// Returns true if the message is a [`MessageContent`]`::StreamLink`
+    pub fn is_stream_link(&self) -> bool {
+        matches!(self.content, MessageContent::StreamLink { .. })
+    }
+
     /// Returns true if the message is a [`MessageContent`]`::Orphan`
     pub fn is_orphan(&self) -> bool {
         matches!(self.content, MessageContent::Orphan { .. })
@@ -193,6 +244,87 @@ impl Message {
         }
     }
 
+    /// If the message is a `Heartbeat` return it as one
+    pub fn as_heartbeat(&self) -> Option<&Heartbeat> {
+        if let MessageContent::Heartbeat(heartbeat) = &self.content {
+            Some(heartbeat)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `StreamCatalog` return it as one
+    pub fn as_stream_catalog(&self) -> Option<&StreamCatalog> {
+        if let MessageContent::StreamCatalog(stream_catalog) = &self.content {
+            Some(stream_catalog)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `DirectMessage` return it as one
+    pub fn as_direct_message(&self) -> Option<&DirectMessage> {
+        if let MessageContent::DirectMessage(direct_message) = &self.content {
+            Some(direct_message)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `StreamMetadata` return it as one
+    pub fn as_stream_metadata(&self) -> Option<&StreamMetadata> {
+        if let MessageContent::StreamMetadata(stream_metadata) = &self.content {
+            Some(stream_metadata)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `StreamMetadataUpdate` return the updated metadata
+    pub fn as_stream_metadata_update(&self) -> Option<&StreamMetadata> {
+        if let MessageContent::StreamMetadataUpdate(stream_metadata) = &self.content {
+            Some(stream_metadata)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `ProofOfInclusion` return it as one
+    pub fn as_proof_of_inclusion(&self) -> Option<&ProofOfInclusion> {
+        if let MessageContent::ProofOfInclusion(proof_of_inclusion) = &self.content {
+            Some(proof_of_inclusion)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `MultipartManifest` return it as one
+    pub fn as_multipart_manifest(&self) -> Option<&MultipartManifest> {
+        if let MessageContent::MultipartManifest(multipart_manifest) = &self.content {
+            Some(multipart_manifest)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is an `AnnouncementAck` return it as one
+    pub fn as_announcement_ack(&self) -> Option<&AnnouncementAck> {
+        if let MessageContent::AnnouncementAck(announcement_ack) = &self.content {
+            Some(announcement_ack)
+        } else {
+            None
+        }
+    }
+
+    /// If the message is a `StreamLink` return it as one
+    pub fn as_stream_link(&self) -> Option<&StreamLink> {
+        if let MessageContent::StreamLink(stream_link) = &self.content {
+            Some(stream_link)
+        } else {
+            None
+        }
+    }
+
     /// If the message is an `Orphan` return it as one
     pub fn as_orphan(&self) -> Option<&Orphan> {
         if let MessageContent::Orphan(orphan) = &self.content {
@@ -225,6 +357,29 @@ impl Message {
             _ => None,
         }
     }
+
+    /// Decodes the send time embedded in the public payload of the message, in seconds since the
+    /// Unix epoch
+    ///
+    /// Returns `Ok(None)` if the message carries no public payload, and an error if the public
+    /// payload is not a [`TimestampedPublicPayload`].
+    #[cfg(feature = "timestamped-payloads")]
+    pub fn decode_timestamp(&self) -> crate::Result<Option<u64>> {
+        self.public_payload()
+            .map(|payload| TimestampedPublicPayload::decode(payload).map(|payload| payload.timestamp_unix_secs))
+            .transpose()
+    }
+
+    /// Decompresses the masked payload of the message, as sent with
+    /// [`User::send_tagged_packet_compressed`](`crate::User::send_tagged_packet_compressed`)
+    ///
+    /// The masked payload is only decompressed if it starts with the magic number of a `zstd`
+    /// frame, and is returned as is otherwise. Returns `Ok(None)` if the message carries no masked
+    /// payload.
+    #[cfg(feature = "compressed-payloads")]
+    pub fn decompress_masked(&self) -> crate::Result<Option<Vec<u8>>> {
+        self.masked_payload().map(compressed_payload::decompress).transpose()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -236,12 +391,23 @@ pub enum MessageContent {
     TaggedPacket(TaggedPacket),
     Subscription(Subscription),
     Unsubscription(Unsubscription),
+    Heartbeat(Heartbeat),
+    StreamCatalog(StreamCatalog),
+    DirectMessage(DirectMessage),
+    StreamMetadata(StreamMetadata),
+    StreamMetadataUpdate(StreamMetadata),
+    ProofOfInclusion(ProofOfInclusion),
+    MultipartManifest(MultipartManifest),
+    AnnouncementAck(AnnouncementAck),
+    StreamLink(StreamLink),
     Orphan(Orphan),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Announcement {
     pub author_identifier: Identifier,
+    /// [`Address`] of the parent stream announcement, if the stream is a child stream
+    pub parent_address: Option<Address>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -292,6 +458,10 @@ pub struct TaggedPacket {
 pub struct Subscription {
     /// [`Identifier`] of the subscribing user
     pub subscriber_identifier: Identifier,
+    /// Whether the subscriber must never be granted write permissions
+    pub readonly: bool,
+    /// Seconds the subscription lasts for, if it is [ephemeral](`crate::User::subscribe_ephemeral`)
+    pub ttl_seconds: Option<u64>,
 }
 
 impl Subscription {
@@ -315,6 +485,82 @@ impl Unsubscription {
     }
 }
 
+/// Heartbeat [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Heartbeat {
+    /// [`Identifier`] of the publisher
+    pub publisher_identifier: Identifier,
+}
+
+/// Stream Catalog [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StreamCatalog {
+    /// [`Identifier`] of the publisher
+    pub publisher_identifier: Identifier,
+    /// [`Address`]es of the streams listed in the catalog
+    pub streams: Vec<Address>,
+}
+
+/// Direct [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DirectMessage {
+    /// [`Identifier`] of the sender
+    pub sender_identifier: Identifier,
+    /// [`Identifier`] of the recipient
+    pub recipient_identifier: Identifier,
+    /// The payload, decrypted by the recipient
+    pub payload: Vec<u8>,
+}
+
+/// Stream Metadata [`Message`], describing a stream in a human-readable way.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct StreamMetadata {
+    /// Name of the stream
+    pub name: String,
+    /// Description of the stream
+    pub description: String,
+    /// Version of the schema of the stream payloads
+    pub schema_version: u32,
+    /// Tags describing the stream
+    pub tags: Vec<String>,
+}
+
+/// Proof of Inclusion [`Message`], attesting that a target message is part of the stream.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProofOfInclusion {
+    /// [`Identifier`] of the publisher
+    pub publisher_identifier: Identifier,
+    /// [`Address`] of the target message
+    pub target_address: Address,
+    /// Digest of the [`Spongos`](spongos::Spongos) state of the target message
+    pub digest: [u8; 32],
+}
+
+/// Multipart Manifest [`Message`], listing the signed packets a document has been split into.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultipartManifest {
+    /// Number of parts of the document
+    pub total_parts: u32,
+    /// [`Address`]es of the parts, in the order they must be concatenated
+    pub part_addresses: Vec<Address>,
+}
+
+/// Announcement Ack [`Message`], confirming that a subscriber processed a keyload.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnnouncementAck {
+    /// [`Identifier`] of the subscriber
+    pub subscriber_identifier: Identifier,
+}
+
+/// Stream Link [`Message`], referencing another stream from a branch.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StreamLink {
+    /// [`Identifier`] of the publisher
+    pub publisher_identifier: Identifier,
+    /// [`Address`] of the remote stream
+    pub remote_stream_address: Address,
+}
+
 /// Orphan [`Message`].
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Orphan {
@@ -327,6 +573,7 @@ pub struct Orphan {
 impl From<announcement::Unwrap> for MessageContent {
     fn from(announce: announcement::Unwrap) -> Self {
         Self::Announcement(Announcement {
+            parent_address: announce.parent_address().copied(),
             author_identifier: announce.into_author_id(),
         })
     }
@@ -343,6 +590,8 @@ impl<'a> From<branch_announcement::Unwrap<'a>> for MessageContent {
 impl<'a> From<subscription::Unwrap<'a>> for MessageContent {
     fn from(subscription: subscription::Unwrap<'a>) -> Self {
         Self::Subscription(Subscription {
+            readonly: subscription.is_readonly(),
+            ttl_seconds: subscription.ttl_seconds(),
             subscriber_identifier: subscription.into_subscriber_identifier(),
         })
     }
@@ -385,3 +634,95 @@ impl<'a> From<unsubscription::Unwrap<'a>> for MessageContent {
         })
     }
 }
+
+impl<'a> From<heartbeat::Unwrap<'a>> for MessageContent {
+    fn from(heartbeat: heartbeat::Unwrap<'a>) -> Self {
+        Self::Heartbeat(Heartbeat {
+            publisher_identifier: heartbeat.into_publisher_identifier(),
+        })
+    }
+}
+
+impl From<stream_catalog::Unwrap> for MessageContent {
+    fn from(stream_catalog: stream_catalog::Unwrap) -> Self {
+        let (publisher_identifier, streams) = stream_catalog.into_parts();
+        Self::StreamCatalog(StreamCatalog {
+            publisher_identifier,
+            streams,
+        })
+    }
+}
+
+impl<'a> From<direct_message::Unwrap<'a>> for MessageContent {
+    fn from(direct_message: direct_message::Unwrap<'a>) -> Self {
+        let (sender_identifier, recipient_identifier, payload) = direct_message.into_parts();
+        Self::DirectMessage(DirectMessage {
+            sender_identifier,
+            recipient_identifier,
+            payload,
+        })
+    }
+}
+
+impl<'a> From<stream_metadata::Unwrap<'a>> for MessageContent {
+    fn from(stream_metadata: stream_metadata::Unwrap<'a>) -> Self {
+        let (_publisher_identifier, name, description, schema_version, tags) = stream_metadata.into_parts();
+        Self::StreamMetadata(StreamMetadata {
+            name,
+            description,
+            schema_version,
+            tags,
+        })
+    }
+}
+
+impl<'a> From<stream_metadata_update::Unwrap<'a>> for MessageContent {
+    fn from(stream_metadata_update: stream_metadata_update::Unwrap<'a>) -> Self {
+        let (_publisher_identifier, name, description, schema_version, tags) = stream_metadata_update.into_parts();
+        Self::StreamMetadataUpdate(StreamMetadata {
+            name,
+            description,
+            schema_version,
+            tags,
+        })
+    }
+}
+
+impl<'a> From<proof_of_inclusion::Unwrap<'a>> for MessageContent {
+    fn from(proof_of_inclusion: proof_of_inclusion::Unwrap<'a>) -> Self {
+        let (publisher_identifier, target_address, digest) = proof_of_inclusion.into_parts();
+        Self::ProofOfInclusion(ProofOfInclusion {
+            publisher_identifier,
+            target_address,
+            digest,
+        })
+    }
+}
+
+impl<'a> From<multipart_manifest::Unwrap<'a>> for MessageContent {
+    fn from(multipart_manifest: multipart_manifest::Unwrap<'a>) -> Self {
+        let (_publisher_identifier, total_parts, part_addresses) = multipart_manifest.into_parts();
+        Self::MultipartManifest(MultipartManifest {
+            total_parts,
+            part_addresses,
+        })
+    }
+}
+
+impl<'a> From<announcement_ack::Unwrap<'a>> for MessageContent {
+    fn from(announcement_ack: announcement_ack::Unwrap<'a>) -> Self {
+        Self::AnnouncementAck(AnnouncementAck {
+            subscriber_identifier: announcement_ack.into_subscriber_identifier(),
+        })
+    }
+}
+
+impl<'a> From<stream_link::Unwrap<'a>> for MessageContent {
+    fn from(stream_link: stream_link::Unwrap<'a>) -> Self {
+        let (publisher_identifier, remote_stream_address) = stream_link.into_parts();
+        Self::StreamLink(StreamLink {
+            publisher_identifier,
+            remote_stream_address,
+        })
+    }
+}
@@ -0,0 +1,47 @@
+use lets::{address::Address, id::Identifier, message::Topic};
+
+/// A record of a message processed by a [`User`](`crate::User`), kept for audit purposes
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct MessageLogEntry {
+    /// [`Topic`] of the branch the message was published to
+    pub topic: Topic,
+    /// [`Address`] of the message
+    pub address: Address,
+    /// Message type identifier found in the message header
+    pub message_type: u8,
+    /// [`Identifier`] of the publisher found in the message header
+    pub publisher: Identifier,
+    /// Cursor of the publisher when the message was published
+    pub cursor: usize,
+    /// Position of the message among all the messages processed by the user, across branches
+    pub processed_at_cursor: usize,
+}
+
+impl MessageLogEntry {
+    /// Creates a new [`MessageLogEntry`]
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch of the message
+    /// * `address`: The [`Address`] of the message
+    /// * `message_type`: The message type identifier from the header
+    /// * `publisher`: The [`Identifier`] of the publisher from the header
+    /// * `cursor`: The cursor of the publisher from the header
+    /// * `processed_at_cursor`: The number of messages processed by the user before this one
+    pub(crate) fn new(
+        topic: Topic,
+        address: Address,
+        message_type: u8,
+        publisher: Identifier,
+        cursor: usize,
+        processed_at_cursor: usize,
+    ) -> Self {
+        Self {
+            topic,
+            address,
+            message_type,
+            publisher,
+            cursor,
+            processed_at_cursor,
+        }
+    }
+}
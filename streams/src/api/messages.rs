@@ -24,6 +24,7 @@ use lets::{
 
 // Local
 use crate::api::{
+    identity_marker::HasIdentity,
     message::{Message, MessageContent, Orphan},
     selector::Selector,
     user::User,
@@ -125,20 +126,20 @@ use crate::api::{
 /// suggested that, when suitable, use the methods in [`futures::TryStreamExt`] to make the
 /// error-handling much more ergonomic (with the use of `?`) and shortcircuit the
 /// [`futures::Stream`] on the first error.
-pub struct Messages<'a, T>(PinBoxFut<'a, (MessagesState<'a, T>, Option<Result<Message>>)>);
+pub struct Messages<'a, T, I = HasIdentity>(PinBoxFut<'a, (MessagesState<'a, T, I>, Option<Result<Message>>)>);
 
 type PinBoxFut<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
 
-struct MessagesState<'a, T> {
-    user: &'a mut User<T>,
+struct MessagesState<'a, T, I = HasIdentity> {
+    user: &'a mut User<T, I>,
     ids_stack: Vec<(Topic, Permissioned<Identifier>, usize)>,
     msg_queue: HashMap<MsgId, VecDeque<(MsgId, TransportMessage)>>,
     stage: VecDeque<(MsgId, TransportMessage)>,
     successful_round: bool,
 }
 
-impl<'a, T> MessagesState<'a, T> {
-    fn new(user: &'a mut User<T>) -> Self {
+impl<'a, T, I> MessagesState<'a, T, I> {
+    fn new(user: &'a mut User<T, I>) -> Self {
         Self {
             user,
             ids_stack: Vec::new(),
@@ -206,10 +207,10 @@ impl<'a, T> MessagesState<'a, T> {
                 None => {
                     // new round
                     self.successful_round = false;
-                    self.ids_stack = self
-                        .user
+                    let user = &*self.user;
+                    self.ids_stack = user
                         .cursors()
-                        .filter(|(_, p, _)| !p.is_readonly())
+                        .filter(|(t, p, _)| !p.is_readonly() && user.follows_topic(t))
                         .map(|(t, p, c)| (t.clone(), p.clone(), c))
                         .collect();
                     self.ids_stack.pop()?
@@ -219,7 +220,7 @@ impl<'a, T> MessagesState<'a, T> {
             let rel_address = MsgId::gen(base_address, publisher.identifier(), &topic, cursor + 1);
             let address = Address::new(base_address, rel_address);
 
-            match self.user.transport_mut().recv_message(address).await {
+            match self.user.configured_transport().recv_message(address).await {
                 Ok(msg) => {
                     self.stage.push_back((address.relative(), msg));
                     self.successful_round = true;
@@ -243,11 +244,11 @@ impl<'a, T> MessagesState<'a, T> {
     }
 }
 
-impl<'a, T> Messages<'a, T>
+impl<'a, T, I> Messages<'a, T, I>
 where
     T: for<'b> Transport<'b, Msg = TransportMessage>,
 {
-    pub(crate) fn new(user: &'a mut User<T>) -> Self {
+    pub(crate) fn new(user: &'a mut User<T, I>) -> Self {
         let mut state = MessagesState::new(user);
         Self(Box::pin(async move {
             let r = state.next().await;
@@ -325,18 +326,31 @@ where
             })
             .try_filter_map(future::ok)
     }
+
+    /// Converts the stream into a blocking [`SyncMessages`] iterator, driving it on the runtime of
+    /// the provided handle.
+    ///
+    /// # Arguments
+    /// * `rt`: The handle of the tokio runtime the stream is driven on
+    #[cfg(feature = "blocking")]
+    pub fn into_sync(self, rt: &tokio::runtime::Handle) -> SyncMessages<'a, T, I> {
+        SyncMessages {
+            messages: self,
+            rt: rt.clone(),
+        }
+    }
 }
 
-impl<'a, T> From<&'a mut User<T>> for Messages<'a, T>
+impl<'a, T, I> From<&'a mut User<T, I>> for Messages<'a, T, I>
 where
     T: for<'b> Transport<'b, Msg = TransportMessage>,
 {
-    fn from(user: &'a mut User<T>) -> Self {
+    fn from(user: &'a mut User<T, I>) -> Self {
         Self::new(user)
     }
 }
 
-impl<'a, T> Stream for Messages<'a, T>
+impl<'a, T, I> Stream for Messages<'a, T, I>
 where
     T: for<'b> Transport<'b, Msg = TransportMessage>,
 {
@@ -356,6 +370,35 @@ where
     }
 }
 
+/// A blocking [`Iterator`] over the messages of the channel, created with
+/// [`Messages::into_sync()`]
+///
+/// Each call to [`Iterator::next()`] blocks the current thread on the wrapped runtime until the next
+/// message of the [`Messages`] stream is available. Blocking is not allowed from within an
+/// asynchronous context: in that case the iterator yields an `Err` instead of panicking.
+#[cfg(feature = "blocking")]
+pub struct SyncMessages<'a, T, I = HasIdentity> {
+    messages: Messages<'a, T, I>,
+    rt: tokio::runtime::Handle,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a, T, I> Iterator for SyncMessages<'a, T, I>
+where
+    T: for<'b> Transport<'b, Msg = TransportMessage>,
+{
+    type Item = Result<Message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return Some(Err(anyhow::anyhow!(
+                "SyncMessages cannot block on the runtime from within an asynchronous context"
+            )));
+        }
+        self.rt.block_on(self.messages.next())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::rc::Rc;
@@ -435,6 +478,50 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn sync_messages_yield_all_messages_in_a_synchronous_thread() {
+        extern crate std;
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let handle = rt.handle().clone();
+        let messages = std::thread::spawn(move || {
+            let (mut author, mut subscriber, _announcement_link, _transport) =
+                handle.block_on(author_subscriber_fixture()).unwrap();
+            let sent = handle
+                .block_on(async {
+                    let keyload = author.send_keyload_for_all_rw("BASE_BRANCH").await?;
+                    let packet_1 = author.send_signed_packet("BASE_BRANCH", b"public", b"masked").await?;
+                    let packet_2 = author.send_signed_packet("BASE_BRANCH", b"public", b"masked").await?;
+                    Result::Ok([keyload.address(), packet_1.address(), packet_2.address()])
+                })
+                .unwrap();
+            let received = subscriber
+                .messages()
+                .into_sync(&handle)
+                .map(|msg| msg.map(|msg| msg.address()))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .unwrap();
+            (sent, received)
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(messages.0.as_slice(), messages.1.as_slice());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[tokio::test]
+    async fn sync_messages_return_error_within_async_context() -> Result<()> {
+        let (mut author, mut subscriber, _announcement_link, _transport) = author_subscriber_fixture().await?;
+        author.send_keyload_for_all_rw("BASE_BRANCH").await?;
+
+        let handle = tokio::runtime::Handle::current();
+        let mut messages = subscriber.messages().into_sync(&handle);
+        assert!(matches!(messages.next(), Some(Err(_))));
+        Ok(())
+    }
+
     /// Prepare a simple scenario with an author, a subscriber, a channel announcement and a bucket
     /// transport
     async fn author_subscriber_fixture() -> Result<(User<Transport>, User<Transport>, Address, Transport)> {
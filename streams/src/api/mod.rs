@@ -1,17 +1,66 @@
+/// Batches of Messages Sent Together
+pub(crate) mod batch;
+/// Branch Chain Integrity Audits
+pub(crate) mod branch_audit;
+/// Semantic Permission Groups of Subscribers
+pub(crate) mod capability;
+/// Compressed Masked Payloads
+#[cfg(feature = "compressed-payloads")]
+pub(crate) mod compressed_payload;
+/// CSV Encoding of the Cursor Store
+#[cfg(feature = "cursor-csv")]
+pub(crate) mod cursor_csv;
+/// Cursor Differences Between Users
+pub(crate) mod cursor_diff;
 /// Identifier Key storage. Used for keeping track of channel state
-mod cursor_store;
+pub(crate) mod cursor_store;
+/// Time-Bounded Subscriptions
+pub(crate) mod ephemeral_subscription;
+
+/// Identity Markers for Users and User Builders
+pub(crate) mod identity_marker;
+/// Invite Links for Out-of-Band Subscriber Onboarding
+#[cfg(feature = "invite-links")]
+pub(crate) mod invite;
 
 /// Unwrapped Message Types
 pub mod message;
 /// Message builder for sending payloads
 pub mod message_builder;
+/// Audit Log Entries of Processed Messages
+pub(crate) mod message_log;
 /// Message Retrieval
 pub mod messages;
+/// Users Set Aside Between Operations
+pub(crate) mod paused_user;
+/// Schema Validation of Sent Payloads
+pub(crate) mod schema;
 /// Message Retrieval Filter Selector
 pub(crate) mod selector;
+/// Hooks Called Around the Dispatch of Sent Messages
+pub(crate) mod send_hook;
 /// Message Wrapper for Sent Messages
 pub(crate) mod send_response;
+/// Spongos Storage Retention Policies
+pub(crate) mod spongos_retention;
+/// Detailed Differences Between User States
+pub(crate) mod stream_diff;
+/// Status Reports of the User State
+pub(crate) mod stream_health;
+/// Timestamped Public Payloads
+#[cfg(feature = "timestamped-payloads")]
+pub(crate) mod timestamped_payload;
+/// Timeouts and Retries of Transport Requests
+#[cfg(feature = "transport-options")]
+pub(crate) mod transport_options;
 /// User Client
 pub mod user;
 /// User Client Builder
 pub mod user_builder;
+/// Verification Result for Raw Messages
+pub(crate) mod verified_message;
+/// Approval Gates of Sent Payloads
+pub(crate) mod verify;
+/// Background Watchers of Branches
+#[cfg(feature = "watch-branch")]
+pub(crate) mod watch;
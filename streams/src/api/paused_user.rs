@@ -0,0 +1,76 @@
+// Rust
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+// Local
+use crate::{
+    api::{
+        identity_marker::{HasIdentity, IdentityMarker},
+        send_hook::SendHooks,
+        user::User,
+    },
+    error::Result,
+};
+
+/// A [`User`] set aside between operations with [`User::pause`], holding its serialized state and
+/// its transport client until it is [resumed](`PausedUser::resume`).
+///
+/// Unlike a [backup](`User::backup`), the serialized state is neither encrypted nor authenticated,
+/// so it must only be persisted to trusted local storage.
+pub struct PausedUser<T, I = HasIdentity> {
+    /// Unencrypted serialization of the [`User`] state
+    state_bytes: Vec<u8>,
+    /// Transport client of the [`User`]
+    transport: T,
+    /// Hooks called around the dispatch of the messages sent by the [`User`]
+    send_hooks: SendHooks<T>,
+    /// Marker of the [`Identity`](`lets::id::Identity`) availability of the [`User`]
+    identity_marker: PhantomData<I>,
+}
+
+impl<T, I> PausedUser<T, I> {
+    /// Creates a new [`PausedUser`] from the parts of a paused [`User`]
+    ///
+    /// # Arguments
+    /// * `state_bytes`: The unencrypted serialization of the [`User`] state
+    /// * `transport`: The transport client of the [`User`]
+    /// * `send_hooks`: The hooks called around the dispatch of the messages sent by the [`User`]
+    pub(crate) fn new(state_bytes: Vec<u8>, transport: T, send_hooks: SendHooks<T>) -> Self {
+        Self {
+            state_bytes,
+            transport,
+            send_hooks,
+            identity_marker: PhantomData,
+        }
+    }
+
+    /// Creates a [`PausedUser`] from state bytes persisted with [`PausedUser::state_bytes`] and a
+    /// transport client, to resume a [`User`] paused by another process.
+    ///
+    /// # Arguments
+    /// * `state_bytes`: The unencrypted serialization of the [`User`] state
+    /// * `transport`: The transport client for sending and receiving messages
+    pub fn from_state_bytes(state_bytes: Vec<u8>, transport: T) -> Self {
+        Self::new(state_bytes, transport, SendHooks::default())
+    }
+
+    /// Returns the unencrypted serialization of the [`User`] state, for quick persistence
+    pub fn state_bytes(&self) -> &[u8] {
+        &self.state_bytes
+    }
+
+    /// Returns a reference to the transport client of the [`User`]
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Reconstructs the [`User`] from its state bytes and its transport client. Errors if the state
+    /// bytes cannot be deserialized, or if the [`User`] is marked with [`HasIdentity`] but its
+    /// state contains no [`Identity`](`lets::id::Identity`).
+    pub async fn resume(self) -> Result<User<T, I>>
+    where
+        I: IdentityMarker,
+    {
+        User::from_state_bytes(&self.state_bytes, self.transport, self.send_hooks).await
+    }
+}
@@ -0,0 +1,64 @@
+// Rust
+#[cfg(feature = "json-schema")]
+use alloc::{format, string::ToString, vec::Vec};
+
+// Local
+#[cfg(feature = "json-schema")]
+use crate::error::Error;
+use crate::error::Result;
+
+/// Schema of the payloads sent with
+/// [`User::send_signed_packet_with_schema`](`crate::User::send_signed_packet_with_schema`), checked
+/// before the message is constructed
+pub trait SchemaValidator {
+    /// Accepts or rejects a payload. Returning an `Err` aborts the send.
+    ///
+    /// # Arguments
+    /// * `payload`: The unmasked payload about to be sent
+    fn validate(&self, payload: &[u8]) -> Result<()>;
+}
+
+/// A [`SchemaValidator`] implementation accepting every payload
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct NoopValidator;
+
+impl SchemaValidator for NoopValidator {
+    fn validate(&self, _payload: &[u8]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A [`SchemaValidator`] implementation accepting the JSON payloads that match a
+/// [JSON Schema](https://json-schema.org/)
+#[cfg(feature = "json-schema")]
+#[derive(Clone, Debug)]
+pub struct JsonSchemaValidator {
+    /// The compiled JSON Schema
+    schema: jsonschema::JSONSchema,
+}
+
+#[cfg(feature = "json-schema")]
+impl JsonSchemaValidator {
+    /// Creates a new [`JsonSchemaValidator`], compiling the JSON Schema once for all the payloads
+    /// it validates. Errors if the schema is not valid.
+    ///
+    /// # Arguments
+    /// * `schema`: The JSON Schema payloads must match
+    pub fn new(schema: &serde_json::Value) -> Result<Self> {
+        let schema = jsonschema::JSONSchema::compile(schema)
+            .map_err(|e| Error::SchemaValidationFailed(format!("invalid JSON schema: {}", e)))?;
+        Ok(Self { schema })
+    }
+}
+
+#[cfg(feature = "json-schema")]
+impl SchemaValidator for JsonSchemaValidator {
+    fn validate(&self, payload: &[u8]) -> Result<()> {
+        let payload: serde_json::Value = serde_json::from_slice(payload)
+            .map_err(|e| Error::SchemaValidationFailed(format!("payload is not valid JSON: {}", e)))?;
+        self.schema.validate(&payload).map_err(|errors| {
+            let errors: Vec<_> = errors.map(|e| e.to_string()).collect();
+            Error::SchemaValidationFailed(errors.join("; "))
+        })
+    }
+}
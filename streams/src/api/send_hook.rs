@@ -0,0 +1,142 @@
+// Rust
+use alloc::sync::Arc;
+use core::fmt::{Debug, Formatter, Result as FormatResult};
+
+// Streams
+use lets::{address::Address, message::TransportMessage, transport::Transport};
+
+/// Hook called with the [`Address`] and the wire-format [`TransportMessage`] of every message a
+/// [`User`](`crate::User`) is about to send, before it is dispatched to the transport
+pub type PreSendHook = Arc<dyn Fn(&Address, &TransportMessage) + Send + Sync>;
+
+/// Hook called with the [`Address`], the wire-format [`TransportMessage`] and the transport
+/// response of every message a [`User`](`crate::User`) has sent
+pub type PostSendHook<TSR> = Arc<dyn Fn(&Address, &TransportMessage, &TSR) + Send + Sync>;
+
+/// Post-send hook typed by the responses of the transport `T` it was configured for
+trait PostSend<T>: Send + Sync {
+    /// Calls the hook
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message was sent to
+    /// * `msg`: The wire-format message
+    /// * `response`: The response of the transport
+    fn call(&self, address: &Address, msg: &TransportMessage, response: &<T as Transport<'static>>::SendResponse)
+    where
+        T: Transport<'static>;
+}
+
+impl<T, TSR> PostSend<T> for PostSendHook<TSR>
+where
+    T: for<'a> Transport<'a, SendResponse = TSR>,
+{
+    fn call(&self, address: &Address, msg: &TransportMessage, response: &<T as Transport<'static>>::SendResponse)
+    where
+        T: Transport<'static>,
+    {
+        self(address, msg, response)
+    }
+}
+
+/// Hooks called by a [`User`](`crate::User`) around the dispatch of its messages to the transport
+/// `T`, as configured with [`UserBuilder::with_pre_send_hook`](`crate::UserBuilder::with_pre_send_hook`)
+/// and [`UserBuilder::with_post_send_hook`](`crate::UserBuilder::with_post_send_hook`)
+pub struct SendHooks<T> {
+    /// Hook called before a message is dispatched
+    pre_send: Option<PreSendHook>,
+    /// Hook called after a message is dispatched
+    post_send: Option<Arc<dyn PostSend<T>>>,
+}
+
+impl<T> SendHooks<T> {
+    /// Sets the hook called before a message is dispatched
+    ///
+    /// # Arguments
+    /// * `hook`: The hook to call
+    pub(crate) fn set_pre_send(&mut self, hook: PreSendHook) {
+        self.pre_send = Some(hook);
+    }
+
+    /// Sets the hook called after a message is dispatched with the responses of the transport
+    ///
+    /// # Arguments
+    /// * `hook`: The hook to call
+    pub(crate) fn set_post_send<TSR>(&mut self, hook: PostSendHook<TSR>)
+    where
+        T: for<'a> Transport<'a, SendResponse = TSR>,
+        TSR: 'static,
+    {
+        self.post_send = Some(Arc::new(hook));
+    }
+
+    /// Returns the hooks to use with a different transport. The post-send hook is typed by the
+    /// responses of the current transport, so only the pre-send hook is kept.
+    pub(crate) fn into_transport<T2>(self) -> SendHooks<T2> {
+        SendHooks {
+            pre_send: self.pre_send,
+            post_send: None,
+        }
+    }
+
+    /// Returns true if a hook is called after a message is dispatched
+    pub(crate) fn has_post_send(&self) -> bool {
+        self.post_send.is_some()
+    }
+
+    /// Calls the pre-send hook, if any
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message is sent to
+    /// * `msg`: The wire-format message
+    pub(crate) fn before_send(&self, address: &Address, msg: &TransportMessage) {
+        if let Some(hook) = &self.pre_send {
+            hook(address, msg)
+        }
+    }
+
+    /// Calls the post-send hook, if any
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message was sent to
+    /// * `msg`: The wire-format message
+    /// * `response`: The response of the transport
+    pub(crate) fn after_send(
+        &self,
+        address: &Address,
+        msg: &TransportMessage,
+        response: &<T as Transport<'static>>::SendResponse,
+    ) where
+        T: Transport<'static>,
+    {
+        if let Some(hook) = &self.post_send {
+            hook.call(address, msg, response)
+        }
+    }
+}
+
+impl<T> Clone for SendHooks<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pre_send: self.pre_send.clone(),
+            post_send: self.post_send.clone(),
+        }
+    }
+}
+
+impl<T> Default for SendHooks<T> {
+    fn default() -> Self {
+        Self {
+            pre_send: None,
+            post_send: None,
+        }
+    }
+}
+
+impl<T> Debug for SendHooks<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.debug_struct("SendHooks")
+            .field("pre_send", &self.pre_send.is_some())
+            .field("post_send", &self.post_send.is_some())
+            .finish()
+    }
+}
@@ -1,4 +1,12 @@
-use lets::address::Address;
+// Rust
+use alloc::sync::Arc;
+use core::fmt::{Debug, Formatter, Result as FormatResult};
+
+// 3rd-party
+use hashbrown::HashMap;
+
+// Streams
+use lets::{address::Address, message::Topic, transport::Transport};
 
 /// A wrapper for a sent message
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
@@ -37,3 +45,125 @@ impl<TSR> SendResponse<TSR> {
         self.transport_response
     }
 }
+
+/// A record of a Signed Packet sent with
+/// [`User::send_signed_packet_idempotent`](`crate::User::send_signed_packet_idempotent`), used to
+/// answer repeated sends with the same idempotency key
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub(crate) struct IdempotentSend {
+    /// Idempotency key chosen by the sender
+    pub(crate) key: [u8; 32],
+    /// [`Topic`] of the branch the packet was sent to
+    pub(crate) topic: Topic,
+    /// Digest of the public and masked payloads of the packet
+    pub(crate) payload_digest: [u8; 32],
+    /// [`Address`] of the packet
+    pub(crate) address: Address,
+}
+
+impl IdempotentSend {
+    /// Creates a new [`IdempotentSend`]
+    ///
+    /// # Arguments
+    /// * `key`: The idempotency key chosen by the sender
+    /// * `topic`: The [`Topic`] of the branch the packet was sent to
+    /// * `payload_digest`: The digest of the payloads of the packet
+    /// * `address`: The [`Address`] of the packet
+    pub(crate) fn new(key: [u8; 32], topic: Topic, payload_digest: [u8; 32], address: Address) -> Self {
+        Self {
+            key,
+            topic,
+            payload_digest,
+            address,
+        }
+    }
+}
+
+/// Transport send response typed by the transport `T` it was returned by
+trait TransportResponse<T>: Send + Sync {
+    /// Returns a copy of the response
+    fn response(&self) -> <T as Transport<'static>>::SendResponse
+    where
+        T: Transport<'static>;
+}
+
+impl<T, TSR> TransportResponse<T> for TSR
+where
+    T: for<'a> Transport<'a, SendResponse = TSR>,
+    TSR: Clone + Send + Sync,
+{
+    fn response(&self) -> <T as Transport<'static>>::SendResponse
+    where
+        T: Transport<'static>,
+    {
+        self.clone()
+    }
+}
+
+/// Transport responses of the Signed Packets a [`User`](`crate::User`) sent through the transport
+/// `T` with idempotency keys, returned when a packet is sent again with the same key. Responses are
+/// not included in backups.
+pub(crate) struct IdempotentResponses<T> {
+    responses: HashMap<[u8; 32], Arc<dyn TransportResponse<T>>>,
+}
+
+impl<T> IdempotentResponses<T> {
+    /// Returns the [`SendResponse`] of the packet sent with an idempotency key, if its transport
+    /// response is held
+    ///
+    /// # Arguments
+    /// * `sent`: The record of the packet sent with the key
+    pub(crate) fn get<TSR>(&self, sent: &IdempotentSend) -> Option<SendResponse<TSR>>
+    where
+        T: for<'a> Transport<'a, SendResponse = TSR>,
+    {
+        self.responses
+            .get(&sent.key)
+            .map(|response| SendResponse::new(sent.address, response.response()))
+    }
+
+    /// Stores the transport response of the packet sent with an idempotency key
+    ///
+    /// # Arguments
+    /// * `key`: The idempotency key of the packet
+    /// * `response`: The response of the transport
+    pub(crate) fn insert<TSR>(&mut self, key: [u8; 32], response: TSR)
+    where
+        T: for<'a> Transport<'a, SendResponse = TSR>,
+        TSR: Clone + Send + Sync + 'static,
+    {
+        self.responses.insert(key, Arc::new(response));
+    }
+
+    /// Drops the transport response of the packet sent with an idempotency key
+    ///
+    /// # Arguments
+    /// * `key`: The idempotency key of the packet
+    pub(crate) fn remove(&mut self, key: &[u8; 32]) {
+        self.responses.remove(key);
+    }
+}
+
+impl<T> Clone for IdempotentResponses<T> {
+    fn clone(&self) -> Self {
+        Self {
+            responses: self.responses.clone(),
+        }
+    }
+}
+
+impl<T> Default for IdempotentResponses<T> {
+    fn default() -> Self {
+        Self {
+            responses: HashMap::new(),
+        }
+    }
+}
+
+impl<T> Debug for IdempotentResponses<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.debug_struct("IdempotentResponses")
+            .field("responses", &self.responses.len())
+            .finish()
+    }
+}
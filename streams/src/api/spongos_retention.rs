@@ -0,0 +1,35 @@
+/// Policy deciding which [`Spongos`](spongos::Spongos) states a [`User`](`crate::User`) keeps in
+/// store once they have been linked to by a newer message.
+///
+/// The state of the stream announcement is always kept, regardless of the policy.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum SpongosRetention {
+    /// Keep every state processed by the user
+    All,
+    /// Only keep the state of the latest message of each branch, removing the state of a message as
+    /// soon as a newer message links to it
+    Lean,
+    /// Keep the states of the last `n` messages linked to a previous one, removing the oldest state
+    /// when the limit is exceeded
+    LastN(usize),
+}
+
+impl Default for SpongosRetention {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SpongosRetention {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::All => serializer.serialize_unit_variant("SpongosRetention", 0, "All"),
+            Self::Lean => serializer.serialize_unit_variant("SpongosRetention", 1, "Lean"),
+            Self::LastN(n) => serializer.serialize_newtype_variant("SpongosRetention", 2, "LastN", n),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use alloc::vec::Vec;
+
+use lets::{id::Identifier, message::Topic};
+
+use crate::api::cursor_diff::CursorDiff;
+
+/// Detailed comparison of the states of two [`User`](`crate::User`)s, as returned by
+/// [`User::stream_diff`](`crate::User::stream_diff`)
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Default)]
+pub struct StreamDiff {
+    /// Branch [topics](`Topic`) known by the user compared against, but not by the user the
+    /// comparison is made from, sorted
+    pub missing_topics_in_self: Vec<Topic>,
+    /// Branch [topics](`Topic`) known by the user the comparison is made from, but not by the user
+    /// compared against, sorted
+    pub missing_topics_in_other: Vec<Topic>,
+    /// Publisher cursors that differ between both users, see
+    /// [`User::cursor_diff`](`crate::User::cursor_diff`)
+    pub cursor_diffs: Vec<CursorDiff>,
+    /// Subscribers only known by the user the comparison is made from, sorted
+    pub extra_subscribers_in_self: Vec<Identifier>,
+    /// Subscribers only known by the user compared against, sorted
+    pub extra_subscribers_in_other: Vec<Identifier>,
+    /// Number of messages whose [`Spongos`](spongos::Spongos) state is stored by the user the
+    /// comparison is made from, minus the number stored by the user compared against. States
+    /// deduplicated into a canonical copy are counted once per message.
+    pub spongos_count_diff: i64,
+    /// Number of pre shared keys stored by the user the comparison is made from, minus the number
+    /// stored by the user compared against
+    pub psk_count_diff: i64,
+}
+
+impl StreamDiff {
+    /// Returns true if no difference was found between both users
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -0,0 +1,71 @@
+// Rust
+use core::fmt::{self, Display, Formatter};
+
+// Streams
+use lets::address::Address;
+
+// Local
+use crate::api::spongos_retention::SpongosRetention;
+
+/// Status report of the state of a [`User`](`crate::User`), as returned by
+/// [`User::stream_health`](`crate::User::stream_health`)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct StreamHealth {
+    /// Whether the user has created or connected to a stream
+    pub is_connected: bool,
+    /// [`Address`] of the stream announcement, if any
+    pub stream_address: Option<Address>,
+    /// Number of known branches
+    pub branch_count: usize,
+    /// Number of known subscribers
+    pub subscriber_count: usize,
+    /// Number of publisher cursors tracked across all branches
+    pub total_cursors: usize,
+    /// Number of messages processed since the oldest processed message whose
+    /// [`Spongos`](spongos::Spongos) state is still held in store, if any
+    pub oldest_spongos_age: Option<usize>,
+    /// Retention policy of the [`Spongos`](spongos::Spongos) states
+    pub lean_mode: SpongosRetention,
+    /// Number of messages waiting for their linked message to be processed
+    pub pending_orphans: usize,
+}
+
+impl Display for StreamHealth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<20} | {}", "connected", self.is_connected)?;
+        match &self.stream_address {
+            Some(stream_address) => writeln!(f, "{:<20} | {}", "stream address", stream_address)?,
+            None => writeln!(f, "{:<20} | -", "stream address")?,
+        }
+        writeln!(f, "{:<20} | {}", "branches", self.branch_count)?;
+        writeln!(f, "{:<20} | {}", "subscribers", self.subscriber_count)?;
+        writeln!(f, "{:<20} | {}", "cursors", self.total_cursors)?;
+        match self.oldest_spongos_age {
+            Some(age) => writeln!(f, "{:<20} | {}", "oldest spongos age", age)?,
+            None => writeln!(f, "{:<20} | -", "oldest spongos age")?,
+        }
+        writeln!(f, "{:<20} | {:?}", "spongos retention", self.lean_mode)?;
+        writeln!(f, "{:<20} | {}", "pending orphans", self.pending_orphans)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for StreamHealth {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut health = serializer.serialize_struct("StreamHealth", 8)?;
+        health.serialize_field("is_connected", &self.is_connected)?;
+        health.serialize_field("stream_address", &self.stream_address)?;
+        health.serialize_field("branch_count", &self.branch_count)?;
+        health.serialize_field("subscriber_count", &self.subscriber_count)?;
+        health.serialize_field("total_cursors", &self.total_cursors)?;
+        health.serialize_field("oldest_spongos_age", &self.oldest_spongos_age)?;
+        health.serialize_field("lean_mode", &self.lean_mode)?;
+        health.serialize_field("pending_orphans", &self.pending_orphans)?;
+        health.end()
+    }
+}
@@ -0,0 +1,52 @@
+// Rust
+use alloc::vec::Vec;
+
+// 3rd-party
+use serde::{Deserialize, Serialize};
+
+// Local
+use crate::error::{Error, Result};
+
+/// Public payload of a packet carrying the time it was sent at, as published by
+/// [`User::send_signed_packet_with_timestamp`](`crate::User::send_signed_packet_with_timestamp`)
+///
+/// The payload is serialized with `bincode`, and as it is part of the signed content of the
+/// packet, the timestamp is authenticated by the publisher signature.
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
+pub struct TimestampedPublicPayload {
+    /// Seconds elapsed since the Unix epoch when the packet was sent
+    pub timestamp_unix_secs: u64,
+    /// Application data of the public payload
+    pub data: Vec<u8>,
+}
+
+impl TimestampedPublicPayload {
+    /// Creates a new [`TimestampedPublicPayload`] stamped with the current system time
+    ///
+    /// # Arguments
+    /// * `data`: The application data of the public payload
+    pub(crate) fn now(data: Vec<u8>) -> Result<Self> {
+        extern crate std;
+        let timestamp_unix_secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(Error::SystemTime)?
+            .as_secs();
+        Ok(Self {
+            timestamp_unix_secs,
+            data,
+        })
+    }
+
+    /// Serializes the [`TimestampedPublicPayload`] into the bytes of a public payload
+    pub(crate) fn encode(&self) -> Result<Vec<u8>> {
+        bincode::serialize(self).map_err(|e| Error::TimestampedPayload("serialize", e))
+    }
+
+    /// Deserializes a [`TimestampedPublicPayload`] from the bytes of a public payload
+    ///
+    /// # Arguments
+    /// * `public_payload`: The public payload of a packet
+    pub fn decode(public_payload: &[u8]) -> Result<Self> {
+        bincode::deserialize(public_payload).map_err(|e| Error::TimestampedPayload("deserialize", e))
+    }
+}
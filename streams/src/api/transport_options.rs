@@ -0,0 +1,119 @@
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+use core::time::Duration;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// Streams
+use lets::{
+    address::Address,
+    error::Result as LetsResult,
+    transport::{
+        retry::{RetryConfig, RetryTransport},
+        timeout::TimeoutTransport,
+        Transport,
+    },
+};
+
+/// Request-level settings applied by a [`User`](`crate::User`) to every request it makes to its
+/// transport, as set with [`User::set_transport_options`](`crate::User::set_transport_options`)
+#[derive(Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct TransportOptions {
+    /// Maximum time a request may take before failing with
+    /// [`Error::Timeout`](`crate::Error::Timeout`). Applies to each attempt of a retried request.
+    pub timeout: Option<Duration>,
+    /// Retry policy of the receive requests failing for reasons other than a missing message. Send
+    /// requests are never repeated, as a failed send may still have been delivered.
+    pub retry: Option<RetryConfig>,
+    /// Whether every request is logged as a `tracing` event. Requires the `tracing` feature.
+    pub log_requests: bool,
+}
+
+/// [`Transport`] applying [`TransportOptions`] to the requests made to the transport of a
+/// [`User`](`crate::User`), or to a copy of it, wrapping it in a [`TimeoutTransport`] and, for
+/// receive requests, a [`RetryTransport`] as configured for the duration of each request
+#[derive(Clone)]
+pub(crate) struct ConfiguredTransport<T> {
+    /// Transport of the user, or a copy of it
+    inner: T,
+    /// Options applied to the requests
+    options: TransportOptions,
+}
+
+impl<T> ConfiguredTransport<T> {
+    /// Creates a new [`ConfiguredTransport`]
+    ///
+    /// # Arguments
+    /// * `inner`: The transport of the user, or a copy of it
+    /// * `options`: The options applied to the requests
+    pub(crate) fn new(inner: T, options: TransportOptions) -> Self {
+        Self { inner, options }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'b, T> Transport<'b> for ConfiguredTransport<T>
+where
+    T: Transport<'b>,
+    T::Msg: Clone,
+{
+    type Msg = T::Msg;
+    type SendResponse = T::SendResponse;
+
+    /// Send a message with the configured options.
+    async fn send_message(&mut self, address: Address, msg: T::Msg) -> LetsResult<T::SendResponse>
+    where
+        Self::Msg: 'async_trait,
+    {
+        #[cfg(feature = "tracing")]
+        if self.options.log_requests {
+            tracing::info!(%address, "sending message");
+        }
+        let inner = &mut self.inner;
+        match self.options.timeout {
+            Some(timeout) => TimeoutTransport::new(inner, timeout).send_message(address, msg).await,
+            None => inner.send_message(address, msg).await,
+        }
+    }
+
+    /// Send several messages, in order, with the configured options. The timeout applies to the
+    /// whole batch.
+    async fn send_messages_batch(&mut self, messages: Vec<(Address, T::Msg)>) -> LetsResult<Vec<T::SendResponse>>
+    where
+        Self::Msg: 'async_trait,
+    {
+        #[cfg(feature = "tracing")]
+        if self.options.log_requests {
+            tracing::info!(messages = messages.len(), "sending message batch");
+        }
+        let inner = &mut self.inner;
+        match self.options.timeout {
+            Some(timeout) => {
+                TimeoutTransport::new(inner, timeout)
+                    .send_messages_batch(messages)
+                    .await
+            }
+            None => inner.send_messages_batch(messages).await,
+        }
+    }
+
+    /// Receive messages with the configured options.
+    async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<T::Msg>> {
+        #[cfg(feature = "tracing")]
+        if self.options.log_requests {
+            tracing::info!(%address, "receiving messages");
+        }
+        let inner = &mut self.inner;
+        match (self.options.timeout, self.options.retry) {
+            (Some(timeout), Some(retry)) => {
+                RetryTransport::new(TimeoutTransport::new(inner, timeout), retry)
+                    .recv_messages(address)
+                    .await
+            }
+            (Some(timeout), None) => TimeoutTransport::new(inner, timeout).recv_messages(address).await,
+            (None, Some(retry)) => RetryTransport::new(inner, retry).recv_messages(address).await,
+            (None, None) => inner.recv_messages(address).await,
+        }
+    }
+}
@@ -1,24 +1,49 @@
 // Rust
+#[cfg(feature = "watch-branch")]
+use alloc::rc::Rc;
 use alloc::{
     borrow::ToOwned,
     boxed::Box,
+    collections::VecDeque,
     format,
     string::{String, ToString},
     vec::Vec,
 };
-use core::fmt::{Debug, Formatter, Result as FormatResult};
+#[cfg(feature = "watch-branch")]
+use core::cell::Cell;
+#[cfg(feature = "cursor-csv")]
+use core::convert::TryInto;
+#[cfg(any(feature = "wait-for-message", feature = "watch-branch"))]
+use core::time::Duration;
+use core::{
+    cmp::Ordering,
+    fmt::{Debug, Formatter, Result as FormatResult},
+    marker::PhantomData,
+    mem::size_of,
+};
+#[cfg(feature = "cursor-csv")]
+use std::io::Read;
+#[cfg(any(feature = "json-log", feature = "cursor-csv"))]
+use std::io::Write;
 
 // 3rd-party
 use async_trait::async_trait;
-use futures::{future, TryStreamExt};
-use hashbrown::{HashMap, HashSet};
+use futures::{future, stream, stream::FuturesUnordered, StreamExt, TryStreamExt};
+use hashbrown::{hash_map::Entry, HashMap, HashSet};
 use rand::{rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "watch-branch")]
+use tokio::sync::Notify;
+#[cfg(any(feature = "wait-for-message", feature = "watch-branch"))]
+use tokio::time::sleep;
+#[cfg(feature = "wait-for-message")]
+use tokio::time::Instant;
 
 // IOTA
 
 // Streams
 use lets::{
     address::{Address, AppAddr, MsgId},
+    error::Result as LetsResult,
     id::{Identifier, Identity, PermissionDuration, Permissioned, Psk, PskId},
     message::{
         ContentSizeof, ContentUnwrap, ContentWrap, Message as LetsMessage, PreparsedMessage, Topic, TopicHash,
@@ -30,31 +55,104 @@ use spongos::{
     ddml::{
         commands::{sizeof, unwrap, wrap, Absorb, Commit, Mask, Squeeze},
         modifiers::External,
-        types::{Mac, Maybe, NBytes, Size, Uint8},
+        types::{Bytes, Mac, Maybe, NBytes, Size, Uint64, Uint8},
     },
     error::{Error as SpongosError, Result as SpongosResult},
     KeccakF1600, Spongos, SpongosRng,
 };
 
 // Local
+#[cfg(feature = "cursor-csv")]
+use crate::api::cursor_csv;
+#[cfg(feature = "transport-options")]
+use crate::api::transport_options::{ConfiguredTransport, TransportOptions};
+#[cfg(feature = "watch-branch")]
+use crate::api::watch::WatchHandle;
 use crate::{
     api::{
-        cursor_store::CursorStore, message::Message, message_builder::MessageBuilder, messages::Messages,
-        send_response::SendResponse, user_builder::UserBuilder,
+        batch::{BatchItem, StagingTransport},
+        branch_audit::BranchAudit,
+        capability::Capability,
+        cursor_diff::CursorDiff,
+        cursor_store::CursorStore,
+        ephemeral_subscription::{ExpiringSubscriber, UnsubscribeToken},
+        identity_marker::{HasIdentity, IdentityMarker},
+        message::{Message, MessageContent, StreamMetadata},
+        message_builder::MessageBuilder,
+        message_log::MessageLogEntry,
+        messages::Messages,
+        paused_user::PausedUser,
+        schema::SchemaValidator,
+        send_hook::SendHooks,
+        send_response::{IdempotentResponses, IdempotentSend, SendResponse},
+        spongos_retention::SpongosRetention,
+        stream_diff::StreamDiff,
+        stream_health::StreamHealth,
+        user_builder::UserBuilder,
+        verified_message::VerifiedMessage,
+        verify::Verify,
     },
     message::{
-        announcement, branch_announcement, keyload, message_types, signed_packet, subscription, tagged_packet,
-        unsubscription,
+        announcement, announcement_ack, branch_announcement, direct_message, heartbeat, keyload, message_types,
+        multipart_manifest, proof_of_inclusion, signed_packet, stream_catalog, stream_link, stream_metadata,
+        stream_metadata_update, subscription, subscription_request, tagged_packet, unsubscription,
     },
-    Error, Result,
+    Error, PartialRekeyError, Result,
 };
 
+#[cfg(feature = "invite-links")]
+use crate::api::invite::InviteInfo;
+
 const ANN_MESSAGE_NUM: usize = 0; // Announcement is always the first message of authors
 const SUB_MESSAGE_NUM: usize = 0; // Subscription is always the first message of subscribers
 const INIT_MESSAGE_NUM: usize = 1; // First non-reserved message number
+const SUBSCRIPTION_REQUESTS_TOPIC: &str = "requests"; // Topic of the subscription requests sent to an author
+const SUBSCRIPTION_APPROVALS_TOPIC: &str = "approvals"; // Topic of the subscription approvals sent by an author
+const CLONED_BRANCH_MESSAGES: usize = 5; // Number of latest packets copied to a branch cloned from another
+const IDEMPOTENCY_KEYS_KEPT: usize = 256; // Number of latest idempotency keys kept to detect repeated sends
+#[cfg(feature = "watch-branch")]
+const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(1); // Default wait between polls of a watched branch
+
+/// Squeezes a digest out of a copy of the [`Spongos`] state of a message, bound to its link
+///
+/// # Arguments
+/// * `spongos`: The [`Spongos`] state of the message
+/// * `msg_address`: The link of the message
+fn link_digest(spongos: &Spongos, msg_address: MsgId) -> [u8; 32] {
+    let mut spongos = *spongos;
+    spongos.sponge(msg_address)
+}
+
+/// Digest of a [`Spongos`] state, used to find identical states held in store
+type SpongosDigest = [u8; 32];
+
+/// Squeezes a digest out of a copy of a [`Spongos`] state
+///
+/// # Arguments
+/// * `spongos`: The [`Spongos`] state to digest
+fn spongos_digest(spongos: &Spongos) -> SpongosDigest {
+    let mut spongos = *spongos;
+    spongos.squeeze()
+}
+
+/// Returns the current Unix time in seconds, or `None` if the system clock is unavailable or set
+/// before the Unix epoch
+fn unix_time_now() -> Option<u64> {
+    #[cfg(feature = "std")]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|elapsed| elapsed.as_secs())
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
+}
 
 /// The state of a user, mapping publisher cursors and link states for message processing.
-#[derive(PartialEq, Eq, Default)]
+#[derive(Clone, PartialEq, Eq, Default)]
 struct State {
     /// Users' [`Identity`] information, contains keys and logic for signing and verification.
     ///
@@ -89,22 +187,101 @@ struct State {
     base_branch: Topic,
 
     /// Users' [`Spongos`] Storage configuration. If lean, only the announcement message and latest
-    /// branch message spongos state is stored. This reduces the overall size of the user
-    /// implementation over time. If not lean, all spongos states processed by the user will be
+    /// branch message spongos state is stored. If limited to the last `n` messages, the oldest
+    /// states are removed once the limit is exceeded. This reduces the overall size of the user
+    /// implementation over time. Otherwise, all spongos states processed by the user will be
     /// stored.
-    lean: bool,
+    retention: SpongosRetention,
+
+    /// Links of the messages whose [`Spongos`] state is subject to removal, in insertion order.
+    /// Only tracked when retaining the last `n` states.
+    spongos_order: VecDeque<MsgId>,
 
     /// List of known branch topics.
     topics: HashSet<Topic>,
+
+    /// Queue of received messages whose linked message had not been processed yet, to be retried
+    /// with [`User::fetch_orphan_messages`].
+    orphan_store: Vec<(Address, TransportMessage)>,
+
+    /// Audit log of the messages processed by the user, in processing order.
+    message_log: Vec<MessageLogEntry>,
+
+    /// Mapping of the links ([`MsgId`]) whose [`Spongos`] state has been stored to the link of the
+    /// message they are linked to, if any, and the digest of the [`Spongos`] state when it was
+    /// stored. Used to audit the integrity of the chain of messages of a branch.
+    link_store: HashMap<MsgId, (Option<MsgId>, [u8; 32])>,
+
+    /// List of branch topics actively followed by the user. If empty, all branches are followed.
+    followed_topics: HashSet<Topic>,
+
+    /// Named groups of subscribers, targeted together by keyloads
+    groups: HashMap<String, Vec<Identifier>>,
+
+    /// Address of the announcement of the parent stream, if the stream is a child stream.
+    parent_address: Option<Address>,
+
+    /// Latest metadata describing the stream, as published by its admins.
+    stream_metadata: Option<StreamMetadata>,
+
+    /// List of Subscribed [Identifiers](`Identifier`) that must never be granted write permissions.
+    readonly_subscribers: HashSet<Identifier>,
+
+    /// Mapping of the links ([`MsgId`]) whose [`Spongos`] state has been removed from store as a
+    /// duplicate to the link of the identical state kept in store.
+    spongos_aliases: HashMap<MsgId, MsgId>,
+
+    /// Mapping of branch topics to the topic of the branch they were created from.
+    branch_parent: HashMap<Topic, Topic>,
+
+    /// Branch the `_default` send methods send to, if set.
+    default_topic: Option<Topic>,
+
+    /// Mapping of branch topics to the subscribers that confirmed, with an announcement ack, that
+    /// they processed a keyload of the branch.
+    confirmed_subscribers: HashMap<Topic, HashSet<Identifier>>,
+
+    /// Mapping of branch topics to the address of the remote stream they reference.
+    linked_streams: HashMap<Topic, Address>,
+
+    /// Records of the latest signed packets sent with [`User::send_signed_packet_idempotent`], from
+    /// oldest to newest. At most [`IDEMPOTENCY_KEYS_KEPT`] records are kept.
+    sent_idempotency_keys: VecDeque<IdempotentSend>,
+
+    /// Mapping of branch topics to the Unix time, in seconds, at which the [`User`] created or
+    /// first processed the branch.
+    branch_creation_times: HashMap<Topic, u64>,
+
+    /// Mapping of subscribers to the [`Capability`] assigned to them with
+    /// [`User::assign_capability`].
+    capabilities: HashMap<Identifier, Capability>,
+
+    /// Mapping of the subscribers that subscribed with [`User::subscribe_ephemeral`] to the Unix
+    /// time, in seconds, from which their subscription is expired.
+    expiring_subscribers: HashMap<Identifier, u64>,
 }
 
 /// Public `API` Client for participation in a `Streams` channel.
-pub struct User<T> {
+///
+/// The `I` [marker](`IdentityMarker`) tracks whether the user holds an [`Identity`]. Only users
+/// marked with [`HasIdentity`] can publish messages; users built without one can only read them.
+pub struct User<T, I = HasIdentity> {
     /// A transport client for sending and receiving messages.
     transport: T,
     /// The internal [state](`State`) of the user, containing message state mappings and publisher
     /// cursors for message processing.
     state: State,
+    /// Marker of the [`Identity`] availability of the user.
+    identity_marker: PhantomData<I>,
+    /// Hooks called around the dispatch of the messages sent by the user.
+    send_hooks: SendHooks<T>,
+    /// Transport responses of the packets sent with [`User::send_signed_packet_idempotent`]. Not
+    /// included in backups.
+    idempotent_responses: IdempotentResponses<T>,
+    /// Timeouts and retries applied to the requests made to the transport, as set with
+    /// [`User::set_transport_options`]. Not included in backups.
+    #[cfg(feature = "transport-options")]
+    transport_options: TransportOptions,
 }
 
 impl User<()> {
@@ -112,17 +289,35 @@ impl User<()> {
     pub fn builder() -> UserBuilder<()> {
         UserBuilder::new()
     }
+
+    /// Decodes an invite link generated with [`User::generate_invite_link`], returning the details
+    /// needed to connect to the stream. The `announcement_address` of the returned [`InviteInfo`] can
+    /// then be passed to [`User::receive_message`] to start the subscription flow.
+    ///
+    /// # Arguments
+    /// * `link`: The `streams://invite/<base64blob>` invite link
+    #[cfg(feature = "invite-links")]
+    pub fn parse_invite_link(link: &str) -> Result<InviteInfo> {
+        InviteInfo::from_link(link)
+    }
 }
 
-impl<T> User<T> {
+impl<T, I> User<T, I> {
     /// Creates a new [`User`] with the provided configurations.
     ///
     /// # Arguments
     /// * `user_id`: The user's [`Identity`]. This is used to sign messages.
     /// * `psks`: A list of trusted pre shared keys.
     /// * `transport`: The transport to use for sending and receiving messages.
-    /// * `lean`: If true, the client will store only required message states.
-    pub(crate) fn new<Psks>(user_id: Option<Identity>, psks: Psks, transport: T, lean: bool) -> Self
+    /// * `retention`: The policy deciding which message states the client stores.
+    /// * `send_hooks`: The hooks called around the dispatch of the sent messages.
+    pub(crate) fn new<Psks>(
+        user_id: Option<Identity>,
+        psks: Psks,
+        transport: T,
+        retention: SpongosRetention,
+        send_hooks: SendHooks<T>,
+    ) -> Self
     where
         Psks: IntoIterator<Item = (PskId, Psk)>,
     {
@@ -145,9 +340,32 @@ impl<T> User<T> {
                 stream_address: None,
                 author_identifier: None,
                 base_branch: Default::default(),
-                lean,
+                retention,
+                spongos_order: Default::default(),
                 topics: Default::default(),
+                orphan_store: Default::default(),
+                message_log: Default::default(),
+                link_store: Default::default(),
+                followed_topics: Default::default(),
+                groups: Default::default(),
+                parent_address: None,
+                stream_metadata: None,
+                readonly_subscribers: Default::default(),
+                spongos_aliases: Default::default(),
+                branch_parent: Default::default(),
+                default_topic: None,
+                confirmed_subscribers: Default::default(),
+                linked_streams: Default::default(),
+                sent_idempotency_keys: Default::default(),
+                branch_creation_times: Default::default(),
+                capabilities: Default::default(),
+                expiring_subscribers: Default::default(),
             },
+            identity_marker: PhantomData,
+            send_hooks,
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
         }
     }
 
@@ -171,6 +389,22 @@ impl<T> User<T> {
             .and_then(|id| self.state.cursor_store.get_permission(topic, id))
     }
 
+    /// Converts the provided value into a [`Topic`], checking that it is valid according to
+    /// [`Topic::is_valid`]
+    ///
+    /// # Arguments
+    /// * `topic`: The value to be converted into a [`Topic`]
+    fn validate_topic<Top: Into<Topic>>(topic: Top) -> Result<Topic> {
+        let topic = topic.into();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("topic", &topic.str());
+        if Topic::is_valid(topic.str()) {
+            Ok(topic)
+        } else {
+            Err(Error::InvalidTopic(topic))
+        }
+    }
+
     /// Returns the [User's](`User`) cursor for a given branch if any
     ///
     /// # Arguments
@@ -180,6 +414,24 @@ impl<T> User<T> {
             .and_then(|id| self.state.cursor_store.get_cursor(topic, id))
     }
 
+    /// Returns the cursor of a publisher in a given branch, as known by the [`User`], if any. The
+    /// cursor is the sequence number of the latest message of the publisher processed in the branch.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to check
+    /// * `id`: The [`Identifier`] of the publisher
+    pub fn get_subscriber_cursor(&self, topic: &Topic, id: &Identifier) -> Option<usize> {
+        self.state.cursor_store.get_cursor(topic, id)
+    }
+
+    /// Returns the [User's](`User`) own cursor in a given branch, if any.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to check
+    pub fn get_my_cursor(&self, topic: &Topic) -> Option<usize> {
+        self.cursor(topic)
+    }
+
     /// Returns the [User's](`User`) next cursor for a given branch. Errors if there is
     /// no cursor present for the [`User`] in [`CursorStore`].
     ///
@@ -199,6 +451,45 @@ impl<T> User<T> {
         self.state.stream_address
     }
 
+    /// Generates an invite link for onboarding new subscribers out-of-band, with the format
+    /// `streams://invite/<base64blob>`, where the blob is a JSON object with the stream address, the
+    /// announcement address and the author [`Identifier`], encoded in URL-safe base64. The link can
+    /// be decoded with [`User::parse_invite_link`].
+    ///
+    /// The [`User`] must be connected to the stream, so that its address and author are known. As
+    /// the author [`Identifier`] is encoded as a JSON Web Key, only streams authored by Ed25519
+    /// identities can be shared through invite links.
+    #[cfg(feature = "invite-links")]
+    pub fn generate_invite_link(&self) -> Result<String> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("generate an invite link"))?;
+        let author_identifier = self
+            .state
+            .author_identifier
+            .clone()
+            .ok_or(Error::NoStream("generate an invite link"))?;
+        InviteInfo {
+            stream_address,
+            announcement_address: stream_address,
+            author_identifier,
+        }
+        .to_link()
+    }
+
+    /// Returns the announcement message [`Address`] of the parent stream, if the stream is a child
+    /// stream.
+    pub fn parent_address(&self) -> Option<&Address> {
+        self.state.parent_address.as_ref()
+    }
+
+    /// Returns the latest [`StreamMetadata`] published by the admins of the stream, either on
+    /// [publication](`User::publish_stream_metadata`) or [update](`User::send_announcement_update`),
+    /// if any has been processed.
+    pub fn stream_metadata(&self) -> Option<&StreamMetadata> {
+        self.state.stream_metadata.as_ref()
+    }
+
     /// Returns a reference to the [`User`] transport client.
     pub fn transport(&self) -> &T {
         &self.transport
@@ -209,11 +500,271 @@ impl<T> User<T> {
         &mut self.transport
     }
 
+    /// Sets the [`TransportOptions`] applied to every request the [`User`] makes to its transport
+    /// client. Requests exceeding the timeout fail with [`Error::Timeout`], and failed receive
+    /// requests are repeated according to the retry policy. The options are not included in backups.
+    ///
+    /// # Arguments
+    /// * `options`: The timeout, retry and logging settings of the requests
+    #[cfg(feature = "transport-options")]
+    pub fn set_transport_options(&mut self, options: TransportOptions) -> &mut Self {
+        self.transport_options = options;
+        self
+    }
+
+    /// Returns the [`TransportOptions`] applied to the requests made to the transport client.
+    #[cfg(feature = "transport-options")]
+    pub fn transport_options(&self) -> &TransportOptions {
+        &self.transport_options
+    }
+
+    /// Returns the transport client with the [`TransportOptions`] of the [`User`] applied to its
+    /// requests.
+    #[cfg(feature = "transport-options")]
+    pub(crate) fn configured_transport(&mut self) -> ConfiguredTransport<&mut T> {
+        ConfiguredTransport::new(&mut self.transport, self.transport_options.clone())
+    }
+
+    /// Returns the transport client the requests of the [`User`] are made through.
+    #[cfg(not(feature = "transport-options"))]
+    pub(crate) fn configured_transport(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Returns a copy of the transport client with the [`TransportOptions`] of the [`User`] applied
+    /// to its requests, for requests made concurrently with other requests of the [`User`].
+    #[cfg(feature = "transport-options")]
+    fn configured_transport_copy(&self) -> ConfiguredTransport<T>
+    where
+        T: Clone,
+    {
+        ConfiguredTransport::new(self.transport.clone(), self.transport_options.clone())
+    }
+
+    /// Returns a copy of the transport client, for requests made concurrently with other requests
+    /// of the [`User`].
+    #[cfg(not(feature = "transport-options"))]
+    fn configured_transport_copy(&self) -> T
+    where
+        T: Clone,
+    {
+        self.transport.clone()
+    }
+
+    /// Returns a point-in-time copy of the [`User`] state, detached from the transport client. The
+    /// snapshot can be inspected or compared, but it cannot send or receive messages until it is
+    /// [restored](`User::restore_from_snapshot`) with a transport.
+    pub fn snapshot(&self) -> User<(), I> {
+        User {
+            transport: (),
+            state: self.state.clone(),
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: self.transport_options.clone(),
+        }
+    }
+
+    /// Reconstructs a live [`User`] from a [snapshot](`User::snapshot`) of its state and a
+    /// transport client.
+    ///
+    /// # Arguments
+    /// * `snapshot`: The transport-free [`User`] holding the state to restore
+    /// * `transport`: The transport to use for sending and receiving messages
+    pub fn restore_from_snapshot(snapshot: User<(), I>, transport: T) -> Self {
+        Self {
+            transport,
+            state: snapshot.state,
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: snapshot.transport_options,
+        }
+    }
+
+    /// Moves the [`User`] state into a new [`User`] using the provided transport client, dropping
+    /// the current one. The state is moved, not copied, so it is a cheap alternative to a backup and
+    /// restore when switching to a different transport endpoint. The post-send hook is typed by the
+    /// responses of the current transport, so only the pre-send hook is kept.
+    ///
+    /// # Arguments
+    /// * `new_transport`: The transport to use for sending and receiving messages
+    pub fn transfer_to_transport<T2>(self, new_transport: T2) -> User<T2, I> {
+        User {
+            transport: new_transport,
+            state: self.state,
+            identity_marker: PhantomData,
+            send_hooks: self.send_hooks.into_transport(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: self.transport_options,
+        }
+    }
+
+    /// Prepares a [`User`] for a child stream of the current stream, sharing the same [`Identity`]
+    /// and pre shared keys. The child stream address is derived from the announcement [`Address`]
+    /// of the current stream and the base [`Topic`] of the child stream, and its announcement
+    /// carries the parent [`Address`] so that readers can link both streams together. The returned
+    /// [`User`] has no transport: once one is [attached](`User::transfer_to_transport`), the child
+    /// stream is created with [`User::create_stream`] using the same base [`Topic`].
+    ///
+    /// # Arguments
+    /// * `child_topic`: The [`Topic`] of the base branch of the child stream
+    pub fn derive_child_stream<Top: Into<Topic>>(&self, child_topic: Top) -> Result<User<()>> {
+        let user_id = self
+            .identity()
+            .ok_or(Error::NoIdentity("derive a child stream"))?
+            .clone();
+        let parent_address = self.stream_address().ok_or(Error::NoStream("derive a child stream"))?;
+        let child_topic = Self::validate_topic(child_topic)?;
+
+        let psks = self.state.psk_store.iter().map(|(pskid, psk)| (*pskid, *psk));
+        let mut child = User::new(Some(user_id), psks, (), self.state.retention, SendHooks::default());
+        child.state.base_branch = child_topic;
+        child.state.parent_address = Some(parent_address);
+        Ok(child)
+    }
+
     /// Returns an iterator over all known branch [topics](`Topic`)
     pub fn topics(&self) -> impl Iterator<Item = &Topic> + ExactSizeIterator {
         self.state.topics.iter()
     }
 
+    /// Returns an iterator over the branch [topics](`Topic`) actively followed by the [`User`]. If
+    /// empty, all known branches are followed.
+    pub fn followed_topics(&self) -> impl Iterator<Item = &Topic> + ExactSizeIterator {
+        self.state.followed_topics.iter()
+    }
+
+    /// Returns the [topics](`Topic`) of the branches the provided branch descends from, starting
+    /// with the branch it was created from and ending with the root branch, usually the base
+    /// branch. Returns an empty list for the root branch itself.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn branch_ancestry(&self, topic: &Topic) -> Result<Vec<Topic>> {
+        if !self.state.topics.contains(topic) {
+            return Err(Error::TopicNotFound(topic.clone()));
+        }
+        let mut ancestry = Vec::new();
+        let mut current = topic;
+        while let Some(parent) = self.state.branch_parent.get(current) {
+            // Branches can be announced again from one of their descendants, do not loop forever
+            if parent == topic || ancestry.contains(parent) {
+                break;
+            }
+            ancestry.push(parent.clone());
+            current = parent;
+        }
+        Ok(ancestry)
+    }
+
+    /// Returns true if the [`User`] follows the branch of the provided [`Topic`], either because
+    /// it has been [subscribed to](`User::subscribe_to_branch`) or because no branch has been.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub(crate) fn follows_topic(&self, topic: &Topic) -> bool {
+        self.state.followed_topics.is_empty() || self.state.followed_topics.contains(topic)
+    }
+
+    /// Registers local interest in a branch, without sending any message. Once at least one branch
+    /// is followed, the [`Messages`] stream only traverses the followed branches.
+    ///
+    /// Errors if the branch is not known, or if the [`User`] holds no permission in it.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to follow
+    pub fn subscribe_to_branch(&mut self, topic: &Topic) -> Result<()> {
+        if !self.state.topics.contains(topic) {
+            return Err(Error::TopicNotFound(topic.clone()));
+        }
+        // Any permission grants at least read access to the branch
+        self.permission(topic).ok_or_else(|| Error::NoCursor(topic.clone()))?;
+        self.state.followed_topics.insert(topic.clone());
+        Ok(())
+    }
+
+    /// Returns the [`Topic`] of the branch the `_default` send methods, such as
+    /// [`User::send_signed_packet_default`], send to, if any
+    pub fn default_topic(&self) -> Option<&Topic> {
+        self.state.default_topic.as_ref()
+    }
+
+    /// Sets the branch the `_default` send methods, such as [`User::send_signed_packet_default`],
+    /// send to. Errors if the branch is not known.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the default branch
+    pub fn set_default_topic(&mut self, topic: Topic) -> Result<()> {
+        if !self.state.topics.contains(&topic) {
+            return Err(Error::TopicNotFound(topic));
+        }
+        self.state.default_topic = Some(topic);
+        Ok(())
+    }
+
+    /// Returns the [`Address`] of the remote stream referenced by a branch with
+    /// [`User::connect_streams`], if any
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn get_linked_stream(&self, topic: &Topic) -> Option<Address> {
+        self.state.linked_streams.get(topic).copied()
+    }
+
+    /// Returns the Unix time, in seconds, at which the [`User`] created the branch or processed its
+    /// announcement, if known. The time is taken from the local system clock, so it is only recorded
+    /// when the `std` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn get_branch_creation_time(&self, topic: &Topic) -> Option<u64> {
+        self.state.branch_creation_times.get(topic).copied()
+    }
+
+    /// Records the current time as the creation time of a branch, unless one is already known
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    fn record_branch_creation_time(&mut self, topic: &Topic) {
+        if let Some(now) = unix_time_now() {
+            self.state.branch_creation_times.entry(topic.clone()).or_insert(now);
+        }
+    }
+
+    /// Returns the known branch [topics](`Topic`) sorted by their
+    /// [creation time](`User::get_branch_creation_time`), oldest first. Branches created within
+    /// the same second are sorted lexicographically, and branches without a known creation time
+    /// come last.
+    pub fn topics_sorted_by_creation(&self) -> Vec<&Topic> {
+        let mut topics: Vec<&Topic> = self.topics().collect();
+        topics.sort_by(|a, b| {
+            let creation_time = |topic| self.get_branch_creation_time(topic).unwrap_or(u64::MAX);
+            creation_time(a)
+                .cmp(&creation_time(b))
+                .then_with(|| a.str().cmp(b.str()))
+        });
+        topics
+    }
+
+    /// Returns the known branch [topics](`Topic`) along with their depth, the number of branches
+    /// they [descend from](`User::branch_ancestry`), sorted by depth. The root branch has a depth of
+    /// 0, and branches of the same depth are sorted lexicographically.
+    pub fn topics_by_depth(&self) -> Vec<(usize, &Topic)> {
+        let mut topics: Vec<(usize, &Topic)> = self
+            .topics()
+            .map(|topic| {
+                let depth = self.branch_ancestry(topic).map_or(0, |ancestry| ancestry.len());
+                (depth, topic)
+            })
+            .collect();
+        topics.sort_by(|(depth_a, a), (depth_b, b)| depth_a.cmp(depth_b).then_with(|| a.str().cmp(b.str())));
+        topics
+    }
+
     /// Iterates through known topics, returning the [`Topic`] that matches the [`TopicHash`]
     /// provided if any
     ///
@@ -223,9 +774,21 @@ impl<T> User<T> {
         self.topics().find(|t| &TopicHash::from(*t) == hash).cloned()
     }
 
-    /// Returns true if [`User`] lean state configuration is true
-    fn lean(&self) -> bool {
-        self.state.lean
+    /// Returns the [`Address`]es of the queued orphan messages, received before the message they
+    /// are linked to, in the order they were queued. See [`User::retry_orphans`].
+    pub fn list_orphans(&self) -> Vec<Address> {
+        self.state.orphan_store.iter().map(|(address, _)| *address).collect()
+    }
+
+    /// Returns the [`User`] [`Spongos`] retention policy
+    pub fn spongos_retention(&self) -> SpongosRetention {
+        self.state.retention
+    }
+
+    /// Returns a reference to the [`CursorStore`] of the [`User`], to be exported with
+    /// [`CursorStore::to_vec`]
+    pub fn cursor_store(&self) -> &CursorStore {
+        &self.state.cursor_store
     }
 
     /// Returns an iterator over [`CursorStore`], producing tuples of [`Topic`], [`Permissioned`]
@@ -252,6 +815,152 @@ impl<T> User<T> {
         self.state.subscribers.iter()
     }
 
+    /// Returns the permissions of the known subscribers to include in a keyload sent by `admin`,
+    /// who is granted [`Permissioned::Admin`]. Every other subscriber is granted the permission
+    /// returned by `grant`, or is left out of the keyload if `grant` returns `None`.
+    ///
+    /// # Arguments
+    /// * `admin`: The [`Identifier`] of the sender of the keyload
+    /// * `grant`: The permission granted to each of the other subscribers
+    fn keyload_subscribers<F>(&self, admin: &Identifier, grant: F) -> Vec<Permissioned<Identifier>>
+    where
+        F: Fn(&Identifier) -> Option<Permissioned<Identifier>>,
+    {
+        self.subscribers()
+            .filter_map(|s| {
+                if s == admin {
+                    Some(Permissioned::Admin(s.clone()))
+                } else {
+                    grant(s)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the permission a subscriber currently holds in a branch, or [`Permissioned::Read`]
+    /// if it holds none
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    /// * `id`: The [`Identifier`] of the subscriber
+    fn current_permission(&self, topic: &Topic, id: &Identifier) -> Permissioned<Identifier> {
+        self.state
+            .cursor_store
+            .get_permission(topic, id)
+            .cloned()
+            .unwrap_or_else(|| Permissioned::Read(id.clone()))
+    }
+
+    /// Returns an iterator over the subscribers whose subscription expires, as subscribed with
+    /// [`User::subscribe_ephemeral`]
+    pub fn expiring_subscribers(&self) -> impl Iterator<Item = ExpiringSubscriber> + '_ {
+        self.state
+            .expiring_subscribers
+            .iter()
+            .map(|(id, expires_at)| ExpiringSubscriber {
+                id: id.clone(),
+                expires_at: *expires_at,
+            })
+    }
+
+    /// Returns the highest publisher cursor seen in each known branch, which approximates the
+    /// number of messages published in it. The counts are derived from the [`CursorStore`] only,
+    /// no transport calls are made.
+    pub fn count_messages_per_branch(&self) -> HashMap<Topic, usize> {
+        let mut counts = HashMap::new();
+        for (topic, _, cursor) in self.cursors() {
+            let count = counts.entry(topic.clone()).or_insert(0);
+            *count = (*count).max(cursor);
+        }
+        counts
+    }
+
+    /// Returns the sum of the [message counts](`User::count_messages_per_branch`) of all known
+    /// branches
+    pub fn total_message_count(&self) -> usize {
+        self.count_messages_per_branch().values().sum()
+    }
+
+    /// Returns the cursor details of every publisher tracked in a branch, as tuples of
+    /// [`Identifier`], [`Permissioned`] and current cursor. Errors if the branch is unknown.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to list the publishers of
+    pub fn list_publishers(&self, topic: &Topic) -> Result<Vec<(Identifier, Permissioned<Identifier>, usize)>> {
+        Ok(self
+            .cursors_by_topic(topic)?
+            .map(|(permission, cursor)| (permission.identifier().clone(), permission.clone(), *cursor))
+            .collect())
+    }
+
+    /// Returns true if the [`Identifier`] has write permission in a branch. Returns false if the
+    /// branch is unknown.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to check
+    /// * `id`: The [`Identifier`] of the publisher to check
+    pub fn is_publisher(&self, topic: &Topic, id: &Identifier) -> bool {
+        self.state
+            .cursor_store
+            .get_permission(topic, id)
+            .map_or(false, |permission| !permission.is_readonly())
+    }
+
+    /// Returns the number of identifiers with write permission in a branch. Returns 0 if the branch
+    /// is unknown.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to check
+    pub fn publisher_count(&self, topic: &Topic) -> usize {
+        self.cursors_by_topic(topic)
+            .map(|cursors| cursors.filter(|(permission, _)| !permission.is_readonly()).count())
+            .unwrap_or_default()
+    }
+
+    /// Resets the cursor of a publisher in a branch to an earlier value, repairing the cursor store
+    /// after the publisher failed to deliver some of its messages. If the message published at the
+    /// reset cursor is known, it becomes the latest link of the branch. Only the branch admin can
+    /// reset cursors.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the cursor is stored in
+    /// * `id`: The [`Identifier`] of the publisher whose cursor will be reset
+    /// * `cursor`: The new cursor of the publisher
+    pub fn reset_cursor(&mut self, topic: &Topic, id: &Identifier, cursor: usize) -> Result<()> {
+        let stream_address = self.stream_address().ok_or(Error::NoStream("reset a cursor"))?;
+        let permission = self.permission(topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "reset a cursor",
+            ));
+        }
+        let publisher_permission = self
+            .state
+            .cursor_store
+            .get_permission(topic, id)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        let current_cursor = self
+            .state
+            .cursor_store
+            .get_cursor(topic, id)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if cursor > current_cursor {
+            return Err(Error::InvalidCursor(topic.clone(), cursor, current_cursor));
+        }
+
+        self.state
+            .cursor_store
+            .insert_cursor(topic, publisher_permission, cursor);
+        let msgid = MsgId::gen(stream_address.base(), id, topic, cursor);
+        if self.stored_spongos(&msgid).is_some() {
+            self.set_latest_link(topic.clone(), msgid);
+        }
+        Ok(())
+    }
+
     /// If the subscriber is not readonly and the [`Permissioned`] is not tracked or the
     /// [`Permissioned`] is tracked and not equal to the provided subscriber [`Permissioned`],
     /// then the cursor should be stored.
@@ -265,9 +974,9 @@ impl<T> User<T> {
         !permission.is_readonly() && !tracked_and_equal
     }
 
-    /// Store a new [`Spongos`] state. If the [`User`] lean state configuration is set to true, and
-    /// if the linked message is not the stream announcement message, remove the previous message
-    /// from store.
+    /// Store a new [`Spongos`] state. If the [`User`] retention policy is lean, and if the linked
+    /// message is not the stream announcement message, remove the previous message from store. If
+    /// the policy keeps the last `n` states, remove the oldest stored state beyond the limit.
     ///
     /// # Arguments:
     /// * `msg_address`: The [`Address`] of the message that we're storing the [`Spongos`] for.
@@ -277,33 +986,325 @@ impl<T> User<T> {
         let is_stream_address = self
             .stream_address()
             .map_or(false, |stream_address| stream_address.relative() == linked_msg_address);
-        // Do not remove announcement message from store
-        if self.lean() && !is_stream_address {
-            self.state.spongos_store.remove(&linked_msg_address);
+        match self.state.retention {
+            // Do not remove announcement message from store
+            SpongosRetention::Lean if !is_stream_address => {
+                self.evict_spongos(&linked_msg_address);
+            }
+            SpongosRetention::LastN(n) => {
+                if !self.state.spongos_order.contains(&msg_address) {
+                    self.state.spongos_order.push_back(msg_address);
+                }
+                let stream_address = self.stream_address().map(|address| address.relative());
+                // The latest links of the branches are kept beyond the limit, as the next message of
+                // their branch is linked to them. They are evicted once they are no longer latest links
+                let latest_links: HashSet<MsgId> = self
+                    .state
+                    .cursor_store
+                    .latest_links()
+                    .into_iter()
+                    .map(|(_, latest_link)| latest_link)
+                    .collect();
+                let mut position = 0;
+                while self.state.spongos_order.len() > n && position < self.state.spongos_order.len() {
+                    let oldest = self.state.spongos_order[position];
+                    if oldest == msg_address || latest_links.contains(&oldest) {
+                        position += 1;
+                        continue;
+                    }
+                    self.state.spongos_order.remove(position);
+                    // Do not remove announcement message from store
+                    if Some(oldest) != stream_address {
+                        self.evict_spongos(&oldest);
+                    }
+                }
+            }
+            _ => {}
         }
 
-        self.state.spongos_store.insert(msg_address, spongos);
+        self.insert_spongos(msg_address, spongos, Some(linked_msg_address));
     }
 
-    /// Store a new subscriber [`Identifier`] in state. Returns true if subscriber was not present.
-    pub fn add_subscriber(&mut self, subscriber: Identifier) -> bool {
-        self.state.subscribers.insert(subscriber)
+    /// Store the [`Spongos`] state of a message, recording the link of the message it is linked to
+    /// and the digest of the state, so that the chain of messages can be audited later on.
+    ///
+    /// # Arguments
+    /// * `msg_address`: The link of the message
+    /// * `spongos`: The [`Spongos`] state of the message
+    /// * `linked_msg_address`: The link of the message it is linked to, if any
+    fn insert_spongos(&mut self, msg_address: MsgId, spongos: Spongos, linked_msg_address: Option<MsgId>) {
+        self.state
+            .link_store
+            .insert(msg_address, (linked_msg_address, link_digest(&spongos, msg_address)));
+        self.state.spongos_aliases.remove(&msg_address);
+        self.state.spongos_store.insert(msg_address, spongos);
     }
 
-    /// Remove a subscriber [`Identifier`] from state. Returns true if the subscriber was present.
-    pub fn remove_subscriber(&mut self, id: &Identifier) -> bool {
-        self.state.subscribers.remove(id)
+    /// Returns the [`Spongos`] state of a message held in store, following the link of the
+    /// canonical copy if the state has been [deduplicated](`User::compress_spongos_store`).
+    ///
+    /// # Arguments
+    /// * `msg_address`: The link of the message
+    fn stored_spongos(&self, msg_address: &MsgId) -> Option<&Spongos> {
+        self.state.spongos_store.get(msg_address).or_else(|| {
+            self.state
+                .spongos_aliases
+                .get(msg_address)
+                .and_then(|canonical| self.state.spongos_store.get(canonical))
+        })
     }
 
-    /// Store a new [Pre-Shared Key](`Psk`) in state. Returns true if [`Psk`] was not present.
-    pub fn add_psk(&mut self, psk: Psk) -> bool {
-        self.state.psk_store.insert(psk.to_pskid(), psk).is_none()
+    /// Removes the [`Spongos`] state of a message from store. The messages whose state had been
+    /// deduplicated into the removed one are given back their own copy.
+    ///
+    /// # Arguments
+    /// * `msg_address`: The link of the message
+    fn remove_spongos(&mut self, msg_address: &MsgId) {
+        self.state.spongos_aliases.remove(msg_address);
+        if let Some(spongos) = self.state.spongos_store.remove(msg_address) {
+            let aliases: Vec<MsgId> = self
+                .state
+                .spongos_aliases
+                .iter()
+                .filter(|(_, canonical)| *canonical == msg_address)
+                .map(|(alias, _)| *alias)
+                .collect();
+            for alias in aliases {
+                self.state.spongos_aliases.remove(&alias);
+                self.state.spongos_store.insert(alias, spongos);
+            }
+        }
     }
 
-    /// Remove a [`Psk`] from state by its [identifier](`PskId`). Returns true if the [`Psk`] was
-    /// present.
-    pub fn remove_psk(&mut self, pskid: PskId) -> bool {
-        self.state.psk_store.remove(&pskid).is_some()
+    /// Evicts the [`Spongos`] state of a message according to the [`SpongosRetention`] policy. Unlike
+    /// states removed to be archived, evicted states are never restored, so the link of the message
+    /// is forgotten as well.
+    ///
+    /// # Arguments
+    /// * `msg_address`: The link of the message
+    fn evict_spongos(&mut self, msg_address: &MsgId) {
+        self.remove_spongos(msg_address);
+        self.state.link_store.remove(msg_address);
+    }
+
+    /// Removes the [`Spongos`] states held in store that are identical to another stored state,
+    /// keeping a single canonical copy that the links of the removed states point to. Returns the
+    /// number of bytes freed.
+    pub fn compress_spongos_store(&mut self) -> Result<usize> {
+        let mut canonical_links: HashMap<SpongosDigest, MsgId> = HashMap::new();
+        let mut duplicates = Vec::new();
+        // Links are visited in order so that the canonical copy does not depend on the store order
+        let mut links: Vec<MsgId> = self.state.spongos_store.keys().copied().collect();
+        links.sort();
+        for link in links {
+            let spongos = self.state.spongos_store[&link];
+            match canonical_links.entry(spongos_digest(&spongos)) {
+                Entry::Occupied(canonical) if self.state.spongos_store[canonical.get()] == spongos => {
+                    duplicates.push((link, *canonical.get()))
+                }
+                Entry::Occupied(_) => {}
+                Entry::Vacant(entry) => {
+                    entry.insert(link);
+                }
+            }
+        }
+
+        for (duplicate, canonical) in &duplicates {
+            self.state.spongos_store.remove(duplicate);
+            self.state.spongos_aliases.insert(*duplicate, *canonical);
+        }
+        // Each duplicate frees a state, but its link is kept to point to the canonical copy
+        Ok(duplicates.len() * (size_of::<Spongos>() - size_of::<MsgId>()))
+    }
+
+    /// Store a new subscriber [`Identifier`] in state. Returns true if subscriber was not present.
+    pub fn add_subscriber(&mut self, subscriber: Identifier) -> bool {
+        self.state.subscribers.insert(subscriber)
+    }
+
+    /// Assigns a [`Capability`] to a subscriber, replacing the one previously assigned to it. Returns
+    /// the previous [`Capability`], if any. The permissions mapped to the capability are only granted
+    /// once a keyload is sent with [`User::send_keyload_for_capabilities`].
+    ///
+    /// # Arguments
+    /// * `id`: The [`Identifier`] of the subscriber
+    /// * `capability`: The [`Capability`] to assign
+    pub fn assign_capability(&mut self, id: &Identifier, capability: Capability) -> Option<Capability> {
+        self.state.capabilities.insert(id.clone(), capability)
+    }
+
+    /// Returns the [`Capability`] assigned to a subscriber, if any
+    ///
+    /// # Arguments
+    /// * `id`: The [`Identifier`] of the subscriber
+    pub fn capability(&self, id: &Identifier) -> Option<Capability> {
+        self.state.capabilities.get(id).copied()
+    }
+
+    /// Stores a list of subscriber [`Identifier`]s in state at once, skipping the ones already
+    /// present. Returns the number of subscribers added. No stream needs to be created beforehand.
+    ///
+    /// # Arguments
+    /// * `identifiers`: The [`Identifier`]s of the subscribers to be added
+    pub fn import_subscriber_list(&mut self, identifiers: Vec<Identifier>) -> Result<usize> {
+        Ok(identifiers
+            .into_iter()
+            .filter(|identifier| self.add_subscriber(identifier.clone()))
+            .count())
+    }
+
+    /// Returns a sorted copy of the subscriber [`Identifier`]s stored in state
+    pub fn export_subscriber_list(&self) -> Vec<Identifier> {
+        let mut identifiers: Vec<Identifier> = self.subscribers().cloned().collect();
+        identifiers.sort();
+        identifiers
+    }
+
+    /// Returns a sorted list of the subscribers that confirmed, with an
+    /// [announcement ack](`User::send_announcement_ack`), that they processed a keyload of the
+    /// provided branch
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn list_confirmed_subscribers(&self, topic: &Topic) -> Vec<Identifier> {
+        let mut identifiers: Vec<Identifier> = self
+            .state
+            .confirmed_subscribers
+            .get(topic)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        identifiers.sort();
+        identifiers
+    }
+
+    /// Remove a subscriber [`Identifier`] from state. Returns true if the subscriber was present.
+    pub fn remove_subscriber(&mut self, id: &Identifier) -> bool {
+        self.state.readonly_subscribers.remove(id);
+        self.state.capabilities.remove(id);
+        self.state.expiring_subscribers.remove(id);
+        for confirmed in self.state.confirmed_subscribers.values_mut() {
+            confirmed.remove(id);
+        }
+        self.state.subscribers.remove(id)
+    }
+
+    /// Removes the [ephemeral subscribers](`User::subscribe_ephemeral`) whose subscription is expired
+    /// at the provided time, as [`User::remove_subscriber`] would. Returns the number of removed
+    /// subscribers.
+    ///
+    /// # Arguments
+    /// * `now`: The current Unix time, in seconds
+    pub fn expire_subscriptions(&mut self, now: u64) -> usize {
+        let expired: Vec<Identifier> = self
+            .state
+            .expiring_subscribers
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &expired {
+            self.remove_subscriber(id);
+        }
+        expired.len()
+    }
+
+    /// Removes the cursors of the publishers that have not published past `inactive_since_cursor`,
+    /// in every branch, along with the subscribers left without any cursor. The cursors of the
+    /// stream author and of branch admins are always kept. Returns the number of removed cursors.
+    ///
+    /// Only an admin of the base branch can prune subscribers.
+    ///
+    /// # Arguments
+    /// * `inactive_since_cursor`: The highest cursor of the publishers to be pruned
+    pub fn prune_subscribers(&mut self, inactive_since_cursor: usize) -> Result<usize> {
+        let identifier = self.identifier().ok_or(Error::NoIdentity("prune subscribers"))?.clone();
+        let base_branch = self.state.base_branch.clone();
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&base_branch, &identifier)
+            .ok_or(Error::NoCursor(base_branch))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole("Admin", identifier, "prune subscribers"));
+        }
+
+        let author_identifier = self.state.author_identifier.clone();
+        let removed = self
+            .state
+            .cursor_store
+            .remove_inactive(inactive_since_cursor, |permission| {
+                permission.is_admin() || Some(permission.identifier()) == author_identifier.as_ref()
+            });
+        for permission in &removed {
+            let id = permission.identifier();
+            if !self.state.cursor_store.cursors().any(|(_, p, _)| p.identifier() == id) {
+                self.remove_subscriber(id);
+            }
+        }
+        Ok(removed.len())
+    }
+
+    /// Returns true if the subscriber [subscribed as read-only](`User::subscribe_readonly`), and
+    /// must never be granted write permissions.
+    ///
+    /// # Arguments
+    /// * `id`: The [`Identifier`] of the subscriber
+    pub fn is_readonly_subscriber(&self, id: &Identifier) -> bool {
+        self.state.readonly_subscribers.contains(id)
+    }
+
+    /// Creates a named group of subscribers, or replaces the members of the group if it already
+    /// exists. Returns true if the group was not present.
+    ///
+    /// # Arguments
+    /// * `name`: The name of the group
+    /// * `members`: The [`Identifier`]s of the members of the group
+    pub fn define_group(&mut self, name: &str, members: Vec<Identifier>) -> bool {
+        self.state.groups.insert(name.to_string(), members).is_none()
+    }
+
+    /// Store a new [Pre-Shared Key](`Psk`) in state. Returns true if [`Psk`] was not present.
+    pub fn add_psk(&mut self, psk: Psk) -> bool {
+        self.state.psk_store.insert(psk.to_pskid(), psk).is_none()
+    }
+
+    /// Remove a [`Psk`] from state by its [identifier](`PskId`). Returns true if the [`Psk`] was
+    /// present.
+    pub fn remove_psk(&mut self, pskid: PskId) -> bool {
+        self.state.psk_store.remove(&pskid).is_some()
+    }
+
+    /// Stores a set of [Pre-Shared Keys](`Psk`) in state, skipping those already present. Returns
+    /// the number of [`Psk`]s added. Errors without storing any [`Psk`] if a [`PskId`] does not
+    /// match the id derived from its [`Psk`].
+    ///
+    /// # Arguments
+    /// * `psks`: The [`Psk`]s to store, along with their [`PskId`]
+    pub fn import_psk_store(&mut self, psks: Vec<(PskId, Psk)>) -> Result<usize> {
+        if let Some((pskid, _)) = psks.iter().find(|(pskid, psk)| psk.to_pskid() != *pskid) {
+            return Err(Error::PskIdMismatch(*pskid));
+        }
+        let added = psks
+            .into_iter()
+            .filter(|(pskid, psk)| match self.state.psk_store.entry(*pskid) {
+                Entry::Occupied(_) => false,
+                Entry::Vacant(entry) => {
+                    entry.insert(*psk);
+                    true
+                }
+            })
+            .count();
+        Ok(added)
+    }
+
+    /// Returns all the [Pre-Shared Keys](`Psk`) stored in state, along with their [`PskId`],
+    /// sorted by [`PskId`].
+    pub fn export_psk_store(&self) -> Vec<(PskId, Psk)> {
+        let mut psks: Vec<(PskId, Psk)> = self.state.psk_store.iter().map(|(pskid, psk)| (*pskid, *psk)).collect();
+        psks.sort_by_key(|(pskid, _)| *pskid);
+        psks
     }
 
     /// Sets the latest message link for a specified branch. If the branch does not exist, it is
@@ -324,6 +1325,31 @@ impl<T> User<T> {
         self.state.cursor_store.get_latest_link(topic)
     }
 
+    /// Returns the [`Address`] of the latest message of a specified branch, if any. This is the
+    /// message the next message published to the branch will be linked to.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn get_latest_message_address(&self, topic: &Topic) -> Option<Address> {
+        let stream_address = self.stream_address()?;
+        self.get_latest_link(topic)
+            .map(|latest_link| Address::new(stream_address.base(), latest_link))
+    }
+
+    /// Returns the [`Address`] of the message published by a publisher at a given cursor of a
+    /// specified branch, if the [`User`] is attached to a stream. The message is not required to
+    /// exist.
+    ///
+    /// # Arguments
+    /// * `cursor`: The cursor of the publisher when the message was published
+    /// * `publisher`: The [`Identifier`] of the publisher
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn get_linked_message_address(&self, cursor: usize, publisher: &Identifier, topic: &Topic) -> Option<Address> {
+        let stream_address = self.stream_address()?;
+        let rel_address = MsgId::gen(stream_address.base(), publisher, topic, cursor);
+        Some(Address::new(stream_address.base(), rel_address))
+    }
+
     /// Parse and process a [`TransportMessage`] dependent on its type.
     ///
     /// # Arguments
@@ -335,130 +1361,1003 @@ impl<T> User<T> {
             .await
             .map_err(|e| Error::Unwrapping("header", address, e))?;
 
-        match preparsed.header().message_type() {
-            message_types::ANNOUNCEMENT => self.handle_announcement(address, preparsed).await,
+        let message = match preparsed.header().message_type() {
+            message_types::ANNOUNCEMENT | message_types::CHILD_ANNOUNCEMENT => {
+                self.handle_announcement(address, preparsed).await
+            }
             message_types::BRANCH_ANNOUNCEMENT => self.handle_branch_announcement(address, preparsed).await,
-            message_types::SUBSCRIPTION => self.handle_subscription(address, preparsed).await,
+            message_types::SUBSCRIPTION | message_types::SUBSCRIPTION_WITH_OPTIONS => {
+                self.handle_subscription(address, preparsed).await
+            }
             message_types::UNSUBSCRIPTION => self.handle_unsubscription(address, preparsed).await,
             message_types::KEYLOAD => self.handle_keyload(address, preparsed).await,
             message_types::SIGNED_PACKET => self.handle_signed_packet(address, preparsed).await,
             message_types::TAGGED_PACKET => self.handle_tagged_packet(address, preparsed).await,
+            message_types::HEARTBEAT => self.handle_heartbeat(address, preparsed).await,
+            message_types::STREAM_CATALOG => self.handle_stream_catalog(address, preparsed).await,
+            message_types::DIRECT_MESSAGE => self.handle_direct_message(address, preparsed).await,
+            message_types::STREAM_METADATA => self.handle_stream_metadata(address, preparsed).await,
+            message_types::STREAM_METADATA_UPDATE => self.handle_metadata_update(address, preparsed).await,
+            message_types::PROOF_OF_INCLUSION => self.handle_proof_of_inclusion(address, preparsed).await,
+            message_types::MULTIPART_MANIFEST => self.handle_multipart_manifest(address, preparsed).await,
+            message_types::ANNOUNCEMENT_ACK => self.handle_announcement_ack(address, preparsed).await,
+            message_types::STREAM_LINK => self.handle_stream_link(address, preparsed).await,
             unknown => Err(Error::MessageTypeUnknown(unknown)),
+        }?;
+
+        // Queue orphans so they can be retried once their linked message has been processed. Direct
+        // messages addressed to someone else are returned as orphans too, but can never be resolved
+        if let MessageContent::Orphan(orphan) = &message.content {
+            let queued = self.state.orphan_store.iter().any(|(queued, _)| queued == &address);
+            if !queued && message.header.message_type() != message_types::DIRECT_MESSAGE {
+                self.state.orphan_store.push((address, orphan.message.clone()));
+            }
+            // Orphans are only logged once they are resolved
+            return Ok(message);
+        }
+
+        // The message may have been queued as an orphan before its linked message was processed
+        self.state.orphan_store.retain(|(queued, _)| queued != &address);
+        if let Some(topic) = self.topic_by_hash(message.header.topic_hash()) {
+            let entry = MessageLogEntry::new(
+                topic,
+                address,
+                message.header.message_type(),
+                message.header.publisher().clone(),
+                message.header.sequence(),
+                self.state.message_log.len(),
+            );
+            self.state.message_log.push(entry);
         }
+        Ok(message)
     }
 
-    /// Processes an announcement message, binding a [`User`] to the stream announced in the
-    /// message.
+    /// Returns the audit log of the messages processed by the [`User`] in a specified branch, in
+    /// processing order. Messages published by the [`User`] itself are not part of the log.
     ///
-    /// # Arguments:
-    /// * `address`: The [`Address`] of the message to be processed
-    /// * `preparsed`: The [`PreparsedMessage`] to be processed
-    async fn handle_announcement(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
-        // Check Topic
-        let publisher = preparsed.header().publisher().clone();
-
-        // Unwrap message
-        let announcement = announcement::Unwrap::default();
-        let (message, spongos) = preparsed
-            .unwrap(announcement)
-            .await
-            .map_err(|e| Error::Unwrapping("announcement", address, e))?;
-
-        let topic = message.payload().content().topic();
-        // Insert new branch into store
-        self.state.cursor_store.new_branch(topic.clone());
-        self.state.topics.insert(topic.clone());
-
-        // When handling an announcement it means that no cursors have been stored, as no topics are
-        // known yet. The message must be unwrapped to retrieve the initial topic before storing cursors
-        self.state
-            .cursor_store
-            .insert_cursor(topic, Permissioned::Admin(publisher), INIT_MESSAGE_NUM);
-
-        // Store spongos
-        self.state.spongos_store.insert(address.relative(), spongos);
-
-        // Store message content into stores
-        let author_id = message.payload().content().author_id().clone();
-
-        // Update branch links
-        self.set_latest_link(topic.clone(), address.relative());
-        self.state.author_identifier = Some(author_id);
-        self.state.base_branch = topic.clone();
-        self.state.stream_address = Some(address);
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn export_message_log(&self, topic: &Topic) -> Result<Vec<MessageLogEntry>> {
+        if !self.state.topics.contains(topic) {
+            return Err(Error::TopicNotFound(topic.clone()));
+        }
+        Ok(self
+            .state
+            .message_log
+            .iter()
+            .filter(|entry| &entry.topic == topic)
+            .cloned()
+            .collect())
+    }
 
-        Ok(Message::from_lets_message(address, message))
+    /// Returns the [`Address`] of the inbox of a stream author, where the subscription requests
+    /// addressed to it are gathered so it can list them.
+    ///
+    /// # Arguments
+    /// * `author`: The [`Identifier`] of the stream author
+    fn subscription_requests_inbox(author: &Identifier) -> Address {
+        Self::subscription_request_address(author, author)
     }
 
-    /// Processes a branch announcement message, creating a new branch in [`CursorStore`], carrying
-    /// over mapped permissions and cursors from the previous branch.
+    /// Returns the [`Address`] the subscription request of a requester to a stream author is sent
+    /// to, derivable by both parties without knowing the address of the stream. The inbox
+    /// [`AppAddr`] is a Keccak hash of the author [`Identifier`].
     ///
-    /// # Arguments:
-    /// * `address`: The [`Address`] of the message to be processed
-    /// * `preparsed`: The [`PreparsedMessage`] to be processed
-    async fn handle_branch_announcement(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
-        // Retrieve header values
-        let prev_topic = self
-            .topic_by_hash(preparsed.header().topic_hash())
-            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+    /// # Arguments
+    /// * `author`: The [`Identifier`] of the stream author
+    /// * `requester`: The [`Identifier`] of the requester
+    fn subscription_request_address(author: &Identifier, requester: &Identifier) -> Address {
+        let mut spongos = Spongos::<KeccakF1600>::init();
+        spongos.absorb(SUBSCRIPTION_REQUESTS_TOPIC);
+        let inbox: AppAddr = spongos.sponge(author);
+        let topic = Topic::from(SUBSCRIPTION_REQUESTS_TOPIC);
+        Address::new(inbox, MsgId::gen(inbox, requester, &topic, SUB_MESSAGE_NUM))
+    }
 
-        let publisher = preparsed.header().publisher().clone();
-        let cursor = preparsed.header().sequence();
+    /// Returns the [`Address`] the approval of the subscription request of a requester is sent to by
+    /// the stream author, derivable by both parties without knowing the address of the stream. The
+    /// [`AppAddr`] is a Keccak hash of the author [`Identifier`].
+    ///
+    /// # Arguments
+    /// * `author`: The [`Identifier`] of the stream author
+    /// * `requester`: The [`Identifier`] of the requester
+    fn subscription_approval_address(author: &Identifier, requester: &Identifier) -> Address {
+        let mut spongos = Spongos::<KeccakF1600>::init();
+        spongos.absorb(SUBSCRIPTION_APPROVALS_TOPIC);
+        let approvals: AppAddr = spongos.sponge(author);
+        let topic = Topic::from(SUBSCRIPTION_APPROVALS_TOPIC);
+        Address::new(approvals, MsgId::gen(approvals, requester, &topic, SUB_MESSAGE_NUM))
+    }
 
-        // From the point of view of cursor tracking, the message exists, regardless of the validity or
-        // accessibility to its content. Therefore we must update the cursor of the publisher before
-        // handling the message
-        let permission = self
-            .state
-            .cursor_store
-            .get_permission(&prev_topic, &publisher)
-            .ok_or(Error::NoCursor(prev_topic.clone()))?
-            .clone();
-        self.state.cursor_store.insert_cursor(&prev_topic, permission, cursor);
+    /// Unwraps a subscription request message and verifies its signature, returning the
+    /// [`Identifier`] of the requester. The [`User`] state is left untouched.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message was fetched from
+    /// * `msg`: The raw subscription request message
+    async fn unwrap_subscription_request(address: Address, msg: TransportMessage) -> Result<Identifier> {
+        let preparsed = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", address, e))?;
+        match preparsed.header().message_type() {
+            message_types::SUBSCRIPTION_REQUEST => {
+                let (message, _spongos) = preparsed
+                    .unwrap(subscription_request::Unwrap::default())
+                    .await
+                    .map_err(|e| Error::Unwrapping("subscription request", address, e))?;
+                Ok(message.into_payload().into_content().into_requester_id())
+            }
+            message_type => Err(Error::UnexpectedMessageType {
+                expected: message_types::SUBSCRIPTION_REQUEST,
+                got: message_type,
+            }),
+        }
+    }
 
-        // Unwrap message
-        let linked_msg_address = preparsed
-            .header()
-            .linked_msg_address()
-            .ok_or(Error::NotLinked("branch announcement", address))?;
-        let mut linked_msg_spongos = {
-            if let Some(spongos) = self.state.spongos_store.get(&linked_msg_address).copied() {
-                // Spongos must be copied because wrapping mutates it
-                spongos
-            } else {
-                return Ok(Message::orphan(address, preparsed));
+    /// Checks the internal consistency of the [`User`] state, returning a description of the first
+    /// violated invariant, if any:
+    /// - every known branch [`Topic`] has an entry in the cursor store, and vice versa
+    /// - the latest link of every branch is the stream announcement or a message processed by the
+    ///   [`User`], whose [`Spongos`] state is stored or has been removed according to the
+    ///   [`SpongosRetention`] policy
+    /// - no publisher cursor of a branch exceeds the cursor of the branch latest link when the latest
+    ///   link is a message of that publisher. Heartbeats, proofs of inclusion, stream catalogs and
+    ///   multipart manifests are the exception: they advance the cursor of their publisher without
+    ///   moving the latest link, so the check only covers messages whose [`Spongos`] state is stored.
+    ///   Unsubscriptions store their state without moving the latest link either, so the check does
+    ///   not hold once a publisher of the branch has unsubscribed
+    #[cfg(test)]
+    pub(crate) fn check_invariants(&self) -> core::result::Result<(), String> {
+        for topic in &self.state.topics {
+            if self.state.cursor_store.cursors_by_topic(topic).is_none() {
+                return Err(format!(
+                    "branch '{}' is known but has no entry in the cursor store",
+                    topic
+                ));
             }
-        };
-        let branch_announcement = branch_announcement::Unwrap::new(&mut linked_msg_spongos);
-        let (message, spongos) = preparsed
-            .unwrap(branch_announcement)
-            .await
-            .map_err(|e| Error::Unwrapping("branch announcement", address, e))?;
+        }
+        let announcement_link = self.stream_address().map(|stream_address| stream_address.relative());
+        for (topic, latest_link) in self.state.cursor_store.latest_links() {
+            if !self.state.topics.contains(&topic) {
+                return Err(format!(
+                    "the cursor store has an entry for branch '{}', which is not known",
+                    topic
+                ));
+            }
+            if Some(latest_link) != announcement_link
+                && self.stored_spongos(&latest_link).is_none()
+                && !self.state.link_store.contains_key(&latest_link)
+            {
+                return Err(format!(
+                    "the latest link {} of branch '{}' is not a processed message",
+                    latest_link, topic
+                ));
+            }
+        }
+        if let Some(stream_address) = self.stream_address() {
+            let base = stream_address.base();
+            for (topic, latest_link) in self.state.cursor_store.latest_links() {
+                if latest_link == stream_address.relative() {
+                    continue;
+                }
+                let cursors: Vec<(&Identifier, usize)> = self
+                    .state
+                    .cursor_store
+                    .cursors_by_topic(&topic)
+                    .into_iter()
+                    .flatten()
+                    .map(|(id, cursor)| (id.identifier(), *cursor))
+                    .collect();
+                // The latest link is usually one of the last messages of its publisher, so the messages of
+                // every publisher are looked up from their most recent cursor backwards
+                let max_cursor = cursors.iter().map(|(_, cursor)| *cursor).max().unwrap_or_default();
+                let latest_link_publisher = (0..max_cursor).find_map(|depth| {
+                    cursors.iter().find_map(|(publisher, cursor)| {
+                        cursor
+                            .checked_sub(depth)
+                            .filter(|link_cursor| *link_cursor >= INIT_MESSAGE_NUM)
+                            .filter(|link_cursor| MsgId::gen(base, publisher, &topic, *link_cursor) == latest_link)
+                            .map(|link_cursor| (*publisher, *cursor, link_cursor))
+                    })
+                });
+                if let Some((publisher, cursor, link_cursor)) = latest_link_publisher {
+                    let beyond_latest_link = (link_cursor + 1..=cursor).any(|next| {
+                        self.stored_spongos(&MsgId::gen(base, publisher, &topic, next))
+                            .is_some()
+                    });
+                    if beyond_latest_link {
+                        return Err(format!(
+                            "the cursor {} of publisher {} in branch '{}' exceeds the cursor {} of the branch latest \
+                             link",
+                            cursor, publisher, topic, link_cursor
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 
-        let new_topic = message.payload().content().new_topic();
-        // Store spongos
-        self.store_spongos(address.relative(), spongos, linked_msg_address);
-        // Insert new branch into store
-        self.state.cursor_store.new_branch(new_topic.clone());
-        self.state.topics.insert(new_topic.clone());
-        // Collect permissions from previous branch and clone them into new branch
-        let prev_permissions = self
-            .cursors_by_topic(&prev_topic)?
-            .map(|(id, _)| id.clone())
-            .collect::<Vec<Permissioned<Identifier>>>();
-        for id in prev_permissions {
-            self.state.cursor_store.insert_cursor(new_topic, id, INIT_MESSAGE_NUM);
+    /// Returns a [`StreamHealth`] report summarizing the state of the [`User`]. The report is
+    /// collected from state only, without accessing the transport.
+    pub fn stream_health(&self) -> StreamHealth {
+        // The message log is in processing order, so the first logged message whose state is still
+        // stored is the oldest one
+        let processed = self.state.message_log.len();
+        let oldest_spongos_age = self
+            .state
+            .message_log
+            .iter()
+            .position(|entry| self.stored_spongos(&entry.address.relative()).is_some())
+            .map(|position| processed - 1 - position);
+
+        StreamHealth {
+            is_connected: self.state.stream_address.is_some(),
+            stream_address: self.state.stream_address,
+            branch_count: self.state.topics.len(),
+            subscriber_count: self.state.subscribers.len(),
+            total_cursors: self.state.cursor_store.cursors().count(),
+            oldest_spongos_age,
+            lean_mode: self.state.retention,
+            pending_orphans: self.state.orphan_store.len(),
         }
+    }
 
-        // Update branch links
-        self.set_latest_link(new_topic.clone(), address.relative());
+    /// Exports the state of the [`User`] as newline-delimited JSON, for inspection by debugging
+    /// tools. One JSON object is written per line: first a `header` line with the stream address and
+    /// the base branch, then one `branch` line per branch with its latest link, one `cursor` line per
+    /// publisher cursor and one `psk` line per pre shared key. Branches and cursors are sorted by
+    /// topic, and pre shared keys by identifier. Secrets are never exported.
+    ///
+    /// Returns the total number of bytes written.
+    ///
+    /// # Arguments
+    /// * `writer`: The destination of the JSON log
+    #[cfg(feature = "json-log")]
+    pub fn export_to_json_log(&self, writer: &mut dyn Write) -> Result<usize> {
+        let mut lines = vec![serde_json::json!({
+            "type": "header",
+            "stream_address": self.state.stream_address.map(|address| address.to_string()),
+            "base_branch": self.state.base_branch.to_string(),
+        })];
+        lines.extend(
+            self.state
+                .cursor_store
+                .latest_links()
+                .into_iter()
+                .map(|(topic, latest_link)| {
+                    serde_json::json!({
+                        "type": "branch",
+                        "topic": topic.to_string(),
+                        "latest_link": latest_link.to_string(),
+                    })
+                }),
+        );
+        lines.extend(
+            self.state
+                .cursor_store
+                .to_vec()
+                .into_iter()
+                .map(|(topic, permission, cursor)| {
+                    let permission_name = match permission {
+                        Permissioned::Read(_) => "read",
+                        Permissioned::ReadWrite(..) => "read_write",
+                        Permissioned::Admin(_) => "admin",
+                    };
+                    serde_json::json!({
+                        "type": "cursor",
+                        "topic": topic.to_string(),
+                        "identifier": permission.identifier().to_string(),
+                        "permission": permission_name,
+                        "cursor": cursor,
+                    })
+                }),
+        );
+        let mut pskids: Vec<&PskId> = self.state.psk_store.keys().collect();
+        pskids.sort();
+        lines.extend(pskids.into_iter().map(|pskid| {
+            serde_json::json!({
+                "type": "psk",
+                "pskid": pskid.to_string(),
+            })
+        }));
+
+        let mut written = 0;
+        for line in lines {
+            let mut bytes = line.to_string().into_bytes();
+            bytes.push(b'\n');
+            writer.write_all(&bytes).map_err(Error::JsonLog)?;
+            written += bytes.len();
+        }
+        Ok(written)
+    }
 
-        Ok(Message::from_lets_message(address, message))
+    /// Exports the [`CursorStore`] of the [`User`] as CSV, for inspection in spreadsheets. After a
+    /// header record, one record is written per publisher cursor, with the columns `topic`,
+    /// `identifier_hex`, `permission_type`, `cursor` and `latest_link_hex`, the latter being the
+    /// latest link of the branch of the cursor. Records are sorted by topic and then by identifier.
+    ///
+    /// The `permission_type` is one of `read`, `read_write` or `admin`. `ReadWrite` permissions
+    /// that are not perpetual carry their duration, such as `read_write:unix:1700000000`,
+    /// `read_write:branch_msgs:10` or `read_write:published_msgs:10`.
+    ///
+    /// # Arguments
+    /// * `writer`: The destination of the CSV
+    #[cfg(feature = "cursor-csv")]
+    pub fn export_cursor_store_csv(&self, writer: &mut dyn Write) -> Result<()> {
+        cursor_csv::write_record(writer, &cursor_csv::HEADER).map_err(|e| Error::CursorCsv("write", e))?;
+        for (topic, permission, cursor) in self.state.cursor_store.to_vec() {
+            let latest_link = self.get_latest_link(&topic).unwrap_or_default();
+            let record = [
+                topic.str(),
+                &permission.identifier().to_string(),
+                &cursor_csv::permission_type(&permission),
+                &cursor.to_string(),
+                &latest_link.to_string(),
+            ];
+            cursor_csv::write_record(writer, &record).map_err(|e| Error::CursorCsv("write", e))?;
+        }
+        Ok(())
     }
 
-    /// Processes a [`User`] subscription message, storing the subscriber [`Identifier`].
+    /// Imports a CSV exported with [`User::export_cursor_store_csv`], merging its records into the
+    /// [`CursorStore`] of the [`User`]. A cursor is imported if it is higher than the known cursor of
+    /// the publisher in the branch; publishers and permissions themselves are only changed by
+    /// keyloads. The latest link of a branch is only imported if the [`CursorStore`] holds none for
+    /// it.
     ///
-    /// # Arguments:
+    /// Every record is validated before any is merged: the import is rejected as a whole if a
+    /// record refers to an unknown branch, has an unknown `permission_type`, refers to a publisher
+    /// the [`User`] does not know in the branch or with a different permission, or has a latest link
+    /// that is not a message processed by the [`User`]. Returns the number of cursors imported.
+    ///
+    /// # Arguments
+    /// * `reader`: The source of the CSV
+    #[cfg(feature = "cursor-csv")]
+    pub fn import_cursor_store_csv(&mut self, reader: &mut dyn Read) -> Result<usize> {
+        let mut csv = String::new();
+        reader
+            .read_to_string(&mut csv)
+            .map_err(|e| Error::CursorCsv("read", e))?;
+        let mut records = cursor_csv::parse_records(&csv)
+            .ok_or(Error::InvalidCursorCsv(0, "unterminated quoted field"))?
+            .into_iter()
+            .enumerate();
+        match records.next() {
+            Some((_, header)) if header == cursor_csv::HEADER => {}
+            _ => return Err(Error::InvalidCursorCsv(0, "missing header")),
+        }
+
+        let mut entries = Vec::new();
+        for (index, record) in records {
+            let invalid = |cause: &'static str| Error::InvalidCursorCsv(index, cause);
+            let [topic, identifier, permission_type, cursor, latest_link]: [String; 5] =
+                record.try_into().map_err(|_| invalid("expected 5 columns"))?;
+            let topic = Topic::from(topic);
+            if !self.state.topics.contains(&topic) {
+                return Err(Error::TopicNotFound(topic));
+            }
+            let identifier: Identifier = identifier.parse().map_err(|_| invalid("invalid identifier_hex"))?;
+            let permission = cursor_csv::parse_permission(&permission_type, identifier)
+                .ok_or_else(|| invalid("unknown permission_type"))?;
+            let cursor: usize = cursor.parse().map_err(|_| invalid("invalid cursor"))?;
+            let latest_link: MsgId = latest_link.parse().map_err(|_| invalid("invalid latest_link_hex"))?;
+            match self.state.cursor_store.get_permission(&topic, permission.identifier()) {
+                Some(known) if known == &permission => {}
+                Some(_) => return Err(invalid("permission does not match the known one")),
+                None => return Err(invalid("unknown identifier")),
+            }
+            let is_announcement = self
+                .stream_address()
+                .map_or(false, |stream_address| stream_address.relative() == latest_link);
+            if !is_announcement && !self.state.link_store.contains_key(&latest_link) {
+                return Err(invalid("unknown latest_link_hex"));
+            }
+            entries.push((topic, permission, cursor, latest_link));
+        }
+
+        let mut imported = 0;
+        for (topic, permission, cursor, latest_link) in entries {
+            if self.get_latest_link(&topic).is_none() {
+                self.set_latest_link(topic.clone(), latest_link);
+            }
+            if self
+                .state
+                .cursor_store
+                .get_cursor(&topic, permission.identifier())
+                .map_or(false, |known_cursor| known_cursor < cursor)
+            {
+                self.state.cursor_store.insert_cursor(&topic, permission, cursor);
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Audits the integrity of the chain of messages of a branch without accessing the transport.
+    /// The chain is traversed from the latest link of the branch back to the stream announcement,
+    /// re-squeezing the stored [`Spongos`] state of each message and comparing it with the digest
+    /// recorded when the state was stored.
+    ///
+    /// A link is reported as broken if its [`Spongos`] state is missing or does not match the
+    /// recorded digest. If the message a link is linked to was never recorded, or its state has
+    /// been evicted by the [`SpongosRetention`] policy, it is reported as broken and the traversal
+    /// stops there.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to audit
+    pub fn audit_branch(&self, topic: &Topic) -> Result<BranchAudit> {
+        let stream_address = self.stream_address().ok_or(Error::NoStream("audit a branch"))?;
+        let latest_link = self
+            .get_latest_link(topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        let address = |msg_address: MsgId| Address::new(stream_address.base(), msg_address);
+
+        let mut chain_length = 0;
+        let mut first_link = latest_link;
+        let mut broken_links = Vec::new();
+        let mut next_link = Some(latest_link);
+        while let Some(link) = next_link {
+            chain_length += 1;
+            first_link = link;
+            match self.state.link_store.get(&link) {
+                Some((linked_msg_address, digest)) => {
+                    let is_intact = self
+                        .stored_spongos(&link)
+                        .map_or(false, |spongos| &link_digest(spongos, link) == digest);
+                    if !is_intact {
+                        broken_links.push(address(link));
+                    }
+                    next_link = *linked_msg_address;
+                }
+                None => {
+                    broken_links.push(address(link));
+                    next_link = None;
+                }
+            }
+        }
+        // Links were found from the latest to the first one
+        broken_links.reverse();
+
+        Ok(BranchAudit {
+            topic: topic.clone(),
+            chain_length,
+            first_address: address(first_link),
+            last_address: address(latest_link),
+            broken_links,
+        })
+    }
+
+    /// Returns the [`Spongos`] state held in store for a message, if any, so that custom protocol
+    /// extensions can chain their own operations to it. States that have been
+    /// [deduplicated](`User::compress_spongos_store`) are resolved to their canonical copy.
+    ///
+    /// # Arguments
+    /// * `msgid`: The link of the message
+    pub fn get_spongos_for_address(&self, msgid: &MsgId) -> Option<&Spongos> {
+        self.stored_spongos(msgid)
+    }
+
+    /// Returns the number of [`Spongos`] states held in store
+    pub fn spongos_count(&self) -> usize {
+        self.state.spongos_store.len()
+    }
+
+    /// Removes the [`Spongos`] states held in store for the messages published in a branch before a
+    /// checkpoint, for long-lived streams whose users commit to never syncing from before it. The
+    /// states of the messages of every known publisher of the branch whose cursor is strictly lower
+    /// than `before_cursor` are removed, except for the stream announcement and the latest message
+    /// of the branch, which the next message will be linked to. Returns the number of removed
+    /// states.
+    ///
+    /// Messages linked to a removed state can no longer be processed.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    /// * `before_cursor`: The publisher cursor of the checkpoint, whose state is kept
+    pub fn truncate_branch_history(&mut self, topic: &Topic, before_cursor: usize) -> Result<usize> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("truncate branch history"))?;
+        let latest_link = self
+            .get_latest_link(topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        let publishers: Vec<Identifier> = self
+            .cursors_by_topic(topic)?
+            .map(|(permission, _)| permission.identifier().clone())
+            .collect();
+
+        let mut removed = 0;
+        for publisher in &publishers {
+            for cursor in 0..before_cursor {
+                let link = MsgId::gen(stream_address.base(), publisher, topic, cursor);
+                if link == stream_address.relative() || link == latest_link {
+                    continue;
+                }
+                if self.stored_spongos(&link).is_some() {
+                    self.remove_spongos(&link);
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Returns a copy of the [`Spongos`] states held in store for the chain of messages of a branch,
+    /// from its latest link back to the stream announcement, so they can be archived externally
+    /// and later given back with [`User::restore_spongos`]. States missing from store are not
+    /// included.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    pub fn snapshot_spongos(&self, topic: &Topic) -> Result<HashMap<MsgId, Spongos>> {
+        Ok(self
+            .branch_chain(topic)?
+            .into_iter()
+            .filter_map(|link| self.stored_spongos(&link).map(|spongos| (link, *spongos)))
+            .collect())
+    }
+
+    /// Restores [`Spongos`] states archived with [`User::snapshot_spongos`] for the chain of
+    /// messages of a branch, inserting those missing from store. States of messages outside of
+    /// the chain, or that do not match the digest recorded when the message was processed, are
+    /// ignored. Restored states are stored according to the [`SpongosRetention`] policy, as if the
+    /// messages were processed again. Returns the number of restored states.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    /// * `spongos`: The archived [`Spongos`] states, mapped by message link
+    pub fn restore_spongos(&mut self, topic: &Topic, spongos: HashMap<MsgId, Spongos>) -> Result<usize> {
+        let mut restored = 0;
+        for link in self.branch_chain(topic)? {
+            if self.stored_spongos(&link).is_some() {
+                continue;
+            }
+            let archived = match spongos.get(&link) {
+                Some(archived) => archived,
+                None => continue,
+            };
+            let linked_msg_address = match self.state.link_store.get(&link) {
+                Some((linked_msg_address, digest)) if &link_digest(archived, link) == digest => *linked_msg_address,
+                _ => continue,
+            };
+            match linked_msg_address {
+                Some(linked_msg_address) => self.store_spongos(link, *archived, linked_msg_address),
+                None => self.insert_spongos(link, *archived, None),
+            }
+            restored += 1;
+        }
+        Ok(restored)
+    }
+
+    /// Reconciles the state of this [`User`] with the state of another instance of the same stream,
+    /// without going through the transport. The [`Spongos`] states of the messages processed by
+    /// only one of the users are copied over to the other one, along with the branches, latest
+    /// links and cursors it is missing. Returns the number of messages applied to this [`User`]
+    /// and to the `peer`.
+    ///
+    /// This is a best-effort operation: messages whose [`Spongos`] state is no longer held by the
+    /// user that processed them, does not match the digest recorded when it was stored, or whose
+    /// linked message is unknown to both users, remain unresolved. Copied states are stored
+    /// according to the [`SpongosRetention`] policy of the receiving user, and logged in its
+    /// message log.
+    ///
+    /// # Arguments
+    /// * `peer`: The [`User`] to sync with
+    pub fn peer_sync<T2, I2>(&mut self, peer: &mut User<T2, I2>) -> Result<(usize, usize)> {
+        if self.stream_address().is_none() || self.stream_address() != peer.stream_address() {
+            return Err(Error::Setup(
+                "peers must be connected to the same stream to sync with each other",
+            ));
+        }
+        let applied_to_self = self.apply_peer_state(peer);
+        let applied_to_peer = peer.apply_peer_state(self);
+        Ok((applied_to_self, applied_to_peer))
+    }
+
+    /// Copies the messages processed by a peer over to this [`User`], as well as the branches,
+    /// latest links and cursors the peer is ahead in. Returns the number of messages applied.
+    ///
+    /// # Arguments
+    /// * `peer`: The [`User`] to copy the state from
+    fn apply_peer_state<T2, I2>(&mut self, peer: &User<T2, I2>) -> usize {
+        // Messages processed by the peer only, whose linked message is known to either user. They are
+        // applied in the order the peer processed them, so that they are logged in that order too
+        let log_positions: HashMap<MsgId, usize> = peer
+            .state
+            .message_log
+            .iter()
+            .enumerate()
+            .map(|(position, entry)| (entry.address.relative(), position))
+            .collect();
+        let mut links: Vec<_> = peer.state.link_store.iter().collect();
+        links.sort_by_key(|(link, _)| log_positions.get(link).copied().unwrap_or(usize::MAX));
+        let mut applied = 0;
+        for (link, (linked_msg_address, digest)) in links {
+            if self.state.link_store.contains_key(link) {
+                continue;
+            }
+            let is_resolvable = linked_msg_address.map_or(true, |linked| {
+                self.state.link_store.contains_key(&linked) || peer.state.link_store.contains_key(&linked)
+            });
+            let spongos = match (is_resolvable, peer.stored_spongos(link)) {
+                (true, Some(spongos)) if &link_digest(spongos, *link) == digest => *spongos,
+                _ => continue,
+            };
+            match linked_msg_address {
+                Some(linked_msg_address) => self.store_spongos(*link, spongos, *linked_msg_address),
+                None => self.insert_spongos(*link, spongos, None),
+            }
+            if let Some(entry) = log_positions
+                .get(link)
+                .map(|position| &peer.state.message_log[*position])
+            {
+                let processed_at_cursor = self.state.message_log.len();
+                self.state.message_log.push(MessageLogEntry {
+                    processed_at_cursor,
+                    ..entry.clone()
+                });
+            }
+            applied += 1;
+        }
+
+        for topic in &peer.state.topics {
+            self.state.topics.insert(topic.clone());
+        }
+        for (topic, parent) in &peer.state.branch_parent {
+            if !self.state.branch_parent.contains_key(topic) {
+                self.state.branch_parent.insert(topic.clone(), parent.clone());
+            }
+        }
+
+        // Latest links the peer has moved further along the same chain
+        for (topic, peer_latest_link) in peer.state.cursor_store.latest_links() {
+            if !self.state.link_store.contains_key(&peer_latest_link) {
+                continue;
+            }
+            let is_ahead = match self.get_latest_link(&topic) {
+                Some(latest_link) => {
+                    latest_link != peer_latest_link
+                        && peer
+                            .branch_chain(&topic)
+                            .map_or(false, |chain| chain.contains(&latest_link))
+                }
+                None => true,
+            };
+            if is_ahead {
+                self.set_latest_link(topic, peer_latest_link);
+            }
+        }
+
+        // Cursors the peer is ahead in, as long as both users agree on the permission
+        for (topic, permission, cursor) in peer.state.cursor_store.to_vec() {
+            let id = permission.identifier();
+            if let Some(own_permission) = self.state.cursor_store.get_permission(&topic, id) {
+                if own_permission != &permission {
+                    continue;
+                }
+            }
+            if self
+                .state
+                .cursor_store
+                .get_cursor(&topic, id)
+                .map_or(true, |own_cursor| own_cursor < cursor)
+            {
+                if self.state.cursor_store.cursors_by_topic(&topic).is_none() {
+                    self.state.cursor_store.new_branch(topic.clone());
+                }
+                self.state.cursor_store.insert_cursor(&topic, permission, cursor);
+            }
+        }
+        applied
+    }
+
+    /// Returns the links of the chain of messages of a branch, from its latest link back to the
+    /// first recorded one
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch
+    fn branch_chain(&self, topic: &Topic) -> Result<Vec<MsgId>> {
+        let mut chain = Vec::new();
+        let mut next_link = Some(
+            self.get_latest_link(topic)
+                .ok_or_else(|| Error::TopicNotFound(topic.clone()))?,
+        );
+        while let Some(link) = next_link {
+            chain.push(link);
+            next_link = self
+                .state
+                .link_store
+                .get(&link)
+                .and_then(|(linked_msg_address, _)| *linked_msg_address);
+        }
+        Ok(chain)
+    }
+
+    /// Compares the cursors of this [`User`] with those of another instance of the same stream,
+    /// returning every cursor that differs between both, sorted by topic and then by publisher
+    /// identifier. Cursors stored by only one of the users are included too. Useful to debug
+    /// synchronization issues.
+    ///
+    /// # Arguments
+    /// * `other`: The [`User`] to compare against
+    pub fn cursor_diff<T2, I2>(&self, other: &User<T2, I2>) -> Vec<CursorDiff> {
+        let self_cursors = self.state.cursor_store.to_vec();
+        let other_cursors = other.state.cursor_store.to_vec();
+        let mut self_cursors = self_cursors.into_iter().peekable();
+        let mut other_cursors = other_cursors.into_iter().peekable();
+
+        // Both lists are sorted by topic then identifier, so they can be merged in a single pass
+        let mut diffs = Vec::new();
+        loop {
+            let order = match (self_cursors.peek(), other_cursors.peek()) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((self_topic, self_permission, _)), Some((other_topic, other_permission, _))) => self_topic
+                    .str()
+                    .cmp(other_topic.str())
+                    .then_with(|| self_permission.identifier().cmp(other_permission.identifier())),
+            };
+            let diff = match order {
+                Ordering::Less => self_cursors.next().map(|(topic, permission, cursor)| CursorDiff {
+                    topic,
+                    identifier: permission.identifier().clone(),
+                    self_cursor: Some(cursor),
+                    other_cursor: None,
+                }),
+                Ordering::Greater => other_cursors.next().map(|(topic, permission, cursor)| CursorDiff {
+                    topic,
+                    identifier: permission.identifier().clone(),
+                    self_cursor: None,
+                    other_cursor: Some(cursor),
+                }),
+                Ordering::Equal => {
+                    let (topic, permission, self_cursor) = self_cursors.next().expect("peeked entry must exist");
+                    let (_, _, other_cursor) = other_cursors.next().expect("peeked entry must exist");
+                    (self_cursor != other_cursor).then(|| CursorDiff {
+                        topic,
+                        identifier: permission.identifier().clone(),
+                        self_cursor: Some(self_cursor),
+                        other_cursor: Some(other_cursor),
+                    })
+                }
+            };
+            diffs.extend(diff);
+        }
+        diffs
+    }
+
+    /// Compares the state of this [`User`] with the state of another instance of the same stream,
+    /// returning the known branches, cursors and subscribers that differ between both, as well as
+    /// the difference in the number of stored [`Spongos`] states and pre shared keys. Useful to
+    /// debug replication issues.
+    ///
+    /// # Arguments
+    /// * `other`: The [`User`] to compare against
+    pub fn stream_diff<T2, I2>(&self, other: &User<T2, I2>) -> StreamDiff {
+        let topics_difference = |topics: &HashSet<Topic>, other_topics: &HashSet<Topic>| {
+            let mut difference: Vec<Topic> = topics.difference(other_topics).cloned().collect();
+            difference.sort_by(|a, b| a.str().cmp(b.str()));
+            difference
+        };
+        let subscribers_difference = |subscribers: &HashSet<Identifier>, other_subscribers: &HashSet<Identifier>| {
+            let mut difference: Vec<Identifier> = subscribers.difference(other_subscribers).cloned().collect();
+            difference.sort();
+            difference
+        };
+
+        StreamDiff {
+            missing_topics_in_self: topics_difference(&other.state.topics, &self.state.topics),
+            missing_topics_in_other: topics_difference(&self.state.topics, &other.state.topics),
+            cursor_diffs: self.cursor_diff(other),
+            extra_subscribers_in_self: subscribers_difference(&self.state.subscribers, &other.state.subscribers),
+            extra_subscribers_in_other: subscribers_difference(&other.state.subscribers, &self.state.subscribers),
+            spongos_count_diff: self.spongos_count() as i64 - other.spongos_count() as i64,
+            psk_count_diff: self.state.psk_store.len() as i64 - other.state.psk_store.len() as i64,
+        }
+    }
+
+    /// Returns the number of messages whose [`Spongos`] state is held in store, counting the
+    /// messages [deduplicated](`User::compress_spongos_store`) into a canonical copy as well.
+    fn spongos_count(&self) -> usize {
+        self.state.spongos_store.len() + self.state.spongos_aliases.len()
+    }
+
+    /// Finds the first cursor that differs between this [`User`] and another instance of the same
+    /// stream, comparing branch by branch and publisher by publisher. Returns the [`Topic`], the
+    /// publisher [`Identifier`] and the cursor stored by this [`User`], or the one stored by
+    /// `other` if this [`User`] has none. Returns `None` if both users hold the same cursors.
+    ///
+    /// # Arguments
+    /// * `other`: The [`User`] to compare against
+    pub fn find_divergence_point<T2, I2>(&self, other: &User<T2, I2>) -> Option<(Topic, Identifier, usize)> {
+        self.cursor_diff(other).into_iter().next().map(|diff| {
+            let cursor = diff.self_cursor.or(diff.other_cursor).unwrap_or_default();
+            (diff.topic, diff.identifier, cursor)
+        })
+    }
+
+    /// Retries processing the queued orphan messages, received before the message they are linked
+    /// to. Messages that are processed successfully are removed from the queue, while those still
+    /// missing their linked message are queued again and returned as orphans. Messages that fail
+    /// to be processed are not returned, but remain queued so they can be retried later or listed
+    /// with [`User::list_orphans`].
+    ///
+    /// Both resolved and unresolved messages are returned as [`Message`]s rather than as raw
+    /// addresses and preparsed messages, as an orphan [`Message`] already holds its [`Address`] and
+    /// its raw message, and the callers syncing the stream need the content of the resolved ones.
+    pub async fn fetch_orphan_messages(&mut self) -> Result<Vec<Message>> {
+        self.fetch_branch_orphan_messages(|_| true).await
+    }
+
+    /// Retries processing the queued orphan messages published in the branches accepted by
+    /// `in_branches`, as [`User::fetch_orphan_messages`] does. The orphans of the other branches are
+    /// left queued.
+    ///
+    /// # Arguments
+    /// * `in_branches`: Returns true for the [`TopicHash`] of the branches to retry the orphans of
+    async fn fetch_branch_orphan_messages<F>(&mut self, in_branches: F) -> Result<Vec<Message>>
+    where
+        F: Fn(&TopicHash) -> bool,
+    {
+        let orphans = core::mem::take(&mut self.state.orphan_store);
+        let mut messages = Vec::with_capacity(orphans.len());
+        for (address, msg) in orphans {
+            let preparsed: core::result::Result<PreparsedMessage, _> = msg.clone().parse_header().await;
+            if !preparsed.map_or(false, |preparsed| in_branches(preparsed.header().topic_hash())) {
+                if !self.state.orphan_store.iter().any(|(queued, _)| queued == &address) {
+                    self.state.orphan_store.push((address, msg));
+                }
+                continue;
+            }
+            match self.handle_message(address, msg.clone()).await {
+                Ok(message) => messages.push(message),
+                Err(_e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(%address, error = %_e, "keeping orphan message that failed to be processed");
+                    if !self.state.orphan_store.iter().any(|(queued, _)| queued == &address) {
+                        self.state.orphan_store.push((address, msg));
+                    }
+                }
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Retries processing the queued orphan messages once, returning only the messages resolved in
+    /// this pass. Orphans still missing their linked message remain queued, see
+    /// [`User::list_orphans`].
+    pub async fn retry_orphans(&mut self) -> Result<Vec<Message>> {
+        Ok(self
+            .fetch_orphan_messages()
+            .await?
+            .into_iter()
+            .filter(|message| !message.is_orphan())
+            .collect())
+    }
+
+    /// Processes an announcement message, binding a [`User`] to the stream announced in the
+    /// message.
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_announcement(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        // Check Topic
+        let publisher = preparsed.header().publisher().clone();
+
+        // Unwrap message
+        let announcement = announcement::Unwrap::new(preparsed.header().message_type());
+        let (message, spongos) = preparsed
+            .unwrap(announcement)
+            .await
+            .map_err(|e| Error::Unwrapping("announcement", address, e))?;
+
+        let topic = message.payload().content().topic();
+        // Insert new branch into store
+        self.state.cursor_store.new_branch(topic.clone());
+        self.state.topics.insert(topic.clone());
+        self.record_branch_creation_time(&topic);
+
+        // When handling an announcement it means that no cursors have been stored, as no topics are
+        // known yet. The message must be unwrapped to retrieve the initial topic before storing cursors
+        self.state
+            .cursor_store
+            .insert_cursor(topic, Permissioned::Admin(publisher), INIT_MESSAGE_NUM);
+
+        // Store spongos
+        self.insert_spongos(address.relative(), spongos, None);
+
+        // Store message content into stores
+        let author_id = message.payload().content().author_id().clone();
+        let parent_address = message.payload().content().parent_address().copied();
+
+        // Update branch links
+        self.set_latest_link(topic.clone(), address.relative());
+        self.state.author_identifier = Some(author_id);
+        self.state.base_branch = topic.clone();
+        self.state.stream_address = Some(address);
+        self.state.parent_address = parent_address;
+
+        Ok(Message::from_lets_message(address, message))
+    }
+
+    /// Processes a branch announcement message, creating a new branch in [`CursorStore`], carrying
+    /// over mapped permissions and cursors from the previous branch.
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_branch_announcement(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        // Retrieve header values
+        let prev_topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+
+        let publisher = preparsed.header().publisher().clone();
+        let cursor = preparsed.header().sequence();
+
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&prev_topic, &publisher)
+            .ok_or(Error::NoCursor(prev_topic.clone()))?
+            .clone();
+        self.state.cursor_store.insert_cursor(&prev_topic, permission, cursor);
+
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("branch announcement", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let branch_announcement = branch_announcement::Unwrap::new(&mut linked_msg_spongos);
+        let (message, spongos) = preparsed
+            .unwrap(branch_announcement)
+            .await
+            .map_err(|e| Error::Unwrapping("branch announcement", address, e))?;
+
+        let new_topic = message.payload().content().new_topic();
+        // Store spongos
+        self.store_spongos(address.relative(), spongos, linked_msg_address);
+        // Insert new branch into store
+        self.state.cursor_store.new_branch(new_topic.clone());
+        self.state.topics.insert(new_topic.clone());
+        self.state.branch_parent.insert(new_topic.clone(), prev_topic.clone());
+        self.record_branch_creation_time(new_topic);
+        // Collect permissions from previous branch and clone them into new branch
+        let prev_permissions = self
+            .cursors_by_topic(&prev_topic)?
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<Permissioned<Identifier>>>();
+        for id in prev_permissions {
+            self.state.cursor_store.insert_cursor(new_topic, id, INIT_MESSAGE_NUM);
+        }
+
+        // Update branch links
+        self.set_latest_link(new_topic.clone(), address.relative());
+
+        Ok(Message::from_lets_message(address, message))
+    }
+
+    /// Processes a [`User`] subscription message, storing the subscriber [`Identifier`]. Subscriptions
+    /// made with a [`Psk`] carry the [`Identifier`] derived from it, which is stored alike. The expiry
+    /// of ephemeral subscriptions is counted from the time the message is processed, and is only
+    /// recorded when the `std` feature is enabled.
+    ///
+    /// # Arguments:
     /// * `address`: The [`Address`] of the message to be processed
     /// * `preparsed`: The [`PreparsedMessage`] to be processed
     async fn handle_subscription(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
@@ -470,7 +2369,7 @@ impl<T> User<T> {
             .linked_msg_address()
             .ok_or(Error::NotLinked("subscription", address))?;
         let mut linked_msg_spongos = {
-            if let Some(spongos) = self.state.spongos_store.get(&linked_msg_address).copied() {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
                 // Spongos must be copied because wrapping mutates it
                 spongos
             } else {
@@ -483,7 +2382,8 @@ impl<T> User<T> {
             .ke_sk()
             .map_err(|_| Error::NoSecretKey)?;
 
-        let subscription = subscription::Unwrap::new(&mut linked_msg_spongos, user_ke_sk);
+        let message_type = preparsed.header().message_type();
+        let subscription = subscription::Unwrap::new(&mut linked_msg_spongos, user_ke_sk, message_type);
         let (message, _spongos) = preparsed
             .unwrap(subscription)
             .await
@@ -495,6 +2395,14 @@ impl<T> User<T> {
 
         // Store message content into stores
         let subscriber_identifier = message.payload().content().subscriber_identifier();
+        if message.payload().content().is_readonly() {
+            self.state.readonly_subscribers.insert(subscriber_identifier.clone());
+        }
+        if let (Some(ttl_seconds), Some(now)) = (message.payload().content().ttl_seconds(), unix_time_now()) {
+            self.state
+                .expiring_subscribers
+                .insert(subscriber_identifier.clone(), now.saturating_add(ttl_seconds));
+        }
         self.add_subscriber(subscriber_identifier.clone());
 
         Ok(Message::from_lets_message(address, message))
@@ -515,7 +2423,7 @@ impl<T> User<T> {
             .linked_msg_address()
             .ok_or(Error::NotLinked("unsubscribe", address))?;
         let mut linked_msg_spongos = {
-            if let Some(spongos) = self.state.spongos_store.get(&linked_msg_address) {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address) {
                 // Spongos must be cloned because wrapping mutates it
                 *spongos
             } else {
@@ -572,9 +2480,7 @@ impl<T> User<T> {
         // Ok to unwrap since an author identifier is set at the same time as the stream address
         let author_identifier = self.state.author_identifier.as_ref().unwrap();
         let mut announcement_spongos = self
-            .state
-            .spongos_store
-            .get(&stream_address.relative())
+            .stored_spongos(&stream_address.relative())
             .copied()
             .expect("a subscriber that has received an stream announcement must keep its spongos in store");
 
@@ -585,13 +2491,14 @@ impl<T> User<T> {
             author_identifier,
             &self.state.psk_store,
         );
+        let linked_msg_address = preparsed.header().linked_msg_address();
         let (message, spongos) = preparsed
             .unwrap(keyload)
             .await
             .map_err(|e| Error::Unwrapping("keyload", address, e))?;
 
         // Store spongos
-        self.state.spongos_store.insert(address.relative(), spongos);
+        self.insert_spongos(address.relative(), spongos, linked_msg_address);
 
         let subscribers = message.payload().content().subscribers();
 
@@ -658,7 +2565,7 @@ impl<T> User<T> {
             .linked_msg_address()
             .ok_or(Error::NotLinked("signed", address))?;
         let mut linked_msg_spongos = {
-            if let Some(spongos) = self.state.spongos_store.get(&linked_msg_address).copied() {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
                 // Spongos must be copied because wrapping mutates it
                 spongos
             } else {
@@ -708,7 +2615,7 @@ impl<T> User<T> {
             .linked_msg_address()
             .ok_or(Error::NotLinked("tagged", address))?;
         let mut linked_msg_spongos = {
-            if let Some(spongos) = self.state.spongos_store.get(&linked_msg_address).copied() {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
                 // Spongos must be copied because wrapping mutates it
                 spongos
             } else {
@@ -730,1034 +2637,8793 @@ impl<T> User<T> {
         Ok(Message::from_lets_message(address, message))
     }
 
-    /// Creates an encrypted, serialised representation of a [`User`] `State` for backup and
-    /// recovery.
+    /// Processes a heartbeat message, verifying the message signature against the publisher
+    /// [`Identifier`] and advancing the publisher cursor.
     ///
-    /// # Arguments
-    /// * `pwd`: The password to encrypt the `State` with
-    pub async fn backup<P>(&mut self, pwd: P) -> Result<Vec<u8>>
-    where
-        P: AsRef<[u8]>,
-    {
-        let mut ctx = sizeof::Context::new();
-        ctx.sizeof(&self.state).await.map_err(Error::Spongos)?;
-        let buf_size = ctx.finalize() + 32; // State + Mac Size
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_heartbeat(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission, preparsed.header().sequence());
 
-        let mut buf = vec![0; buf_size];
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("heartbeat", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let heartbeat = heartbeat::Unwrap::new(&mut linked_msg_spongos);
+        let (message, _spongos) = preparsed
+            .unwrap(heartbeat)
+            .await
+            .map_err(|e| Error::Unwrapping("heartbeat", address, e))?;
 
-        let mut ctx = wrap::Context::new(&mut buf[..]);
-        let key: [u8; 32] = SpongosRng::<KeccakF1600>::new(pwd).gen();
-        ctx.absorb(External::new(&NBytes::new(key)))
-            .map_err(Error::Spongos)?
-            .commit()
-            .map_err(Error::Spongos)?
-            .squeeze(&Mac::new(32))
-            .map_err(Error::Spongos)?;
-        ctx.wrap(&mut self.state).await.map_err(Error::Spongos)?;
-        assert!(
-            ctx.stream().is_empty(),
-            "Missmatch between buffer size expected by SizeOf ({buf_size}) and actual size of Wrap ({})",
-            ctx.stream().len()
-        );
+        // Store spongos
+        // Heartbeat messages are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
 
-        Ok(buf)
+        Ok(Message::from_lets_message(address, message))
     }
 
-    /// Restore a [`User`] from an encrypted binary stream using the provided password and transport
-    /// client.
+    /// Processes an announcement ack message. The MAC is verified against the [`Spongos`] state of
+    /// the acknowledged keyload, so it only verifies if the subscriber could unwrap the keyload,
+    /// and the signature is verified against the subscriber [`Identifier`]. Once verified, the
+    /// subscriber is marked as confirmed in the branch of the keyload.
     ///
     /// # Arguments
-    /// * `backup`: Encrypted binary stream of backed up `State`.
-    /// * `pwd`: The decryption password.
-    /// * `transport`: The transport client for sending and receiving messages.
-    pub async fn restore<B, P>(backup: B, pwd: P, transport: T) -> Result<Self>
-    where
-        P: AsRef<[u8]>,
-        B: AsRef<[u8]>,
-    {
-        let mut ctx = unwrap::Context::new(backup.as_ref());
-        let key: [u8; 32] = SpongosRng::<KeccakF1600>::new(pwd).gen();
-        ctx.absorb(External::new(&NBytes::new(key)))
-            .map_err(Error::Spongos)?
-            .commit()
-            .map_err(Error::Spongos)?
-            .squeeze(&Mac::new(32))
-            .map_err(Error::Spongos)?;
-        let mut state = State::default();
-        ctx.unwrap(&mut state).await.map_err(Error::Spongos)?;
-        Ok(User { transport, state })
-    }
-}
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_announcement_ack(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        // Cursor is not stored, as acks are not part of the sequence of messages of the subscriber
 
-impl<T> User<T>
-where
-    T: for<'a> Transport<'a, Msg = TransportMessage>,
-{
-    /// Receive a raw message packet using the internal [`Transport`] client
-    ///
-    /// # Arguments
-    /// * `address`: The [`Address`] of the message to be retrieved.
-    pub async fn receive_message(&mut self, address: Address) -> Result<Message>
-    where
-        T: for<'a> Transport<'a, Msg = TransportMessage>,
-    {
-        let msg = self
-            .transport
-            .recv_message(address)
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("announcement ack", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let announcement_ack = announcement_ack::Unwrap::new(&mut linked_msg_spongos);
+        let (message, _spongos) = preparsed
+            .unwrap(announcement_ack)
             .await
-            .map_err(|e| Error::Transport(address, "receive message", e))?;
-        self.handle_message(address, msg).await
-    }
-
-    /// Start a [`Messages`] stream to traverse the channel messages
-    ///
-    /// See the documentation in [`Messages`] for more details and examples.
-    pub fn messages(&mut self) -> Messages<T> {
-        Messages::new(self)
-    }
+            .map_err(|e| Error::Unwrapping("announcement ack", address, e))?;
 
-    /// Iteratively fetches all the next messages until internal state has caught up
-    ///
-    /// If succeeded, returns the number of messages advanced.
-    pub async fn sync(&mut self) -> Result<usize> {
-        // ignoring the result is sound as Drain::Error is Infallible
-        self.messages()
-            .try_fold(0, |n, _| future::ok(n + 1))
-            .await
-            .map_err(Error::Messages)
-    }
+        // Store message content into stores
+        // Announcement acks are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
+        let subscriber = message.payload().content().subscriber_identifier().clone();
+        self.state
+            .confirmed_subscribers
+            .entry(topic)
+            .or_default()
+            .insert(subscriber);
 
-    /// Iteratively fetches all the pending messages from the transport
-    ///
-    /// Return a vector with all the messages collected. This is a convenience
-    /// method around the [`Messages`] stream. Check out its docs for more
-    /// advanced usages.
-    pub async fn fetch_next_messages(&mut self) -> Result<Vec<Message>> {
-        self.messages().try_collect().await.map_err(Error::Messages)
+        Ok(Message::from_lets_message(address, message))
     }
-}
 
-impl<T, TSR> User<T>
-where
-    T: for<'a> Transport<'a, Msg = TransportMessage, SendResponse = TSR>,
-{
-    /// Create and send a stream Announcement message, anchoring the stream for others to attach to.
-    /// Errors if the [`User`] is already attached to a stream, or if the message already exists in
-    /// the transport layer.
+    /// Processes a stream link message, recording the [`Address`] of the remote stream referenced
+    /// by the branch, retrievable with [`User::get_linked_stream`].
     ///
     /// # Arguments
-    /// * `topic`: The [`Topic`] that will be used for the base branch
-    pub async fn create_stream<Top: Into<Topic>>(&mut self, topic: Top) -> Result<SendResponse<TSR>> {
-        // Check conditions
-        if self.stream_address().is_some() {
-            return Err(Error::Setup(
-                "Cannot create a channel, user is already registered to channel",
-            ));
-        }
-        // Confirm user has identity
-        let identifier = self.identifier().ok_or(Error::NoIdentity("create a stream"))?.clone();
-        // Convert topic
-        let topic = topic.into();
-        // Generate stream address
-        let stream_base_address = AppAddr::gen(&identifier, &topic);
-        let stream_rel_address = MsgId::gen(stream_base_address, &identifier, &topic, INIT_MESSAGE_NUM);
-        let stream_address = Address::new(stream_base_address, stream_rel_address);
-
-        // Prepare HDF and PCF
-        let header = HDF::new(message_types::ANNOUNCEMENT, ANN_MESSAGE_NUM, identifier.clone(), &topic);
-        let content = PCF::new_final_frame().with_content(announcement::Wrap::new(self.identity().unwrap(), &topic));
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_stream_link(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission, preparsed.header().sequence());
 
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("stream link", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let stream_link = stream_link::Unwrap::new(&mut linked_msg_spongos);
+        let (message, spongos) = preparsed
+            .unwrap(stream_link)
             .await
-            .map_err(|e| Error::Wrapped("wrap announce", e))?;
+            .map_err(|e| Error::Unwrapping("stream link", address, e))?;
 
-        // Attempt to send message
-        if !self.transport.recv_message(stream_address).await.is_err() {
-            return Err(Error::Setup("Cannot create a channel, announce address already in use"));
-        }
+        // Store spongos
+        self.store_spongos(address.relative(), spongos, linked_msg_address);
 
-        let send_response = self
-            .transport
-            .send_message(stream_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send announce message", e))?;
+        // Store message content into stores
+        let remote_stream_address = message.payload().content().remote_stream_address();
+        self.state.linked_streams.insert(topic.clone(), remote_stream_address);
+        self.set_latest_link(topic, address.relative());
+        Ok(Message::from_lets_message(address, message))
+    }
 
-        // If a message has been sent successfully, insert the base branch into store
-        self.state.cursor_store.new_branch(topic.clone());
-        self.state.topics.insert(topic.clone());
-        // Commit message to stores
+    /// Processes a stream catalog message, verifying the message signature against the publisher
+    /// [`Identifier`] and advancing the publisher cursor.
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_stream_catalog(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
         self.state
             .cursor_store
-            .insert_cursor(&topic, Permissioned::Admin(identifier.clone()), INIT_MESSAGE_NUM);
-        self.state.spongos_store.insert(stream_address.relative(), spongos);
+            .insert_cursor(&topic, permission, preparsed.header().sequence());
 
-        // Update branch links
-        self.set_latest_link(topic.clone(), stream_address.relative());
+        // Unwrap message
+        // Stream catalogs are not joined to any previous message, so no spongos is required
+        let (message, _spongos) = preparsed
+            .unwrap(stream_catalog::Unwrap::default())
+            .await
+            .map_err(|e| Error::Unwrapping("stream catalog", address, e))?;
 
-        // Commit Author Identifier and Stream Address to store
-        self.state.stream_address = Some(stream_address);
-        self.state.author_identifier = Some(identifier);
-        self.state.base_branch = topic;
+        // Store spongos
+        // Stream catalog messages are never linked to by other messages, so neither their spongos nor
+        // the branch latest link are stored
 
-        Ok(SendResponse::new(stream_address, send_response))
+        Ok(Message::from_lets_message(address, message))
     }
 
-    /// Create and send a new Branch Announcement message, creating a new branch in `CursorStore`
-    /// with the previous branches permissions carried forward.
+    /// Processes a direct message, decrypting its payload if the [`User`] is the recipient. If the
+    /// message is addressed to someone else, an orphan [`Message`] is returned instead.
     ///
-    /// # Arguments
-    /// * `from_topic`: The [`Topic`] of the branch to generate the new branch from.
-    /// * `to_topic`: The [`Topic`] of the new branch being created.
-    pub async fn new_branch(
-        &mut self,
-        from_topic: impl Into<Topic>,
-        to_topic: impl Into<Topic>,
-    ) -> Result<SendResponse<TSR>> {
-        // Check conditions
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_direct_message(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
         let stream_address = self
             .stream_address()
-            .ok_or(Error::Setup("before starting a new branch, the stream must be created"))?;
-        // Confirm user has identity
-        let identifier = self.identifier().ok_or(Error::NoIdentity("create a branch"))?.clone();
-        // Check Topic
-        let topic: Topic = to_topic.into();
-        let prev_topic: Topic = from_topic.into();
-        // Check Permission
+            .ok_or(Error::NoStream("handling a direct message"))?;
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Direct messages can be sent by any participant holding a
+        // cursor, so the cursor is only updated if the publisher is already known in the branch
+        if let Some(permission) = self.state.cursor_store.get_permission(&topic, publisher).cloned() {
+            self.state
+                .cursor_store
+                .insert_cursor(&topic, permission, preparsed.header().sequence());
+        }
+
+        // Unwrap message
+        // All direct messages are joined to the stream announcement message spongos
+        let mut announcement_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .expect("a subscriber that has received an stream announcement must keep its spongos in store");
+        let direct_message = direct_message::Unwrap::new(&mut announcement_spongos, self.state.user_id.as_ref());
+        let (message, _spongos) = preparsed
+            .clone()
+            .unwrap(direct_message)
+            .await
+            .map_err(|e| Error::Unwrapping("direct message", address, e))?;
+        if !message.payload().content().is_decrypted() {
+            return Ok(Message::orphan(address, preparsed));
+        }
+
+        // Store spongos
+        // Direct messages are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
+
+        Ok(Message::from_lets_message(address, message))
+    }
+
+    /// Processes a stream metadata message, confirming it was published by an admin of the branch.
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_stream_metadata(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("handling a stream metadata"))?;
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher().clone();
+        // Confirm stream metadata came from administrator
+        if !self
+            .state
+            .cursor_store
+            .get_permission(&topic, &publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .is_admin()
+        {
+            return Err(Error::WrongRole("admin", publisher, "receive stream metadata"));
+        }
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(publisher), preparsed.header().sequence());
+
+        // Unwrap message
+        // All stream metadata messages are joined to the stream announcement message spongos
+        let mut announcement_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .expect("a subscriber that has received an stream announcement must keep its spongos in store");
+        let (message, _spongos) = preparsed
+            .unwrap(stream_metadata::Unwrap::new(&mut announcement_spongos))
+            .await
+            .map_err(|e| Error::Unwrapping("stream metadata", address, e))?;
+
+        // Store spongos
+        // Stream metadata messages are never linked to by other messages, so neither their spongos nor
+        // the branch latest link are stored
+
+        let message = Message::from_lets_message(address, message);
+        self.state.stream_metadata = message.as_stream_metadata().cloned();
+        Ok(message)
+    }
+
+    /// Processes a stream metadata update message, confirming it was published by an admin of the
+    /// branch and replacing the stream metadata held by the [`User`].
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_metadata_update(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher().clone();
+        // Confirm stream metadata update came from administrator
+        if !self
+            .state
+            .cursor_store
+            .get_permission(&topic, &publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .is_admin()
+        {
+            return Err(Error::WrongRole("admin", publisher, "receive stream metadata update"));
+        }
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(publisher), preparsed.header().sequence());
+
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("stream metadata update", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let (message, _spongos) = preparsed
+            .unwrap(stream_metadata_update::Unwrap::new(&mut linked_msg_spongos))
+            .await
+            .map_err(|e| Error::Unwrapping("stream metadata update", address, e))?;
+
+        // Store spongos
+        // Stream metadata update messages are never linked to by other messages, so neither their
+        // spongos nor the branch latest link are stored
+
+        let message = Message::from_lets_message(address, message);
+        self.state.stream_metadata = message.as_stream_metadata_update().cloned();
+        Ok(message)
+    }
+
+    /// Processes a proof of inclusion message, verifying the message signature against the
+    /// publisher [`Identifier`] and advancing the publisher cursor. The proof itself is not checked
+    /// against the target message, see [`User::verify_proof_of_inclusion`].
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_proof_of_inclusion(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
         let permission = self
             .state
             .cursor_store
-            .get_permission(&prev_topic, &identifier)
-            .ok_or(Error::NoCursor(topic.clone()))?;
-        if permission.is_readonly() {
-            return Err(Error::WrongRole("ReadWrite", identifier, "make a new branch"));
+            .get_permission(&topic, publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission, preparsed.header().sequence());
+
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("proof of inclusion", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let proof_of_inclusion = proof_of_inclusion::Unwrap::new(&mut linked_msg_spongos);
+        let (message, _spongos) = preparsed
+            .unwrap(proof_of_inclusion)
+            .await
+            .map_err(|e| Error::Unwrapping("proof of inclusion", address, e))?;
+
+        // Store spongos
+        // Proof of inclusion messages are never linked to by other messages, so neither their spongos
+        // nor the branch latest link are stored
+
+        Ok(Message::from_lets_message(address, message))
+    }
+
+    /// Processes a multipart manifest message, verifying the message signature against the
+    /// publisher [`Identifier`] and advancing the publisher cursor. The parts themselves are not
+    /// fetched, see [`User::receive_multipart_message`].
+    ///
+    /// # Arguments:
+    /// * `address`: The [`Address`] of the message to be processed
+    /// * `preparsed`: The [`PreparsedMessage`] to be processed
+    async fn handle_multipart_manifest(&mut self, address: Address, preparsed: PreparsedMessage) -> Result<Message> {
+        let topic = self
+            .topic_by_hash(preparsed.header().topic_hash())
+            .ok_or(Error::UnknownTopic(*preparsed.header().topic_hash()))?;
+        let publisher = preparsed.header().publisher();
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, publisher)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // From the point of view of cursor tracking, the message exists, regardless of the validity or
+        // accessibility to its content. Therefore we must update the cursor of the publisher before
+        // handling the message
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission, preparsed.header().sequence());
+
+        // Unwrap message
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("multipart manifest", address))?;
+        let mut linked_msg_spongos = {
+            if let Some(spongos) = self.stored_spongos(&linked_msg_address).copied() {
+                // Spongos must be copied because wrapping mutates it
+                spongos
+            } else {
+                return Ok(Message::orphan(address, preparsed));
+            }
+        };
+        let multipart_manifest = multipart_manifest::Unwrap::new(&mut linked_msg_spongos);
+        let (message, _spongos) = preparsed
+            .unwrap(multipart_manifest)
+            .await
+            .map_err(|e| Error::Unwrapping("multipart manifest", address, e))?;
+
+        // Store spongos
+        // Multipart manifest messages are never linked to by other messages, so neither their spongos
+        // nor the branch latest link are stored
+
+        Ok(Message::from_lets_message(address, message))
+    }
+
+    /// Computes the digest of the [`Spongos`] state of a message held in store, as carried by
+    /// proof of inclusion messages.
+    ///
+    /// # Arguments
+    /// * `target_address`: The [`Address`] of the message to digest
+    fn inclusion_digest(&self, target_address: Address) -> Result<[u8; 32]> {
+        let spongos = self
+            .stored_spongos(&target_address.relative())
+            .ok_or(Error::MessageMissing(target_address.relative(), "spongos store"))?;
+        Ok(proof_of_inclusion::digest(spongos, target_address))
+    }
+
+    /// Verifies a raw [`TransportMessage`] against the current [`User`] state without processing
+    /// it. Linked [`Spongos`] states are copied out of store before unwrapping, so neither cursors
+    /// nor stored [`Spongos`] states are modified.
+    ///
+    /// If the message content cannot be unwrapped, including when the state of the linked message
+    /// is not in store, a [`VerifiedMessage`] with `is_valid` set to false is returned. Errors if
+    /// the message header cannot be parsed or the message type is unknown.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message to verify
+    /// * `msg`: The raw [`TransportMessage`]
+    pub async fn verify_message(&self, address: Address, msg: TransportMessage) -> Result<VerifiedMessage> {
+        let preparsed: PreparsedMessage = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", address, e))?;
+        let message_type = preparsed.header().message_type();
+        let publisher = preparsed.header().publisher().clone();
+        // Spongos must be copied because unwrapping mutates it
+        let linked_msg_spongos = preparsed
+            .header()
+            .linked_msg_address()
+            .and_then(|linked_msg_address| self.stored_spongos(&linked_msg_address).copied());
+
+        let is_valid = match message_type {
+            message_types::ANNOUNCEMENT | message_types::CHILD_ANNOUNCEMENT => {
+                preparsed.unwrap(announcement::Unwrap::new(message_type)).await.is_ok()
+            }
+            message_types::BRANCH_ANNOUNCEMENT => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(branch_announcement::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::SUBSCRIPTION | message_types::SUBSCRIPTION_WITH_OPTIONS => {
+                let user_ke_sk = self.identity().and_then(|id| id.ke_sk().ok());
+                match (linked_msg_spongos, user_ke_sk) {
+                    (Some(mut spongos), Some(user_ke_sk)) => preparsed
+                        .unwrap(subscription::Unwrap::new(&mut spongos, &user_ke_sk, message_type))
+                        .await
+                        .is_ok(),
+                    _ => false,
+                }
+            }
+            message_types::UNSUBSCRIPTION => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(unsubscription::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::KEYLOAD => {
+                let announcement_spongos = self
+                    .stream_address()
+                    .and_then(|stream_address| self.stored_spongos(&stream_address.relative()).copied());
+                match (announcement_spongos, self.state.author_identifier.as_ref()) {
+                    (Some(mut spongos), Some(author_identifier)) => preparsed
+                        .unwrap(keyload::Unwrap::new(
+                            &mut spongos,
+                            self.state.user_id.as_ref(),
+                            author_identifier,
+                            &self.state.psk_store,
+                        ))
+                        .await
+                        .is_ok(),
+                    _ => false,
+                }
+            }
+            message_types::SIGNED_PACKET => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(signed_packet::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::TAGGED_PACKET => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(tagged_packet::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::HEARTBEAT => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(heartbeat::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::STREAM_CATALOG => preparsed.unwrap(stream_catalog::Unwrap::default()).await.is_ok(),
+            message_types::DIRECT_MESSAGE => {
+                let announcement_spongos = self
+                    .stream_address()
+                    .and_then(|stream_address| self.stored_spongos(&stream_address.relative()).copied());
+                match announcement_spongos {
+                    Some(mut spongos) => preparsed
+                        .unwrap(direct_message::Unwrap::new(&mut spongos, self.state.user_id.as_ref()))
+                        .await
+                        .map_or(false, |(message, _)| message.payload().content().is_decrypted()),
+                    None => false,
+                }
+            }
+            message_types::STREAM_METADATA => {
+                let announcement_spongos = self
+                    .stream_address()
+                    .and_then(|stream_address| self.stored_spongos(&stream_address.relative()).copied());
+                match announcement_spongos {
+                    Some(mut spongos) => preparsed
+                        .unwrap(stream_metadata::Unwrap::new(&mut spongos))
+                        .await
+                        .is_ok(),
+                    None => false,
+                }
+            }
+            message_types::STREAM_METADATA_UPDATE => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(stream_metadata_update::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::PROOF_OF_INCLUSION => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(proof_of_inclusion::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::MULTIPART_MANIFEST => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(multipart_manifest::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::ANNOUNCEMENT_ACK => match linked_msg_spongos {
+                Some(mut spongos) => preparsed
+                    .unwrap(announcement_ack::Unwrap::new(&mut spongos))
+                    .await
+                    .is_ok(),
+                None => false,
+            },
+            message_types::STREAM_LINK => match linked_msg_spongos {
+                Some(mut spongos) => preparsed.unwrap(stream_link::Unwrap::new(&mut spongos)).await.is_ok(),
+                None => false,
+            },
+            unknown => return Err(Error::MessageTypeUnknown(unknown)),
+        };
+
+        Ok(VerifiedMessage::new(address, message_type, publisher, is_valid))
+    }
+
+    /// Computes the size in bytes of the backup [`User::backup`] would produce for the current
+    /// `State`, without serializing it.
+    pub async fn estimate_backup_size(&self) -> Result<usize> {
+        let mut ctx = sizeof::Context::new();
+        ctx.sizeof(&self.state).await.map_err(Error::Spongos)?;
+        Ok(ctx.finalize() + 32) // State + Mac Size
+    }
+
+    /// Creates an encrypted, serialised representation of a [`User`] `State` for backup and
+    /// recovery.
+    ///
+    /// # Arguments
+    /// * `pwd`: The password to encrypt the `State` with
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn backup<P>(&mut self, pwd: P) -> Result<Vec<u8>>
+    where
+        P: AsRef<[u8]>,
+    {
+        let key: [u8; 32] = SpongosRng::<KeccakF1600>::new(pwd).gen();
+        self.serialize_state(Some(key)).await
+    }
+
+    /// Serializes the `State` of the [`User`]. When a `key` is provided, the `State` is encrypted
+    /// with it and preceded by a MAC, as expected by [`User::restore`].
+    async fn serialize_state(&mut self, key: Option<[u8; 32]>) -> Result<Vec<u8>> {
+        let buf_size = match key {
+            Some(_) => self.estimate_backup_size().await?,
+            None => {
+                let mut ctx = sizeof::Context::new();
+                ctx.sizeof(&self.state).await.map_err(Error::Spongos)?;
+                ctx.finalize()
+            }
+        };
+
+        let mut buf = vec![0; buf_size];
+
+        let mut ctx = wrap::Context::new(&mut buf[..]);
+        if let Some(key) = key {
+            ctx.absorb(External::new(&NBytes::new(key)))
+                .map_err(Error::Spongos)?
+                .commit()
+                .map_err(Error::Spongos)?
+                .squeeze(&Mac::new(32))
+                .map_err(Error::Spongos)?;
         }
-        let link_to = self
-            .get_latest_link(&prev_topic)
-            .ok_or_else(|| Error::TopicNotFound(prev_topic.clone()))?;
+        ctx.wrap(&mut self.state).await.map_err(Error::Spongos)?;
+        assert!(
+            ctx.stream().is_empty(),
+            "Mismatch between buffer size expected by SizeOf ({buf_size}) and actual size of Wrap ({})",
+            ctx.stream().len()
+        );
+
+        Ok(buf)
+    }
+
+    /// Restore a [`User`] from an encrypted binary stream using the provided password and transport
+    /// client.
+    ///
+    /// # Arguments
+    /// * `backup`: Encrypted binary stream of backed up `State`.
+    /// * `pwd`: The decryption password.
+    /// * `transport`: The transport client for sending and receiving messages.
+    ///
+    /// Errors if the [`User`] is marked with [`HasIdentity`] but the backup contains no
+    /// [`Identity`].
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn restore<B, P>(backup: B, pwd: P, transport: T) -> Result<Self>
+    where
+        P: AsRef<[u8]>,
+        B: AsRef<[u8]>,
+        I: IdentityMarker,
+    {
+        let mut ctx = unwrap::Context::new(backup.as_ref());
+        let key: [u8; 32] = SpongosRng::<KeccakF1600>::new(pwd).gen();
+        ctx.absorb(External::new(&NBytes::new(key)))
+            .map_err(Error::Spongos)?
+            .commit()
+            .map_err(Error::Spongos)?
+            .squeeze(&Mac::new(32))
+            .map_err(Error::Spongos)?;
+        let mut state = State::default();
+        ctx.unwrap(&mut state).await.map_err(Error::Spongos)?;
+        if I::HAS_IDENTITY && state.user_id.is_none() {
+            return Err(Error::NoIdentity("restore a user marked with an identity"));
+        }
+        Ok(User {
+            transport,
+            state,
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
+        })
+    }
+
+    /// Sets the [`User`] aside between operations, serializing its state without encryption and
+    /// keeping its transport client until it is [resumed](`PausedUser::resume`). This is a cheaper
+    /// checkpoint than a [backup](`User::backup`), meant for trusted local storage only. As with
+    /// backups, the transport options are not kept.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub async fn pause(mut self) -> Result<PausedUser<T, I>> {
+        let buf = self.serialize_state(None).await?;
+        Ok(PausedUser::new(buf, self.transport, self.send_hooks))
+    }
+
+    /// Reconstructs a [`User`] from the unencrypted state bytes of a [`PausedUser`].
+    ///
+    /// # Arguments
+    /// * `state_bytes`: The unencrypted serialization of the `State`
+    /// * `transport`: The transport client for sending and receiving messages
+    /// * `send_hooks`: The hooks called around the dispatch of the sent messages
+    pub(crate) async fn from_state_bytes(state_bytes: &[u8], transport: T, send_hooks: SendHooks<T>) -> Result<Self>
+    where
+        I: IdentityMarker,
+    {
+        let mut ctx = unwrap::Context::new(state_bytes);
+        let mut state = State::default();
+        ctx.unwrap(&mut state).await.map_err(Error::Spongos)?;
+        if I::HAS_IDENTITY && state.user_id.is_none() {
+            return Err(Error::NoIdentity("resume a user marked with an identity"));
+        }
+        Ok(User {
+            transport,
+            state,
+            identity_marker: PhantomData,
+            send_hooks,
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
+        })
+    }
+}
+
+impl<T, I> User<T, I>
+where
+    T: for<'a> Transport<'a, Msg = TransportMessage>,
+{
+    /// Receive a raw message packet using the internal [`Transport`] client
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message to be retrieved.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn receive_message(&mut self, address: Address) -> Result<Message>
+    where
+        T: for<'a> Transport<'a, Msg = TransportMessage>,
+    {
+        let msg = self
+            .configured_transport()
+            .recv_message(address)
+            .await
+            .map_err(|e| Error::transport(address, "receive message", e))?;
+        self.handle_message(address, msg).await
+    }
+
+    /// Receive the Announcement message of a stream using the internal [`Transport`] client,
+    /// attaching the [`User`] to the stream so that it is ready to [subscribe](`User::subscribe`).
+    /// Errors without processing the message if it is not an Announcement.
+    ///
+    /// # Arguments
+    /// * `stream_address`: The [`Address`] of the stream Announcement message.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn receive_announcement(&mut self, stream_address: Address) -> Result<Message> {
+        let msg = self
+            .configured_transport()
+            .recv_message(stream_address)
+            .await
+            .map_err(|e| Error::transport(stream_address, "receive announcement", e))?;
+        let preparsed: PreparsedMessage = msg
+            .clone()
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", stream_address, e))?;
+        let message_type = preparsed.header().message_type();
+        if message_type != message_types::ANNOUNCEMENT && message_type != message_types::CHILD_ANNOUNCEMENT {
+            return Err(Error::UnexpectedMessageType {
+                expected: message_types::ANNOUNCEMENT,
+                got: message_type,
+            });
+        }
+        self.handle_message(stream_address, msg).await
+    }
+
+    /// Receive the messages at the provided [`Address`]es, fetching them concurrently from the
+    /// internal [`Transport`] client. The retrieved messages are then processed one after the
+    /// other, in the order of `addresses`, so messages should be listed after the messages they
+    /// are linked to. Returns the result of receiving each message, in the same order.
+    ///
+    /// # Arguments
+    /// * `addresses`: The [`Address`]es of the messages to be retrieved.
+    pub async fn batch_receive(&mut self, addresses: Vec<Address>) -> Result<Vec<Result<Message>>>
+    where
+        T: Clone,
+    {
+        let fetches = addresses.iter().map(|address| {
+            let mut transport = self.configured_transport_copy();
+            let address = *address;
+            async move {
+                transport
+                    .recv_message(address)
+                    .await
+                    .map_err(|e| Error::transport(address, "receive message", e))
+            }
+        });
+        let fetched = future::join_all(fetches).await;
+
+        let mut received = Vec::with_capacity(addresses.len());
+        for (address, msg) in addresses.into_iter().zip(fetched) {
+            received.push(match msg {
+                Ok(msg) => self.handle_message(address, msg).await,
+                Err(e) => Err(e),
+            });
+        }
+        Ok(received)
+    }
+
+    /// Receive the Keyload message at the provided [`Address`] using the internal [`Transport`]
+    /// client, returning the encryption key it shares with its subscribers along with its nonce,
+    /// for instance to escrow the key of a branch. The [`User`] must be included in the keyload,
+    /// either through its [`Identity`] or through one of its [`Psk`]s. The message is not
+    /// processed, so the [`User`] state is left untouched.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the keyload message to be retrieved.
+    pub async fn receive_keyload_keys(&mut self, address: Address) -> Result<([u8; 32], [u8; 16])> {
+        let msg = self
+            .configured_transport()
+            .recv_message(address)
+            .await
+            .map_err(|e| Error::transport(address, "receive message", e))?;
+        let preparsed = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", address, e))?;
+        let message_type = preparsed.header().message_type();
+        if message_type != message_types::KEYLOAD {
+            return Err(Error::UnexpectedMessageType {
+                expected: message_types::KEYLOAD,
+                got: message_type,
+            });
+        }
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("receive the keys of a keyload"))?;
+        // Ok to unwrap since an author identifier is set at the same time as the stream address
+        let author_identifier = self.state.author_identifier.as_ref().unwrap();
+        // Spongos must be copied because unwrapping mutates it
+        let mut announcement_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .ok_or(Error::Setup("a user must keep a stream announcement spongos in store"))?;
+        let (message, _) = preparsed
+            .unwrap(keyload::Unwrap::new(
+                &mut announcement_spongos,
+                self.state.user_id.as_ref(),
+                author_identifier,
+                &self.state.psk_store,
+            ))
+            .await
+            .map_err(|e| Error::Unwrapping("keyload", address, e))?;
+        let keyload = message.payload().content();
+        let key = keyload.key().ok_or(Error::NotInKeyload(address))?;
+        Ok((key, keyload.nonce()))
+    }
+
+    /// Waits for a message to be available at the provided [`Address`], polling the internal
+    /// [`Transport`] client every `poll_interval` until it is retrieved, then processes it. Returns
+    /// [`Error::MessageNotFound`] if the message could not be retrieved before `timeout` elapsed.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message to be retrieved.
+    /// * `poll_interval`: Time to wait between two attempts to retrieve the message
+    /// * `timeout`: Maximum time to wait for the message
+    #[cfg(feature = "wait-for-message")]
+    pub async fn wait_for_message(
+        &mut self,
+        address: Address,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Message> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Ok(msg) = self.configured_transport().recv_message(address).await {
+                return self.handle_message(address, msg).await;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::MessageNotFound(address));
+            }
+            sleep(poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Receive a Signed or Tagged Packet using the internal [`Transport`] client, deserializing its
+    /// public payload from JSON into `M`. Returns the [`Address`] of the message, the deserialized
+    /// public payload and the raw masked payload.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message to be retrieved.
+    #[cfg(feature = "typed-payloads")]
+    pub async fn receive_message_typed<M>(&mut self, address: Address) -> Result<(Address, M, Vec<u8>)>
+    where
+        M: serde::de::DeserializeOwned,
+    {
+        let message = self.receive_message(address).await?;
+        let message_type = message.header().message_type();
+        let (public_payload, masked_payload) = match message.content {
+            MessageContent::SignedPacket(packet) => (packet.public_payload, packet.masked_payload),
+            MessageContent::TaggedPacket(packet) => (packet.public_payload, packet.masked_payload),
+            _ => {
+                return Err(Error::UnexpectedMessageType {
+                    expected: message_types::SIGNED_PACKET,
+                    got: message_type,
+                })
+            }
+        };
+        let value = serde_json::from_slice(&public_payload).map_err(|e| Error::TypedPayload("deserialize", e))?;
+        Ok((message.address, value, masked_payload))
+    }
+
+    /// Retrieves the stream catalog message found at the provided [`Address`] using the internal
+    /// [`Transport`] client, returning the stream [`Address`]es it lists. The catalog is verified
+    /// against its publisher signature, but the [`User`] state is left untouched.
+    ///
+    /// # Arguments
+    /// * `discovery_address`: The [`Address`] of the stream catalog message.
+    pub async fn read_catalog(&mut self, discovery_address: Address) -> Result<Vec<Address>> {
+        let msg = self
+            .configured_transport()
+            .recv_message(discovery_address)
+            .await
+            .map_err(|e| Error::transport(discovery_address, "read stream catalog", e))?;
+        let preparsed = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", discovery_address, e))?;
+        match preparsed.header().message_type() {
+            message_types::STREAM_CATALOG => {
+                let (message, _spongos) = preparsed
+                    .unwrap(stream_catalog::Unwrap::default())
+                    .await
+                    .map_err(|e| Error::Unwrapping("stream catalog", discovery_address, e))?;
+                let (_publisher, streams) = message.into_payload().into_content().into_parts();
+                Ok(streams)
+            }
+            message_type => Err(Error::UnexpectedMessageType {
+                expected: message_types::STREAM_CATALOG,
+                got: message_type,
+            }),
+        }
+    }
+
+    /// Receive a Stream Metadata message using the internal [`Transport`] client, returning the
+    /// latest [`StreamMetadata`] of the stream. The stream is synced to follow the
+    /// [updates](`User::send_announcement_update`) published after the message, and the
+    /// [`StreamMetadata`] of the latest one processed is returned.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the stream metadata message to be retrieved.
+    pub async fn read_stream_metadata(&mut self, address: Address) -> Result<StreamMetadata> {
+        let message = self.read_linked_message(address).await?;
+        let metadata = match message.content {
+            MessageContent::StreamMetadata(metadata) => metadata,
+            _ => {
+                return Err(Error::UnexpectedMessageType {
+                    expected: message_types::STREAM_METADATA,
+                    got: message.header.message_type(),
+                })
+            }
+        };
+        // Processing the stream in order leaves the latest metadata in state
+        self.sync().await?;
+        Ok(self.stream_metadata().cloned().unwrap_or(metadata))
+    }
+
+    /// Receives a proof of inclusion message and checks that the digest it carries matches the
+    /// [`Spongos`] state of its target message. Returns false if the digests differ.
+    ///
+    /// The target message must have been processed by the [`User`] beforehand, so that its state is
+    /// held in store.
+    ///
+    /// # Arguments
+    /// * `proof_address`: The [`Address`] of the proof of inclusion message
+    pub async fn verify_proof_of_inclusion(&mut self, proof_address: Address) -> Result<bool> {
+        let message = self.receive_message(proof_address).await?;
+        match message.content {
+            MessageContent::ProofOfInclusion(proof) => Ok(self.inclusion_digest(proof.target_address)? == proof.digest),
+            _ => Err(Error::UnexpectedMessageType {
+                expected: message_types::PROOF_OF_INCLUSION,
+                got: message.header.message_type(),
+            }),
+        }
+    }
+
+    /// Receives a multipart manifest message and reassembles the document it advertises,
+    /// concatenating the masked payloads of its parts in order.
+    ///
+    /// The manifest and its parts are read without being processed, so the [`User`] must hold the
+    /// [`Spongos`] states of the messages they are linked to. This is the case once the [`User`] has
+    /// synced up to the manifest, provided it retains the states of all the parts.
+    ///
+    /// # Arguments
+    /// * `manifest_address`: The [`Address`] of the multipart manifest message
+    pub async fn receive_multipart_message(&mut self, manifest_address: Address) -> Result<Vec<u8>> {
+        let message = self.read_linked_message(manifest_address).await?;
+        let manifest = match message.content {
+            MessageContent::MultipartManifest(manifest) => manifest,
+            _ => {
+                return Err(Error::UnexpectedMessageType {
+                    expected: message_types::MULTIPART_MANIFEST,
+                    got: message.header.message_type(),
+                })
+            }
+        };
+        let mut document = Vec::new();
+        for part_address in manifest.part_addresses {
+            let part = self.read_linked_message(part_address).await?;
+            match part.content {
+                MessageContent::SignedPacket(part) => document.extend(part.masked_payload),
+                _ => {
+                    return Err(Error::UnexpectedMessageType {
+                        expected: message_types::SIGNED_PACKET,
+                        got: part.header.message_type(),
+                    })
+                }
+            }
+        }
+        Ok(document)
+    }
+
+    /// Receives a signed packet, multipart manifest or stream metadata message and unwraps it out of
+    /// a copy of the [`Spongos`] state of its linked message, without processing it. Neither cursors
+    /// nor stored [`Spongos`] states are modified.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message to read
+    async fn read_linked_message(&mut self, address: Address) -> Result<Message> {
+        let msg = self
+            .configured_transport()
+            .recv_message(address)
+            .await
+            .map_err(|e| Error::transport(address, "receive message", e))?;
+        let preparsed: PreparsedMessage = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", address, e))?;
+        let linked_msg_address = preparsed
+            .header()
+            .linked_msg_address()
+            .ok_or(Error::NotLinked("read", address))?;
+        // Spongos must be copied because unwrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&linked_msg_address)
+            .copied()
+            .ok_or(Error::MessageMissing(linked_msg_address, "spongos store"))?;
+        match preparsed.header().message_type() {
+            message_types::SIGNED_PACKET => {
+                let (message, _spongos) = preparsed
+                    .unwrap(signed_packet::Unwrap::new(&mut linked_msg_spongos))
+                    .await
+                    .map_err(|e| Error::Unwrapping("signed packet", address, e))?;
+                Ok(Message::from_lets_message(address, message))
+            }
+            message_types::MULTIPART_MANIFEST => {
+                let (message, _spongos) = preparsed
+                    .unwrap(multipart_manifest::Unwrap::new(&mut linked_msg_spongos))
+                    .await
+                    .map_err(|e| Error::Unwrapping("multipart manifest", address, e))?;
+                Ok(Message::from_lets_message(address, message))
+            }
+            message_types::STREAM_METADATA => {
+                let (message, _spongos) = preparsed
+                    .unwrap(stream_metadata::Unwrap::new(&mut linked_msg_spongos))
+                    .await
+                    .map_err(|e| Error::Unwrapping("stream metadata", address, e))?;
+                Ok(Message::from_lets_message(address, message))
+            }
+            unknown => Err(Error::MessageTypeUnknown(unknown)),
+        }
+    }
+
+    /// Start a [`Messages`] stream to traverse the channel messages
+    ///
+    /// See the documentation in [`Messages`] for more details and examples.
+    pub fn messages(&mut self) -> Messages<T, I> {
+        Messages::new(self)
+    }
+
+    /// Iteratively fetches all the next messages until internal state has caught up
+    ///
+    /// If succeeded, returns the number of messages advanced.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn sync(&mut self) -> Result<usize> {
+        // ignoring the result is sound as Drain::Error is Infallible
+        self.messages()
+            .try_fold(0, |n, _| future::ok(n + 1))
+            .await
+            .map_err(Error::Messages)
+    }
+
+    /// Iteratively fetches all the next messages until internal state has caught up, fetching the
+    /// messages of up to `concurrency` branches at the same time. Each branch is fetched with its
+    /// own copy of the transport client, while the fetched messages are processed one at a time in
+    /// branch order, so the state is never updated concurrently.
+    ///
+    /// If succeeded, returns the number of messages advanced.
+    ///
+    /// # Arguments
+    /// * `concurrency`: The maximum number of branches fetched at the same time
+    pub async fn parallel_sync(&mut self, concurrency: usize) -> Result<usize>
+    where
+        T: Clone,
+    {
+        let mut count = 0;
+        loop {
+            let base_address = match self.stream_address() {
+                Some(stream_address) => stream_address.base(),
+                None => return Ok(count),
+            };
+            let branches = self.writable_cursors_by_branch(|_| true);
+            let transport = self.configured_transport_copy();
+            let mut fetched: Vec<(usize, Vec<(Address, TransportMessage)>)> =
+                stream::iter(branches.into_iter().enumerate())
+                    .map(|(index, (topic, publishers))| {
+                        let transport = transport.clone();
+                        async move {
+                            let msgs = Self::fetch_branch_messages(transport, base_address, topic, publishers).await;
+                            (index, msgs)
+                        }
+                    })
+                    .buffer_unordered(concurrency.max(1))
+                    .collect()
+                    .await;
+            fetched.sort_by_key(|(index, _)| *index);
+
+            let mut advanced = 0;
+            for (address, msg) in fetched.into_iter().flat_map(|(_, msgs)| msgs) {
+                // message-Handling errors are a normal execution path, just skip them
+                if let Ok(message) = self.handle_message(address, msg).await {
+                    if !message.is_orphan() {
+                        advanced += 1;
+                    }
+                }
+            }
+            // Messages linked to a message of another publisher may have been processed before it
+            advanced += self
+                .fetch_orphan_messages()
+                .await?
+                .iter()
+                .filter(|message| !message.is_orphan())
+                .count();
+
+            if advanced == 0 {
+                return Ok(count);
+            }
+            count += advanced;
+        }
+    }
+
+    /// Iteratively fetches the next messages of the specified branches until internal state has
+    /// caught up with them, fetching the messages of all the branches at the same time. Each branch
+    /// is fetched with its own copy of the transport client, while the fetched messages are
+    /// processed one at a time in branch order, so the state is never updated concurrently.
+    /// Messages of other branches are not fetched, so their cursors are left unchanged.
+    ///
+    /// Returns the number of messages advanced in each of the specified branches. Topics of
+    /// unknown branches are skipped.
+    ///
+    /// # Arguments
+    /// * `topics`: The [`Topic`]s of the branches to sync
+    pub async fn multi_branch_sync(&mut self, topics: &[Topic]) -> Result<HashMap<Topic, usize>>
+    where
+        T: Clone,
+    {
+        let mut counts: HashMap<Topic, usize> = topics
+            .iter()
+            .filter(|topic| self.state.topics.contains(*topic))
+            .map(|topic| (topic.clone(), 0))
+            .collect();
+        loop {
+            let base_address = match self.stream_address() {
+                Some(stream_address) => stream_address.base(),
+                None => return Ok(counts),
+            };
+            let branches = self.writable_cursors_by_branch(|topic| counts.contains_key(topic));
+            let transport = self.configured_transport_copy();
+            let mut fetches: FuturesUnordered<_> = branches
+                .into_iter()
+                .enumerate()
+                .map(|(index, (topic, publishers))| {
+                    let transport = transport.clone();
+                    async move {
+                        let msgs = Self::fetch_branch_messages(transport, base_address, topic, publishers).await;
+                        (index, msgs)
+                    }
+                })
+                .collect();
+            let mut fetched: Vec<(usize, Vec<(Address, TransportMessage)>)> = Vec::new();
+            while let Some(branch_msgs) = fetches.next().await {
+                fetched.push(branch_msgs);
+            }
+            fetched.sort_by_key(|(index, _)| *index);
+
+            let mut processed = Vec::new();
+            for (address, msg) in fetched.into_iter().flat_map(|(_, msgs)| msgs) {
+                // message-Handling errors are a normal execution path, just skip them
+                if let Ok(message) = self.handle_message(address, msg).await {
+                    processed.push(message);
+                }
+            }
+            // Messages linked to a message of another publisher may have been processed before it
+            let topic_hashes: Vec<TopicHash> = counts.keys().map(TopicHash::from).collect();
+            processed.extend(
+                self.fetch_branch_orphan_messages(|topic_hash| topic_hashes.contains(topic_hash))
+                    .await?,
+            );
+
+            let mut advanced = 0;
+            for message in processed.iter().filter(|message| !message.is_orphan()) {
+                advanced += 1;
+                if let Some(count) = self
+                    .topic_by_hash(message.topic_hash())
+                    .and_then(|topic| counts.get_mut(&topic))
+                {
+                    *count += 1;
+                }
+            }
+            if advanced == 0 {
+                return Ok(counts);
+            }
+        }
+    }
+
+    /// Iteratively fetches the next messages of the specified branches until internal state has
+    /// caught up with them, sending a copy of every fetched message to `other_transport` at its
+    /// original [`Address`] before processing it. The [`User`] state is updated exactly as with
+    /// [`User::multi_branch_sync`], so the messages mirrored by a call are not mirrored again by the
+    /// next one. Useful to replicate a stream to a secondary node or an archive.
+    ///
+    /// The stream announcement and the subscriptions of the subscribers known to the [`User`] are
+    /// mirrored as well, unless `other_transport` already holds them, so that users can join the
+    /// stream from `other_transport`.
+    ///
+    /// Returns the number of messages mirrored. Branches that are not known yet are mirrored as
+    /// soon as their announcement has been processed, while topics of branches that are never
+    /// announced are skipped.
+    ///
+    /// # Arguments
+    /// * `other_transport`: The [`Transport`] client the messages are mirrored to
+    /// * `topics`: The [`Topic`]s of the branches to mirror
+    pub async fn mirror_to_transport<T2>(&mut self, mut other_transport: T2, topics: &[Topic]) -> Result<usize>
+    where
+        T: Clone,
+        T2: for<'a> Transport<'a, Msg = TransportMessage>,
+    {
+        let mut mirrored = HashSet::new();
+        // The announcement and the subscriptions are not linked to by the cursors of any publisher
+        if let Some(stream_address) = self.stream_address() {
+            let base_address = stream_address.base();
+            let subscription_addresses: Vec<Address> = self
+                .state
+                .subscribers
+                .iter()
+                .chain(self.identifier())
+                .map(|subscriber| {
+                    let rel_address = MsgId::gen(base_address, subscriber, &self.state.base_branch, SUB_MESSAGE_NUM);
+                    Address::new(base_address, rel_address)
+                })
+                .collect();
+            for address in core::iter::once(stream_address).chain(subscription_addresses) {
+                // Subscriptions are looked up for every known subscriber, so some are not found
+                let msg = match self.configured_transport().recv_message(address).await {
+                    Ok(msg) => msg,
+                    Err(_) => continue,
+                };
+                if other_transport.recv_message(address).await.is_err() && mirrored.insert(address) {
+                    other_transport
+                        .send_message(address, msg)
+                        .await
+                        .map_err(|e| Error::transport(address, "mirror message", e))?;
+                }
+            }
+        }
+        loop {
+            let base_address = match self.stream_address() {
+                Some(stream_address) => stream_address.base(),
+                None => return Ok(mirrored.len()),
+            };
+            let mut fetched = Vec::new();
+            for (topic, publishers) in self.writable_cursors_by_branch(|topic| topics.contains(topic)) {
+                let transport = self.configured_transport_copy();
+                fetched.extend(Self::fetch_branch_messages(transport, base_address, topic, publishers).await);
+            }
+
+            let mirrored_before = mirrored.len();
+            for (address, msg) in fetched {
+                if mirrored.insert(address) {
+                    other_transport
+                        .send_message(address, msg.clone())
+                        .await
+                        .map_err(|e| Error::transport(address, "mirror message", e))?;
+                }
+                // message-Handling errors are a normal execution path, just skip them
+                let _ = self.handle_message(address, msg).await;
+            }
+            // Messages linked to a message of another publisher may have been processed before it
+            self.fetch_orphan_messages().await?;
+
+            if mirrored.len() == mirrored_before {
+                return Ok(mirrored.len());
+            }
+        }
+    }
+
+    /// Groups the cursors of the publishers able to write by branch, for the branches matching
+    /// `filter`
+    ///
+    /// # Arguments
+    /// * `filter`: Whether the branch of a [`Topic`] must be included
+    fn writable_cursors_by_branch<F>(&self, filter: F) -> Vec<(Topic, Vec<(Identifier, usize)>)>
+    where
+        F: Fn(&Topic) -> bool,
+    {
+        let mut branches: Vec<(Topic, Vec<(Identifier, usize)>)> = Vec::new();
+        for (topic, publisher, cursor) in self.cursors().filter(|(topic, p, _)| !p.is_readonly() && filter(topic)) {
+            let publisher = (publisher.identifier().clone(), cursor);
+            match branches.iter_mut().find(|(t, _)| t == topic) {
+                Some((_, publishers)) => publishers.push(publisher),
+                None => branches.push((topic.clone(), vec![publisher])),
+            }
+        }
+        branches
+    }
+
+    /// Fetches the messages published in a branch after the cursors of its publishers, stopping
+    /// for each publisher at the first message not found
+    ///
+    /// # Arguments
+    /// * `transport`: The copy of the transport client used to fetch the messages
+    /// * `base_address`: The [`AppAddr`] of the stream
+    /// * `topic`: The [`Topic`] of the branch
+    /// * `publishers`: The [`Identifier`]s of the publishers of the branch, along with their cursor
+    async fn fetch_branch_messages<Tsp>(
+        mut transport: Tsp,
+        base_address: AppAddr,
+        topic: Topic,
+        publishers: Vec<(Identifier, usize)>,
+    ) -> Vec<(Address, TransportMessage)>
+    where
+        Tsp: for<'a> Transport<'a, Msg = TransportMessage>,
+    {
+        let mut msgs = Vec::new();
+        for (publisher, cursor) in publishers {
+            // Fetch the messages of the publisher until one is not found
+            for next_cursor in cursor + 1.. {
+                let rel_address = MsgId::gen(base_address, &publisher, &topic, next_cursor);
+                let address = Address::new(base_address, rel_address);
+                match transport.recv_message(address).await {
+                    Ok(msg) => msgs.push((address, msg)),
+                    Err(_) => break,
+                }
+            }
+        }
+        msgs
+    }
+
+    /// Iteratively fetches all the pending messages from the transport
+    ///
+    /// Return a vector with all the messages collected. This is a convenience
+    /// method around the [`Messages`] stream. Check out its docs for more
+    /// advanced usages.
+    pub async fn fetch_next_messages(&mut self) -> Result<Vec<Message>> {
+        self.messages().try_collect().await.map_err(Error::Messages)
+    }
+
+    /// Iteratively fetches all the pending messages from the transport, returning the messages sent
+    /// at or after `since`, according to the timestamp embedded in their public payload by
+    /// [`User::send_signed_packet_with_timestamp`]. Messages without a timestamp are returned as
+    /// well, see [`User::fetch_messages_since_strict`] to leave them out.
+    ///
+    /// Every pending message is processed, so that the state of the [`User`] stays consistent, even
+    /// if it is filtered out.
+    ///
+    /// # Arguments
+    /// * `since`: The earliest send time of the returned messages
+    #[cfg(feature = "timestamped-payloads")]
+    pub async fn fetch_messages_since(&mut self, since: std::time::SystemTime) -> Result<Vec<Message>> {
+        self.fetch_timestamped_messages(since, true).await
+    }
+
+    /// Iteratively fetches all the pending messages from the transport, returning only the messages
+    /// carrying a timestamp, embedded by [`User::send_signed_packet_with_timestamp`], at or after
+    /// `since`.
+    ///
+    /// # Arguments
+    /// * `since`: The earliest send time of the returned messages
+    #[cfg(feature = "timestamped-payloads")]
+    pub async fn fetch_messages_since_strict(&mut self, since: std::time::SystemTime) -> Result<Vec<Message>> {
+        self.fetch_timestamped_messages(since, false).await
+    }
+
+    /// Iteratively fetches all the pending messages from the transport, keeping the messages whose
+    /// embedded timestamp is at or after `since`
+    ///
+    /// # Arguments
+    /// * `since`: The earliest send time of the kept messages
+    /// * `keep_untimestamped`: Whether messages without a timestamp are kept
+    #[cfg(feature = "timestamped-payloads")]
+    async fn fetch_timestamped_messages(
+        &mut self,
+        since: std::time::SystemTime,
+        keep_untimestamped: bool,
+    ) -> Result<Vec<Message>> {
+        let since = since
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(Error::SystemTime)?
+            .as_secs();
+        Ok(self
+            .fetch_next_messages()
+            .await?
+            .into_iter()
+            .filter(|message| match message.decode_timestamp() {
+                Ok(Some(timestamp)) => timestamp >= since,
+                _ => keep_untimestamped,
+            })
+            .collect())
+    }
+
+    /// Iteratively fetches all the next messages until internal state has caught up, returning the
+    /// payloads of the Signed Packet messages of the specified branch, in processing order, as
+    /// `(address, public payload, masked payload)` tuples.
+    ///
+    /// Messages of any other type or branch are processed as well, so that the state of the
+    /// [`User`] stays consistent, but are discarded.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to receive the packets of
+    pub async fn receive_signed_packets_only<Top>(&mut self, topic: Top) -> Result<Vec<(Address, Vec<u8>, Vec<u8>)>>
+    where
+        Top: Into<Topic>,
+    {
+        self.receive_packets_only(topic.into(), |content| match content {
+            MessageContent::SignedPacket(packet) => Some((packet.public_payload, packet.masked_payload)),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Iteratively fetches all the next messages until internal state has caught up, returning the
+    /// payloads of the Tagged Packet messages of the specified branch, in processing order, as
+    /// `(address, public payload, masked payload)` tuples.
+    ///
+    /// Messages of any other type or branch are processed as well, so that the state of the
+    /// [`User`] stays consistent, but are discarded.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to receive the packets of
+    pub async fn receive_tagged_packets_only<Top>(&mut self, topic: Top) -> Result<Vec<(Address, Vec<u8>, Vec<u8>)>>
+    where
+        Top: Into<Topic>,
+    {
+        self.receive_packets_only(topic.into(), |content| match content {
+            MessageContent::TaggedPacket(packet) => Some((packet.public_payload, packet.masked_payload)),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Iteratively fetches all the next messages until internal state has caught up, returning the
+    /// payloads extracted by `payloads` from the messages of the specified branch, in processing
+    /// order, as `(address, public payload, masked payload)` tuples
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to receive the packets of
+    /// * `payloads`: Returns the public and masked payloads of the packets to keep
+    async fn receive_packets_only<F>(&mut self, topic: Topic, payloads: F) -> Result<Vec<(Address, Vec<u8>, Vec<u8>)>>
+    where
+        F: Fn(MessageContent) -> Option<(Vec<u8>, Vec<u8>)>,
+    {
+        let topic_hash = TopicHash::from(&topic);
+        Ok(self
+            .fetch_next_messages()
+            .await?
+            .into_iter()
+            .filter(|message| message.topic_hash() == &topic_hash)
+            .filter_map(|message| {
+                let address = message.address;
+                payloads(message.content)
+                    .map(|(public_payload, masked_payload)| (address, public_payload, masked_payload))
+            })
+            .collect())
+    }
+
+    /// Watches a branch for new messages, handing them over to `handler` as they are published.
+    /// The [`User`] is moved into a background task that fetches the new messages of the branch,
+    /// calls `handler` for each of them, and then waits for the interval of the returned
+    /// [`WatchHandle`] before fetching again. The interval defaults to one second and can be
+    /// adjusted with [`WatchHandle::set_interval`]. The [`User`] is given back once the watch is
+    /// [stopped](`WatchHandle::stop`).
+    ///
+    /// Only the messages of the watched branch are fetched, so the cursors of the other branches are
+    /// left unchanged. Errors met while fetching are handed over to `handler` as well, and the branch
+    /// keeps being watched.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to watch
+    /// * `handler`: Function called with each new message of the branch, or fetch error
+    ///
+    /// # Panics
+    /// The background task is spawned with [`tokio::task::spawn_local`], so this function panics if
+    /// called outside of a [`LocalSet`](`tokio::task::LocalSet`).
+    #[cfg(feature = "watch-branch")]
+    pub fn watch_branch<Top, H>(self, topic: Top, mut handler: H) -> WatchHandle<T, I>
+    where
+        Top: Into<Topic>,
+        H: FnMut(Result<Message>) + 'static,
+        T: Clone + 'static,
+        I: 'static,
+    {
+        let topic = topic.into();
+        let interval = Rc::new(Cell::new(DEFAULT_WATCH_INTERVAL));
+        let stop = Rc::new(Notify::new());
+        let (task_interval, task_stop) = (interval.clone(), stop.clone());
+        let mut user = self;
+        let task = tokio::task::spawn_local(async move {
+            loop {
+                match user.fetch_next_branch_messages(&topic).await {
+                    Ok(messages) => messages.into_iter().for_each(|message| handler(Ok(message))),
+                    Err(e) => handler(Err(e)),
+                }
+                let wait = Box::pin(sleep(task_interval.get()));
+                let stopped = Box::pin(task_stop.notified());
+                if let future::Either::Right(_) = future::select(wait, stopped).await {
+                    break;
+                }
+            }
+            user
+        });
+        WatchHandle::new(interval, stop, task)
+    }
+
+    /// Iteratively fetches the next messages of a branch until internal state has caught up with it,
+    /// returning the processed messages. Messages of other branches are not fetched.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to fetch the messages of
+    #[cfg(feature = "watch-branch")]
+    async fn fetch_next_branch_messages(&mut self, topic: &Topic) -> Result<Vec<Message>>
+    where
+        T: Clone,
+    {
+        let base_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("fetch the messages of a branch"))?
+            .base();
+        let topic_hash = TopicHash::from(topic);
+        let mut messages = Vec::new();
+        loop {
+            let transport = self.configured_transport_copy();
+            let mut processed = Vec::new();
+            for (_, publishers) in self.writable_cursors_by_branch(|branch| branch == topic) {
+                let msgs =
+                    Self::fetch_branch_messages(transport.clone(), base_address, topic.clone(), publishers).await;
+                for (address, msg) in msgs {
+                    // message-Handling errors are a normal execution path, just skip them
+                    if let Ok(message) = self.handle_message(address, msg).await {
+                        processed.push(message);
+                    }
+                }
+            }
+            // Messages linked to a message of another publisher may have been processed before it
+            processed.extend(
+                self.fetch_branch_orphan_messages(|branch_hash| branch_hash == &topic_hash)
+                    .await?,
+            );
+
+            let fetched = messages.len();
+            messages.extend(processed.into_iter().filter(|message| !message.is_orphan()));
+            if messages.len() == fetched {
+                return Ok(messages);
+            }
+        }
+    }
+
+    /// Fetches and handles the message published by `publisher` at the provided cursor of a
+    /// branch. The message is handled on a copy of the [`User`] state, so neither cursors nor any
+    /// other internal state are modified. The message is returned as an orphan if the message it
+    /// is linked to has not been processed by the [`User`].
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the message was published to
+    /// * `publisher`: The [`Identifier`] of the publisher of the message
+    /// * `cursor`: The cursor of the publisher when the message was published
+    pub async fn get_message_at_cursor(
+        &mut self,
+        topic: &Topic,
+        publisher: &Identifier,
+        cursor: usize,
+    ) -> Result<Message> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("get a message at a cursor"))?;
+        let rel_address = MsgId::gen(stream_address.base(), publisher, topic, cursor);
+        let address = Address::new(stream_address.base(), rel_address);
+        let msg = self
+            .configured_transport()
+            .recv_message(address)
+            .await
+            .map_err(|e| Error::transport(address, "receive message", e))?;
+
+        let state = self.state.clone();
+        let message = self.handle_message(address, msg).await;
+        self.state = state;
+        message
+    }
+
+    /// Fetches and handles all the messages published by the provided publisher in the branches
+    /// where it holds a cursor, from the first message up to its stored cursor. Messages that are
+    /// missing from the transport, that cannot be handled or that turn out not to be published by
+    /// `publisher` are skipped. Messages up to the stored cursors have already been processed, so
+    /// they are handled on a copy of the [`User`] state, as with [`User::get_message_at_cursor`].
+    ///
+    /// Return a vector with all the messages successfully handled.
+    ///
+    /// # Arguments
+    /// * `publisher`: The [`Identifier`] of the publisher whose messages will be retrieved.
+    pub async fn receive_messages_for_publisher(&mut self, publisher: &Identifier) -> Result<Vec<Message>> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("receive the messages of a publisher"))?;
+        let publisher_cursors: Vec<(Topic, usize)> = self
+            .cursors()
+            .filter(|(_, permission, _)| permission.identifier() == publisher)
+            .map(|(topic, _, cursor)| (topic.clone(), cursor))
+            .collect();
+
+        let mut messages = Vec::new();
+        let state = self.state.clone();
+        for (topic, max_cursor) in publisher_cursors {
+            for cursor in INIT_MESSAGE_NUM..=max_cursor {
+                let rel_address = MsgId::gen(stream_address.base(), publisher, &topic, cursor);
+                let address = Address::new(stream_address.base(), rel_address);
+                let msg = match self.configured_transport().recv_message(address).await {
+                    Ok(msg) => msg,
+                    Err(_) => continue,
+                };
+                match self.handle_message(address, msg).await {
+                    Ok(message) if !message.is_orphan() && message.header().publisher() == publisher => {
+                        messages.push(message)
+                    }
+                    _ => continue,
+                }
+            }
+        }
+        self.state = state;
+        Ok(messages)
+    }
+
+    /// Fetches and handles the messages published by `publisher` in a branch, from the provided
+    /// cursor onwards, to catch up from a known position. Messages up to the cursor stored for the
+    /// publisher have already been processed, so they are handled on a copy of the [`User`] state,
+    /// as with [`User::get_message_at_cursor`], and those that cannot be fetched from the transport
+    /// are skipped. The following messages are processed as with [`User::receive_message`], until
+    /// one is not found.
+    ///
+    /// Return a vector with the result of handling each fetched message, in cursor order.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the messages were published to
+    /// * `publisher`: The [`Identifier`] of the publisher of the messages
+    /// * `from_cursor`: The cursor of the first message to be retrieved
+    pub async fn receive_all_from_cursor(
+        &mut self,
+        topic: &Topic,
+        publisher: &Identifier,
+        from_cursor: usize,
+    ) -> Result<Vec<Result<Message>>> {
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("receive messages from a cursor"))?;
+        let stored_cursor = self
+            .state
+            .cursor_store
+            .get_cursor(topic, publisher)
+            .ok_or_else(|| Error::NoCursor(topic.clone()))?;
+
+        let mut messages = Vec::new();
+        let state = self.state.clone();
+        for cursor in from_cursor..=stored_cursor {
+            let rel_address = MsgId::gen(stream_address.base(), publisher, topic, cursor);
+            let address = Address::new(stream_address.base(), rel_address);
+            let msg = match self.configured_transport().recv_message(address).await {
+                Ok(msg) => msg,
+                Err(_e) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(%address, cursor, error = %_e, "skipping message that could not be fetched");
+                    continue;
+                }
+            };
+            messages.push(self.handle_message(address, msg).await);
+        }
+        self.state = state;
+
+        for cursor in from_cursor.max(stored_cursor + 1).. {
+            let rel_address = MsgId::gen(stream_address.base(), publisher, topic, cursor);
+            let address = Address::new(stream_address.base(), rel_address);
+            let msg = match self.configured_transport().recv_message(address).await {
+                Ok(msg) => msg,
+                Err(_) => break,
+            };
+            messages.push(self.handle_message(address, msg).await);
+        }
+        Ok(messages)
+    }
+
+    /// Lists the pending subscription requests sent to this [`User`] with
+    /// [`User::request_subscription`], returning the [`Address`] of each request along with the
+    /// [`Identifier`] of its requester. Requests from known subscribers and requests that cannot be
+    /// verified are skipped. If the inbox of the [`User`] cannot be fetched, no request is listed.
+    pub async fn list_subscription_requests(&mut self) -> Result<Vec<(Address, Identifier)>> {
+        let author = self
+            .identifier()
+            .ok_or(Error::NoIdentity("list subscription requests"))?
+            .clone();
+        let inbox_address = Self::subscription_requests_inbox(&author);
+        let msgs = match self.configured_transport().recv_messages(inbox_address).await {
+            Ok(msgs) => msgs,
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let mut requests = Vec::new();
+        for msg in msgs {
+            let requester = match Self::unwrap_subscription_request(inbox_address, msg).await {
+                Ok(requester) => requester,
+                Err(_) => continue,
+            };
+            if self.state.subscribers.contains(&requester) || requests.iter().any(|(_, r)| r == &requester) {
+                continue;
+            }
+            requests.push((Self::subscription_request_address(&author, &requester), requester));
+        }
+        Ok(requests)
+    }
+}
+
+impl<T, I, TSR> User<T, I>
+where
+    T: for<'a> Transport<'a, Msg = TransportMessage, SendResponse = TSR>,
+{
+    /// Sends a message to the transport, calling the send hooks of the [`User`] around its
+    /// dispatch.
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] the message is sent to
+    /// * `msg`: The wire-format message
+    async fn send_to_transport(&mut self, address: Address, msg: TransportMessage) -> LetsResult<TSR> {
+        let hooks = self.send_hooks.clone();
+        hooks.before_send(&address, &msg);
+        if !hooks.has_post_send() {
+            return self.configured_transport().send_message(address, msg).await;
+        }
+        let send_response = self.configured_transport().send_message(address, msg.clone()).await?;
+        hooks.after_send(&address, &msg, &send_response);
+        Ok(send_response)
+    }
+
+    /// Create and send a new Subscription message on behalf of the holders of a [`Psk`], awaiting the
+    /// stream author's acceptance into the stream. The subscriber [`Identifier`] and the unsubscribe
+    /// key are both derived from the [`Psk`], so no [`Identity`] is required. The [`Psk`] is stored
+    /// in state to be able to read the keyloads that include it.
+    ///
+    /// # Arguments
+    /// * `psk`: The [`Psk`] to subscribe with
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn subscribe_with_psk(&mut self, psk: Psk) -> Result<SendResponse<TSR>> {
+        let user_id = Identity::from(&psk);
+        let send_response = self
+            .send_subscription(&user_id, psk.to_unsubscribe_key(), false, 0)
+            .await?;
+        self.add_psk(psk);
+        Ok(send_response)
+    }
+
+    /// Create and send a new Subscription message for the provided [`Identity`].
+    ///
+    /// # Arguments
+    /// * `user_id`: The [`Identity`] of the subscriber
+    /// * `unsubscribe_key`: The key used for unsubscribing later
+    /// * `readonly`: Whether the subscriber must never be granted write permissions
+    /// * `ttl_seconds`: Seconds the subscription lasts for, or 0 if it does not expire
+    async fn send_subscription(
+        &mut self,
+        user_id: &Identity,
+        unsubscribe_key: [u8; 32],
+        readonly: bool,
+        ttl_seconds: u64,
+    ) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before starting a new branch, the stream must be created"))?;
+        let identifier = user_id.identifier();
+        // Get base branch topic
+        let base_branch = &self.state.base_branch;
+        // Link message to channel announcement
+        let link_to = stream_address.relative();
+        let rel_address = MsgId::gen(stream_address.base(), identifier, base_branch, SUB_MESSAGE_NUM);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let author_ke_pk = self
+            .state
+            .author_identifier
+            .as_ref()
+            .unwrap()
+            .ke_pk()
+            .await
+            .map_err(|_| Error::Setup("Failed to generate Public Key from author identifier"))?;
+
+        let subscription = subscription::Wrap::new(
+            &mut linked_msg_spongos,
+            unsubscribe_key,
+            user_id,
+            &author_ke_pk,
+            readonly,
+            ttl_seconds,
+        );
+        let header = HDF::new(
+            subscription.message_type(),
+            SUB_MESSAGE_NUM,
+            identifier.clone(),
+            base_branch,
+        )
+        .with_linked_msg_address(link_to);
+        let content = PCF::new_final_frame().with_content(subscription);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("subscribe", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+
+        // Attempt to send message
+        let has_msg = self.configured_transport().recv_message(message_address).await;
+        if !has_msg.is_err() {
+            return Err(Error::AddressUsed("subscribe", message_address));
+        }
+
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(message_address, "send subscribe message", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        // - Subscription messages are not stored in the cursor store
+        // - Subscription messages are never stored in spongos to maintain consistency about the view of the
+        // set of messages of the stream between all the subscribers and across stateless recovers
+        Ok(SendResponse::new(message_address, send_response))
+    }
+}
+
+impl<T, TSR> User<T, HasIdentity>
+where
+    T: for<'a> Transport<'a, Msg = TransportMessage, SendResponse = TSR>,
+{
+    /// Create and send a stream Announcement message, anchoring the stream for others to attach to.
+    /// Errors if the [`User`] is already attached to a stream, or if the message already exists in
+    /// the transport layer.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] that will be used for the base branch
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+                topic = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn create_stream<Top: Into<Topic>>(&mut self, topic: Top) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        if self.stream_address().is_some() {
+            return Err(Error::Setup(
+                "Cannot create a channel, user is already registered to channel",
+            ));
+        }
+        // Confirm user has identity
+        let identifier = self.identifier().ok_or(Error::NoIdentity("create a stream"))?.clone();
+        // Convert topic
+        let topic = Self::validate_topic(topic)?;
+        // Generate stream address, derived from the parent stream if this is a child stream
+        let stream_base_address = match &self.state.parent_address {
+            Some(parent_address) => AppAddr::gen_child(parent_address, &topic),
+            None => AppAddr::gen(&identifier, &topic),
+        };
+        let stream_rel_address = MsgId::gen(stream_base_address, &identifier, &topic, INIT_MESSAGE_NUM);
+        let stream_address = Address::new(stream_base_address, stream_rel_address);
+
+        // Prepare HDF and PCF
+        let announcement =
+            announcement::Wrap::new(self.identity().unwrap(), &topic, self.state.parent_address.as_ref());
+        let header = HDF::new(announcement.message_type(), ANN_MESSAGE_NUM, identifier.clone(), &topic);
+        let content = PCF::new_final_frame().with_content(announcement);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("wrap announce", e))?;
+
+        // Attempt to send message
+        if !self.configured_transport().recv_message(stream_address).await.is_err() {
+            return Err(Error::Setup("Cannot create a channel, announce address already in use"));
+        }
+
+        let send_response = self
+            .send_to_transport(stream_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send announce message", e))?;
+
+        // If a message has been sent successfully, insert the base branch into store
+        self.state.cursor_store.new_branch(topic.clone());
+        self.state.topics.insert(topic.clone());
+        self.record_branch_creation_time(&topic);
+        // Commit message to stores
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(identifier.clone()), INIT_MESSAGE_NUM);
+        self.insert_spongos(stream_address.relative(), spongos, None);
+
+        // Update branch links
+        self.set_latest_link(topic.clone(), stream_address.relative());
+
+        // Commit Author Identifier and Stream Address to store
+        self.state.stream_address = Some(stream_address);
+        self.state.author_identifier = Some(identifier);
+        self.state.base_branch = topic;
+
+        Ok(SendResponse::new(stream_address, send_response))
+    }
+
+    /// Create and send a new Branch Announcement message, creating a new branch in `CursorStore`
+    /// with the previous branches permissions carried forward.
+    ///
+    /// # Arguments
+    /// * `from_topic`: The [`Topic`] of the branch to generate the new branch from.
+    /// * `to_topic`: The [`Topic`] of the new branch being created.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+                from_topic = tracing::field::Empty,
+                to_topic = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn new_branch(
+        &mut self,
+        from_topic: impl Into<Topic>,
+        to_topic: impl Into<Topic>,
+    ) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before starting a new branch, the stream must be created"))?;
+        // Confirm user has identity
+        let identifier = self.identifier().ok_or(Error::NoIdentity("create a branch"))?.clone();
+        // Check Topic
+        let topic = Self::validate_topic(to_topic)?;
+        let prev_topic = Self::validate_topic(from_topic)?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("from_topic", &prev_topic.str())
+            .record("to_topic", &topic.str());
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&prev_topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole("ReadWrite", identifier, "make a new branch"));
+        }
+        let link_to = self
+            .get_latest_link(&prev_topic)
+            .ok_or_else(|| Error::TopicNotFound(prev_topic.clone()))?;
+
+        // Update own's cursor
+        let user_cursor = self
+            .next_cursor(&prev_topic)
+            .map_err(|_| Error::NoCursor(prev_topic.clone()))?;
+        let msgid = MsgId::gen(stream_address.base(), &identifier, &prev_topic, user_cursor);
+        let address = Address::new(stream_address.base(), msgid);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let header = HDF::new(
+            message_types::BRANCH_ANNOUNCEMENT,
+            user_cursor,
+            identifier.clone(),
+            &prev_topic,
+        )
+        .with_linked_msg_address(link_to);
+        let content = PCF::new_final_frame().with_content(branch_announcement::Wrap::new(
+            &mut linked_msg_spongos,
+            self.identity().unwrap(),
+            &topic,
+        ));
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("wrap new branch", e))?;
+
+        if !self.configured_transport().recv_message(address).await.is_err() {
+            return Err(Error::AddressUsed("new branch", address));
+        }
+
+        let send_response = self
+            .send_to_transport(address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send new branch message", e))?;
+
+        // If message has been sent successfully, create the new branch in store
+        self.state.cursor_store.new_branch(topic.clone());
+        self.state.topics.insert(topic.clone());
+        self.state.branch_parent.insert(topic.clone(), prev_topic.clone());
+        self.record_branch_creation_time(&topic);
+        // Commit message to stores and update cursors
+        self.state.cursor_store.insert_cursor(
+            &prev_topic,
+            Permissioned::Admin(identifier.clone()),
+            self.next_cursor(&prev_topic)?,
+        );
+        self.insert_spongos(address.relative(), spongos, Some(link_to));
+        // Collect permissions from previous branch and clone them into new branch
+        let prev_permissions = self
+            .cursors_by_topic(&prev_topic)?
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<Permissioned<Identifier>>>();
+        for id in prev_permissions {
+            self.state.cursor_store.insert_cursor(&topic, id, INIT_MESSAGE_NUM);
+        }
+
+        // Update branch links
+        self.state.cursor_store.set_latest_link(topic, address.relative());
+        Ok(SendResponse::new(address, send_response))
+    }
+
+    /// Create a new branch from an existing one, as [`User::new_branch`] does, bootstrapping it with
+    /// the latest Signed Packet messages of the source branch. The payloads of the last 5
+    /// Signed Packets linked from the latest link of the source branch are sent again to the new
+    /// branch as Signed Packets of this [`User`], oldest first. Returns the [`SendResponse`] of the
+    /// Branch Announcement message.
+    ///
+    /// The packets of the source branch are retrieved with [`User::receive_all_from_cursor`] for each
+    /// of its publishers, without altering the state of the [`User`]. Packets that cannot be handled,
+    /// or that are not part of the link chain of the branch, are left out.
+    ///
+    /// # Arguments
+    /// * `from_topic`: The [`Topic`] of the branch to clone.
+    /// * `to_topic`: The [`Topic`] of the new branch being created.
+    pub async fn clone_branch_to_topic(&mut self, from_topic: &Topic, to_topic: &Topic) -> Result<SendResponse<TSR>> {
+        let publishers: Vec<Identifier> = self
+            .cursors_by_topic(from_topic)?
+            .map(|(permission, _)| permission.identifier().clone())
+            .collect();
+
+        // Fetching the packets handles them again, so the state is restored afterwards
+        let state = self.state.clone();
+        let mut packets = HashMap::new();
+        for publisher in &publishers {
+            let messages = self
+                .receive_all_from_cursor(from_topic, publisher, INIT_MESSAGE_NUM)
+                .await;
+            let messages = match messages {
+                Ok(messages) => messages,
+                Err(e) => {
+                    self.state = state;
+                    return Err(e);
+                }
+            };
+            packets.extend(messages.into_iter().filter_map(Result::ok).filter_map(|message| {
+                let address = message.address().relative();
+                match message.content {
+                    MessageContent::SignedPacket(packet) => {
+                        Some((address, (packet.public_payload, packet.masked_payload)))
+                    }
+                    _ => None,
+                }
+            }));
+        }
+
+        // Walk the branch back from its latest link, so packets keep the order they were linked in
+        let mut latest_packets = Vec::new();
+        let mut link = self.get_latest_link(from_topic);
+        while let Some(address) = link {
+            if latest_packets.len() == CLONED_BRANCH_MESSAGES {
+                break;
+            }
+            if let Some(packet) = packets.remove(&address) {
+                latest_packets.push(packet);
+            }
+            link = self.state.link_store.get(&address).and_then(|(linked, _)| *linked);
+        }
+        self.state = state;
+
+        let send_response = self.new_branch(from_topic.clone(), to_topic.clone()).await?;
+        for (public_payload, masked_payload) in latest_packets.into_iter().rev() {
+            self.send_signed_packet(to_topic.clone(), public_payload, masked_payload)
+                .await?;
+        }
+        Ok(send_response)
+    }
+
+    /// Create and send a new Subscription message, awaiting the stream author's acceptance into the
+    /// stream.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn subscribe(&mut self) -> Result<SendResponse<TSR>> {
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("subscribe"))?.clone();
+        let unsubscribe_key = StdRng::from_entropy().gen();
+        self.send_subscription(&user_id, unsubscribe_key, false, 0).await
+    }
+
+    /// Create and send a new Subscription message as an observer, signaling to the stream author
+    /// that the [`User`] only intends to read the stream. Read-only subscribers are never granted
+    /// write permissions by [`User::send_keyload_for_all_rw`].
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn subscribe_readonly(&mut self) -> Result<SendResponse<TSR>> {
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("subscribe"))?.clone();
+        let unsubscribe_key = StdRng::from_entropy().gen();
+        self.send_subscription(&user_id, unsubscribe_key, true, 0).await
+    }
+
+    /// Create and send a new Subscription message that expires after `ttl_seconds`, for short-lived
+    /// consumers. The stream author records the expiry when processing the subscription and removes
+    /// the subscriber with [`User::expire_subscriptions`] once it is reached. The returned
+    /// [`UnsubscribeToken`] can be [redeemed](`UnsubscribeToken::redeem`) to unsubscribe earlier.
+    ///
+    /// # Arguments
+    /// * `ttl_seconds`: Seconds the subscription lasts for, must not be 0
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn subscribe_ephemeral(&mut self, ttl_seconds: u64) -> Result<(SendResponse<TSR>, UnsubscribeToken)> {
+        if ttl_seconds == 0 {
+            return Err(Error::Setup("an ephemeral subscription must last at least one second"));
+        }
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("subscribe"))?.clone();
+        let unsubscribe_key = StdRng::from_entropy().gen();
+        let send_response = self
+            .send_subscription(&user_id, unsubscribe_key, false, ttl_seconds)
+            .await?;
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before subscribing, the stream must be received"))?;
+        let token = UnsubscribeToken::new(stream_address, user_id.identifier().clone());
+        Ok((send_response, token))
+    }
+
+    /// Create and send a new Subscription Request message to the author of a stream, asking to be
+    /// accepted as a subscriber without knowing the address of the stream. The request is sent to
+    /// an address derived from the [`Identifier`]s of the author and the requester, and gathered in
+    /// the inbox of the author, who can [list](`User::list_subscription_requests`) and
+    /// [approve](`User::approve_subscription_request`) it.
+    ///
+    /// Returns the [`Address`] of the request.
+    ///
+    /// # Arguments
+    /// * `author`: The [`Identifier`] of the author of the stream
+    pub async fn request_subscription(&mut self, author: &Identifier) -> Result<Address> {
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("request a subscription"))?;
+        let identifier = user_id.identifier().clone();
+        let topic = Topic::from(SUBSCRIPTION_REQUESTS_TOPIC);
+
+        // Prepare HDF and PCF
+        let content = PCF::new_final_frame().with_content(subscription_request::Wrap::new(user_id));
+        let header = HDF::new(
+            message_types::SUBSCRIPTION_REQUEST,
+            SUB_MESSAGE_NUM,
+            identifier.clone(),
+            &topic,
+        );
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("wrap subscription request", e))?;
+
+        // Attempt to send message, both to its own address and to the inbox of the author
+        let request_address = Self::subscription_request_address(author, &identifier);
+        if self.configured_transport().recv_message(request_address).await.is_ok() {
+            return Err(Error::AddressUsed("subscription request", request_address));
+        }
+        self.send_to_transport(request_address, transport_msg.clone())
+            .await
+            .map_err(|e| Error::transport(request_address, "send subscription request", e))?;
+        let inbox_address = Self::subscription_requests_inbox(author);
+        self.send_to_transport(inbox_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(inbox_address, "send subscription request", e))?;
+
+        Ok(request_address)
+    }
+
+    /// Approves a subscription request sent with [`User::request_subscription`]: the requester is
+    /// stored as a subscriber, and a new Keyload message is sent on the base branch granting it
+    /// read permission. The other subscribers keep the permission they currently hold in the base
+    /// branch. Once the keyload is sent, the address of the stream is published, signed, at an
+    /// address derived from the author and the requester, for the requester to
+    /// [receive](`User::receive_subscription_approval`) it.
+    ///
+    /// # Arguments
+    /// * `request_address`: The [`Address`] of the subscription request
+    pub async fn approve_subscription_request(&mut self, request_address: Address) -> Result<SendResponse<TSR>> {
+        let author = self
+            .identifier()
+            .ok_or(Error::NoIdentity("approve a subscription request"))?
+            .clone();
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::NoStream("approve a subscription request"))?;
+        let msg = self
+            .configured_transport()
+            .recv_message(request_address)
+            .await
+            .map_err(|e| Error::transport(request_address, "receive subscription request", e))?;
+        let requester = Self::unwrap_subscription_request(request_address, msg).await?;
+        // A request can only be replayed at the address derived from its own requester
+        if Self::subscription_request_address(&author, &requester) != request_address {
+            return Err(Error::InvalidSubscriptionRequest(request_address));
+        }
+
+        self.add_subscriber(requester.clone());
+        let base_branch = self.base_branch().clone();
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(&author, |s| Some(self.current_permission(&base_branch, s)));
+        let send_response = self
+            .send_keyload(
+                base_branch,
+                // Alas, must collect to release the &self immutable borrow
+                subscribers.iter().map(Permissioned::as_ref),
+                psks,
+            )
+            .await?;
+
+        // Publish the stream address for the requester, outside of the stream
+        let user_id = self
+            .identity()
+            .ok_or(Error::NoIdentity("approve a subscription request"))?;
+        let topic = Topic::from(SUBSCRIPTION_APPROVALS_TOPIC);
+        let mut spongos = Spongos::init();
+        let content =
+            PCF::new_final_frame().with_content(stream_link::Wrap::new(&mut spongos, user_id, &stream_address));
+        let header = HDF::new(message_types::STREAM_LINK, SUB_MESSAGE_NUM, author.clone(), &topic);
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("wrap subscription approval", e))?;
+        let approval_address = Self::subscription_approval_address(&author, &requester);
+        self.send_to_transport(approval_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(approval_address, "send subscription approval", e))?;
+        Ok(send_response)
+    }
+
+    /// Receives the approval of a subscription request sent with [`User::request_subscription`],
+    /// published by the stream author when [approving](`User::approve_subscription_request`) it,
+    /// and attaches the [`User`] to the stream. Returns the [`Address`] of the stream. Errors if the
+    /// request has not been approved yet, or if the approval is not signed by the author.
+    ///
+    /// # Arguments
+    /// * `author`: The [`Identifier`] of the author of the stream
+    pub async fn receive_subscription_approval(&mut self, author: &Identifier) -> Result<Address> {
+        let identifier = self
+            .identifier()
+            .ok_or(Error::NoIdentity("receive a subscription approval"))?
+            .clone();
+        let approval_address = Self::subscription_approval_address(author, &identifier);
+        let msg = self
+            .configured_transport()
+            .recv_message(approval_address)
+            .await
+            .map_err(|e| Error::transport(approval_address, "receive subscription approval", e))?;
+        let preparsed = msg
+            .parse_header()
+            .await
+            .map_err(|e| Error::Unwrapping("header", approval_address, e))?;
+        let message_type = preparsed.header().message_type();
+        if message_type != message_types::STREAM_LINK {
+            return Err(Error::UnexpectedMessageType {
+                expected: message_types::STREAM_LINK,
+                got: message_type,
+            });
+        }
+        let mut spongos = Spongos::init();
+        let (message, _spongos) = preparsed
+            .unwrap(stream_link::Unwrap::new(&mut spongos))
+            .await
+            .map_err(|e| Error::Unwrapping("subscription approval", approval_address, e))?;
+        let (publisher, stream_address) = message.into_payload().into_content().into_parts();
+        if &publisher != author {
+            return Err(Error::InvalidSubscriptionApproval(approval_address));
+        }
+
+        self.receive_message(stream_address).await?;
+        Ok(stream_address)
+    }
+
+    /// Create and send a new Unsubscription message, informing the stream author that this [`User`]
+    /// instance can be removed from the stream.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip(self),
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+            )
+        )
+    )]
+    pub async fn unsubscribe(&mut self) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before unsubscribing, the stream must be created"))?;
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("unsubscribe"))?;
+        let identifier = user_id.identifier().clone();
+        // Get base branch topic
+        let base_branch = &self.state.base_branch;
+        // Link message to channel announcement
+        let link_to = self
+            .get_latest_link(base_branch)
+            .ok_or_else(|| Error::TopicNotFound(base_branch.clone()))?;
+
+        // Update own's cursor
+        let new_cursor = self.next_cursor(base_branch)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, base_branch, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(unsubscription::Wrap::new(&mut linked_msg_spongos, user_id));
+        let header = HDF::new(
+            message_types::UNSUBSCRIPTION,
+            new_cursor,
+            identifier.clone(),
+            base_branch,
+        )
+        .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("unsubscribe", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("unsubscribe", message_address));
+        }
+
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send unsubscribe message", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        let permission = Permissioned::Read(identifier);
+        self.state
+            .cursor_store
+            .insert_cursor(base_branch, permission, new_cursor);
+        self.store_spongos(rel_address, spongos, link_to);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send an Announcement Ack message, confirming to the stream admins that the
+    /// [`User`] processed the latest keyload of the base branch. The message carries a MAC over the
+    /// [`Spongos`] state of the keyload, which only verifies if the [`User`] was included in it.
+    /// Admins mark the [`User`] as confirmed once they receive the ack, see
+    /// [`User::list_confirmed_subscribers`].
+    ///
+    /// Acks are linked to the keyload, but are not part of the sequence of messages of the [`User`]:
+    /// their address is derived from the address of the keyload, and must be passed to the admins
+    /// out-of-band, like subscription addresses.
+    ///
+    /// # Arguments
+    /// * `stream_address`: The [`Address`] of the stream the keyload was received in
+    pub async fn send_announcement_ack(&mut self, stream_address: Address) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        if self.stream_address() != Some(stream_address) {
+            return Err(Error::Setup(
+                "before acknowledging a keyload, the stream announcement must be received",
+            ));
+        }
+        let user_id = self.identity().ok_or(Error::NoIdentity("send announcement ack"))?;
+        let identifier = user_id.identifier().clone();
+        // Link message to the latest keyload processed in the base branch
+        let base_branch = &self.state.base_branch;
+        let keyload = self
+            .state
+            .message_log
+            .iter()
+            .rev()
+            .find(|entry| entry.message_type == message_types::KEYLOAD && &entry.topic == base_branch)
+            .ok_or(Error::Setup(
+                "before acknowledging a keyload, a keyload of the base branch must be received",
+            ))?;
+        let link_to = keyload.address.relative();
+        // Each subscriber acks a keyload at most once, at an address bound to both
+        let mut address_spongos = Spongos::<KeccakF1600>::init();
+        address_spongos.absorb(link_to);
+        let rel_address: MsgId = address_spongos.sponge(&identifier);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content =
+            PCF::new_final_frame().with_content(announcement_ack::Wrap::new(&mut linked_msg_spongos, user_id));
+        let header = HDF::new(
+            message_types::ANNOUNCEMENT_ACK,
+            keyload.cursor,
+            identifier.clone(),
+            base_branch,
+        )
+        .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send announcement ack", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("announcement ack", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(message_address, "send announcement ack", e))?;
+
+        // Announcement acks are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Keyload message, updating the read/write permissions for a specified
+    /// branch. All keyload messages are linked to the announcement message to ensure they
+    /// can always be read by a [`User`] that can sequence up to it.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    /// * `subscribers`: The updated [`Permissioned`] list for the branch.
+    /// * `psk_ids`: A list of [Psk Id's](`PskId`) with read access for the branch.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+                topic = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_keyload<'a, Subscribers, Psks, Top>(
+        &mut self,
+        topic: Top,
+        subscribers: Subscribers,
+        psk_ids: Psks,
+    ) -> Result<SendResponse<TSR>>
+    where
+        Subscribers: IntoIterator<Item = Permissioned<&'a Identifier>> + Clone,
+        Subscribers::IntoIter: ExactSizeIterator,
+        Top: Into<Topic>,
+        Psks: IntoIterator<Item = PskId>,
+    {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before sending a keyload, the stream must be created"))?;
+        // Confirm user has identity
+        let user_id = self.identity().ok_or(Error::NoIdentity("send keyload"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole("Admin", identifier, "send a keyload"));
+        }
+
+        // Link message to edge of branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // All Keyload messages will attach to stream Announcement message spongos
+        let mut announcement_msg_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .ok_or(Error::Setup("a user must keep a stream announcement spongos in store"))?;
+
+        let mut rng = StdRng::from_entropy();
+        let encryption_key = rng.gen();
+        let nonce = rng.gen();
+        let psk_ids_with_psks = psk_ids
+            .into_iter()
+            .map(|pskid| Ok((pskid, self.state.psk_store.get(&pskid).ok_or(Error::UnknownPsk(pskid))?)))
+            .collect::<Result<Vec<(_, _)>>>()?; // collect to handle possible error
+        let content = PCF::new_final_frame().with_content(keyload::Wrap::new(
+            &mut announcement_msg_spongos,
+            subscribers.clone().into_iter().collect::<Vec<_>>(),
+            &psk_ids_with_psks,
+            encryption_key,
+            nonce,
+            user_id,
+        ));
+        let header =
+            HDF::new(message_types::KEYLOAD, new_cursor, identifier.clone(), &topic).with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send keyload", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if !self.configured_transport().recv_message(message_address).await.is_err() {
+            return Err(Error::AddressUsed("keyload", message_address));
+        }
+
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send keyload message", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        for subscriber in subscribers {
+            if self.should_store_cursor(&topic, subscriber) {
+                self.state
+                    .cursor_store
+                    .insert_cursor(&topic, subscriber.into(), INIT_MESSAGE_NUM);
+            }
+        }
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(identifier), new_cursor);
+        self.store_spongos(rel_address, spongos, link_to);
+        // Update Branch Links
+        self.set_latest_link(topic, message_address.relative());
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Keyload message for all participants, updating the specified branch to
+    /// grant all known subscribers read permissions.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    pub async fn send_keyload_for_all<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| Some(Permissioned::Read(s.clone())));
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            subscribers.iter().map(Permissioned::as_ref),
+            psks,
+        )
+        .await
+    }
+
+    /// Rotates the session keys of every known branch, sending a new Keyload message for all
+    /// participants to each of them as [`User::send_keyload_for_all`] would, with a freshly generated
+    /// key and nonce. Branches are rekeyed in the lexicographic order of their topics. Returns the
+    /// [`SendResponse`] of each keyload, in the same order.
+    ///
+    /// A branch failing to be rekeyed does not stop the remaining ones from being rekeyed. If any
+    /// branch fails, an [`Error::PartialRekey`] is returned instead, listing the branches rekeyed and
+    /// the branches that failed, along with their error.
+    pub async fn rekey_all_branches(&mut self) -> Result<Vec<SendResponse<TSR>>> {
+        let mut topics: Vec<Topic> = self.state.topics.iter().cloned().collect();
+        topics.sort_by(|a, b| a.str().cmp(b.str()));
+        let mut successes = Vec::with_capacity(topics.len());
+        let mut failures = Vec::new();
+        for topic in topics {
+            match self.send_keyload_for_all(topic.clone()).await {
+                Ok(send_response) => successes.push((topic, send_response)),
+                Err(e) => failures.push((topic, e)),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(Error::PartialRekey(PartialRekeyError {
+                successes: successes
+                    .into_iter()
+                    .map(|(topic, send_response)| (topic, send_response.address()))
+                    .collect(),
+                failures,
+            }));
+        }
+        Ok(successes.into_iter().map(|(_, send_response)| send_response).collect())
+    }
+
+    /// Create and send a new Keyload message for all participants, updating the specified branch to
+    /// grant all known subscribers read and write permissions. Subscribers that
+    /// [subscribed as read-only](`User::subscribe_readonly`) are only granted read permissions.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    pub async fn send_keyload_for_all_rw<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| {
+            if self.is_readonly_subscriber(s) {
+                Some(Permissioned::Read(s.clone()))
+            } else {
+                Some(Permissioned::ReadWrite(s.clone(), PermissionDuration::Perpetual))
+            }
+        });
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            subscribers.iter().map(Permissioned::as_ref),
+            psks,
+        )
+        .await
+    }
+
+    /// Create and send a new Keyload message for all known subscribers, updating the specified branch
+    /// to grant the subscribers [assigned](`User::assign_capability`) a [`Capability`] the
+    /// [permission mapped to it](`Capability::permission`). The other subscribers keep the
+    /// permission they currently hold in the branch, or are granted read permission if they hold
+    /// none. The pre shared keys stored by the user are included.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    pub async fn send_keyload_for_capabilities<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| {
+            Some(match self.capability(s) {
+                Some(capability) => capability.permission(s).into(),
+                None => self.current_permission(&topic, s),
+            })
+        });
+        self.send_keyload(topic, subscribers.iter().map(Permissioned::as_ref), psks)
+            .await
+    }
+
+    /// Create and send a new Keyload message granting access to the specified branch only to the
+    /// holders of the pre shared keys stored by the user. No subscriber is included in the keyload.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    pub async fn send_keyload_with_psks_only<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        self.send_keyload(topic, Vec::<Permissioned<&Identifier>>::new(), psks)
+            .await
+    }
+
+    /// Create and send a new Keyload message for all known subscribers, updating the specified branch
+    /// to grant them read permissions. No pre shared key is included in the keyload.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    pub async fn send_keyload_with_subscribers_only<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| Some(Permissioned::Read(s.clone())));
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            subscribers.iter().map(Permissioned::as_ref),
+            Vec::<PskId>::new(),
+        )
+        .await
+    }
+
+    /// Create and send a new Keyload message for the members of a named group, updating the
+    /// specified branch to grant them access. Members keep the permission they currently hold in
+    /// the branch, while members without one are granted read permissions. No pre shared key is
+    /// included in the keyload.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    /// * `group_name`: The name of the group, as [defined](`User::define_group`) beforehand.
+    pub async fn send_keyload_for_group<Top>(&mut self, topic: Top, group_name: &str) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let members = self
+            .state
+            .groups
+            .get(group_name)
+            .ok_or_else(|| Error::UnknownGroup(group_name.to_string()))?;
+        let members: Vec<Permissioned<Identifier>> = members
+            .iter()
+            .map(|member| {
+                if member == permission.identifier() {
+                    Permissioned::Admin(member.clone())
+                } else {
+                    self.current_permission(&topic, member)
+                }
+            })
+            .collect();
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            members.iter().map(Permissioned::as_ref),
+            Vec::<PskId>::new(),
+        )
+        .await
+    }
+
+    /// Rotates a [Pre-Shared Key](`Psk`): stores the new [`Psk`], sends a new Keyload message on the
+    /// base branch including it along with all known subscribers, and removes the old [`Psk`] from
+    /// state. Subscribers keep the permission they currently hold in the base branch. Holders of the
+    /// old [`Psk`] only can no longer read the messages linked to the new keyload.
+    ///
+    /// If the keyload cannot be sent, the new [`Psk`] is not stored and the old one is kept.
+    ///
+    /// # Arguments
+    /// * `old_pskid`: The [identifier](`PskId`) of the [`Psk`] to be replaced.
+    /// * `new_psk`: The [`Psk`] replacing it.
+    pub async fn rotate_psk(&mut self, old_pskid: PskId, new_psk: Psk) -> Result<SendResponse<TSR>> {
+        if !self.state.psk_store.contains_key(&old_pskid) {
+            return Err(Error::UnknownPsk(old_pskid));
+        }
+        let topic = self.base_branch().clone();
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "rotate a pre shared key",
+            ));
+        }
+
+        let subscribers =
+            self.keyload_subscribers(permission.identifier(), |s| Some(self.current_permission(&topic, s)));
+        let new_pskid = new_psk.to_pskid();
+        let new_psk_known = self.state.psk_store.contains_key(&new_pskid);
+        self.add_psk(new_psk);
+        let psks: Vec<PskId> = self
+            .state
+            .psk_store
+            .keys()
+            .filter(|pskid| **pskid != old_pskid)
+            .copied()
+            .collect();
+        let send_response = match self
+            .send_keyload(
+                topic,
+                // Alas, must collect to release the &self immutable borrow
+                subscribers.iter().map(Permissioned::as_ref),
+                psks,
+            )
+            .await
+        {
+            Ok(send_response) => send_response,
+            Err(e) => {
+                // The new PSK is only kept once the keyload distributing it is sent
+                if !new_psk_known {
+                    self.remove_psk(new_pskid);
+                }
+                return Err(e);
+            }
+        };
+        self.remove_psk(old_pskid);
+        Ok(send_response)
+    }
+
+    /// Revokes a [Pre-Shared Key](`Psk`): sends a new Keyload message on the specified branch, with
+    /// a fresh encryption key, including all known subscribers and the remaining [`Psk`]s, and
+    /// removes the [`Psk`] from state. Subscribers keep the permission they currently hold in the
+    /// branch, and [read-only subscribers](`User::subscribe_readonly`) are granted read permission.
+    /// The subscriber whose [`Identifier`] was
+    /// [derived from the revoked `Psk`](`User::subscribe_with_psk`), if any, is excluded from the
+    /// keyload and removed from the subscribers, along with its cursors. Holders of the revoked [`Psk`] can no longer read
+    /// the messages linked to the new keyload.
+    ///
+    /// If the keyload cannot be sent, the [`Psk`] is kept in state.
+    ///
+    /// # Arguments
+    /// * `pskid`: The [identifier](`PskId`) of the [`Psk`] to be revoked.
+    /// * `topic`: The [`Topic`] of the branch to be rekeyed.
+    pub async fn revoke_psk_and_rekey(&mut self, pskid: PskId, topic: &Topic) -> Result<SendResponse<TSR>> {
+        let psk = *self.state.psk_store.get(&pskid).ok_or(Error::UnknownPsk(pskid))?;
+        let topic = Self::validate_topic(topic.clone())?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "revoke a pre shared key",
+            ));
+        }
+
+        let psk_subscriber = Identity::from(&psk).identifier().clone();
+        let psks: Vec<PskId> = self
+            .state
+            .psk_store
+            .keys()
+            .filter(|known_pskid| **known_pskid != pskid)
+            .copied()
+            .collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| {
+            if *s == psk_subscriber {
+                None
+            } else if self.is_readonly_subscriber(s) {
+                Some(Permissioned::Read(s.clone()))
+            } else {
+                Some(self.current_permission(&topic, s))
+            }
+        });
+        let send_response = self
+            .send_keyload(
+                topic,
+                // Alas, must collect to release the &self immutable borrow
+                subscribers.iter().map(Permissioned::as_ref),
+                psks,
+            )
+            .await?;
+        self.remove_psk(pskid);
+        self.remove_subscriber(&psk_subscriber);
+        self.state.cursor_store.remove(&psk_subscriber);
+        Ok(send_response)
+    }
+
+    /// Prepares a batch of messages in order, each one linked to the state left by the previous
+    /// ones, and sends them all once the whole batch has been prepared. If any message of the batch
+    /// fails to be prepared, or if any of their addresses is already used, nothing is sent and the
+    /// [`User`] state is left as it was before the batch.
+    ///
+    /// Messages are handed over to the transport in a single
+    /// [`send_messages_batch`](`Transport::send_messages_batch`) call, in the order of the batch. If
+    /// the transport fails while sending them, the messages sent so far are not reverted: the
+    /// [`User`] state is committed up to the last message of the batch found in the transport, and
+    /// the error is returned.
+    ///
+    /// # Arguments
+    /// * `items`: The [messages](`BatchItem`) of the batch, in the order they must be sent.
+    pub async fn send_batch<I>(&mut self, items: I) -> Result<Vec<SendResponse<TSR>>>
+    where
+        I: IntoIterator<Item = BatchItem>,
+    {
+        // Prepare the messages on a copy of the state, which is discarded if the batch is aborted. The
+        // state left by each message is kept in case the transport fails partway through the batch
+        let mut staging = User::<StagingTransport, HasIdentity> {
+            transport: StagingTransport::default(),
+            state: self.state.clone(),
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
+        };
+        let mut staged_states = Vec::new();
+        for item in items {
+            match item {
+                BatchItem::SignedPacket { topic, public, masked } => {
+                    staging.send_signed_packet(topic, public, masked).await?;
+                }
+                BatchItem::TaggedPacket { topic, public, masked } => {
+                    staging.send_tagged_packet(topic, public, masked).await?;
+                }
+                BatchItem::Keyload {
+                    topic,
+                    subscribers,
+                    psks,
+                } => {
+                    staging
+                        .send_keyload(topic, subscribers.iter().map(Permissioned::as_ref), psks)
+                        .await?;
+                }
+            }
+            staged_states.push(staging.state.clone());
+        }
+        let User {
+            transport: staging_transport,
+            ..
+        } = staging;
+        let messages = staging_transport.into_messages();
+
+        for (address, _) in &messages {
+            if self.configured_transport().recv_message(*address).await.is_ok() {
+                return Err(Error::AddressUsed("batch", *address));
+            }
+        }
+        let addresses: Vec<Address> = messages.iter().map(|(address, _)| *address).collect();
+        match self.send_staged_messages(messages, "send batch message").await {
+            Ok(send_responses) => {
+                if let Some(state) = staged_states.pop() {
+                    self.state = state;
+                }
+                Ok(send_responses)
+            }
+            Err(e) => {
+                // Commit the messages the transport received before failing, so they are not sent
+                // again from the same cursors
+                let mut sent = 0;
+                for address in &addresses {
+                    if self.configured_transport().recv_message(*address).await.is_err() {
+                        break;
+                    }
+                    sent += 1;
+                }
+                if sent > 0 {
+                    self.state = staged_states.swap_remove(sent - 1);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Sends messages prepared on a [`StagingTransport`] in a single batch, pairing each transport
+    /// response with the [`Address`] of its message
+    ///
+    /// # Arguments
+    /// * `messages`: The prepared messages, along with the [`Address`] they must be sent to
+    /// * `operation`: Description of the operation, used to report transport failures
+    async fn send_staged_messages(
+        &mut self,
+        messages: Vec<(Address, TransportMessage)>,
+        operation: &'static str,
+    ) -> Result<Vec<SendResponse<TSR>>> {
+        let addresses: Vec<Address> = messages.iter().map(|(address, _)| *address).collect();
+        let batch_address = match addresses.first() {
+            Some(address) => *address,
+            None => return Ok(Vec::new()),
+        };
+        let hooks = self.send_hooks.clone();
+        for (address, msg) in &messages {
+            hooks.before_send(address, msg);
+        }
+        let sent = if hooks.has_post_send() {
+            messages.clone()
+        } else {
+            Vec::new()
+        };
+        let send_responses = self
+            .configured_transport()
+            .send_messages_batch(messages)
+            .await
+            .map_err(|e| Error::transport(batch_address, operation, e))?;
+        for ((address, msg), send_response) in sent.iter().zip(&send_responses) {
+            hooks.after_send(address, msg, send_response);
+        }
+        Ok(addresses
+            .into_iter()
+            .zip(send_responses)
+            .map(|(address, send_response)| SendResponse::new(address, send_response))
+            .collect())
+    }
+
+    /// Create and send a new Keyload message for all participants except the `revoked` ones,
+    /// revoking their access to the specified branch. The remaining subscribers keep the permission
+    /// they currently hold in the branch, or are granted read permission if they hold none.
+    /// Identifiers in `revoked` that are not known subscribers are ignored.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
+    /// * `revoked`: The [`Identifier`]s of the subscribers whose access will be revoked.
+    pub async fn send_keyload_revoke<Top>(&mut self, topic: Top, revoked: &[Identifier]) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic> + Clone,
+    {
+        let topic = Self::validate_topic(topic)?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "send a keyload",
+            ));
+        }
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| {
+            (!revoked.contains(s)).then(|| self.current_permission(&topic, s))
+        });
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            subscribers.iter().map(Permissioned::as_ref),
+            psks,
+        )
+        .await
+    }
+
+    /// Create and send a new Keyload message upgrading the permission of a subscriber in the
+    /// specified branch, from [`Permissioned::Read`] to [`Permissioned::ReadWrite`] to
+    /// [`Permissioned::Admin`]. All the other subscribers keep the permission they currently hold
+    /// in the branch, or read permission if they hold none, and all known [`Psk`]s are included.
+    ///
+    /// Errors if the subscriber is not known, if `new_permission` is not an upgrade of the
+    /// permission it currently holds, or if the subscriber
+    /// [subscribed as read-only](`User::subscribe_readonly`).
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch the permission will be upgraded in.
+    /// * `id`: The [`Identifier`] of the subscriber.
+    /// * `new_permission`: The [`Permissioned`] [`Identifier`] of the subscriber to grant.
+    pub async fn upgrade_permission(
+        &mut self,
+        topic: &Topic,
+        id: &Identifier,
+        new_permission: Permissioned<Identifier>,
+    ) -> Result<SendResponse<TSR>> {
+        fn rank(permission: &Permissioned<Identifier>) -> u8 {
+            match permission {
+                Permissioned::Read(_) => 0,
+                Permissioned::ReadWrite(_, _) => 1,
+                Permissioned::Admin(_) => 2,
+            }
+        }
+
+        let topic = Self::validate_topic(topic.clone())?;
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole(
+                "Admin",
+                permission.identifier().clone(),
+                "upgrade a permission",
+            ));
+        }
+        if new_permission.identifier() != id {
+            return Err(Error::Setup(
+                "the new permission must be granted to the upgraded subscriber",
+            ));
+        }
+        if !self.state.subscribers.contains(id) {
+            return Err(Error::Setup(
+                "only known subscribers can have their permission upgraded",
+            ));
+        }
+        if self.is_readonly_subscriber(id) && !new_permission.is_readonly() {
+            return Err(Error::WrongRole("ReadWrite", id.clone(), "be granted write permission"));
+        }
+        let current_permission = self.current_permission(&topic, id);
+        if rank(&new_permission) <= rank(&current_permission) {
+            return Err(Error::Setup("the new permission must be an upgrade of the current one"));
+        }
+
+        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
+        let subscribers = self.keyload_subscribers(permission.identifier(), |s| {
+            if s == id {
+                Some(new_permission.clone())
+            } else {
+                Some(self.current_permission(&topic, s))
+            }
+        });
+        self.send_keyload(
+            topic,
+            // Alas, must collect to release the &self immutable borrow
+            subscribers.iter().map(Permissioned::as_ref),
+            psks,
+        )
+        .await
+    }
+
+    /// Create a new [`MessageBuilder`] instance.
+    pub fn message<P: Default>(&mut self) -> MessageBuilder<P, T> {
+        MessageBuilder::new(self)
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch. The message will
+    /// contain a masked and an unmasked payload. The message will be signed by the [`User`]
+    /// [`Identity`] keys.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+                topic = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_signed_packet<P, M, Top>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic>,
+    {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before sending a signed packet, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send signed packet"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "send a signed packet",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+
+        let content = PCF::new_final_frame().with_content(signed_packet::Wrap::new(
+            &mut linked_msg_spongos,
+            &(*user_id),
+            public_payload.as_ref(),
+            masked_payload.as_ref(),
+        ));
+        let header = HDF::new(message_types::SIGNED_PACKET, new_cursor, identifier.clone(), &topic)
+            .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send signed packet", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if !self.configured_transport().recv_message(message_address).await.is_err() {
+            return Err(Error::AddressUsed("signed packet", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send signed packet", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        self.store_spongos(rel_address, spongos, link_to);
+        // Update Branch Links
+        self.set_latest_link(topic, message_address.relative());
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Signed Packet message to the [default branch](`User::set_default_topic`).
+    /// The message will contain a masked and an unmasked payload, and will be signed by the
+    /// [`User`] [`Identity`] keys. Returns [`Error::NoDefaultTopic`] if no default branch is set.
+    ///
+    /// # Arguments
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    pub async fn send_signed_packet_default<P, M>(
+        &mut self,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        let topic = self.default_topic().cloned().ok_or(Error::NoDefaultTopic)?;
+        self.send_signed_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch, unless a packet has
+    /// already been sent with the same idempotency key, so that sends retried under at-least-once
+    /// transport semantics do not publish duplicate messages. If the key is known, nothing is sent
+    /// and the [`SendResponse`] of the original packet is returned instead. Errors if the key was
+    /// used for a packet with a different branch or payloads.
+    ///
+    /// The latest 256 keys are kept, and included in [backups](`User::backup`). Transport responses
+    /// are not: a key repeated after a restore errors with the [`Address`] of the original packet.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    /// * `idempotency_key`: A key unique to the packet, chosen by the caller.
+    pub async fn send_signed_packet_idempotent<P, M, Top>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+        idempotency_key: &[u8; 32],
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic>,
+        TSR: Clone + Send + Sync + 'static,
+    {
+        let topic = topic.into();
+        let mut digest_spongos = Spongos::<KeccakF1600>::init();
+        digest_spongos.absorb(public_payload.as_ref().len().to_be_bytes());
+        digest_spongos.absorb(&public_payload);
+        let payload_digest: [u8; 32] = digest_spongos.sponge(&masked_payload);
+
+        if let Some(sent) = self
+            .state
+            .sent_idempotency_keys
+            .iter()
+            .find(|sent| &sent.key == idempotency_key)
+        {
+            if sent.topic != topic || sent.payload_digest != payload_digest {
+                return Err(Error::IdempotencyKeyReused(sent.address));
+            }
+            return self
+                .idempotent_responses
+                .get(sent)
+                .ok_or(Error::IdempotentResponseMissing(sent.address));
+        }
+
+        let send_response = self
+            .send_signed_packet(topic.clone(), public_payload, masked_payload)
+            .await?;
+        if self.state.sent_idempotency_keys.len() == IDEMPOTENCY_KEYS_KEPT {
+            if let Some(oldest) = self.state.sent_idempotency_keys.pop_front() {
+                self.idempotent_responses.remove(&oldest.key);
+            }
+        }
+        self.state.sent_idempotency_keys.push_back(IdempotentSend::new(
+            *idempotency_key,
+            topic,
+            payload_digest,
+            send_response.address(),
+        ));
+        self.idempotent_responses
+            .insert(*idempotency_key, send_response.response().clone());
+        Ok(send_response)
+    }
+
+    /// Create and send the same Signed Packet message to several branches, such as a sensor reading
+    /// relevant to each of them. The packets are sent one after the other, in the order the topics
+    /// are provided, so the cursor of the [`User`] in each branch advances deterministically.
+    /// Returns the [`SendResponse`] of each packet, in the same order.
+    ///
+    /// Every branch is checked before any packet is sent, so the send is aborted with the [`User`]
+    /// state left untouched if a topic is invalid or if the [`User`] cannot write to one of the
+    /// branches. Packets sent before a transport failure are not rolled back.
+    ///
+    /// # Arguments
+    /// * `payload`: The unmasked and masked payloads of the packets.
+    /// * `topics`: The [topics](`Topic`) of the branches to send the packets to.
+    pub async fn send_to_multiple_topics<I, Top>(
+        &mut self,
+        payload: (&[u8], &[u8]),
+        topics: I,
+    ) -> Result<Vec<SendResponse<TSR>>>
+    where
+        I: IntoIterator<Item = Top>,
+        Top: Into<Topic>,
+    {
+        let (public_payload, masked_payload) = payload;
+        let identifier = self
+            .identifier()
+            .ok_or(Error::NoIdentity("send to multiple topics"))?
+            .clone();
+        let topics = topics
+            .into_iter()
+            .map(Self::validate_topic)
+            .collect::<Result<Vec<Topic>>>()?;
+        for topic in &topics {
+            let permission = self
+                .state
+                .cursor_store
+                .get_permission(topic, &identifier)
+                .ok_or_else(|| Error::NoCursor(topic.clone()))?;
+            if permission.is_readonly() {
+                return Err(Error::WrongRole(
+                    "ReadWrite",
+                    permission.identifier().clone(),
+                    "send a signed packet",
+                ));
+            }
+        }
+
+        let mut responses = Vec::with_capacity(topics.len());
+        for topic in topics {
+            responses.push(self.send_signed_packet(topic, public_payload, masked_payload).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch, once `verifier` has
+    /// approved its unmasked payload. The message will contain a masked and an unmasked payload, and
+    /// will be signed by the [`User`] [`Identity`] keys.
+    ///
+    /// The payload is verified before the message is constructed: if the verifier returns an
+    /// error, the send is aborted with that error and the [`User`] state is left untouched.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    /// * `verifier`: The [`Verify`] gate approving the unmasked payload.
+    pub async fn send_signed_packet_verified<P, M, Top, V>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+        verifier: V,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic>,
+        V: Verify,
+    {
+        verifier.verify(public_payload.as_ref()).await?;
+        self.send_signed_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch, once its unmasked
+    /// payload has been validated against `schema`. The message will contain a masked and an
+    /// unmasked payload, and will be signed by the [`User`] [`Identity`] keys.
+    ///
+    /// If the payload does not match the schema, the send is aborted with
+    /// [`Error::SchemaValidationFailed`] and the [`User`] state is left untouched.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    /// * `schema`: The [`SchemaValidator`] the unmasked payload must match.
+    pub async fn send_signed_packet_with_schema<P, M, Top, S>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+        schema: &S,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic>,
+        S: SchemaValidator,
+    {
+        schema.validate(public_payload.as_ref())?;
+        self.send_signed_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch, serializing `value` to
+    /// JSON as the unmasked payload. The message will be signed by the [`User`] [`Identity`] keys.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `value`: The value to be serialized as the unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    #[cfg(feature = "typed-payloads")]
+    pub async fn send_signed_packet_typed<M, Top>(
+        &mut self,
+        topic: Top,
+        value: &M,
+        masked_payload: &[u8],
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: serde::Serialize,
+        Top: Into<Topic>,
+    {
+        let public_payload = serde_json::to_vec(value).map_err(|e| Error::TypedPayload("serialize", e))?;
+        self.send_signed_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Signed Packet message to the specified branch, embedding the current
+    /// system time in its public payload. The time can be read back by receivers with
+    /// [`Message::decode_timestamp`], and is covered by the publisher signature.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message, wrapped in a [`TimestampedPublicPayload`].
+    /// * `masked_payload`: The masked payload of the message.
+    ///
+    /// [`TimestampedPublicPayload`]: crate::TimestampedPublicPayload
+    #[cfg(feature = "timestamped-payloads")]
+    pub async fn send_signed_packet_with_timestamp<P, M, Top>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        P: AsRef<[u8]>,
+        M: AsRef<[u8]>,
+        Top: Into<Topic>,
+    {
+        let public_payload =
+            crate::api::timestamped_payload::TimestampedPublicPayload::now(public_payload.as_ref().to_vec())?
+                .encode()?;
+        self.send_signed_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Tagged Packet message to the specified branch, compressing its masked
+    /// payload with `zstd`. The unmasked payload is sent as is. The masked payload can be read back
+    /// by receivers with [`Message::decompress_masked`].
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message, to be compressed.
+    #[cfg(feature = "compressed-payloads")]
+    pub async fn send_tagged_packet_compressed<P, M, Top>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        P: AsRef<[u8]>,
+        M: AsRef<[u8]>,
+        Top: Into<Topic>,
+    {
+        let masked_payload = crate::api::compressed_payload::compress(masked_payload.as_ref())?;
+        self.send_tagged_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Tagged Packet message to the specified branch. The message will
+    /// contain a masked and an unmasked payload.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                stream_address = ?self.stream_address(),
+                identifier = ?self.identifier(),
+                topic = tracing::field::Empty,
+            )
+        )
+    )]
+    pub async fn send_tagged_packet<P, M, Top>(
+        &mut self,
+        topic: Top,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic>,
+    {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before sending a tagged packet, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send tagged packet"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "send a tagged packet",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(tagged_packet::Wrap::new(
+            &mut linked_msg_spongos,
+            public_payload.as_ref(),
+            masked_payload.as_ref(),
+        ));
+        let header = HDF::new(message_types::TAGGED_PACKET, new_cursor, identifier.clone(), &topic)
+            .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send tagged packet", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if !self.configured_transport().recv_message(message_address).await.is_err() {
+            return Err(Error::AddressUsed("tagged packet", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send tagged packet", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        self.store_spongos(rel_address, spongos, link_to);
+        // Update Branch Links
+        self.set_latest_link(topic, rel_address);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Tagged Packet message to the [default branch](`User::set_default_topic`).
+    /// The message will contain a masked and an unmasked payload. Returns
+    /// [`Error::NoDefaultTopic`] if no default branch is set.
+    ///
+    /// # Arguments
+    /// * `public_payload`: The unmasked payload of the message.
+    /// * `masked_payload`: The masked payload of the message.
+    pub async fn send_tagged_packet_default<P, M>(
+        &mut self,
+        public_payload: P,
+        masked_payload: M,
+    ) -> Result<SendResponse<TSR>>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+    {
+        let topic = self.default_topic().cloned().ok_or(Error::NoDefaultTopic)?;
+        self.send_tagged_packet(topic, public_payload, masked_payload).await
+    }
+
+    /// Create and send a new Proof of Inclusion message to the specified branch, attesting that the
+    /// target message is part of the stream. The message carries the [`Address`] of the target
+    /// message and a digest of its [`Spongos`] state, and will be signed by the [`User`]
+    /// [`Identity`] keys.
+    ///
+    /// Like heartbeats, proofs are linked to the latest message of the branch, but subsequent
+    /// messages are not linked to them, so only the [`User`] cursor is advanced.
+    ///
+    /// # Arguments
+    /// * `target_address`: The [`Address`] of the message to prove the inclusion of.
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    pub async fn send_proof_of_inclusion<Top>(
+        &mut self,
+        target_address: Address,
+        topic: Top,
+    ) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic>,
+    {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before sending a proof of inclusion, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send proof of inclusion"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "send a proof of inclusion",
+            ));
+        }
+        // Digest target message
+        let digest = self.inclusion_digest(target_address)?;
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(proof_of_inclusion::Wrap::new(
+            &mut linked_msg_spongos,
+            user_id,
+            target_address,
+            digest,
+        ));
+        let header = HDF::new(
+            message_types::PROOF_OF_INCLUSION,
+            new_cursor,
+            identifier.clone(),
+            &topic,
+        )
+        .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send proof of inclusion", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("proof of inclusion", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send proof of inclusion", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Proof of inclusion messages are never linked to by other messages, so neither their spongos
+        // nor the branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Split a document across several signed packets sent to the specified branch, followed by a
+    /// Multipart Manifest message listing the [`Address`]es of the parts, in order. Each part is a
+    /// pair of public and masked payloads, and every message is signed by the [`User`] [`Identity`]
+    /// keys. Returns the [`Address`] of the manifest and the [`Address`]es of the parts.
+    ///
+    /// The parts are prepared first and sent together in a single
+    /// [`send_messages_batch`](`Transport::send_messages_batch`) call. As the manifest carries the
+    /// addresses of the parts, it is sent after them. Like heartbeats, it is linked to the latest
+    /// message of the branch, but subsequent messages are not linked to it, so only the [`User`]
+    /// cursor is advanced.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the messages to.
+    /// * `parts`: The public and masked payloads of each part, in order.
+    pub async fn send_multipart_message<P, M, Top>(
+        &mut self,
+        topic: Top,
+        parts: Vec<(P, M)>,
+    ) -> Result<(Address, Vec<Address>)>
+    where
+        M: AsRef<[u8]>,
+        P: AsRef<[u8]>,
+        Top: Into<Topic> + Clone,
+    {
+        // Prepare the parts on a copy of the state, then send them in a single batch
+        let mut staging = User::<StagingTransport, HasIdentity> {
+            transport: StagingTransport::default(),
+            state: self.state.clone(),
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
+        };
+        for (public_payload, masked_payload) in parts {
+            staging
+                .send_signed_packet(topic.clone(), public_payload, masked_payload)
+                .await?;
+        }
+        let User {
+            transport: staging_transport,
+            state: staged_state,
+            ..
+        } = staging;
+        let messages = staging_transport.into_messages();
+        for (address, _) in &messages {
+            if self.configured_transport().recv_message(*address).await.is_ok() {
+                return Err(Error::AddressUsed("multipart message part", *address));
+            }
+        }
+        let part_addresses: Vec<Address> = self
+            .send_staged_messages(messages, "send multipart message part")
+            .await?
+            .iter()
+            .map(SendResponse::address)
+            .collect();
+        self.state = staged_state;
+
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before sending a multipart manifest, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send multipart manifest"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "send a multipart manifest",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(multipart_manifest::Wrap::new(
+            &mut linked_msg_spongos,
+            user_id,
+            &part_addresses,
+        ));
+        let header = HDF::new(
+            message_types::MULTIPART_MANIFEST,
+            new_cursor,
+            identifier.clone(),
+            &topic,
+        )
+        .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send multipart manifest", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("multipart manifest", message_address));
+        }
+        self.send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send multipart manifest", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Multipart manifest messages are never linked to by other messages, so neither their spongos
+        // nor the branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        Ok((message_address, part_addresses))
+    }
+
+    /// Create and send a new Heartbeat message to the specified branch, signaling that the [`User`]
+    /// is still active in it. The message carries no payload and will be signed by the [`User`]
+    /// [`Identity`] keys.
+    ///
+    /// Heartbeats are linked to the latest message of the branch, but subsequent messages are not
+    /// linked to them, so only the [`User`] cursor is advanced.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    pub async fn send_heartbeat<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic>,
+    {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before sending a heartbeat, the stream must be created"))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send heartbeat"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "send a heartbeat",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(heartbeat::Wrap::new(&mut linked_msg_spongos, user_id));
+        let header =
+            HDF::new(message_types::HEARTBEAT, new_cursor, identifier.clone(), &topic).with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send heartbeat", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("heartbeat", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send heartbeat", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Heartbeat messages are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Stream Link message to the specified branch, referencing another
+    /// stream. The readers of the branch learn the [`Address`] of the remote stream when processing
+    /// the message, and can retrieve it with [`User::get_linked_stream`]. A branch references a
+    /// single remote stream: connecting it again replaces the reference.
+    ///
+    /// # Arguments
+    /// * `topic`: The [`Topic`] of the branch to send the message to.
+    /// * `remote_stream_address`: The [`Address`] of the remote stream.
+    pub async fn connect_streams(
+        &mut self,
+        topic: &Topic,
+        remote_stream_address: Address,
+    ) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self
+            .stream_address()
+            .ok_or(Error::Setup("before connecting streams, the stream must be created"))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("connect streams"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(topic.clone())?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "connect streams",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(stream_link::Wrap::new(
+            &mut linked_msg_spongos,
+            user_id,
+            &remote_stream_address,
+        ));
+        let header = HDF::new(message_types::STREAM_LINK, new_cursor, identifier.clone(), &topic)
+            .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send stream link", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("stream link", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send stream link", e))?;
+
+        // If message has been sent successfully, commit message to stores
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        self.store_spongos(rel_address, spongos, link_to);
+        self.state.linked_streams.insert(topic.clone(), remote_stream_address);
+        // Update Branch Links
+        self.set_latest_link(topic, message_address.relative());
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Stream Catalog message to the specified discovery branch, listing the
+    /// provided stream [`Address`]es. The message will be signed by the [`User`] [`Identity`] keys.
+    ///
+    /// The catalog is not joined to any previous message, so it can be read by anyone who knows its
+    /// [`Address`] using [`User::read_catalog`], without being attached to the stream.
+    ///
+    /// # Arguments
+    /// * `discovery_topic`: The [`Topic`] of the branch to publish the catalog to.
+    /// * `known_streams`: The [`Address`]es of the streams to be listed in the catalog.
+    pub async fn publish_catalog<Top>(
+        &mut self,
+        discovery_topic: Top,
+        known_streams: &[Address],
+    ) -> Result<SendResponse<TSR>>
+    where
+        Top: Into<Topic>,
+    {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before publishing a stream catalog, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("publish stream catalog"))?;
+        let identifier = user_id.identifier().clone();
+        // Check Topic
+        let topic = Self::validate_topic(discovery_topic)?;
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?;
+        if permission.is_readonly() {
+            return Err(Error::WrongRole(
+                "ReadWrite",
+                permission.identifier().clone(),
+                "publish a stream catalog",
+            ));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        let content = PCF::new_final_frame().with_content(stream_catalog::Wrap::new(user_id, known_streams));
+        let header = HDF::new(message_types::STREAM_CATALOG, new_cursor, identifier.clone(), &topic)
+            .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("publish stream catalog", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("stream catalog", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "publish stream catalog", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Stream catalog messages are never linked to by other messages, so neither their spongos nor
+        // the branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, permission.clone(), new_cursor);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Direct Message to a single recipient. The payload is masked with a key
+    /// encapsulated for the recipient X25519 public key, derived from its [`Identifier`], so only the
+    /// recipient can read it. The message will be signed by the [`User`] [`Identity`] keys.
+    ///
+    /// Direct messages are published in the base branch and joined to the stream announcement. The
+    /// [`User`] must hold a cursor in the base branch, but no write permission is required.
+    ///
+    /// # Arguments
+    /// * `recipient`: The [`Identifier`] of the recipient of the message.
+    /// * `payload`: The payload that will be masked for the recipient.
+    pub async fn send_direct_message(&mut self, recipient: &Identifier, payload: &[u8]) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before sending a direct message, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("send direct message"))?;
+        let identifier = user_id.identifier().clone();
+        let topic = self.base_branch().clone();
+        // Check Permission
+        let permission = self
+            .state
+            .cursor_store
+            .get_permission(&topic, &identifier)
+            .ok_or(Error::NoCursor(topic.clone()))?
+            .clone();
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // All direct messages will attach to stream Announcement message spongos
+        let mut announcement_msg_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .ok_or(Error::Setup("a user must keep a stream announcement spongos in store"))?;
+        let mut rng = StdRng::from_entropy();
+        let key = rng.gen();
+        let content = PCF::new_final_frame().with_content(direct_message::Wrap::new(
+            &mut announcement_msg_spongos,
+            user_id,
+            recipient,
+            key,
+            payload,
+        ));
+        let header = HDF::new(message_types::DIRECT_MESSAGE, new_cursor, identifier, &topic)
+            .with_linked_msg_address(stream_address.relative());
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("send direct message", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("direct message", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "send direct message", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Direct messages are never linked to by other messages, so neither their spongos nor the
+        // branch latest link are stored
+        self.state.cursor_store.insert_cursor(&topic, permission, new_cursor);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Stream Metadata message, describing the stream with a human-readable
+    /// [`StreamMetadata`]. The message is published in the base branch, joined to the stream
+    /// announcement and signed by the [`User`] [`Identity`] keys. Only admins of the base branch may
+    /// publish stream metadata.
+    ///
+    /// # Arguments
+    /// * `meta`: The [`StreamMetadata`] describing the stream.
+    pub async fn publish_stream_metadata(&mut self, meta: StreamMetadata) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before publishing stream metadata, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("publish stream metadata"))?;
+        let identifier = user_id.identifier().clone();
+        let topic = self.base_branch().clone();
+        // Check Permission
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole("Admin", identifier, "publish stream metadata"));
+        }
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // All stream metadata messages will attach to stream Announcement message spongos
+        let mut announcement_msg_spongos = self
+            .stored_spongos(&stream_address.relative())
+            .copied()
+            .ok_or(Error::Setup("a user must keep a stream announcement spongos in store"))?;
+        let content = PCF::new_final_frame().with_content(stream_metadata::Wrap::new(
+            &mut announcement_msg_spongos,
+            user_id,
+            &meta.name,
+            &meta.description,
+            meta.schema_version,
+            &meta.tags,
+        ));
+        let header = HDF::new(message_types::STREAM_METADATA, new_cursor, identifier.clone(), &topic)
+            .with_linked_msg_address(stream_address.relative());
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("publish stream metadata", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("stream metadata", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "publish stream metadata", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Stream metadata messages are never linked to by other messages, so neither their spongos nor
+        // the branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(identifier), new_cursor);
+        self.state.stream_metadata = Some(meta);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+
+    /// Create and send a new Stream Metadata Update message, replacing the [`StreamMetadata`] of
+    /// the stream after it has been created. The message is published in the base branch, linked to
+    /// its latest message and signed by the [`User`] [`Identity`] keys. Only admins of the base
+    /// branch may update the stream metadata.
+    ///
+    /// # Arguments
+    /// * `new_metadata`: The [`StreamMetadata`] replacing the current one.
+    pub async fn send_announcement_update(&mut self, new_metadata: StreamMetadata) -> Result<SendResponse<TSR>> {
+        // Check conditions
+        let stream_address = self.stream_address().ok_or(Error::Setup(
+            "before updating stream metadata, the stream must be created",
+        ))?;
+        let user_id = self.identity().ok_or(Error::NoIdentity("update stream metadata"))?;
+        let identifier = user_id.identifier().clone();
+        let topic = self.base_branch().clone();
+        // Check Permission
+        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
+        if !permission.is_admin() {
+            return Err(Error::WrongRole("Admin", identifier, "update stream metadata"));
+        }
+        // Link message to latest message in branch
+        let link_to = self
+            .get_latest_link(&topic)
+            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        // Update own's cursor
+        let new_cursor = self.next_cursor(&topic)?;
+        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+
+        // Prepare HDF and PCF
+        // Spongos must be copied because wrapping mutates it
+        let mut linked_msg_spongos = self
+            .stored_spongos(&link_to)
+            .copied()
+            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let content = PCF::new_final_frame().with_content(stream_metadata_update::Wrap::new(
+            &mut linked_msg_spongos,
+            user_id,
+            &new_metadata.name,
+            &new_metadata.description,
+            new_metadata.schema_version,
+            &new_metadata.tags,
+        ));
+        let header = HDF::new(
+            message_types::STREAM_METADATA_UPDATE,
+            new_cursor,
+            identifier.clone(),
+            &topic,
+        )
+        .with_linked_msg_address(link_to);
+
+        // Wrap message
+        let (transport_msg, _spongos) = LetsMessage::new(header, content)
+            .wrap()
+            .await
+            .map_err(|e| Error::Wrapped("update stream metadata", e))?;
+
+        // Attempt to send message
+        let message_address = Address::new(stream_address.base(), rel_address);
+        if self.configured_transport().recv_message(message_address).await.is_ok() {
+            return Err(Error::AddressUsed("stream metadata update", message_address));
+        }
+        let send_response = self
+            .send_to_transport(message_address, transport_msg)
+            .await
+            .map_err(|e| Error::transport(stream_address, "update stream metadata", e))?;
+
+        // If message has been sent successfully, update own's cursor
+        // Stream metadata update messages are never linked to by other messages, so neither their
+        // spongos nor the branch latest link are stored
+        self.state
+            .cursor_store
+            .insert_cursor(&topic, Permissioned::Admin(identifier), new_cursor);
+        self.state.stream_metadata = Some(new_metadata);
+        Ok(SendResponse::new(message_address, send_response))
+    }
+}
+
+#[async_trait(?Send)]
+impl ContentSizeof<State> for sizeof::Context {
+    async fn sizeof(&mut self, user_state: &State) -> SpongosResult<&mut Self> {
+        self.mask(Maybe::new(user_state.user_id.as_ref()))?
+            .mask(Maybe::new(user_state.stream_address.as_ref()))?
+            .mask(Maybe::new(user_state.author_identifier.as_ref()))?
+            .mask(&user_state.base_branch)?;
+
+        let amount_spongos = user_state.spongos_store.len();
+        self.mask(Size::new(amount_spongos))?;
+        for (address, spongos) in &user_state.spongos_store {
+            self.mask(address)?.mask(spongos)?;
+        }
+
+        // Only keep topics that exist in cursor store, any others serve no purpose
+        let topics = user_state
+            .topics
+            .iter()
+            .filter(|t| user_state.cursor_store.get_latest_link(t).is_some());
+        let amount_topics = topics.clone().count();
+        self.mask(Size::new(amount_topics))?;
+
+        for topic in topics {
+            self.mask(topic)?;
+            let latest_link = user_state
+                .cursor_store
+                .get_latest_link(topic)
+                .ok_or(SpongosError::InvalidAction(
+                    "calculate sizeof for topic latest link",
+                    topic.to_string(),
+                    "No Cursor".to_owned(),
+                ))?;
+            self.mask(&latest_link)?;
+
+            let cursors: Vec<(&Permissioned<Identifier>, &usize)> = user_state
+                .cursor_store
+                .cursors_by_topic(topic)
+                .ok_or(SpongosError::InvalidAction(
+                    "get cursor for topic",
+                    topic.to_string(),
+                    "No Cursor".to_owned(),
+                ))?
+                .collect();
+            let amount_cursors = cursors.len();
+            self.mask(Size::new(amount_cursors))?;
+            for (subscriber, cursor) in cursors {
+                self.mask(subscriber)?.mask(Size::new(*cursor))?;
+            }
+        }
+
+        let subs = &user_state.subscribers;
+        let amount_subs = subs.len();
+        self.mask(Size::new(amount_subs))?;
+        for subscriber in subs {
+            self.mask(subscriber)?;
+        }
+
+        let psks = user_state.psk_store.iter();
+        let amount_psks = psks.len();
+        self.mask(Size::new(amount_psks))?;
+        for (pskid, psk) in psks {
+            self.mask(pskid)?.mask(psk)?;
+        }
+
+        match user_state.retention {
+            SpongosRetention::All => self.mask(Uint8::new(0))?,
+            SpongosRetention::Lean => self.mask(Uint8::new(1))?,
+            SpongosRetention::LastN(n) => self.mask(Uint8::new(2))?.mask(Size::new(n))?,
+        };
+        self.mask(Size::new(user_state.spongos_order.len()))?;
+        for address in &user_state.spongos_order {
+            self.mask(address)?;
+        }
+
+        let amount_log_entries = user_state.message_log.len();
+        self.mask(Size::new(amount_log_entries))?;
+        for entry in &user_state.message_log {
+            self.mask(&entry.topic)?
+                .mask(&entry.address)?
+                .mask(Uint8::new(entry.message_type))?
+                .mask(&entry.publisher)?
+                .mask(Size::new(entry.cursor))?
+                .mask(Size::new(entry.processed_at_cursor))?;
+        }
+
+        let amount_links = user_state.link_store.len();
+        self.mask(Size::new(amount_links))?;
+        for (msg_address, (linked_msg_address, digest)) in &user_state.link_store {
+            self.mask(msg_address)?;
+            match linked_msg_address {
+                Some(linked_msg_address) => self.mask(Uint8::new(1))?.mask(linked_msg_address)?,
+                None => self.mask(Uint8::new(0))?,
+            };
+            self.mask(NBytes::new(digest))?;
+        }
+
+        let amount_followed_topics = user_state.followed_topics.len();
+        self.mask(Size::new(amount_followed_topics))?;
+        for topic in &user_state.followed_topics {
+            self.mask(topic)?;
+        }
+
+        let amount_groups = user_state.groups.len();
+        self.mask(Size::new(amount_groups))?;
+        for (name, members) in &user_state.groups {
+            self.mask(Bytes::new(name))?.mask(Size::new(members.len()))?;
+            for member in members {
+                self.mask(member)?;
+            }
+        }
+
+        self.mask(Maybe::new(user_state.parent_address.as_ref()))?;
+
+        match &user_state.stream_metadata {
+            Some(metadata) => {
+                self.mask(Uint8::new(1))?
+                    .mask(Bytes::new(&metadata.name))?
+                    .mask(Bytes::new(&metadata.description))?
+                    .mask(NBytes::new(metadata.schema_version.to_be_bytes()))?
+                    .mask(Size::new(metadata.tags.len()))?;
+                for tag in &metadata.tags {
+                    self.mask(Bytes::new(tag))?;
+                }
+            }
+            None => {
+                self.mask(Uint8::new(0))?;
+            }
+        }
+
+        let amount_readonly_subs = user_state.readonly_subscribers.len();
+        self.mask(Size::new(amount_readonly_subs))?;
+        for subscriber in &user_state.readonly_subscribers {
+            self.mask(subscriber)?;
+        }
+
+        let amount_aliases = user_state.spongos_aliases.len();
+        self.mask(Size::new(amount_aliases))?;
+        for (alias, canonical) in &user_state.spongos_aliases {
+            self.mask(alias)?.mask(canonical)?;
+        }
+
+        let amount_branch_parents = user_state.branch_parent.len();
+        self.mask(Size::new(amount_branch_parents))?;
+        for (topic, parent) in &user_state.branch_parent {
+            self.mask(topic)?.mask(parent)?;
+        }
+
+        self.mask(Maybe::new(user_state.default_topic.as_ref()))?;
+
+        let amount_confirmed_topics = user_state.confirmed_subscribers.len();
+        self.mask(Size::new(amount_confirmed_topics))?;
+        for (topic, subscribers) in &user_state.confirmed_subscribers {
+            self.mask(topic)?.mask(Size::new(subscribers.len()))?;
+            for subscriber in subscribers {
+                self.mask(subscriber)?;
+            }
+        }
+
+        let amount_linked_streams = user_state.linked_streams.len();
+        self.mask(Size::new(amount_linked_streams))?;
+        for (topic, remote_stream_address) in &user_state.linked_streams {
+            self.mask(topic)?.mask(remote_stream_address)?;
+        }
+
+        let amount_idempotency_keys = user_state.sent_idempotency_keys.len();
+        self.mask(Size::new(amount_idempotency_keys))?;
+        for sent in &user_state.sent_idempotency_keys {
+            self.mask(NBytes::new(sent.key))?
+                .mask(&sent.topic)?
+                .mask(NBytes::new(sent.payload_digest))?
+                .mask(&sent.address)?;
+        }
+
+        let amount_branch_creation_times = user_state.branch_creation_times.len();
+        self.mask(Size::new(amount_branch_creation_times))?;
+        for (topic, creation_time) in &user_state.branch_creation_times {
+            self.mask(topic)?.mask(NBytes::new(creation_time.to_be_bytes()))?;
+        }
+
+        let amount_capabilities = user_state.capabilities.len();
+        self.mask(Size::new(amount_capabilities))?;
+        for (id, capability) in &user_state.capabilities {
+            self.mask(id)?;
+            match capability {
+                Capability::DataProducer => self.mask(Uint8::new(0))?,
+                Capability::DataConsumer => self.mask(Uint8::new(1))?,
+                Capability::Auditor => self.mask(Uint8::new(2))?,
+            };
+        }
+
+        let amount_expiring_subscribers = user_state.expiring_subscribers.len();
+        self.mask(Size::new(amount_expiring_subscribers))?;
+        for (id, expires_at) in &user_state.expiring_subscribers {
+            self.mask(id)?.mask(Uint64::new(*expires_at))?;
+        }
+
+        self.commit()?.squeeze(Mac::new(32))
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentWrap<State> for wrap::Context<&'a mut [u8]> {
+    async fn wrap(&mut self, user_state: &mut State) -> SpongosResult<&mut Self> {
+        self.mask(Maybe::new(user_state.user_id.as_ref()))?
+            .mask(Maybe::new(user_state.stream_address.as_ref()))?
+            .mask(Maybe::new(user_state.author_identifier.as_ref()))?
+            .mask(&user_state.base_branch)?;
+
+        let amount_spongos = user_state.spongos_store.len();
+        self.mask(Size::new(amount_spongos))?;
+        for (address, spongos) in &user_state.spongos_store {
+            self.mask(address)?.mask(spongos)?;
+        }
+
+        // Only keep topics that exist in cursor store, any others serve no purpose
+        let topics = user_state
+            .topics
+            .iter()
+            .filter(|t| user_state.cursor_store.get_latest_link(t).is_some());
+        let amount_topics = topics.clone().count();
+        self.mask(Size::new(amount_topics))?;
+
+        for topic in topics {
+            self.mask(topic)?;
+            let latest_link = user_state
+                .cursor_store
+                .get_latest_link(topic)
+                .ok_or(SpongosError::InvalidAction(
+                    "get latest link topic for wrap",
+                    topic.to_string(),
+                    "No latest link".to_owned(),
+                ))?;
+            self.mask(&latest_link)?;
+
+            let cursors: Vec<(&Permissioned<Identifier>, &usize)> = user_state
+                .cursor_store
+                .cursors_by_topic(topic)
+                .ok_or(SpongosError::InvalidAction(
+                    "get cursor for topic",
+                    topic.to_string(),
+                    "No cursor found".to_owned(),
+                ))?
+                .collect();
+            let amount_cursors = cursors.len();
+            self.mask(Size::new(amount_cursors))?;
+            for (subscriber, cursor) in cursors {
+                self.mask(subscriber)?.mask(Size::new(*cursor))?;
+            }
+        }
+
+        let subs = &user_state.subscribers;
+        let amount_subs = subs.len();
+        self.mask(Size::new(amount_subs))?;
+        for subscriber in subs {
+            self.mask(subscriber)?;
+        }
+
+        let psks = user_state.psk_store.iter();
+        let amount_psks = psks.len();
+        self.mask(Size::new(amount_psks))?;
+        for (pskid, psk) in psks {
+            self.mask(pskid)?.mask(psk)?;
+        }
+
+        match user_state.retention {
+            SpongosRetention::All => self.mask(Uint8::new(0))?,
+            SpongosRetention::Lean => self.mask(Uint8::new(1))?,
+            SpongosRetention::LastN(n) => self.mask(Uint8::new(2))?.mask(Size::new(n))?,
+        };
+        self.mask(Size::new(user_state.spongos_order.len()))?;
+        for address in &user_state.spongos_order {
+            self.mask(address)?;
+        }
+
+        let amount_log_entries = user_state.message_log.len();
+        self.mask(Size::new(amount_log_entries))?;
+        for entry in &user_state.message_log {
+            self.mask(&entry.topic)?
+                .mask(&entry.address)?
+                .mask(Uint8::new(entry.message_type))?
+                .mask(&entry.publisher)?
+                .mask(Size::new(entry.cursor))?
+                .mask(Size::new(entry.processed_at_cursor))?;
+        }
+
+        let amount_links = user_state.link_store.len();
+        self.mask(Size::new(amount_links))?;
+        for (msg_address, (linked_msg_address, digest)) in &user_state.link_store {
+            self.mask(msg_address)?;
+            match linked_msg_address {
+                Some(linked_msg_address) => self.mask(Uint8::new(1))?.mask(linked_msg_address)?,
+                None => self.mask(Uint8::new(0))?,
+            };
+            self.mask(NBytes::new(digest))?;
+        }
+
+        let amount_followed_topics = user_state.followed_topics.len();
+        self.mask(Size::new(amount_followed_topics))?;
+        for topic in &user_state.followed_topics {
+            self.mask(topic)?;
+        }
+
+        let amount_groups = user_state.groups.len();
+        self.mask(Size::new(amount_groups))?;
+        for (name, members) in &user_state.groups {
+            self.mask(Bytes::new(name))?.mask(Size::new(members.len()))?;
+            for member in members {
+                self.mask(member)?;
+            }
+        }
+
+        self.mask(Maybe::new(user_state.parent_address.as_ref()))?;
+
+        match &user_state.stream_metadata {
+            Some(metadata) => {
+                self.mask(Uint8::new(1))?
+                    .mask(Bytes::new(&metadata.name))?
+                    .mask(Bytes::new(&metadata.description))?
+                    .mask(NBytes::new(metadata.schema_version.to_be_bytes()))?
+                    .mask(Size::new(metadata.tags.len()))?;
+                for tag in &metadata.tags {
+                    self.mask(Bytes::new(tag))?;
+                }
+            }
+            None => {
+                self.mask(Uint8::new(0))?;
+            }
+        }
+
+        let amount_readonly_subs = user_state.readonly_subscribers.len();
+        self.mask(Size::new(amount_readonly_subs))?;
+        for subscriber in &user_state.readonly_subscribers {
+            self.mask(subscriber)?;
+        }
+
+        let amount_aliases = user_state.spongos_aliases.len();
+        self.mask(Size::new(amount_aliases))?;
+        for (alias, canonical) in &user_state.spongos_aliases {
+            self.mask(alias)?.mask(canonical)?;
+        }
+
+        let amount_branch_parents = user_state.branch_parent.len();
+        self.mask(Size::new(amount_branch_parents))?;
+        for (topic, parent) in &user_state.branch_parent {
+            self.mask(topic)?.mask(parent)?;
+        }
+
+        self.mask(Maybe::new(user_state.default_topic.as_ref()))?;
+
+        let amount_confirmed_topics = user_state.confirmed_subscribers.len();
+        self.mask(Size::new(amount_confirmed_topics))?;
+        for (topic, subscribers) in &user_state.confirmed_subscribers {
+            self.mask(topic)?.mask(Size::new(subscribers.len()))?;
+            for subscriber in subscribers {
+                self.mask(subscriber)?;
+            }
+        }
+
+        let amount_linked_streams = user_state.linked_streams.len();
+        self.mask(Size::new(amount_linked_streams))?;
+        for (topic, remote_stream_address) in &user_state.linked_streams {
+            self.mask(topic)?.mask(remote_stream_address)?;
+        }
+
+        let amount_idempotency_keys = user_state.sent_idempotency_keys.len();
+        self.mask(Size::new(amount_idempotency_keys))?;
+        for sent in &user_state.sent_idempotency_keys {
+            self.mask(NBytes::new(sent.key))?
+                .mask(&sent.topic)?
+                .mask(NBytes::new(sent.payload_digest))?
+                .mask(&sent.address)?;
+        }
+
+        let amount_branch_creation_times = user_state.branch_creation_times.len();
+        self.mask(Size::new(amount_branch_creation_times))?;
+        for (topic, creation_time) in &user_state.branch_creation_times {
+            self.mask(topic)?.mask(NBytes::new(creation_time.to_be_bytes()))?;
+        }
+
+        let amount_capabilities = user_state.capabilities.len();
+        self.mask(Size::new(amount_capabilities))?;
+        for (id, capability) in &user_state.capabilities {
+            self.mask(id)?;
+            match capability {
+                Capability::DataProducer => self.mask(Uint8::new(0))?,
+                Capability::DataConsumer => self.mask(Uint8::new(1))?,
+                Capability::Auditor => self.mask(Uint8::new(2))?,
+            };
+        }
+
+        let amount_expiring_subscribers = user_state.expiring_subscribers.len();
+        self.mask(Size::new(amount_expiring_subscribers))?;
+        for (id, expires_at) in &user_state.expiring_subscribers {
+            self.mask(id)?.mask(Uint64::new(*expires_at))?;
+        }
+
+        self.commit()?.squeeze(Mac::new(32))
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentUnwrap<State> for unwrap::Context<&'a [u8]> {
+    async fn unwrap(&mut self, user_state: &mut State) -> SpongosResult<&mut Self> {
+        self.mask(Maybe::new(&mut user_state.user_id))?
+            .mask(Maybe::new(&mut user_state.stream_address))?
+            .mask(Maybe::new(&mut user_state.author_identifier))?
+            .mask(&mut user_state.base_branch)?;
+
+        let mut amount_spongos = Size::default();
+        self.mask(&mut amount_spongos)?;
+        for _ in 0..amount_spongos.inner() {
+            let mut address = MsgId::default();
+            let mut spongos = Spongos::default();
+            self.mask(&mut address)?.mask(&mut spongos)?;
+            user_state.spongos_store.insert(address, spongos);
+        }
+
+        let mut amount_topics = Size::default();
+        self.mask(&mut amount_topics)?;
+
+        for _ in 0..amount_topics.inner() {
+            let mut topic = Topic::default();
+            self.mask(&mut topic)?;
+            let mut latest_link = MsgId::default();
+            self.mask(&mut latest_link)?;
+
+            user_state.topics.insert(topic.clone());
+            user_state.cursor_store.set_latest_link(topic.clone(), latest_link);
+
+            let mut amount_cursors = Size::default();
+            self.mask(&mut amount_cursors)?;
+            for _ in 0..amount_cursors.inner() {
+                let mut subscriber = Permissioned::default();
+                let mut cursor = Size::default();
+                self.mask(&mut subscriber)?.mask(&mut cursor)?;
+                user_state
+                    .cursor_store
+                    .insert_cursor(&topic, subscriber, cursor.inner());
+            }
+        }
+
+        let mut amount_subs = Size::default();
+        self.mask(&mut amount_subs)?;
+        for _ in 0..amount_subs.inner() {
+            let mut subscriber = Identifier::default();
+            self.mask(&mut subscriber)?;
+            user_state.subscribers.insert(subscriber);
+        }
+
+        let mut amount_psks = Size::default();
+        self.mask(&mut amount_psks)?;
+        for _ in 0..amount_psks.inner() {
+            let mut pskid = PskId::default();
+            let mut psk = Psk::default();
+            self.mask(&mut pskid)?.mask(&mut psk)?;
+            user_state.psk_store.insert(pskid, psk);
+        }
+
+        let mut retention = Uint8::new(0);
+        self.mask(&mut retention)?;
+        user_state.retention = match retention.inner() {
+            0 => SpongosRetention::All,
+            1 => SpongosRetention::Lean,
+            2 => {
+                let mut n = Size::default();
+                self.mask(&mut n)?;
+                SpongosRetention::LastN(n.inner())
+            }
+            o => return Err(SpongosError::InvalidOption("spongos retention", o)),
+        };
+        let mut amount_order = Size::default();
+        self.mask(&mut amount_order)?;
+        for _ in 0..amount_order.inner() {
+            let mut address = MsgId::default();
+            self.mask(&mut address)?;
+            user_state.spongos_order.push_back(address);
+        }
+
+        let mut amount_log_entries = Size::default();
+        self.mask(&mut amount_log_entries)?;
+        for _ in 0..amount_log_entries.inner() {
+            let mut topic = Topic::default();
+            let mut address = Address::default();
+            let mut message_type = Uint8::new(0);
+            let mut publisher = Identifier::default();
+            let mut cursor = Size::default();
+            let mut processed_at_cursor = Size::default();
+            self.mask(&mut topic)?
+                .mask(&mut address)?
+                .mask(&mut message_type)?
+                .mask(&mut publisher)?
+                .mask(&mut cursor)?
+                .mask(&mut processed_at_cursor)?;
+            user_state.message_log.push(MessageLogEntry::new(
+                topic,
+                address,
+                message_type.inner(),
+                publisher,
+                cursor.inner(),
+                processed_at_cursor.inner(),
+            ));
+        }
+
+        let mut amount_links = Size::default();
+        self.mask(&mut amount_links)?;
+        for _ in 0..amount_links.inner() {
+            let mut msg_address = MsgId::default();
+            let mut has_link = Uint8::new(0);
+            self.mask(&mut msg_address)?.mask(&mut has_link)?;
+            let linked_msg_address = match has_link.inner() {
+                0 => None,
+                1 => {
+                    let mut linked_msg_address = MsgId::default();
+                    self.mask(&mut linked_msg_address)?;
+                    Some(linked_msg_address)
+                }
+                o => return Err(SpongosError::InvalidOption("linked message address", o)),
+            };
+            let mut digest = [0u8; 32];
+            self.mask(NBytes::new(&mut digest))?;
+            user_state.link_store.insert(msg_address, (linked_msg_address, digest));
+        }
+
+        let mut amount_followed_topics = Size::default();
+        self.mask(&mut amount_followed_topics)?;
+        for _ in 0..amount_followed_topics.inner() {
+            let mut topic = Topic::default();
+            self.mask(&mut topic)?;
+            user_state.followed_topics.insert(topic);
+        }
+
+        let mut amount_groups = Size::default();
+        self.mask(&mut amount_groups)?;
+        for _ in 0..amount_groups.inner() {
+            let mut name = Bytes::default();
+            let mut amount_members = Size::default();
+            self.mask(name.as_mut())?.mask(&mut amount_members)?;
+            let name = name
+                .to_string()
+                .ok_or_else(|| SpongosError::Context("Mask", "group name is not valid UTF-8".to_string()))?;
+            let mut members = Vec::with_capacity(amount_members.inner());
+            for _ in 0..amount_members.inner() {
+                let mut member = Identifier::default();
+                self.mask(&mut member)?;
+                members.push(member);
+            }
+            user_state.groups.insert(name, members);
+        }
+
+        self.mask(Maybe::new(&mut user_state.parent_address))?;
+
+        let mut has_metadata = Uint8::default();
+        self.mask(&mut has_metadata)?;
+        if has_metadata.inner() == 1 {
+            let into_string = |bytes: Bytes<Vec<u8>>, field: &str| {
+                bytes.to_string().ok_or_else(|| {
+                    SpongosError::Context("Mask", format!("stream metadata {} is not valid UTF-8", field))
+                })
+            };
+            let mut name = Bytes::default();
+            let mut description = Bytes::default();
+            let mut schema_version = [0u8; 4];
+            let mut amount_tags = Size::default();
+            self.mask(name.as_mut())?
+                .mask(description.as_mut())?
+                .mask(NBytes::new(&mut schema_version))?
+                .mask(&mut amount_tags)?;
+            let mut tags = Vec::with_capacity(amount_tags.inner());
+            for _ in 0..amount_tags.inner() {
+                let mut tag = Bytes::default();
+                self.mask(tag.as_mut())?;
+                tags.push(into_string(tag, "tag")?);
+            }
+            user_state.stream_metadata = Some(StreamMetadata {
+                name: into_string(name, "name")?,
+                description: into_string(description, "description")?,
+                schema_version: u32::from_be_bytes(schema_version),
+                tags,
+            });
+        }
+
+        let mut amount_readonly_subs = Size::default();
+        self.mask(&mut amount_readonly_subs)?;
+        for _ in 0..amount_readonly_subs.inner() {
+            let mut subscriber = Identifier::default();
+            self.mask(&mut subscriber)?;
+            user_state.readonly_subscribers.insert(subscriber);
+        }
+
+        let mut amount_aliases = Size::default();
+        self.mask(&mut amount_aliases)?;
+        for _ in 0..amount_aliases.inner() {
+            let mut alias = MsgId::default();
+            let mut canonical = MsgId::default();
+            self.mask(&mut alias)?.mask(&mut canonical)?;
+            user_state.spongos_aliases.insert(alias, canonical);
+        }
+
+        let mut amount_branch_parents = Size::default();
+        self.mask(&mut amount_branch_parents)?;
+        for _ in 0..amount_branch_parents.inner() {
+            let mut topic = Topic::default();
+            let mut parent = Topic::default();
+            self.mask(&mut topic)?.mask(&mut parent)?;
+            user_state.branch_parent.insert(topic, parent);
+        }
+
+        self.mask(Maybe::new(&mut user_state.default_topic))?;
+
+        let mut amount_confirmed_topics = Size::default();
+        self.mask(&mut amount_confirmed_topics)?;
+        for _ in 0..amount_confirmed_topics.inner() {
+            let mut topic = Topic::default();
+            let mut amount_subscribers = Size::default();
+            self.mask(&mut topic)?.mask(&mut amount_subscribers)?;
+            let mut subscribers = HashSet::new();
+            for _ in 0..amount_subscribers.inner() {
+                let mut subscriber = Identifier::default();
+                self.mask(&mut subscriber)?;
+                subscribers.insert(subscriber);
+            }
+            user_state.confirmed_subscribers.insert(topic, subscribers);
+        }
+
+        let mut amount_linked_streams = Size::default();
+        self.mask(&mut amount_linked_streams)?;
+        for _ in 0..amount_linked_streams.inner() {
+            let mut topic = Topic::default();
+            let mut remote_stream_address = Address::default();
+            self.mask(&mut topic)?.mask(&mut remote_stream_address)?;
+            user_state.linked_streams.insert(topic, remote_stream_address);
+        }
+
+        let mut amount_idempotency_keys = Size::default();
+        self.mask(&mut amount_idempotency_keys)?;
+        for _ in 0..amount_idempotency_keys.inner() {
+            let mut idempotency_key = [0u8; 32];
+            let mut topic = Topic::default();
+            let mut payload_digest = [0u8; 32];
+            let mut address = Address::default();
+            self.mask(NBytes::new(&mut idempotency_key))?
+                .mask(&mut topic)?
+                .mask(NBytes::new(&mut payload_digest))?
+                .mask(&mut address)?;
+            user_state.sent_idempotency_keys.push_back(IdempotentSend::new(
+                idempotency_key,
+                topic,
+                payload_digest,
+                address,
+            ));
+        }
+
+        let mut amount_branch_creation_times = Size::default();
+        self.mask(&mut amount_branch_creation_times)?;
+        for _ in 0..amount_branch_creation_times.inner() {
+            let mut topic = Topic::default();
+            let mut creation_time = [0u8; 8];
+            self.mask(&mut topic)?.mask(NBytes::new(&mut creation_time))?;
+            user_state
+                .branch_creation_times
+                .insert(topic, u64::from_be_bytes(creation_time));
+        }
+
+        let mut amount_capabilities = Size::default();
+        self.mask(&mut amount_capabilities)?;
+        for _ in 0..amount_capabilities.inner() {
+            let mut id = Identifier::default();
+            let mut capability = Uint8::new(0);
+            self.mask(&mut id)?.mask(&mut capability)?;
+            let capability = match capability.inner() {
+                0 => Capability::DataProducer,
+                1 => Capability::DataConsumer,
+                2 => Capability::Auditor,
+                o => return Err(SpongosError::InvalidOption("capability", o)),
+            };
+            user_state.capabilities.insert(id, capability);
+        }
+
+        let mut amount_expiring_subscribers = Size::default();
+        self.mask(&mut amount_expiring_subscribers)?;
+        for _ in 0..amount_expiring_subscribers.inner() {
+            let mut id = Identifier::default();
+            let mut expires_at = Uint64::default();
+            self.mask(&mut id)?.mask(&mut expires_at)?;
+            user_state.expiring_subscribers.insert(id, expires_at.inner());
+        }
+
+        self.commit()?.squeeze(Mac::new(32))
+    }
+}
+
+impl<T, I> Debug for User<T, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "\n* identifier: <{:?}>\n* topic: {}\n{:?}\n* PSKs: \n{}\n* messages:\n{}\n* retention: {:?}\n",
+            self.identifier(),
+            self.base_branch(),
+            self.state.cursor_store,
+            self.state
+                .psk_store
+                .keys()
+                .map(|pskid| format!("\t<{:?}>\n", pskid))
+                .collect::<String>(),
+            self.state
+                .spongos_store
+                .keys()
+                .map(|key| format!("\t<{}>\n", key))
+                .collect::<String>(),
+            self.state.retention
+        )
+    }
+}
+
+/// An streams user equality is determined by the equality of its state. The major consequence of
+/// this fact is that two users with the same identity but different transport configurations are
+/// considered equal
+impl<T, I> PartialEq for User<T, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+    }
+}
+
+/// An streams user equality is determined by the equality of its state. The major consequence of
+/// this fact is that two users with the same identity but different transport configurations are
+/// considered equal
+impl<T, I> Eq for User<T, I> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, string::ToString, vec::Vec};
+    use core::cell::RefCell;
+
+    use lets::{
+        address::{Address, MsgId},
+        id::{Ed25519, Identifier, Identity, PermissionDuration, Permissioned, Psk, PskId},
+        message::{Topic, TransportMessage},
+        transport::bucket,
+    };
+
+    use crate::{
+        api::{
+            batch::BatchItem,
+            capability::Capability,
+            message::{Message, StreamMetadata},
+            spongos_retention::SpongosRetention,
+            user::{unix_time_now, User, IDEMPOTENCY_KEYS_KEPT},
+            verify::{CallbackVerify, NoopVerify},
+        },
+        message::message_types,
+        Error, Result,
+    };
+
+    type Transport = Rc<RefCell<bucket::Client>>;
+
+    const BASE_BRANCH: &str = "BASE_BRANCH";
+
+    /// Prepare a simple scenario with an author, a subscriber that has read the channel
+    /// announcement and a shared bucket transport
+    async fn author_subscriber_fixture() -> Result<(User<Transport>, User<Transport>, Address)> {
+        let transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(transport.clone())
+            .build();
+        let announcement = author.create_stream(BASE_BRANCH).await?;
+        let mut subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("subscriber"))
+            .with_transport(transport)
+            .build();
+        subscriber.receive_message(announcement.address()).await?;
+        Ok((author, subscriber, announcement.address()))
+    }
+
+    #[tokio::test]
+    async fn verify_message_does_not_alter_state() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let verified = subscriber
+            .verify_message(packet.address(), packet.response().clone())
+            .await?;
+        assert!(verified.is_valid());
+        assert_eq!(verified.publisher(), author.identifier().unwrap());
+
+        let mut tampered: Vec<u8> = packet.response().clone().into();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        let verified = subscriber
+            .verify_message(packet.address(), TransportMessage::new(tampered))
+            .await?;
+        assert!(!verified.is_valid());
+
+        // The packet has not been processed, so the subscriber still has to fetch it
+        assert_eq!(subscriber.sync().await?, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn message_counts_follow_publisher_cursors() -> Result<()> {
+        let (mut author, _, _) = author_subscriber_fixture().await?;
+        let branch = "BRANCH";
+        author.new_branch(BASE_BRANCH, branch).await?;
+        for _ in 0..3 {
+            author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        }
+        for _ in 0..2 {
+            author.send_tagged_packet(branch, b"public", b"masked").await?;
+        }
+
+        let counts = author.count_messages_per_branch();
+        assert_eq!(counts.len(), 2);
+        // Announcement, branch announcement and 3 signed packets
+        assert_eq!(counts[&Topic::from(BASE_BRANCH)], 5);
+        // Inherited initial cursor and 2 tagged packets
+        assert_eq!(counts[&Topic::from(branch)], 3);
+        assert_eq!(author.total_message_count(), 8);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn heartbeats_advance_cursors_without_moving_the_branch_link() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let author_id = author.identifier().unwrap().clone();
+        let base_branch = Topic::from(BASE_BRANCH);
+        let latest_link = author.get_latest_link(&base_branch);
+
+        author.send_heartbeat(BASE_BRANCH).await?;
+        assert_eq!(author.state.cursor_store.get_cursor(&base_branch, &author_id), Some(2));
+        assert_eq!(author.get_latest_link(&base_branch), latest_link);
+
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert_eq!(author.state.cursor_store.get_cursor(&base_branch, &author_id), Some(3));
+
+        let msgs = subscriber.fetch_next_messages().await?;
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].header().message_type(), message_types::HEARTBEAT);
+        assert!(msgs[0].is_heartbeat());
+        assert_eq!(msgs[1].address(), packet.address());
+        assert!(msgs[1].is_signed_packet());
+        assert_eq!(
+            subscriber.state.cursor_store.get_cursor(&base_branch, &author_id),
+            Some(3)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_catalogs_can_be_read_without_stream_state() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let known_streams = [
+            Address::new([1; 40], [1; 12]),
+            Address::new([2; 40], [2; 12]),
+            Address::new([3; 40], [3; 12]),
+        ];
+        author.new_branch(BASE_BRANCH, "DISCOVERY").await?;
+        let catalog = author.publish_catalog("DISCOVERY", &known_streams).await?;
+
+        let mut discoverer = User::builder().with_transport(author.transport().clone()).build();
+        assert_eq!(discoverer.read_catalog(catalog.address()).await?, known_streams);
+        assert!(discoverer.stream_address().is_none());
+        assert_eq!(discoverer.topics().len(), 0);
+
+        let msgs = subscriber.fetch_next_messages().await?;
+        assert_eq!(msgs.len(), 2);
+        assert!(msgs[0].is_branch_announcement());
+        assert_eq!(msgs[1].address(), catalog.address());
+        assert_eq!(msgs[1].as_stream_catalog().unwrap().streams, known_streams);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invalid_topics_are_rejected() -> Result<()> {
+        let transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(transport)
+            .build();
+        let oversized = "a".repeat(Topic::MAX_LENGTH + 1);
+        assert!(matches!(
+            author.create_stream(oversized.as_str()).await,
+            Err(Error::InvalidTopic(_))
+        ));
+
+        author.create_stream(BASE_BRANCH).await?;
+        assert!(matches!(
+            author.new_branch(BASE_BRANCH, "BRANCH\0").await,
+            Err(Error::InvalidTopic(_))
+        ));
+        assert!(matches!(
+            author
+                .send_signed_packet(oversized.as_str(), b"public", b"masked")
+                .await,
+            Err(Error::InvalidTopic(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn publishers_follow_write_permissions() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let author_id = author.identifier().unwrap().clone();
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+
+        assert_eq!(author.publisher_count(&base_branch), 1);
+        assert!(author.is_publisher(&base_branch, &author_id));
+        assert!(!author.is_publisher(&base_branch, &subscriber_id));
+
+        author.add_subscriber(subscriber_id.clone());
+        author
+            .send_keyload(
+                BASE_BRANCH,
+                [Permissioned::ReadWrite(&subscriber_id, PermissionDuration::Perpetual)],
+                [],
+            )
+            .await?;
+        assert_eq!(author.publisher_count(&base_branch), 2);
+        assert!(author.is_publisher(&base_branch, &subscriber_id));
+        let publishers = author.list_publishers(&base_branch)?;
+        assert_eq!(publishers.len(), 2);
+        assert!(publishers
+            .iter()
+            .any(|(id, permission, _)| id == &subscriber_id && !permission.is_readonly()));
+
+        subscriber.sync().await?;
+        assert_eq!(subscriber.publisher_count(&base_branch), 2);
+
+        let unknown = Topic::from("UNKNOWN");
+        assert_eq!(author.publisher_count(&unknown), 0);
+        assert!(!author.is_publisher(&unknown, &author_id));
+        assert!(matches!(author.list_publishers(&unknown), Err(Error::TopicNotFound(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reset_cursor_repairs_publisher_sequence() -> Result<()> {
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let author_id = author.identifier().unwrap().clone();
+        author.send_signed_packet(BASE_BRANCH, b"public", b"first").await?;
+
+        // Simulate a crash after the cursor was advanced but before the messages were delivered
+        let permission = author.permission(&base_branch).unwrap().clone();
+        author.state.cursor_store.insert_cursor(&base_branch, permission, 5);
+        assert!(matches!(
+            author.reset_cursor(&base_branch, &author_id, 6),
+            Err(Error::InvalidCursor(_, 6, 5))
+        ));
+        assert!(matches!(
+            subscriber.reset_cursor(&base_branch, &author_id, 2),
+            Err(Error::NoCursor(_))
+        ));
+
+        author.reset_cursor(&base_branch, &author_id, 2)?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"second").await?;
+        let expected = MsgId::gen(announcement.base(), &author_id, &base_branch, 3);
+        assert_eq!(packet.address().relative(), expected);
+
+        let msgs = subscriber.fetch_next_messages().await?;
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[1].address(), packet.address());
+        assert_eq!(msgs[1].as_signed_packet().unwrap().masked_payload, b"second");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn bucket_transport_tracks_sent_messages() -> Result<()> {
+        let transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(transport.clone())
+            .build();
+        let announcement = author.create_stream(BASE_BRANCH).await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        assert_eq!(transport.borrow().message_count(), 2);
+        assert_eq!(transport.borrow().address_count(), 2);
+        let addresses: Vec<Address> = transport.borrow().addresses().copied().collect();
+        assert!(addresses.contains(&announcement.address()));
+        assert!(addresses.contains(&packet.address()));
+
+        transport.borrow_mut().clear();
+        assert_eq!(transport.borrow().message_count(), 0);
+        assert_eq!(transport.borrow().addresses().count(), 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "typed-payloads")]
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct SensorReading {
+        temp: f32,
+        humidity: f32,
+    }
+
+    #[cfg(feature = "typed-payloads")]
+    #[tokio::test]
+    async fn typed_payloads_round_trip() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let reading = SensorReading {
+            temp: 21.5,
+            humidity: 0.4,
+        };
+        let packet = author
+            .send_signed_packet_typed(BASE_BRANCH, &reading, b"masked")
+            .await?;
+
+        let (address, received, masked): (_, SensorReading, _) =
+            subscriber.receive_message_typed(packet.address()).await?;
+        assert_eq!(address, packet.address());
+        assert_eq!(received, reading);
+        assert_eq!(masked, b"masked");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keyload_revoke_excludes_revoked_subscribers() -> Result<()> {
+        let (mut author, _, announcement_link) = author_subscriber_fixture().await?;
+        let transport = author.transport().clone();
+
+        let mut subscribers = Vec::new();
+        for seed in ["subscriber1", "subscriber2", "subscriber3"] {
+            let mut subscriber = User::builder()
+                .with_identity(Ed25519::from_seed(seed))
+                .with_transport(transport.clone())
+                .build();
+            subscriber.receive_message(announcement_link).await?;
+            let subscription = subscriber.subscribe().await?;
+            author.receive_message(subscription.address()).await?;
+            subscribers.push(subscriber);
+        }
+
+        let revoked = subscribers[1].identifier().unwrap().clone();
+        let keyload = author.send_keyload_revoke(BASE_BRANCH, &[revoked.clone()]).await?;
+
+        let message = subscribers[1].receive_message(keyload.address()).await?;
+        let keyload_content = message.as_keyload().expect("message should be a keyload");
+        assert!(!keyload_content.includes_subscriber(&revoked));
+        assert!(keyload_content.includes_subscriber(subscribers[0].identifier().unwrap()));
+        assert!(keyload_content.includes_subscriber(subscribers[2].identifier().unwrap()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn snapshot_restores_an_equal_user() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+
+        let snapshot = author.snapshot();
+        let restored = User::restore_from_snapshot(snapshot, author.transport().clone());
+        assert_eq!(restored, author);
+
+        let snapshot = subscriber.snapshot();
+        let restored = User::restore_from_snapshot(snapshot, subscriber.transport().clone());
+        assert_eq!(restored, subscriber);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn messages_for_publisher_only_include_that_publisher() -> Result<()> {
+        let (mut author, mut publisher, announcement_link) = author_subscriber_fixture().await?;
+        let mut reader = User::builder()
+            .with_identity(Ed25519::from_seed("reader"))
+            .with_transport(author.transport().clone())
+            .build();
+        reader.receive_message(announcement_link).await?;
+        for user in [&mut publisher, &mut reader] {
+            let subscription = user.subscribe().await?;
+            author.receive_message(subscription.address()).await?;
+        }
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+        publisher.sync().await?;
+
+        let publisher_packets = [
+            publisher.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?,
+            publisher.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?,
+        ];
+        author.sync().await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        reader.sync().await?;
+
+        // The messages have already been processed by the reader, whose state is left untouched
+        let before = reader.snapshot();
+        let messages = reader
+            .receive_messages_for_publisher(publisher.identifier().unwrap())
+            .await?;
+        assert_eq!(reader.snapshot(), before);
+        assert_eq!(messages.len(), 2);
+        for (message, packet) in messages.iter().zip(&publisher_packets) {
+            assert_eq!(message.address(), packet.address());
+            assert_eq!(message.header().publisher(), publisher.identifier().unwrap());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn user_operations_are_traced() -> Result<()> {
+        extern crate std;
+
+        use alloc::{string::String, sync::Arc};
+        use std::{io, sync::Mutex};
+
+        use tracing_subscriber::fmt::{format::FmtSpan, MakeWriter};
+
+        #[derive(Clone, Default)]
+        struct CapturedOutput(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for CapturedOutput {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        impl<'a> MakeWriter<'a> for CapturedOutput {
+            type Writer = Self;
+
+            fn make_writer(&'a self) -> Self::Writer {
+                self.clone()
+            }
+        }
+
+        let output = CapturedOutput::default();
+        let tracing_subscriber = tracing_subscriber::fmt()
+            .with_writer(output.clone())
+            .with_span_events(FmtSpan::CLOSE)
+            .finish();
+        let guard = tracing::subscriber::set_default(tracing_subscriber);
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        subscriber.sync().await?;
+        drop(guard);
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        for span in ["create_stream", "receive_message", "send_signed_packet", "sync"] {
+            assert!(output.contains(span), "span {} not found in output:\n{}", span, output);
+        }
+        assert!(output.contains(BASE_BRANCH));
+        Ok(())
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn transport_messages_roundtrip_through_cbor() -> Result<()> {
+        use lets::transport::Transport as _;
+
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let announcement = author.transport_mut().recv_message(announcement_address).await.unwrap();
+        let mut sent = vec![(announcement_address, announcement)];
+
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let mut responses = vec![subscription];
+        responses.push(author.send_keyload_for_all_rw(BASE_BRANCH).await?);
+        responses.push(author.new_branch(BASE_BRANCH, "BRANCH").await?);
+        responses.push(author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?);
+        responses.push(author.send_tagged_packet(BASE_BRANCH, b"public", b"masked").await?);
+        responses.push(author.send_heartbeat(BASE_BRANCH).await?);
+        responses.push(author.publish_catalog(BASE_BRANCH, &[announcement_address]).await?);
+        subscriber.sync().await?;
+        responses.push(subscriber.unsubscribe().await?);
+        sent.extend(
+            responses
+                .into_iter()
+                .map(|response| (response.address(), response.into_response())),
+        );
+
+        for (address, message) in sent {
+            let encoded = message.to_cbor(address).unwrap();
+            assert_eq!(TransportMessage::from_cbor(&encoded).unwrap(), (address, message));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn direct_messages_can_only_be_read_by_their_recipient() -> Result<()> {
+        let (mut author, mut subscriber, announcement_link) = author_subscriber_fixture().await?;
+        let mut third_party = User::builder()
+            .with_identity(Ed25519::from_seed("third party"))
+            .with_transport(author.transport().clone())
+            .build();
+        third_party.receive_message(announcement_link).await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        subscriber.sync().await?;
+
+        let author_identifier = author.identifier().unwrap().clone();
+        let direct_message = subscriber
+            .send_direct_message(&author_identifier, b"for the author only")
+            .await?;
+
+        let received = author.receive_message(direct_message.address()).await?;
+        let content = received
+            .as_direct_message()
+            .expect("message should be a direct message");
+        assert_eq!(content.payload, b"for the author only");
+        assert_eq!(&content.sender_identifier, subscriber.identifier().unwrap());
+        assert_eq!(content.recipient_identifier, author_identifier);
+
+        let received = third_party.receive_message(direct_message.address()).await?;
+        assert!(received.is_orphan());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_metadata_can_be_read_back() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let metadata = StreamMetadata {
+            name: "Sensor readings".into(),
+            description: "Temperature and humidity of the greenhouse".into(),
+            schema_version: 3,
+            tags: vec!["iot".into(), "greenhouse".into()],
+        };
+        let sent = author.publish_stream_metadata(metadata.clone()).await?;
+
+        assert_eq!(subscriber.read_stream_metadata(sent.address()).await?, metadata);
+        assert!(matches!(
+            subscriber.publish_stream_metadata(metadata.clone()).await,
+            Err(Error::NoCursor(..))
+        ));
+
+        // Reading the metadata follows its updates
+        let updated = StreamMetadata {
+            schema_version: 4,
+            ..metadata
+        };
+        author.send_announcement_update(updated.clone()).await?;
+        assert_eq!(subscriber.read_stream_metadata(sent.address()).await?, updated);
+
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert!(matches!(
+            subscriber.read_stream_metadata(packet.address()).await,
+            Err(Error::UnexpectedMessageType {
+                expected: message_types::STREAM_METADATA,
+                got: message_types::SIGNED_PACKET,
+            })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn proof_of_inclusion_of_signed_packet_can_be_verified() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let packet = author
+            .send_signed_packet(BASE_BRANCH, b"public payload", b"masked payload")
+            .await?;
+        let proof = author.send_proof_of_inclusion(packet.address(), BASE_BRANCH).await?;
+
+        subscriber.sync().await?;
+        assert!(subscriber.verify_proof_of_inclusion(proof.address()).await?);
+        let received = subscriber.receive_message(proof.address()).await?;
+        let content = received
+            .as_proof_of_inclusion()
+            .expect("message should be a proof of inclusion");
+        assert_eq!(content.target_address, packet.address());
+        assert_eq!(&content.publisher_identifier, author.identifier().unwrap());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn latest_and_linked_message_addresses_match_sent_messages() -> Result<()> {
+        let (mut author, mut subscriber, announcement_link) = author_subscriber_fixture().await?;
+        let topic = Topic::from(BASE_BRANCH);
+        assert_eq!(author.get_latest_message_address(&topic), Some(announcement_link));
+
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        assert_eq!(author.get_latest_message_address(&topic), Some(keyload.address()));
+
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert_eq!(author.get_latest_message_address(&topic), Some(packet.address()));
+        subscriber.sync().await?;
+        assert_eq!(subscriber.get_latest_message_address(&topic), Some(packet.address()));
+
+        let author_identifier = author.identifier().unwrap().clone();
+        let cursor = author.cursor(&topic).unwrap();
+        assert_eq!(
+            subscriber.get_linked_message_address(cursor, &author_identifier, &topic),
+            Some(packet.address())
+        );
+        assert_eq!(
+            subscriber.get_linked_message_address(cursor - 1, &author_identifier, &topic),
+            Some(keyload.address())
+        );
+        assert_eq!(author.get_latest_message_address(&Topic::from("unknown")), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn orphans_are_queued_and_resolved_once_their_link_arrives() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        let first = author.send_signed_packet(BASE_BRANCH, b"first", b"").await?;
+        let second = author.send_signed_packet(BASE_BRANCH, b"second", b"").await?;
+        subscriber.receive_message(keyload.address()).await?;
+
+        // The second packet arrives before the first one it is linked to
+        assert!(subscriber.receive_message(second.address()).await?.is_orphan());
+        assert_eq!(subscriber.state.orphan_store.len(), 1);
+        let retried = subscriber.fetch_orphan_messages().await?;
+        assert_eq!(retried.len(), 1);
+        assert!(retried[0].is_orphan());
+        assert_eq!(subscriber.state.orphan_store.len(), 1);
+
+        subscriber.receive_message(first.address()).await?;
+        let resolved = subscriber.fetch_orphan_messages().await?;
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].address(), second.address());
+        assert_eq!(resolved[0].public_payload(), Some(&b"second"[..]));
+        assert!(subscriber.state.orphan_store.is_empty());
+
+        // Orphans failing to be processed are kept queued
+        let malformed = Address::new(keyload.address().base(), MsgId::from([7; 12]));
+        subscriber
+            .state
+            .orphan_store
+            .push((malformed, TransportMessage::new(vec![0; 8])));
+        assert!(subscriber.fetch_orphan_messages().await?.is_empty());
+        assert_eq!(subscriber.list_orphans(), vec![malformed]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn message_log_records_processed_messages_and_survives_backup() -> Result<()> {
+        let (mut author, mut subscriber, announcement_link) = author_subscriber_fixture().await?;
+        let topic = Topic::from(BASE_BRANCH);
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        subscriber.sync().await?;
+
+        let mut sent = Vec::new();
+        for i in 0..10u8 {
+            sent.push(author.send_signed_packet(BASE_BRANCH, [i], b"").await?.address());
+        }
+        subscriber.sync().await?;
+
+        let log = subscriber.export_message_log(&topic)?;
+        assert_eq!(log[0].address, announcement_link);
+        let packets: Vec<_> = log
+            .iter()
+            .filter(|entry| entry.message_type == message_types::SIGNED_PACKET)
+            .collect();
+        assert_eq!(packets.len(), 10);
+        assert_eq!(packets.iter().map(|entry| entry.address).collect::<Vec<_>>(), sent);
+        for pair in packets.windows(2) {
+            assert_eq!(pair[1].cursor, pair[0].cursor + 1);
+            assert_eq!(pair[1].processed_at_cursor, pair[0].processed_at_cursor + 1);
+        }
+        assert!(packets
+            .iter()
+            .all(|entry| &entry.publisher == author.identifier().unwrap()));
+
+        let backup = subscriber.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.export_message_log(&topic)?, log);
+        assert!(matches!(
+            subscriber.export_message_log(&Topic::from("unknown")),
+            Err(Error::TopicNotFound(..))
+        ));
+        Ok(())
+    }
 
-        // Update own's cursor
-        let user_cursor = self
-            .next_cursor(&prev_topic)
-            .map_err(|_| Error::NoCursor(prev_topic.clone()))?;
-        let msgid = MsgId::gen(stream_address.base(), &identifier, &prev_topic, user_cursor);
-        let address = Address::new(stream_address.base(), msgid);
+    #[tokio::test]
+    async fn lean_threshold_keeps_last_n_states_and_announcement() -> Result<()> {
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(Rc::new(RefCell::new(bucket::Client::new())))
+            .lean_threshold(5)
+            .build::<Transport>();
+        let announcement = author.create_stream(BASE_BRANCH).await?;
+        let mut sent = Vec::new();
+        for i in 0..20u8 {
+            sent.push(author.send_signed_packet(BASE_BRANCH, [i], b"").await?.address());
+        }
 
-        // Prepare HDF and PCF
-        // Spongos must be copied because wrapping mutates it
-        let mut linked_msg_spongos = self
+        assert_eq!(author.spongos_retention(), SpongosRetention::LastN(5));
+        assert_eq!(author.state.spongos_store.len(), 6);
+        assert_eq!(author.state.link_store.len(), 6);
+        assert!(author
             .state
             .spongos_store
-            .get(&link_to)
-            .copied()
-            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
-        let header = HDF::new(
-            message_types::BRANCH_ANNOUNCEMENT,
-            user_cursor,
-            identifier.clone(),
-            &prev_topic,
-        )
-        .with_linked_msg_address(link_to);
-        let content = PCF::new_final_frame().with_content(branch_announcement::Wrap::new(
-            &mut linked_msg_spongos,
-            self.identity().unwrap(),
-            &topic,
+            .contains_key(&announcement.address().relative()));
+        for address in &sent[15..] {
+            assert!(author.state.spongos_store.contains_key(&address.relative()));
+        }
+
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored, author);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lean_threshold_keeps_the_latest_link_of_every_branch() -> Result<()> {
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(Rc::new(RefCell::new(bucket::Client::new())))
+            .lean_threshold(2)
+            .build::<Transport>();
+        author.create_stream(BASE_BRANCH).await?;
+        author.new_branch(BASE_BRANCH, "QUIET").await?;
+        let quiet = author.send_signed_packet("QUIET", b"first", b"").await?;
+        for i in 0..5u8 {
+            author.send_signed_packet(BASE_BRANCH, [i], b"").await?;
+        }
+
+        assert!(author.stored_spongos(&quiet.address().relative()).is_some());
+        author.send_signed_packet("QUIET", b"second", b"").await?;
+        // Once it is no longer a latest link, the state is evicted like any other
+        author.send_signed_packet(BASE_BRANCH, b"last", b"").await?;
+        assert!(author.stored_spongos(&quiet.address().relative()).is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parallel_sync_matches_sequential_sync() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let mut branches = vec![BASE_BRANCH.to_string()];
+        for i in 1..8 {
+            let branch = format!("BRANCH_{}", i);
+            author.new_branch(BASE_BRANCH, branch.as_str()).await?;
+            branches.push(branch);
+        }
+        for branch in &branches {
+            for i in 0..2u8 {
+                author.send_signed_packet(branch.as_str(), [i], b"").await?;
+            }
+        }
+
+        let mut sequential = User::restore_from_snapshot(subscriber.snapshot(), subscriber.transport().clone());
+        let sequential_count = sequential.sync().await?;
+        let parallel_count = subscriber.parallel_sync(4).await?;
+
+        assert_eq!(parallel_count, 1 + 7 + 8 * 2);
+        assert_eq!(parallel_count, sequential_count);
+        assert_eq!(subscriber.state.cursor_store, sequential.state.cursor_store);
+        assert_eq!(subscriber.state.spongos_store, sequential.state.spongos_store);
+        assert_eq!(subscriber.state.topics, sequential.state.topics);
+        assert_eq!(subscriber.parallel_sync(4).await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn psk_only_keyload_grants_access_to_psk_holders() -> Result<()> {
+        let psk = Psk::from_seed("a pre shared key");
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        author.add_psk(psk);
+        let mut reader = User::builder()
+            .with_identity(Ed25519::from_seed("reader"))
+            .with_transport(author.transport().clone())
+            .with_psk(psk.to_pskid(), psk)
+            .build();
+        reader.receive_message(announcement_address).await?;
+
+        author.send_keyload_with_psks_only(BASE_BRANCH).await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let messages = reader.fetch_next_messages().await?;
+        assert_eq!(messages.len(), 2);
+        let keyload = messages[0].as_keyload().expect("first message should be a keyload");
+        assert!(keyload.subscribers.is_empty());
+        assert!(keyload.includes_psk(&psk.to_pskid()));
+        assert_eq!(messages[1].masked_payload(), Some(&b"masked"[..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn psk_holder_without_identity_can_subscribe() -> Result<()> {
+        let psk = Psk::from_seed("a pre shared key");
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        author.add_psk(psk);
+        let mut reader = User::builder().with_transport(author.transport().clone()).build();
+        reader.receive_message(announcement_address).await?;
+
+        let subscription = reader.subscribe_with_psk(psk).await?;
+        author.receive_message(subscription.address()).await?;
+        assert!(author.subscribers().any(|s| s == &Identifier::from(&psk)));
+
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let messages = reader.fetch_next_messages().await?;
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0]
+            .as_keyload()
+            .expect("first message should be a keyload")
+            .includes_psk(&psk.to_pskid()));
+        assert_eq!(messages[1].masked_payload(), Some(&b"masked"[..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn multipart_message_is_reassembled() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let document: Vec<u8> = (0..1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let parts = document
+            .chunks((document.len() + 9) / 10)
+            .enumerate()
+            .map(|(i, chunk)| (i.to_be_bytes(), chunk))
+            .collect::<Vec<_>>();
+        assert_eq!(parts.len(), 10);
+
+        let (manifest_address, part_addresses) = author.send_multipart_message(BASE_BRANCH, parts).await?;
+        assert_eq!(part_addresses.len(), 10);
+
+        let messages = subscriber.fetch_next_messages().await?;
+        assert_eq!(messages.len(), 11);
+        let manifest = messages[10]
+            .as_multipart_manifest()
+            .expect("last message should be a multipart manifest");
+        assert_eq!(manifest.total_parts, 10);
+        assert_eq!(manifest.part_addresses, part_addresses);
+        assert_eq!(subscriber.receive_multipart_message(manifest_address).await?, document);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn audit_branch_reports_corrupted_spongos() -> Result<()> {
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        let mut packets = Vec::new();
+        for _ in 0..3 {
+            packets.push(
+                author
+                    .send_signed_packet(BASE_BRANCH, b"public", b"masked")
+                    .await?
+                    .address(),
+            );
+        }
+
+        let topic = Topic::from(BASE_BRANCH);
+        let audit = author.audit_branch(&topic)?;
+        assert!(audit.is_intact());
+        assert_eq!(audit.chain_length, 4);
+        assert_eq!(audit.first_address, announcement_address);
+        assert_eq!(audit.last_address, packets[2]);
+
+        let corrupted = packets[1].relative();
+        let mut spongos = author.state.spongos_store[&corrupted];
+        spongos.absorb("corrupted");
+        author.state.spongos_store.insert(corrupted, spongos);
+
+        let audit = author.audit_branch(&topic)?;
+        assert_eq!(audit.chain_length, 4);
+        assert_eq!(audit.broken_links, [packets[1]]);
+
+        // The recorded digests survive a backup
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.audit_branch(&topic)?, audit);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn backup_size_estimate_matches_backup() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        assert_eq!(
+            author.estimate_backup_size().await?,
+            author.backup("password").await?.len()
+        );
+
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        for i in 0..5u8 {
+            author.send_signed_packet(BASE_BRANCH, [i], b"masked").await?;
+        }
+        subscriber.sync().await?;
+
+        assert_eq!(
+            author.estimate_backup_size().await?,
+            author.backup("password").await?.len()
+        );
+        assert_eq!(
+            subscriber.estimate_backup_size().await?,
+            subscriber.backup("password").await?.len()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn message_at_cursor_is_fetched_without_altering_state() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let mut packets = Vec::new();
+        for i in 0..5u8 {
+            packets.push(author.send_signed_packet(BASE_BRANCH, [i], b"masked").await?.address());
+        }
+        subscriber.sync().await?;
+
+        let before = subscriber.snapshot();
+        let author_id = author.identifier().unwrap().clone();
+        let message = subscriber
+            .get_message_at_cursor(&Topic::from(BASE_BRANCH), &author_id, 3)
+            .await?;
+        assert_eq!(subscriber.snapshot(), before);
+
+        // The first packet is published at cursor 2, so cursor 3 is the second packet
+        assert_eq!(message.address(), packets[1]);
+        assert_eq!(message.header().sequence(), 3);
+        assert_eq!(message.public_payload(), Some(&[1][..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn user_can_be_transferred_to_another_transport() -> Result<()> {
+        let (author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let old_transport = author.transport().clone();
+        let new_transport = old_transport.borrow().clone();
+        let before = author.snapshot();
+
+        let mut author: User<bucket::Client> = author.transfer_to_transport(new_transport);
+        assert_eq!(author.snapshot(), before);
+
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert!(author
+            .transport()
+            .addresses()
+            .any(|address| address == &packet.address()));
+        assert!(!old_transport
+            .borrow()
+            .addresses()
+            .any(|address| address == &packet.address()));
+        assert_eq!(subscriber.sync().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn messages_only_traverse_followed_branches() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        subscriber.sync().await?;
+
+        let branch = Topic::from("FOLLOWED");
+        author.new_branch(BASE_BRANCH, "FOLLOWED").await?;
+        subscriber.sync().await?;
+
+        assert!(matches!(
+            subscriber.subscribe_to_branch(&Topic::from("UNKNOWN")),
+            Err(Error::TopicNotFound(_))
         ));
+        subscriber.subscribe_to_branch(&branch)?;
+        assert_eq!(subscriber.followed_topics().collect::<Vec<_>>(), vec![&branch]);
 
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
-            .await
-            .map_err(|e| Error::Wrapped("wrap new branch", e))?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"ignored").await?;
+        let packet = author.send_signed_packet("FOLLOWED", b"public", b"followed").await?;
 
-        if !self.transport.recv_message(address).await.is_err() {
-            return Err(Error::AddressUsed("new branch", address));
+        let msgs = subscriber.fetch_next_messages().await?;
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].address(), packet.address());
+
+        let backup = subscriber.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.followed_topics().collect::<Vec<_>>(), vec![&branch]);
+        Ok(())
+    }
+
+    #[cfg(feature = "timestamped-payloads")]
+    #[tokio::test]
+    async fn timestamped_packets_carry_their_send_time() -> Result<()> {
+        extern crate std;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let packet = author
+            .send_signed_packet_with_timestamp(BASE_BRANCH, b"public", b"masked")
+            .await?;
+
+        let message = subscriber.receive_message(packet.address()).await?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let timestamp = message
+            .decode_timestamp()?
+            .expect("signed packets carry a public payload");
+        assert!(now.abs_diff(timestamp) <= 2);
+
+        let payload = crate::TimestampedPublicPayload::decode(message.public_payload().unwrap())?;
+        assert_eq!(payload.data, b"public");
+        assert_eq!(message.masked_payload().unwrap(), b"masked");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cursor_diff_lists_branches_processed_differently() -> Result<()> {
+        let (mut author, mut subscriber_a, announcement_address) = author_subscriber_fixture().await?;
+        let mut subscriber_b = User::builder()
+            .with_identity(Ed25519::from_seed("subscriber b"))
+            .with_transport(author.transport().clone())
+            .build();
+        subscriber_b.receive_message(announcement_address).await?;
+        author.new_branch(BASE_BRANCH, "BRANCH A").await?;
+        author.new_branch(BASE_BRANCH, "BRANCH B").await?;
+        subscriber_a.sync().await?;
+        subscriber_b.sync().await?;
+        assert_eq!(subscriber_a.find_divergence_point(&subscriber_b), None);
+
+        let packet_a = author.send_signed_packet("BRANCH A", b"public", b"masked").await?;
+        let packet_b = author.send_signed_packet("BRANCH B", b"public", b"masked").await?;
+        subscriber_a.receive_message(packet_a.address()).await?;
+        subscriber_b.receive_message(packet_b.address()).await?;
+
+        let author_id = author.identifier().unwrap().clone();
+        let diffs = subscriber_a.cursor_diff(&subscriber_b);
+        let topics: Vec<&str> = diffs.iter().map(|diff| diff.topic.str()).collect();
+        assert_eq!(topics, ["BRANCH A", "BRANCH B"]);
+        assert!(diffs.iter().all(|diff| diff.identifier == author_id));
+        assert_eq!((diffs[0].self_cursor, diffs[0].other_cursor), (Some(2), Some(1)));
+        assert_eq!((diffs[1].self_cursor, diffs[1].other_cursor), (Some(1), Some(2)));
+
+        assert_eq!(
+            subscriber_a.find_divergence_point(&subscriber_b),
+            Some((Topic::from("BRANCH A"), author_id, 2))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn group_keyloads_grant_access_to_group_members() -> Result<()> {
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        let mut subscribers = Vec::new();
+        for seed in ["sensor1", "sensor2", "admin1"] {
+            let mut subscriber = User::builder()
+                .with_identity(Ed25519::from_seed(seed))
+                .with_transport(author.transport().clone())
+                .build();
+            subscriber.receive_message(announcement_address).await?;
+            let subscription = subscriber.subscribe().await?;
+            author.receive_message(subscription.address()).await?;
+            subscribers.push(subscriber);
         }
 
-        let send_response = self
-            .transport
-            .send_message(address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send new branch message", e))?;
+        assert!(matches!(
+            author.send_keyload_for_group(BASE_BRANCH, "sensors").await,
+            Err(Error::UnknownGroup(_))
+        ));
+        let sensors: Vec<Identifier> = subscribers[..2]
+            .iter()
+            .map(|subscriber| subscriber.identifier().unwrap().clone())
+            .collect();
+        assert!(author.define_group("sensors", sensors));
+        let keyload = author.send_keyload_for_group(BASE_BRANCH, "sensors").await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        for (index, subscriber) in subscribers.iter_mut().enumerate() {
+            let message = subscriber.receive_message(keyload.address()).await?;
+            let is_member = index < 2;
+            assert_eq!(
+                message
+                    .as_keyload()
+                    .unwrap()
+                    .includes_subscriber(subscriber.identifier().unwrap()),
+                is_member
+            );
+            let message = subscriber.receive_message(packet.address()).await;
+            if is_member {
+                assert_eq!(message?.masked_payload(), Some(&b"masked"[..]));
+            } else {
+                assert!(message.is_err());
+            }
+        }
 
-        // If message has been sent successfully, create the new branch in store
-        self.state.cursor_store.new_branch(topic.clone());
-        self.state.topics.insert(topic.clone());
-        // Commit message to stores and update cursors
-        self.state.cursor_store.insert_cursor(
-            &prev_topic,
-            Permissioned::Admin(identifier.clone()),
-            self.next_cursor(&prev_topic)?,
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.state.groups, author.state.groups);
+        Ok(())
+    }
+
+    #[test]
+    fn subscriber_lists_can_be_imported_and_exported() -> Result<()> {
+        let mut user = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(Rc::new(RefCell::new(bucket::Client::new())))
+            .build();
+        let identifiers: Vec<Identifier> = (0..100)
+            .map(|i| {
+                Identity::from(Ed25519::from_seed(format!("subscriber {}", i)))
+                    .identifier()
+                    .clone()
+            })
+            .collect();
+
+        assert!(user.add_subscriber(identifiers[0].clone()));
+        assert_eq!(user.import_subscriber_list(identifiers.clone())?, 99);
+        assert_eq!(user.import_subscriber_list(identifiers[..10].to_vec())?, 0);
+
+        let exported = user.export_subscriber_list();
+        assert_eq!(exported.len(), 100);
+        assert!(exported.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(user.export_subscriber_list(), exported);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn messages_can_be_received_again_from_a_cursor() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let author_id = author.identifier().unwrap().clone();
+        let base_branch = Topic::from(BASE_BRANCH);
+        let mut packets = Vec::new();
+        for i in 1..=10 {
+            let payload = format!("message {}", i);
+            packets.push(author.send_signed_packet(BASE_BRANCH, b"public", payload).await?);
+        }
+        assert_eq!(subscriber.fetch_next_messages().await?.len(), 10);
+        let latest_link = subscriber.get_latest_link(&base_branch);
+        let logged = subscriber.state.message_log.len();
+
+        // The first packet is published at cursor 2, so messages 6 to 10 start at cursor 7
+        let messages = subscriber
+            .receive_all_from_cursor(&base_branch, &author_id, 7)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<Message>>>()?;
+        assert_eq!(messages.len(), 5);
+        for (message, packet) in messages.iter().zip(&packets[5..]) {
+            assert_eq!(message.address(), packet.address());
+        }
+        assert_eq!(messages[0].masked_payload(), Some(&b"message 6"[..]));
+        // Messages that had already been processed leave the state untouched
+        assert_eq!(
+            subscriber.state.cursor_store.get_cursor(&base_branch, &author_id),
+            Some(11)
         );
-        self.state.spongos_store.insert(address.relative(), spongos);
-        // Collect permissions from previous branch and clone them into new branch
-        let prev_permissions = self
-            .cursors_by_topic(&prev_topic)?
-            .map(|(id, _)| id.clone())
-            .collect::<Vec<Permissioned<Identifier>>>();
-        for id in prev_permissions {
-            self.state.cursor_store.insert_cursor(&topic, id, INIT_MESSAGE_NUM);
+        assert_eq!(subscriber.get_latest_link(&base_branch), latest_link);
+        assert_eq!(subscriber.state.message_log.len(), logged);
+
+        // Messages published after the stored cursor are processed
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"message 11").await?;
+        let messages = subscriber.receive_all_from_cursor(&base_branch, &author_id, 11).await?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].as_ref().unwrap().address(), packet.address());
+        assert_eq!(
+            subscriber.state.cursor_store.get_cursor(&base_branch, &author_id),
+            Some(12)
+        );
+        assert_eq!(
+            subscriber.get_latest_link(&base_branch),
+            Some(packet.address().relative())
+        );
+        assert_eq!(subscriber.state.message_log.len(), logged + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rotated_psks_keep_access_for_new_psk_holders_only() -> Result<()> {
+        let old_psk = Psk::from_seed("old pre shared key");
+        let new_psk = Psk::from_seed("new pre shared key");
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        author.add_psk(old_psk);
+        let mut readers = Vec::new();
+        for _ in 0..2 {
+            let mut reader = User::builder()
+                .with_transport(author.transport().clone())
+                .with_psk(old_psk.to_pskid(), old_psk)
+                .build();
+            reader.receive_message(announcement_address).await?;
+            readers.push(reader);
         }
 
-        // Update branch links
-        self.state.cursor_store.set_latest_link(topic, address.relative());
-        Ok(SendResponse::new(address, send_response))
+        author.send_keyload_with_psks_only(BASE_BRANCH).await?;
+        let before = author.send_signed_packet(BASE_BRANCH, b"public", b"before").await?;
+        for reader in &mut readers {
+            let messages = reader.fetch_next_messages().await?;
+            assert_eq!(messages[1].address(), before.address());
+            assert_eq!(messages[1].masked_payload(), Some(&b"before"[..]));
+        }
+
+        assert!(matches!(
+            author.rotate_psk(new_psk.to_pskid(), new_psk).await,
+            Err(Error::UnknownPsk(_))
+        ));
+        let keyload = author.rotate_psk(old_psk.to_pskid(), new_psk).await?;
+        assert!(author.state.psk_store.contains_key(&new_psk.to_pskid()));
+        assert!(!author.state.psk_store.contains_key(&old_psk.to_pskid()));
+        let after = author.send_signed_packet(BASE_BRANCH, b"public", b"after").await?;
+
+        // The new PSK is distributed to the first reader only
+        readers[0].add_psk(new_psk);
+        let message = readers[0].receive_message(keyload.address()).await?;
+        let keyload_content = message.as_keyload().unwrap();
+        assert!(keyload_content.includes_psk(&new_psk.to_pskid()));
+        assert!(!keyload_content.includes_psk(&old_psk.to_pskid()));
+        let message = readers[0].receive_message(after.address()).await?;
+        assert_eq!(message.masked_payload(), Some(&b"after"[..]));
+
+        readers[1].receive_message(keyload.address()).await?;
+        assert!(readers[1].receive_message(after.address()).await.is_err());
+        Ok(())
     }
 
-    /// Create and send a new Subscription message, awaiting the stream author's acceptance into the
-    /// stream.
-    pub async fn subscribe(&mut self) -> Result<SendResponse<TSR>> {
-        // Check conditions
-        let stream_address = self
-            .stream_address()
-            .ok_or(Error::Setup("before starting a new branch, the stream must be created"))?;
-        // Confirm user has identity
-        let user_id = self.identity().ok_or(Error::NoIdentity("subscribe"))?;
-        let identifier = user_id.identifier();
-        // Get base branch topic
-        let base_branch = &self.state.base_branch;
-        // Link message to channel announcement
-        let link_to = stream_address.relative();
-        let rel_address = MsgId::gen(stream_address.base(), identifier, base_branch, SUB_MESSAGE_NUM);
+    #[tokio::test]
+    async fn failed_psk_rotations_do_not_store_the_new_psk() -> Result<()> {
+        use alloc::boxed::Box;
 
-        // Prepare HDF and PCF
-        // Spongos must be copied because wrapping mutates it
-        let mut linked_msg_spongos = self
-            .state
-            .spongos_store
-            .get(&link_to)
-            .copied()
-            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
-        let unsubscribe_key = StdRng::from_entropy().gen();
-        let author_ke_pk = self
-            .state
-            .author_identifier
-            .as_ref()
-            .unwrap()
-            .ke_pk()
-            .await
-            .map_err(|_| Error::Setup("Failed to generate Public Key from author identifier"))?;
+        use async_trait::async_trait;
+        use lets::error::{Error as LetsError, Result as LetsResult};
 
-        let content = PCF::new_final_frame().with_content(subscription::Wrap::new(
-            &mut linked_msg_spongos,
-            unsubscribe_key,
-            user_id,
-            &author_ke_pk,
+        /// Transport failing to send any message
+        struct UnavailableTransport(Transport);
+
+        #[async_trait(?Send)]
+        impl lets::transport::Transport<'_> for UnavailableTransport {
+            type Msg = TransportMessage;
+            type SendResponse = TransportMessage;
+
+            async fn send_message(&mut self, address: Address, _msg: TransportMessage) -> LetsResult<TransportMessage>
+            where
+                Self::Msg: 'async_trait,
+            {
+                Err(LetsError::AddressError("transport unavailable", address))
+            }
+
+            async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<TransportMessage>> {
+                self.0.recv_messages(address).await
+            }
+        }
+
+        let old_psk = Psk::from_seed("old pre shared key");
+        let new_psk = Psk::from_seed("new pre shared key");
+        let (mut author, _, _) = author_subscriber_fixture().await?;
+        author.add_psk(old_psk);
+        let transport = author.transport().clone();
+        let mut author = author.transfer_to_transport(UnavailableTransport(transport));
+
+        assert!(author.rotate_psk(old_psk.to_pskid(), new_psk).await.is_err());
+        assert!(author.state.psk_store.contains_key(&old_psk.to_pskid()));
+        assert!(!author.state.psk_store.contains_key(&new_psk.to_pskid()));
+
+        // A new PSK stored before the rotation is kept
+        author.add_psk(new_psk);
+        assert!(author.rotate_psk(old_psk.to_pskid(), new_psk).await.is_err());
+        assert!(author.state.psk_store.contains_key(&new_psk.to_pskid()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn subscription_requests_can_be_listed_and_approved() -> Result<()> {
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        // The requester does not know the address of the stream
+        let mut subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("requester"))
+            .with_transport(author.transport().clone())
+            .build();
+        let author_id = author.identifier().unwrap().clone();
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        assert!(author.list_subscription_requests().await?.is_empty());
+        let mut writer = User::builder()
+            .with_identity(Ed25519::from_seed("writer"))
+            .with_transport(author.transport().clone())
+            .build();
+        writer.receive_message(announcement_address).await?;
+        let subscription = writer.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+
+        let request_address = subscriber.request_subscription(&author_id).await?;
+        assert!(matches!(
+            subscriber.request_subscription(&author_id).await,
+            Err(Error::AddressUsed(..))
         ));
-        let header = HDF::new(
-            message_types::SUBSCRIPTION,
-            SUB_MESSAGE_NUM,
-            identifier.clone(),
-            base_branch,
-        )
-        .with_linked_msg_address(link_to);
+        assert_eq!(
+            author.list_subscription_requests().await?,
+            vec![(request_address, subscriber_id.clone())]
+        );
+
+        assert!(subscriber.receive_subscription_approval(&author_id).await.is_err());
+        let keyload = author.approve_subscription_request(request_address).await?;
+        assert!(author.subscribers().any(|s| s == &subscriber_id));
+        assert!(author.list_subscription_requests().await?.is_empty());
+        assert_eq!(
+            subscriber.receive_subscription_approval(&author_id).await?,
+            announcement_address
+        );
+        assert_eq!(subscriber.stream_address(), Some(announcement_address));
+
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let message = subscriber.receive_message(keyload.address()).await?;
+        let keyload_content = message.as_keyload().unwrap();
+        assert!(keyload_content.includes_subscriber(&subscriber_id));
+        // Approving a request leaves the permissions of the other subscribers untouched
+        assert!(keyload_content
+            .subscribers
+            .contains(&Permissioned::Read(subscriber_id.clone())));
+        assert!(keyload_content.subscribers.iter().any(|permission| matches!(
+            permission,
+            Permissioned::ReadWrite(id, _) if Some(id) == writer.identifier()
+        )));
+        let message = subscriber.receive_message(packet.address()).await?;
+        assert_eq!(message.masked_payload(), Some(&b"masked"[..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batches_are_sent_in_order_or_not_at_all() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        let packet = |topic: &str, masked: &[u8]| BatchItem::SignedPacket {
+            topic: Topic::from(topic),
+            public: b"public".to_vec(),
+            masked: masked.to_vec(),
+        };
+
+        let snapshot = author.snapshot();
+        let message_count = author.transport().borrow().message_count();
+        let aborted = author
+            .send_batch(vec![
+                packet(BASE_BRANCH, b"first"),
+                packet("MISSING", b"second"),
+                packet(BASE_BRANCH, b"third"),
+            ])
+            .await;
+        assert!(aborted.is_err());
+        assert_eq!(author.snapshot(), snapshot);
+        assert_eq!(author.transport().borrow().message_count(), message_count);
+
+        let responses = author
+            .send_batch(vec![
+                BatchItem::Keyload {
+                    topic: Topic::from(BASE_BRANCH),
+                    subscribers: vec![Permissioned::Read(subscriber_id)],
+                    psks: Vec::new(),
+                },
+                packet(BASE_BRANCH, b"first"),
+                BatchItem::TaggedPacket {
+                    topic: Topic::from(BASE_BRANCH),
+                    public: b"public".to_vec(),
+                    masked: b"second".to_vec(),
+                },
+            ])
+            .await?;
+        assert_eq!(responses.len(), 3);
+
+        let messages = subscriber.fetch_next_messages().await?;
+        let addresses: Vec<Address> = messages.iter().map(|message| message.address()).collect();
+        let sent: Vec<Address> = responses.iter().map(|response| response.address()).collect();
+        assert_eq!(addresses, sent);
+        assert!(messages[0].is_keyload());
+        assert_eq!(messages[1].masked_payload(), Some(&b"first"[..]));
+        assert_eq!(messages[2].masked_payload(), Some(&b"second"[..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batches_interrupted_by_the_transport_commit_the_sent_messages() -> Result<()> {
+        use alloc::boxed::Box;
+
+        use async_trait::async_trait;
+        use lets::error::{Error as LetsError, Result as LetsResult};
+
+        /// Transport failing to send any message after a number of successful sends
+        struct FailingTransport {
+            inner: Transport,
+            remaining_sends: usize,
+        }
+
+        #[async_trait(?Send)]
+        impl lets::transport::Transport<'_> for FailingTransport {
+            type Msg = TransportMessage;
+            type SendResponse = TransportMessage;
+
+            async fn send_message(&mut self, address: Address, msg: TransportMessage) -> LetsResult<TransportMessage>
+            where
+                Self::Msg: 'async_trait,
+            {
+                if self.remaining_sends == 0 {
+                    return Err(LetsError::AddressError("transport unavailable", address));
+                }
+                self.remaining_sends -= 1;
+                self.inner.send_message(address, msg).await
+            }
+
+            async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<TransportMessage>> {
+                self.inner.recv_messages(address).await
+            }
+        }
+
+        let (author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let transport = author.transport().clone();
+        let mut author = author.transfer_to_transport(FailingTransport {
+            inner: transport,
+            remaining_sends: 2,
+        });
+        let packet = |masked: &[u8]| BatchItem::SignedPacket {
+            topic: base_branch.clone(),
+            public: b"public".to_vec(),
+            masked: masked.to_vec(),
+        };
+        let cursor = author.get_my_cursor(&base_branch).unwrap();
+        let result = author
+            .send_batch(vec![packet(b"first"), packet(b"second"), packet(b"third")])
+            .await;
+        assert!(result.is_err());
+        assert_eq!(author.get_my_cursor(&base_branch), Some(cursor + 2));
+
+        // The rest of the batch can be sent again once the transport is back
+        author.transport_mut().remaining_sends = 1;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"third").await?;
+        let messages = subscriber.fetch_next_messages().await?;
+        let masked: Vec<_> = messages.iter().map(|message| message.masked_payload()).collect();
+        assert_eq!(
+            masked,
+            vec![Some(&b"first"[..]), Some(&b"second"[..]), Some(&b"third"[..])]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn child_stream_announcement_links_to_parent() -> Result<()> {
+        let (author, _, parent_address) = author_subscriber_fixture().await?;
+
+        let child = author.derive_child_stream("CHILD")?;
+        assert_eq!(child.parent_address(), Some(&parent_address));
+        let mut child = child.transfer_to_transport(author.transport().clone());
+        let child_announcement = child.create_stream("CHILD").await?;
+        assert_ne!(child_announcement.address(), parent_address);
+        assert_ne!(child_announcement.address().base(), parent_address.base());
+
+        let mut reader = User::builder()
+            .with_identity(Ed25519::from_seed("reader"))
+            .with_transport(author.transport().clone())
+            .build();
+        let message = reader.receive_message(child_announcement.address()).await?;
+        assert_eq!(message.header().message_type(), message_types::CHILD_ANNOUNCEMENT);
+        assert_eq!(message.as_announcement().unwrap().parent_address, Some(parent_address));
+        assert_eq!(reader.parent_address(), Some(&parent_address));
+        assert_eq!(author.parent_address(), None);
+
+        let backup = reader.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.parent_address(), Some(&parent_address));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn publisher_cursors_are_exposed() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let author_id = author.identifier().unwrap().clone();
+        let cursor_before = author.get_my_cursor(&base_branch).unwrap();
+
+        let sent = 5;
+        for _ in 0..sent {
+            author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        }
+        subscriber.sync().await?;
+
+        assert_eq!(author.get_my_cursor(&base_branch), Some(cursor_before + sent));
+        assert_eq!(
+            subscriber.get_subscriber_cursor(&base_branch, &author_id),
+            Some(cursor_before + sent)
+        );
+        assert_eq!(subscriber.get_my_cursor(&base_branch), None);
+        assert_eq!(author.get_my_cursor(&Topic::from("unknown")), None);
+        Ok(())
+    }
+
+    #[cfg(feature = "wait-for-message")]
+    #[tokio::test]
+    async fn wait_for_message_polls_until_available() -> Result<()> {
+        use alloc::boxed::Box;
+        use core::time::Duration;
+
+        use async_trait::async_trait;
+        use lets::error::{Error as LetsError, Result as LetsResult};
+
+        /// Transport failing to deliver a message for a number of attempts before it is available
+        struct DelayedTransport {
+            inner: Transport,
+            failed_attempts: usize,
+            attempts: usize,
+        }
+
+        #[async_trait(?Send)]
+        impl lets::transport::Transport<'_> for DelayedTransport {
+            type Msg = TransportMessage;
+            type SendResponse = TransportMessage;
+
+            async fn send_message(&mut self, address: Address, msg: TransportMessage) -> LetsResult<TransportMessage>
+            where
+                Self::Msg: 'async_trait,
+            {
+                self.inner.send_message(address, msg).await
+            }
+
+            async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<TransportMessage>> {
+                self.attempts += 1;
+                if self.attempts <= self.failed_attempts {
+                    return Err(LetsError::AddressError("message not yet available", address));
+                }
+                self.inner.recv_messages(address).await
+            }
+        }
+
+        let (mut author, subscriber, _) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let mut subscriber = subscriber.transfer_to_transport(DelayedTransport {
+            inner: author.transport().clone(),
+            failed_attempts: 2,
+            attempts: 0,
+        });
+        let message = subscriber
+            .wait_for_message(packet.address(), Duration::from_millis(10), Duration::from_secs(1))
+            .await?;
+        assert_eq!(subscriber.transport().attempts, 3);
+        assert_eq!(message.address(), packet.address());
+        assert_eq!(message.as_signed_packet().unwrap().public_payload, b"public");
+        assert_eq!(
+            subscriber.get_subscriber_cursor(&Topic::from(BASE_BRANCH), author.identifier().unwrap()),
+            author.get_my_cursor(&Topic::from(BASE_BRANCH))
+        );
+
+        let missing = Address::new(packet.address().base(), MsgId::from([0; 12]));
+        let result = subscriber
+            .wait_for_message(missing, Duration::from_millis(10), Duration::from_millis(50))
+            .await;
+        assert!(matches!(result, Err(Error::MessageNotFound(address)) if address == missing));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_metadata_can_be_updated() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let metadata = StreamMetadata {
+            name: "Sensor readings".into(),
+            description: "Temperature of the greenhouse".into(),
+            schema_version: 1,
+            tags: vec!["iot".into()],
+        };
+        author.publish_stream_metadata(metadata.clone()).await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let updated = StreamMetadata {
+            name: "Sensor readings".into(),
+            description: "Temperature and humidity of the greenhouse".into(),
+            schema_version: 2,
+            tags: vec!["iot".into(), "greenhouse".into()],
+        };
+        author.send_announcement_update(updated.clone()).await?;
+        assert_eq!(author.stream_metadata(), Some(&updated));
+
+        assert_eq!(subscriber.stream_metadata(), None);
+        subscriber.sync().await?;
+        assert_eq!(subscriber.stream_metadata(), Some(&updated));
+
+        let backup = subscriber.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.stream_metadata(), Some(&updated));
+
+        assert!(matches!(
+            subscriber.send_announcement_update(metadata).await,
+            Err(Error::NoCursor(..))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn readonly_subscribers_are_never_granted_write_permissions() -> Result<()> {
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let mut observer = User::builder()
+            .with_identity(Ed25519::from_seed("observer"))
+            .with_transport(author.transport().clone())
+            .build();
+        observer.receive_message(announcement).await?;
+
+        let subscription = subscriber.subscribe().await?;
+        let observer_subscription = observer.subscribe_readonly().await?;
+        let message = author.receive_message(subscription.address()).await?;
+        // Plain subscriptions keep the layout readable by authors unaware of subscription options
+        assert_eq!(message.header().message_type(), message_types::SUBSCRIPTION);
+        assert!(!message.as_subscription().unwrap().readonly);
+        let message = author.receive_message(observer_subscription.address()).await?;
+        assert_eq!(
+            message.header().message_type(),
+            message_types::SUBSCRIPTION_WITH_OPTIONS
+        );
+        assert!(message.as_subscription().unwrap().readonly);
+
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        let observer_id = observer.identifier().unwrap().clone();
+        assert!(!author.is_readonly_subscriber(&subscriber_id));
+        assert!(author.is_readonly_subscriber(&observer_id));
+
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        assert!(matches!(
+            author.state.cursor_store.get_permission(&base_branch, &subscriber_id),
+            Some(Permissioned::ReadWrite(..))
+        ));
+        assert!(author
+            .state
+            .cursor_store
+            .get_permission(&base_branch, &observer_id)
+            .is_none());
+
+        observer.sync().await?;
+        assert!(observer.permission(&base_branch).is_none());
+        assert!(matches!(
+            observer.send_signed_packet(BASE_BRANCH, b"public", b"masked").await,
+            Err(Error::NoCursor(..))
+        ));
+
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert!(restored.is_readonly_subscriber(&observer_id));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn compress_spongos_store_removes_duplicate_states() -> Result<()> {
+        use core::mem::size_of;
+
+        use spongos::Spongos;
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert_eq!(author.compress_spongos_store()?, 0);
+
+        let duplicate = MsgId::from([7; 12]);
+        let spongos = author.state.spongos_store[&packet.address().relative()];
+        author.state.spongos_store.insert(duplicate, spongos);
+        let stored_states = author.state.spongos_store.len();
+
+        let freed = author.compress_spongos_store()?;
+        assert_eq!(freed, size_of::<Spongos>() - size_of::<MsgId>());
+        assert_eq!(author.state.spongos_store.len(), stored_states - 1);
+        assert_eq!(author.stored_spongos(&duplicate), Some(&spongos));
+        assert_eq!(author.stored_spongos(&packet.address().relative()), Some(&spongos));
+        assert_eq!(author.compress_spongos_store()?, 0);
+
+        // The deduplicated store keeps serving the states messages are linked to
+        let next = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        subscriber.sync().await?;
+        assert_eq!(
+            subscriber.get_latest_link(&Topic::from(BASE_BRANCH)),
+            Some(next.address().relative())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_health_reports_user_state() -> Result<()> {
+        let transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(transport.clone())
+            .build();
+        let health = author.stream_health();
+        assert!(!health.is_connected);
+        assert_eq!(health.stream_address, None);
+        assert_eq!(health.branch_count, 0);
+
+        let announcement = author.create_stream(BASE_BRANCH).await?;
+        author.new_branch(BASE_BRANCH, "BRANCH_A").await?;
+        author.new_branch(BASE_BRANCH, "BRANCH_B").await?;
+        author.add_subscriber(Identity::from(Ed25519::from_seed("subscriber")).identifier().clone());
+        for _ in 0..3 {
+            author.send_signed_packet("BRANCH_A", b"public", b"masked").await?;
+        }
+
+        let mut reader = User::builder()
+            .with_identity(Ed25519::from_seed("reader"))
+            .with_transport(transport)
+            .lean()
+            .build();
+        reader.receive_message(announcement.address()).await?;
+        reader.sync().await?;
+
+        let health = author.stream_health();
+        assert!(health.is_connected);
+        assert_eq!(health.stream_address, Some(announcement.address()));
+        assert_eq!(health.branch_count, 3);
+        assert_eq!(health.subscriber_count, 1);
+        assert_eq!(health.total_cursors, 3);
+        assert_eq!(health.oldest_spongos_age, None);
+        assert_eq!(health.lean_mode, SpongosRetention::All);
+        assert_eq!(health.pending_orphans, 0);
+
+        let health = reader.stream_health();
+        assert_eq!(health.branch_count, 3);
+        assert_eq!(health.subscriber_count, 0);
+        assert_eq!(health.lean_mode, SpongosRetention::Lean);
+        // The state of the stream announcement, processed first, is never removed from store
+        assert_eq!(health.oldest_spongos_age, Some(5));
+        assert!(health.to_string().contains(&format!("{:<20} | 3", "branches")));
+        Ok(())
+    }
+
+    #[test]
+    fn user_builder_identity_shortcuts() {
+        use crate::api::user_builder::UserBuilder;
+
+        let alice: User<Transport> = UserBuilder::from_seed("alice")
+            .with_transport(Transport::default())
+            .build();
+        let alice_again: User<Transport> = UserBuilder::from_seed("alice")
+            .with_transport(Transport::default())
+            .lean()
+            .build();
+        assert_eq!(alice.identifier(), alice_again.identifier());
+        assert_eq!(
+            alice.identifier(),
+            Some(Identity::from(Ed25519::from_seed("alice")).identifier())
+        );
+
+        let first: User<Transport> = UserBuilder::from_entropy().with_transport(Transport::default()).build();
+        let second: User<Transport> = UserBuilder::from_entropy().with_transport(Transport::default()).build();
+        assert_ne!(first.identifier(), second.identifier());
+    }
+
+    #[tokio::test]
+    async fn stream_diff_reports_diverged_states() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        author.new_branch(BASE_BRANCH, "BRANCH").await?;
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let author_only = Identity::from(Ed25519::from_seed("author only")).identifier().clone();
+        let subscriber_only = Identity::from(Ed25519::from_seed("subscriber only"))
+            .identifier()
+            .clone();
+        author.add_subscriber(author_only.clone());
+        subscriber.add_subscriber(subscriber_only.clone());
+        let psk = Psk::from_seed("psk");
+        subscriber.add_psk(psk);
+
+        let diff = subscriber.stream_diff(&author.snapshot());
+        assert_eq!(diff.missing_topics_in_self, vec![Topic::from("BRANCH")]);
+        assert!(diff.missing_topics_in_other.is_empty());
+        assert_eq!(diff.cursor_diffs, subscriber.cursor_diff(&author));
+        assert!(!diff.cursor_diffs.is_empty());
+        assert_eq!(diff.extra_subscribers_in_self, vec![subscriber_only]);
+        assert_eq!(diff.extra_subscribers_in_other, vec![author_only]);
+        // The author holds the states of the branch announcement and of the packet
+        assert_eq!(diff.spongos_count_diff, -2);
+        assert_eq!(diff.psk_count_diff, 1);
+        assert!(!diff.is_empty());
+
+        assert!(author.stream_diff(&author.snapshot()).is_empty());
+
+        // Deduplicated states are still counted once per message
+        let packet_link = author.get_latest_link(&Topic::from(BASE_BRANCH)).unwrap();
+        let packet_spongos = author.state.spongos_store[&packet_link];
+        author.state.spongos_store.insert(MsgId::from([9; 12]), packet_spongos);
+        let uncompressed = author.snapshot();
+        assert!(author.compress_spongos_store()? > 0);
+        assert!(author.stream_diff(&uncompressed).is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "invite-links")]
+    #[tokio::test]
+    async fn invite_links_encode_and_decode_symmetrically() -> Result<()> {
+        let (author, _, announcement_address) = author_subscriber_fixture().await?;
+        let link = author.generate_invite_link()?;
+        assert!(link.starts_with("streams://invite/"));
+
+        let invite = User::parse_invite_link(&link)?;
+        assert_eq!(invite.stream_address, announcement_address);
+        assert_eq!(invite.announcement_address, announcement_address);
+        assert_eq!(&invite.author_identifier, author.identifier().unwrap());
+
+        let mut subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("invited subscriber"))
+            .with_transport(author.transport().clone())
+            .build();
+        assert!(subscriber.generate_invite_link().is_err());
+        subscriber.receive_message(invite.announcement_address).await?;
+        assert_eq!(subscriber.stream_address(), Some(invite.stream_address));
+        assert_eq!(subscriber.generate_invite_link()?, link);
+
+        assert!(User::parse_invite_link("streams://invite/not-base64!").is_err());
+        assert!(User::parse_invite_link(&link.replace("streams://invite/", "https://invite/")).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "watch-branch")]
+    #[tokio::test]
+    async fn watch_branch_hands_over_each_new_message() -> Result<()> {
+        let (mut author, subscriber, _) = author_subscriber_fixture().await?;
+        let received = Rc::new(RefCell::new(Vec::new()));
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let handler_received = received.clone();
+                let watcher = subscriber.watch_branch(BASE_BRANCH, move |message| {
+                    handler_received
+                        .borrow_mut()
+                        .push(message.expect("watched branch is fetched").address())
+                });
+                watcher.set_interval(core::time::Duration::from_millis(10));
+
+                let mut sent = Vec::new();
+                for _ in 0..5 {
+                    let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+                    sent.push(packet.address());
+                }
+                for _ in 0..100 {
+                    if received.borrow().len() == sent.len() {
+                        break;
+                    }
+                    tokio::time::sleep(core::time::Duration::from_millis(10)).await;
+                }
 
-        // Wrap message
-        let (transport_msg, _spongos) = LetsMessage::new(header, content)
-            .wrap()
+                let mut subscriber = watcher.stop().await?;
+                assert_eq!(*received.borrow(), sent);
+                assert_eq!(subscriber.sync().await?, 0);
+                Ok(())
+            })
             .await
-            .map_err(|e| Error::Wrapped("subscribe", e))?;
+    }
 
-        // Attempt to send message
-        let message_address = Address::new(stream_address.base(), rel_address);
+    #[cfg(feature = "json-log")]
+    #[tokio::test]
+    async fn json_log_export_matches_user_state() -> Result<()> {
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        author.new_branch(BASE_BRANCH, "BRANCH").await?;
+        let subscriber_id = Identity::from(Ed25519::from_seed("subscriber")).identifier().clone();
+        author.add_subscriber(subscriber_id.clone());
+        author
+            .send_keyload("BRANCH", [Permissioned::Read(&subscriber_id)], [])
+            .await?;
+        let psk = Psk::from_seed("psk");
+        author.add_psk(psk);
+
+        let mut log = Vec::new();
+        let written = author.export_to_json_log(&mut log)?;
+        assert_eq!(written, log.len());
+        let lines: Vec<serde_json::Value> = core::str::from_utf8(&log)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let of_type = |line_type: &str| {
+            lines
+                .iter()
+                .filter(|line| line["type"] == line_type)
+                .collect::<Vec<_>>()
+        };
 
-        // Attempt to send message
-        let has_msg = self.transport.recv_message(message_address).await;
-        if !has_msg.is_err() {
-            return Err(Error::AddressUsed("subscribe", message_address));
+        assert_eq!(lines[0]["type"], "header");
+        assert_eq!(lines[0]["stream_address"], announcement_address.to_string());
+        assert_eq!(lines[0]["base_branch"], BASE_BRANCH);
+
+        let latest_links = author.state.cursor_store.latest_links();
+        let branches = of_type("branch");
+        assert_eq!(branches.len(), latest_links.len());
+        for (branch, (topic, latest_link)) in branches.iter().zip(latest_links) {
+            assert_eq!(branch["topic"], topic.to_string());
+            assert_eq!(branch["latest_link"], latest_link.to_string());
         }
 
-        let send_response = self
-            .transport
-            .send_message(message_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(message_address, "send subscribe message", e))?;
+        let stored_cursors = author.state.cursor_store.to_vec();
+        let cursors = of_type("cursor");
+        assert_eq!(cursors.len(), stored_cursors.len());
+        for (cursor, (topic, permission, position)) in cursors.iter().zip(stored_cursors) {
+            assert_eq!(cursor["topic"], topic.to_string());
+            assert_eq!(cursor["identifier"], permission.identifier().to_string());
+            assert_eq!(cursor["cursor"], position);
+        }
+        assert!(cursors.iter().any(|cursor| cursor["topic"] == "BRANCH"
+            && cursor["identifier"] == subscriber_id.to_string()
+            && cursor["permission"] == "read"));
+        assert!(cursors.iter().any(|cursor| cursor["permission"] == "admin"));
+
+        let psks = of_type("psk");
+        assert_eq!(psks.len(), 1);
+        assert_eq!(psks[0]["pskid"], psk.to_pskid().to_string());
+        Ok(())
+    }
 
-        // If message has been sent successfully, commit message to stores
-        // - Subscription messages are not stored in the cursor store
-        // - Subscription messages are never stored in spongos to maintain consistency about the view of the
-        // set of messages of the stream between all the subscribers and across stateless recovers
-        Ok(SendResponse::new(message_address, send_response))
+    #[tokio::test]
+    async fn verifier_gates_signed_packets() -> Result<()> {
+        fn reject_null_prefix(payload: &[u8]) -> Result<()> {
+            match payload.first() {
+                Some(0x00) => Err(Error::Setup("payload rejected by the verifier")),
+                _ => Ok(()),
+            }
+        }
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let verifier = CallbackVerify(reject_null_prefix);
+        let before = author.snapshot();
+        let result = author
+            .send_signed_packet_verified(BASE_BRANCH, [0x00, 1, 2], b"masked", verifier)
+            .await;
+        assert!(matches!(result, Err(Error::Setup("payload rejected by the verifier"))));
+        assert_eq!(author.snapshot(), before);
+        assert_eq!(subscriber.sync().await?, 0);
+
+        let packet = author
+            .send_signed_packet_verified(BASE_BRANCH, [0x01, 1, 2], b"masked", verifier)
+            .await?;
+        let noop_packet = author
+            .send_signed_packet_verified(BASE_BRANCH, [0x00, 1, 2], b"masked", NoopVerify)
+            .await?;
+        let received: Vec<Address> = subscriber
+            .fetch_next_messages()
+            .await?
+            .iter()
+            .map(Message::address)
+            .collect();
+        assert_eq!(received, vec![packet.address(), noop_packet.address()]);
+        Ok(())
     }
 
-    /// Create and send a new Unsubscription message, informing the stream author that this [`User`]
-    /// instance can be removed from the stream.
-    pub async fn unsubscribe(&mut self) -> Result<SendResponse<TSR>> {
-        // Check conditions
-        let stream_address = self
-            .stream_address()
-            .ok_or(Error::Setup("before unsubscribing, the stream must be created"))?;
-        // Confirm user has identity
-        let user_id = self.identity().ok_or(Error::NoIdentity("unsubscribe"))?;
-        let identifier = user_id.identifier().clone();
-        // Get base branch topic
-        let base_branch = &self.state.base_branch;
-        // Link message to channel announcement
-        let link_to = self
-            .get_latest_link(base_branch)
-            .ok_or_else(|| Error::TopicNotFound(base_branch.clone()))?;
+    #[tokio::test]
+    async fn packets_only_receivers_discard_other_messages() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let mut tagged_reader = User::builder()
+            .with_identity(Ed25519::from_seed("tagged reader"))
+            .with_transport(author.transport().clone())
+            .build();
+        tagged_reader.receive_message(announcement_address).await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let subscription = tagged_reader.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+
+        let signed_1 = author.send_signed_packet(BASE_BRANCH, b"signed 1", b"masked 1").await?;
+        let tagged = author.send_tagged_packet(BASE_BRANCH, b"tagged", b"masked").await?;
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+        let signed_2 = author.send_signed_packet(BASE_BRANCH, b"signed 2", b"masked 2").await?;
+
+        let signed_packets = subscriber.receive_signed_packets_only(BASE_BRANCH).await?;
+        assert_eq!(
+            signed_packets,
+            vec![
+                (signed_1.address(), b"signed 1".to_vec(), b"masked 1".to_vec()),
+                (signed_2.address(), b"signed 2".to_vec(), b"masked 2".to_vec()),
+            ]
+        );
+        assert!(subscriber.receive_signed_packets_only(BASE_BRANCH).await?.is_empty());
 
-        // Update own's cursor
-        let new_cursor = self.next_cursor(base_branch)?;
-        let rel_address = MsgId::gen(stream_address.base(), &identifier, base_branch, new_cursor);
+        let tagged_packets = tagged_reader.receive_tagged_packets_only(BASE_BRANCH).await?;
+        assert_eq!(
+            tagged_packets,
+            vec![(tagged.address(), b"tagged".to_vec(), b"masked".to_vec())]
+        );
+        Ok(())
+    }
 
-        // Prepare HDF and PCF
-        // Spongos must be copied because wrapping mutates it
-        let mut linked_msg_spongos = self
+    #[tokio::test]
+    async fn prune_subscribers_removes_inactive_publishers() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let author_id = author.identifier().unwrap().clone();
+        let author_cursor = author.state.cursor_store.get_cursor(&base_branch, &author_id);
+        let subscribers: Vec<Identifier> = ["subscriber 0", "subscriber 5", "subscriber 15"]
+            .iter()
+            .map(|seed| Identity::from(Ed25519::from_seed(seed)).identifier().clone())
+            .collect();
+        for (id, cursor) in subscribers.iter().zip([0, 5, 15]) {
+            author.add_subscriber(id.clone());
+            author.state.cursor_store.insert_cursor(
+                &base_branch,
+                Permissioned::ReadWrite(id.clone(), PermissionDuration::Perpetual),
+                cursor,
+            );
+        }
+
+        assert_eq!(author.prune_subscribers(5)?, 2);
+        assert_eq!(author.subscribers().collect::<Vec<_>>(), vec![&subscribers[2]]);
+        assert!(author
             .state
-            .spongos_store
-            .get(&link_to)
-            .copied()
-            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
-        let content = PCF::new_final_frame().with_content(unsubscription::Wrap::new(&mut linked_msg_spongos, user_id));
-        let header = HDF::new(
-            message_types::UNSUBSCRIPTION,
-            new_cursor,
-            identifier.clone(),
-            base_branch,
-        )
-        .with_linked_msg_address(link_to);
+            .cursor_store
+            .get_cursor(&base_branch, &subscribers[0])
+            .is_none());
+        assert!(author
+            .state
+            .cursor_store
+            .get_cursor(&base_branch, &subscribers[1])
+            .is_none());
+        assert_eq!(
+            author.state.cursor_store.get_cursor(&base_branch, &subscribers[2]),
+            Some(15)
+        );
+        assert_eq!(
+            author.state.cursor_store.get_cursor(&base_branch, &author_id),
+            author_cursor
+        );
 
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
-            .await
-            .map_err(|e| Error::Wrapped("unsubscribe", e))?;
+        assert!(subscriber.prune_subscribers(5).is_err());
+        Ok(())
+    }
 
-        // Attempt to send message
-        let message_address = Address::new(stream_address.base(), rel_address);
-        if self.transport.recv_message(message_address).await.is_err() {
-            return Err(Error::AddressUsed("unsubscribe", message_address));
+    #[tokio::test]
+    async fn multi_branch_sync_only_processes_the_requested_branches() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let branches: Vec<Topic> = (1..=4).map(|i| Topic::from(format!("BRANCH_{}", i))).collect();
+        for branch in &branches {
+            author.new_branch(BASE_BRANCH, branch.clone()).await?;
+        }
+        subscriber.sync().await?;
+        for branch in &branches {
+            for i in 0..2u8 {
+                author.send_signed_packet(branch.clone(), [i], b"").await?;
+            }
         }
 
-        let send_response = self
-            .transport
-            .send_message(message_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send unsubscribe message", e))?;
+        let author_id = author.identifier().unwrap().clone();
+        let cursors_before: Vec<Option<usize>> = branches
+            .iter()
+            .map(|branch| subscriber.state.cursor_store.get_cursor(branch, &author_id))
+            .collect();
+        let requested = [branches[0].clone(), branches[2].clone(), Topic::from("UNKNOWN")];
+        let counts = subscriber.multi_branch_sync(&requested).await?;
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.get(&branches[0]), Some(&2));
+        assert_eq!(counts.get(&branches[2]), Some(&2));
+        for (index, branch) in branches.iter().enumerate() {
+            let cursor = subscriber.state.cursor_store.get_cursor(branch, &author_id);
+            match index {
+                0 | 2 => assert_eq!(cursor, cursors_before[index].map(|cursor| cursor + 2)),
+                _ => assert_eq!(cursor, cursors_before[index]),
+            }
+        }
+        assert_eq!(subscriber.sync().await?, 4);
+        Ok(())
+    }
 
-        // If message has been sent successfully, commit message to stores
-        let permission = Permissioned::Read(identifier);
-        self.state
-            .cursor_store
-            .insert_cursor(base_branch, permission, new_cursor);
-        self.store_spongos(rel_address, spongos, link_to);
-        Ok(SendResponse::new(message_address, send_response))
+    #[tokio::test]
+    async fn multi_branch_sync_leaves_the_orphans_of_other_branches_queued() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let requested = Topic::from("REQUESTED");
+        let other = Topic::from("OTHER");
+        author.new_branch(BASE_BRANCH, requested.clone()).await?;
+        author.new_branch(BASE_BRANCH, other.clone()).await?;
+        subscriber.sync().await?;
+
+        let first = author.send_signed_packet(other.clone(), b"first", b"").await?;
+        let second = author.send_signed_packet(other, b"second", b"").await?;
+        assert!(subscriber.receive_message(second.address()).await?.is_orphan());
+        subscriber.receive_message(first.address()).await?;
+
+        subscriber.multi_branch_sync(&[requested]).await?;
+        assert_eq!(subscriber.list_orphans(), vec![second.address()]);
+        assert_eq!(subscriber.retry_orphans().await?.len(), 1);
+        Ok(())
     }
 
-    /// Create and send a new Keyload message, updating the read/write permissions for a specified
-    /// branch. All keyload messages are linked to the announcement message to ensure they
-    /// can always be read by a [`User`] that can sequence up to it.
-    ///
-    /// # Arguments
-    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
-    /// * `subscribers`: The updated [`Permissioned`] list for the branch.
-    /// * `psk_ids`: A list of [Psk Id's](`PskId`) with read access for the branch.
-    pub async fn send_keyload<'a, Subscribers, Psks, Top>(
-        &mut self,
-        topic: Top,
-        subscribers: Subscribers,
-        psk_ids: Psks,
-    ) -> Result<SendResponse<TSR>>
-    where
-        Subscribers: IntoIterator<Item = Permissioned<&'a Identifier>> + Clone,
-        Subscribers::IntoIter: ExactSizeIterator,
-        Top: Into<Topic>,
-        Psks: IntoIterator<Item = PskId>,
-    {
-        // Check conditions
-        let stream_address = self
-            .stream_address()
-            .ok_or(Error::Setup("before sending a keyload, the stream must be created"))?;
-        // Confirm user has identity
-        let user_id = self.identity().ok_or(Error::NoIdentity("send keyload"))?;
-        let identifier = user_id.identifier().clone();
-        // Check Topic
-        let topic = topic.into();
-        // Check Permission
-        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
-        if !permission.is_admin() {
-            return Err(Error::WrongRole("Admin", identifier, "send a keyload"));
+    #[tokio::test]
+    async fn restored_spongos_states_allow_handling_pruned_links() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let first = author.send_signed_packet(BASE_BRANCH, b"first", b"masked").await?;
+        subscriber.sync().await?;
+
+        let archive = subscriber.snapshot_spongos(&base_branch)?;
+        assert!(archive.contains_key(&first.address().relative()));
+        subscriber.remove_spongos(&first.address().relative());
+        assert!(subscriber.stored_spongos(&first.address().relative()).is_none());
+        let mut pruned = User::restore_from_snapshot(subscriber.snapshot(), subscriber.transport().clone());
+
+        let second = author.send_signed_packet(BASE_BRANCH, b"second", b"masked").await?;
+        assert!(pruned.receive_message(second.address()).await?.is_orphan());
+
+        assert_eq!(subscriber.restore_spongos(&base_branch, archive.clone())?, 1);
+        assert_eq!(subscriber.restore_spongos(&base_branch, archive)?, 0);
+        let message = subscriber.receive_message(second.address()).await?;
+        assert!(!message.is_orphan());
+        assert_eq!(message.public_payload(), Some(&b"second"[..]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn orphan_queue_can_be_listed_and_retried() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let first = author.send_signed_packet(BASE_BRANCH, b"first", b"").await?;
+        let second = author.send_signed_packet(BASE_BRANCH, b"second", b"").await?;
+
+        // The second packet arrives before the first one it is linked to
+        assert!(subscriber.receive_message(second.address()).await?.is_orphan());
+        assert_eq!(subscriber.list_orphans(), vec![second.address()]);
+        assert!(subscriber.retry_orphans().await?.is_empty());
+        assert_eq!(subscriber.list_orphans(), vec![second.address()]);
+
+        subscriber.receive_message(first.address()).await?;
+        let resolved = subscriber.retry_orphans().await?;
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].address(), second.address());
+        assert!(subscriber.list_orphans().is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "compressed-payloads")]
+    #[tokio::test]
+    async fn compressed_tagged_packets_round_trip() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let reading = br#"{"temperature": 21.5, "humidity": 0.4, "pressure": 1013}"#;
+        let masked: Vec<u8> = reading.iter().copied().cycle().take(reading.len() * 50).collect();
+
+        let compressed = author
+            .send_tagged_packet_compressed(BASE_BRANCH, b"public", &masked)
+            .await?;
+        let plain = author.send_tagged_packet(BASE_BRANCH, b"public", &masked).await?;
+
+        let message = subscriber.receive_message(compressed.address()).await?;
+        assert_eq!(message.public_payload(), Some(&b"public"[..]));
+        assert!(message.masked_payload().unwrap().len() * 2 <= masked.len());
+        assert_eq!(message.decompress_masked()?, Some(masked.clone()));
+
+        let message = subscriber.receive_message(plain.address()).await?;
+        assert_eq!(message.masked_payload(), Some(&masked[..]));
+        assert_eq!(message.decompress_masked()?, Some(masked));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn peer_sync_reconciles_users_that_processed_different_branches() -> Result<()> {
+        let (mut author, mut subscriber_a, announcement_address) = author_subscriber_fixture().await?;
+        let mut subscriber_b = User::builder()
+            .with_identity(Ed25519::from_seed("subscriber b"))
+            .with_transport(author.transport().clone())
+            .build();
+        subscriber_b.receive_message(announcement_address).await?;
+        for subscriber in [&mut subscriber_a, &mut subscriber_b] {
+            let subscription = subscriber.subscribe().await?;
+            author.receive_message(subscription.address()).await?;
+        }
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (branch_a, branch_b) = (Topic::from("BRANCH_A"), Topic::from("BRANCH_B"));
+        author.new_branch(BASE_BRANCH, branch_a.clone()).await?;
+        author.new_branch(BASE_BRANCH, branch_b.clone()).await?;
+        subscriber_a.sync().await?;
+        subscriber_b.sync().await?;
+        for branch in [&branch_a, &branch_b] {
+            for i in 0..2u8 {
+                author.send_signed_packet(branch.clone(), [i], b"").await?;
+            }
         }
 
-        // Link message to edge of branch
-        let link_to = self
-            .get_latest_link(&topic)
-            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
-        // Update own's cursor
-        let new_cursor = self.next_cursor(&topic)?;
-        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+        // Each subscriber processes half of the stream
+        subscriber_a.multi_branch_sync(&[branch_a.clone()]).await?;
+        subscriber_b.multi_branch_sync(&[branch_b.clone()]).await?;
+        assert!(!subscriber_a.cursor_diff(&subscriber_b).is_empty());
 
-        // Prepare HDF and PCF
-        // All Keyload messages will attach to stream Announcement message spongos
-        let mut announcement_msg_spongos = self
+        // States that do not match their recorded digest are not copied over
+        let mut copy_a = User::restore_from_snapshot(subscriber_a.snapshot(), author.transport().clone());
+        let mut tampered_b = User::restore_from_snapshot(subscriber_b.snapshot(), author.transport().clone());
+        let tampered_link = subscriber_b.get_latest_link(&branch_b).unwrap();
+        tampered_b
             .state
             .spongos_store
-            .get(&stream_address.relative())
-            .copied()
-            .ok_or(Error::Setup("a user must keep a stream announcement spongos in store"))?;
+            .insert(tampered_link, spongos::Spongos::init());
+        assert_eq!(copy_a.peer_sync(&mut tampered_b)?, (1, 2));
+        assert!(copy_a.stored_spongos(&tampered_link).is_none());
+
+        assert_eq!(subscriber_a.peer_sync(&mut subscriber_b)?, (2, 2));
+        assert!(subscriber_a.cursor_diff(&subscriber_b).is_empty());
+        let logged_addresses = |user: &User<Transport>| -> Result<Vec<Address>> {
+            Ok(user
+                .export_message_log(&branch_b)?
+                .into_iter()
+                .map(|entry| entry.address)
+                .collect())
+        };
+        assert_eq!(logged_addresses(&subscriber_a)?, logged_addresses(&subscriber_b)?);
+        assert_eq!(
+            subscriber_a.get_latest_link(&branch_a),
+            subscriber_b.get_latest_link(&branch_a)
+        );
+        assert_eq!(
+            subscriber_a.get_latest_link(&branch_b),
+            subscriber_b.get_latest_link(&branch_b)
+        );
+        assert_eq!(subscriber_a.peer_sync(&mut subscriber_b)?, (0, 0));
+        assert_eq!(subscriber_a.sync().await?, 0);
+
+        // Both subscribers can keep following both branches
+        let packet = author.send_signed_packet(branch_b.clone(), b"after", b"").await?;
+        assert!(!subscriber_a.receive_message(packet.address()).await?.is_orphan());
+        let packet = author.send_signed_packet(branch_a.clone(), b"after", b"").await?;
+        assert!(!subscriber_b.receive_message(packet.address()).await?.is_orphan());
+        Ok(())
+    }
 
-        let mut rng = StdRng::from_entropy();
-        let encryption_key = rng.gen();
-        let nonce = rng.gen();
-        let psk_ids_with_psks = psk_ids
-            .into_iter()
-            .map(|pskid| Ok((pskid, self.state.psk_store.get(&pskid).ok_or(Error::UnknownPsk(pskid))?)))
-            .collect::<Result<Vec<(_, _)>>>()?; // collect to handle possible error
-        let content = PCF::new_final_frame().with_content(keyload::Wrap::new(
-            &mut announcement_msg_spongos,
-            subscribers.clone().into_iter().collect::<Vec<_>>(),
-            &psk_ids_with_psks,
-            encryption_key,
-            nonce,
-            user_id,
+    #[cfg(feature = "json-schema")]
+    #[tokio::test]
+    async fn schema_validator_gates_signed_packets() -> Result<()> {
+        use crate::api::schema::{JsonSchemaValidator, NoopValidator};
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let schema = JsonSchemaValidator::new(&serde_json::json!({
+            "type": "object",
+            "properties": { "value": { "type": "number" } },
+            "required": ["value"],
+        }))?;
+        assert!(matches!(
+            JsonSchemaValidator::new(&serde_json::json!({ "type": "no such type" })),
+            Err(Error::SchemaValidationFailed(_))
         ));
-        let header =
-            HDF::new(message_types::KEYLOAD, new_cursor, identifier.clone(), &topic).with_linked_msg_address(link_to);
-
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
-            .await
-            .map_err(|e| Error::Wrapped("send keyload", e))?;
-
-        // Attempt to send message
-        let message_address = Address::new(stream_address.base(), rel_address);
-        if !self.transport.recv_message(message_address).await.is_err() {
-            return Err(Error::AddressUsed("keyload", message_address));
+        let before = author.snapshot();
+        for invalid in [&br#"{"value": "high"}"#[..], br#"{"reading": 21.5}"#, b"not json"] {
+            let result = author
+                .send_signed_packet_with_schema(BASE_BRANCH, invalid, b"masked", &schema)
+                .await;
+            assert!(matches!(result, Err(Error::SchemaValidationFailed(_))));
         }
+        assert_eq!(author.snapshot(), before);
+        assert_eq!(subscriber.sync().await?, 0);
+
+        let packet = author
+            .send_signed_packet_with_schema(BASE_BRANCH, br#"{"value": 21.5}"#, b"masked", &schema)
+            .await?;
+        let noop_packet = author
+            .send_signed_packet_with_schema(BASE_BRANCH, b"not json", b"masked", &NoopValidator)
+            .await?;
+        let received: Vec<Address> = subscriber
+            .fetch_next_messages()
+            .await?
+            .iter()
+            .map(Message::address)
+            .collect();
+        assert_eq!(received, vec![packet.address(), noop_packet.address()]);
+        Ok(())
+    }
 
-        let send_response = self
-            .transport
-            .send_message(message_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send keyload message", e))?;
-
-        // If message has been sent successfully, commit message to stores
-        for subscriber in subscribers {
-            if self.should_store_cursor(&topic, subscriber) {
-                self.state
-                    .cursor_store
-                    .insert_cursor(&topic, subscriber.into(), INIT_MESSAGE_NUM);
-            }
+    #[tokio::test]
+    async fn batch_receive_processes_messages_in_order() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let mut addresses = Vec::new();
+        for i in 0..5u8 {
+            let packet = author.send_signed_packet(BASE_BRANCH, [i], b"").await?;
+            addresses.push(packet.address());
         }
-        self.state
-            .cursor_store
-            .insert_cursor(&topic, Permissioned::Admin(identifier), new_cursor);
-        self.store_spongos(rel_address, spongos, link_to);
-        // Update Branch Links
-        self.set_latest_link(topic, message_address.relative());
-        Ok(SendResponse::new(message_address, send_response))
+        let missing = Address::new(addresses[0].base(), MsgId::from([0; 12]));
+
+        let mut requested = addresses.clone();
+        requested.push(missing);
+        let received = subscriber.batch_receive(requested).await?;
+        assert_eq!(received.len(), 6);
+        for (i, (message, address)) in received.iter().zip(&addresses).enumerate() {
+            let message = message.as_ref().expect("message should be received");
+            assert_eq!(message.address(), *address);
+            assert!(!message.is_orphan());
+            assert_eq!(message.public_payload(), Some(&[i as u8][..]));
+        }
+        assert!(matches!(received[5], Err(Error::Transport(address, _, _)) if address == missing));
+        assert_eq!(subscriber.sync().await?, 0);
+        Ok(())
     }
 
-    /// Create and send a new Keyload message for all participants, updating the specified branch to
-    /// grant all known subscribers read permissions.
-    ///
-    /// # Arguments
-    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
-    pub async fn send_keyload_for_all<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
-    where
-        Top: Into<Topic> + Clone,
-    {
-        let topic = topic.into();
-        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
-        if !permission.is_admin() {
-            return Err(Error::WrongRole(
-                "Admin",
-                permission.identifier().clone(),
-                "send a keyload",
-            ));
-        }
-        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
-        let subscribers: Vec<Permissioned<Identifier>> = self
-            .subscribers()
-            .map(|s| {
-                if s == permission.identifier() {
-                    Permissioned::Admin(s.clone())
-                } else {
-                    Permissioned::Read(s.clone())
-                }
-            })
-            .collect();
-        self.send_keyload(
-            topic,
-            // Alas, must collect to release the &self immutable borrow
-            subscribers.iter().map(Permissioned::as_ref),
-            psks,
-        )
-        .await
+    #[tokio::test]
+    async fn revoked_psks_lose_access_after_rekey() -> Result<()> {
+        let psk = Psk::from_seed("revoked pre shared key");
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.add_psk(psk);
+        let mut reader = User::builder()
+            .with_transport(author.transport().clone())
+            .with_psk(psk.to_pskid(), psk)
+            .build();
+        reader.receive_message(announcement_address).await?;
+        let psk_subscription = reader.subscribe_with_psk(psk).await?;
+        author.receive_message(psk_subscription.address()).await?;
+        let mut observer = User::builder()
+            .with_identity(Ed25519::from_seed("observer"))
+            .with_transport(author.transport().clone())
+            .build();
+        observer.receive_message(announcement_address).await?;
+        let readonly_subscription = observer.subscribe_readonly().await?;
+        author.receive_message(readonly_subscription.address()).await?;
+
+        let base_branch = Topic::from(BASE_BRANCH);
+        let psk_subscriber_id = Identity::from(&psk).identifier().clone();
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+        assert!(author
+            .cursor_store()
+            .get_permission(&base_branch, &psk_subscriber_id)
+            .is_some());
+        let before = author.send_signed_packet(BASE_BRANCH, b"public", b"before").await?;
+        let messages = reader.fetch_next_messages().await?;
+        assert_eq!(messages[1].address(), before.address());
+        assert_eq!(messages[1].masked_payload(), Some(&b"before"[..]));
+        subscriber.sync().await?;
+
+        let unknown_psk = Psk::from_seed("unknown pre shared key");
+        assert!(matches!(
+            author.revoke_psk_and_rekey(unknown_psk.to_pskid(), &base_branch).await,
+            Err(Error::UnknownPsk(_))
+        ));
+        let keyload = author.revoke_psk_and_rekey(psk.to_pskid(), &base_branch).await?;
+        assert!(!author.state.psk_store.contains_key(&psk.to_pskid()));
+        let after = author.send_signed_packet(BASE_BRANCH, b"public", b"after").await?;
+
+        assert!(author
+            .cursor_store()
+            .get_permission(&base_branch, &psk_subscriber_id)
+            .is_none());
+
+        // The reader still holds the revoked PSK, but it no longer unlocks the new keyload
+        let message = reader.receive_message(keyload.address()).await?;
+        let keyload_content = message.as_keyload().unwrap();
+        assert!(!keyload_content.includes_psk(&psk.to_pskid()));
+        assert!(!keyload_content.includes_subscriber(&psk_subscriber_id));
+        assert!(keyload_content
+            .subscribers
+            .contains(&Permissioned::Read(observer.identifier().unwrap().clone())));
+        assert!(reader.receive_message(after.address()).await.is_err());
+
+        let messages = subscriber.fetch_next_messages().await?;
+        assert_eq!(messages[0].address(), keyload.address());
+        assert_eq!(messages[1].masked_payload(), Some(&b"after"[..]));
+        Ok(())
     }
 
-    /// Create and send a new Keyload message for all participants, updating the specified branch to
-    /// grant all known subscribers read and write permissions.
-    ///
-    /// # Arguments
-    /// * `topic`: The [`Topic`] of the branch the permissions will be updated for.
-    pub async fn send_keyload_for_all_rw<Top>(&mut self, topic: Top) -> Result<SendResponse<TSR>>
-    where
-        Top: Into<Topic> + Clone,
-    {
-        let topic = topic.into();
-        let permission = self.permission(&topic).ok_or(Error::NoCursor(topic.clone()))?;
-        if !permission.is_admin() {
-            return Err(Error::WrongRole(
-                "Admin",
-                permission.identifier().clone(),
-                "send a keyload",
-            ));
+    #[tokio::test]
+    async fn branch_ancestry_walks_up_to_the_base_branch() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (branch_a, branch_b, branch_c) = (Topic::from("A"), Topic::from("B"), Topic::from("C"));
+        author.new_branch(BASE_BRANCH, branch_a.clone()).await?;
+        author.new_branch(branch_a.clone(), branch_b.clone()).await?;
+        author.new_branch(branch_b.clone(), branch_c.clone()).await?;
+        subscriber.sync().await?;
+
+        let expected = vec![branch_b.clone(), branch_a.clone(), Topic::from(BASE_BRANCH)];
+        assert_eq!(author.branch_ancestry(&branch_c)?, expected);
+        assert_eq!(subscriber.branch_ancestry(&branch_c)?, expected);
+        assert_eq!(subscriber.branch_ancestry(&branch_a)?, vec![Topic::from(BASE_BRANCH)]);
+        assert!(subscriber.branch_ancestry(&Topic::from(BASE_BRANCH))?.is_empty());
+        assert!(matches!(
+            subscriber.branch_ancestry(&Topic::from("D")),
+            Err(Error::TopicNotFound(_))
+        ));
+
+        let backup = subscriber.backup("password").await?;
+        let restored = User::restore(backup, "password", subscriber.transport().clone()).await?;
+        assert_eq!(restored.branch_ancestry(&branch_c)?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mirrored_messages_are_sent_to_the_other_transport() -> Result<()> {
+        use lets::transport::Transport as _;
+
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        let branch = Topic::from("BRANCH");
+        let branch_announcement = author.new_branch(BASE_BRANCH, branch.clone()).await?;
+        let mut addresses = vec![keyload.address(), branch_announcement.address()];
+        for topic in [&Topic::from(BASE_BRANCH), &branch] {
+            for i in 0..2u8 {
+                let packet = author.send_signed_packet(topic.clone(), [i], b"masked").await?;
+                addresses.push(packet.address());
+            }
         }
-        let psks: Vec<PskId> = self.state.psk_store.keys().copied().collect();
-        let subscribers: Vec<Permissioned<Identifier>> = self
+
+        let mut mirror: Transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let topics = [Topic::from(BASE_BRANCH), branch.clone()];
+        // The announcement and the subscription are mirrored along with the branch messages
+        assert_eq!(subscriber.mirror_to_transport(mirror.clone(), &topics).await?, 8);
+        addresses.push(announcement_address);
+        addresses.push(subscription.address());
+        for address in &addresses {
+            assert_eq!(
+                mirror.recv_message(*address).await.unwrap(),
+                author.transport_mut().recv_message(*address).await.unwrap()
+            );
+        }
+
+        // The subscriber state is updated as if the messages had been synced
+        assert_eq!(subscriber.sync().await?, 0);
+        assert_eq!(subscriber.mirror_to_transport(mirror.clone(), &topics).await?, 0);
+        author.send_signed_packet(branch.clone(), b"public", b"masked").await?;
+        assert_eq!(subscriber.mirror_to_transport(mirror.clone(), &topics).await?, 1);
+
+        // Users can join and read the stream from the other transport alone
+        let mut mirrored_subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("subscriber"))
+            .with_transport(mirror.clone())
+            .build();
+        mirrored_subscriber.receive_message(announcement_address).await?;
+        assert_eq!(mirrored_subscriber.sync().await?, 7);
+        let mut mirrored_author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(mirror)
+            .build();
+        mirrored_author.receive_message(announcement_address).await?;
+        mirrored_author.receive_message(subscription.address()).await?;
+        assert!(mirrored_author
             .subscribers()
-            .map(|s| {
-                if s == permission.identifier() {
-                    Permissioned::Admin(s.clone())
-                } else {
-                    Permissioned::ReadWrite(s.clone(), PermissionDuration::Perpetual)
-                }
+            .any(|id| Some(id) == subscriber.identifier()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn default_topic_is_used_by_default_sends() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let branch = Topic::from("BRANCH");
+        author.new_branch(BASE_BRANCH, branch.clone()).await?;
+
+        assert!(matches!(
+            author.send_signed_packet_default(b"public", b"masked").await,
+            Err(Error::NoDefaultTopic)
+        ));
+        assert!(matches!(
+            author.set_default_topic(Topic::from("UNKNOWN")),
+            Err(Error::TopicNotFound(_))
+        ));
+        assert_eq!(author.default_topic(), None);
+        author.set_default_topic(branch.clone())?;
+        assert_eq!(author.default_topic(), Some(&branch));
+
+        // A copy of the author, on a copy of the transport, sending to the branch explicitly
+        let explicit_transport: Transport = Rc::new(RefCell::new(author.transport().borrow().clone()));
+        let mut explicit_author = User::restore_from_snapshot(author.snapshot(), explicit_transport);
+        let signed = author.send_signed_packet_default(b"public", b"masked").await?;
+        let tagged = author.send_tagged_packet_default(b"public", b"masked").await?;
+        let explicit_signed = explicit_author
+            .send_signed_packet(branch.clone(), b"public", b"masked")
+            .await?;
+        let explicit_tagged = explicit_author
+            .send_tagged_packet(branch.clone(), b"public", b"masked")
+            .await?;
+        assert_eq!(signed.address(), explicit_signed.address());
+        assert_eq!(tagged.address(), explicit_tagged.address());
+        assert_eq!(author.snapshot(), explicit_author.snapshot());
+
+        let messages = subscriber.fetch_next_messages().await?;
+        let received: Vec<Address> = messages[messages.len() - 2..].iter().map(Message::address).collect();
+        assert_eq!(received, vec![signed.address(), tagged.address()]);
+        assert!(messages
+            .iter()
+            .rev()
+            .take(2)
+            .all(|message| message.masked_payload() == Some(&b"masked"[..])));
+
+        let backup = author.backup("password").await?;
+        let restored = User::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.default_topic(), Some(&branch));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn keyload_keys_are_shared_by_its_readers_only() -> Result<()> {
+        use hashbrown::HashMap;
+        use lets::transport::Transport as _;
+
+        use crate::message::{keyload, signed_packet};
+
+        let psk = Psk::from_seed("keyload keys pre shared key");
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.add_psk(psk);
+        let mut reader = User::builder()
+            .with_transport(author.transport().clone())
+            .with_psk(psk.to_pskid(), psk)
+            .build();
+        reader.receive_message(announcement_address).await?;
+        let mut outsider = User::builder()
+            .with_identity(Ed25519::from_seed("outsider"))
+            .with_transport(author.transport().clone())
+            .build();
+        outsider.receive_message(announcement_address).await?;
+
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let before = subscriber.snapshot();
+        let (key, nonce) = subscriber.receive_keyload_keys(keyload.address()).await?;
+        assert_eq!(subscriber.snapshot(), before);
+        assert_ne!(key, [0; 32]);
+        assert_eq!(reader.receive_keyload_keys(keyload.address()).await?, (key, nonce));
+        assert!(matches!(
+            outsider.receive_keyload_keys(keyload.address()).await,
+            Err(Error::NotInKeyload(_))
+        ));
+        assert!(matches!(
+            subscriber.receive_keyload_keys(packet.address()).await,
+            Err(Error::UnexpectedMessageType {
+                expected: message_types::KEYLOAD,
+                got: message_types::SIGNED_PACKET
             })
-            .collect();
-        self.send_keyload(
-            topic,
-            // Alas, must collect to release the &self immutable borrow
-            subscribers.iter().map(Permissioned::as_ref),
-            psks,
-        )
-        .await
+        ));
+
+        // The key alone decrypts the keyload, and with it the packet linked to the keyload
+        let mut announcement_spongos = *subscriber
+            .stored_spongos(&announcement_address.relative())
+            .expect("subscriber keeps the announcement spongos");
+        let psk_store = HashMap::new();
+        let author_identifier = author.identifier().unwrap().clone();
+        let (_, mut keyload_spongos) = author
+            .transport_mut()
+            .recv_message(keyload.address())
+            .await
+            .unwrap()
+            .parse_header()
+            .await
+            .unwrap()
+            .unwrap(keyload::Unwrap::with_key(
+                &mut announcement_spongos,
+                &author_identifier,
+                &psk_store,
+                key,
+            ))
+            .await
+            .unwrap();
+        let (decrypted, _) = author
+            .transport_mut()
+            .recv_message(packet.address())
+            .await
+            .unwrap()
+            .parse_header()
+            .await
+            .unwrap()
+            .unwrap(signed_packet::Unwrap::new(&mut keyload_spongos))
+            .await
+            .unwrap();
+        let decrypted = Message::from_lets_message(packet.address(), decrypted);
+        assert_eq!(decrypted.masked_payload(), Some(&b"masked"[..]));
+
+        // Every keyload shares a fresh key
+        let rekey = author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (new_key, new_nonce) = subscriber.receive_keyload_keys(rekey.address()).await?;
+        assert_ne!(new_key, key);
+        assert_ne!(new_nonce, nonce);
+
+        // The subscriber can still process the messages as usual
+        let messages = subscriber.fetch_next_messages().await?;
+        assert_eq!(messages[0].address(), keyload.address());
+        assert_eq!(messages[1].masked_payload(), Some(&b"masked"[..]));
+        Ok(())
     }
 
-    /// Create a new [`MessageBuilder`] instance.
-    pub fn message<P: Default>(&mut self) -> MessageBuilder<P, T> {
-        MessageBuilder::new(self)
+    #[tokio::test]
+    async fn invariants_hold_after_sending_and_handling_messages() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        author.new_branch(BASE_BRANCH, "BRANCH").await?;
+        author.send_signed_packet("BRANCH", b"public", b"masked").await?;
+        subscriber.sync().await?;
+        assert_eq!(author.check_invariants(), Ok(()));
+        assert_eq!(subscriber.check_invariants(), Ok(()));
+        Ok(())
     }
 
-    /// Create and send a new Signed Packet message to the specified branch. The message will
-    /// contain a masked and an unmasked payload. The message will be signed by the [`User`]
-    /// [`Identity`] keys.
-    ///
-    /// # Arguments
-    /// * `topic`: The [`Topic`] of the branch to send the message to.
-    /// * `public_payload`: The unmasked payload of the message.
-    /// * `masked_payload`: The masked payload of the message.
-    pub async fn send_signed_packet<P, M, Top>(
-        &mut self,
-        topic: Top,
-        public_payload: P,
-        masked_payload: M,
-    ) -> Result<SendResponse<TSR>>
-    where
-        M: AsRef<[u8]>,
-        P: AsRef<[u8]>,
-        Top: Into<Topic>,
-    {
-        // Check conditions
-        let stream_address = self.stream_address().ok_or(Error::Setup(
-            "before sending a signed packet, the stream must be created",
-        ))?;
-        let user_id = self.identity().ok_or(Error::NoIdentity("send signed packet"))?;
-        let identifier = user_id.identifier().clone();
-        // Check Topic
-        let topic = topic.into();
-        // Check Permission
-        let permission = self
+    #[tokio::test]
+    async fn invariants_reject_topics_without_cursor_store_entry() {
+        let (mut author, _, _) = author_subscriber_fixture().await.unwrap();
+        author.state.topics.insert(Topic::from("BRANCH"));
+        assert!(author
+            .check_invariants()
+            .unwrap_err()
+            .contains("branch 'BRANCH' is known but has no entry in the cursor store"));
+    }
+
+    #[tokio::test]
+    async fn invariants_reject_cursor_store_entries_of_unknown_topics() {
+        let (mut author, _, _) = author_subscriber_fixture().await.unwrap();
+        let latest_link = author.get_latest_link(&Topic::from(BASE_BRANCH)).unwrap();
+        author
             .state
             .cursor_store
-            .get_permission(&topic, &identifier)
-            .ok_or(Error::NoCursor(topic.clone()))?;
-        if permission.is_readonly() {
-            return Err(Error::WrongRole(
-                "ReadWrite",
-                permission.identifier().clone(),
-                "send a signed packet",
-            ));
+            .set_latest_link(Topic::from("BRANCH"), latest_link);
+        assert!(author
+            .check_invariants()
+            .unwrap_err()
+            .contains("the cursor store has an entry for branch 'BRANCH', which is not known"));
+    }
+
+    #[tokio::test]
+    async fn invariants_reject_latest_links_to_unprocessed_messages() {
+        let (mut author, _, _) = author_subscriber_fixture().await.unwrap();
+        author.set_latest_link(Topic::from(BASE_BRANCH), MsgId::from([7; 12]));
+        assert!(author
+            .check_invariants()
+            .unwrap_err()
+            .contains("of branch 'BASE_BRANCH' is not a processed message"));
+    }
+
+    #[tokio::test]
+    async fn invariants_reject_cursors_beyond_the_latest_link() {
+        let (mut author, _, _) = author_subscriber_fixture().await.unwrap();
+        let first = author.send_signed_packet(BASE_BRANCH, b"first", b"").await.unwrap();
+        author.send_signed_packet(BASE_BRANCH, b"second", b"").await.unwrap();
+        author.set_latest_link(Topic::from(BASE_BRANCH), first.address().relative());
+        assert!(author
+            .check_invariants()
+            .unwrap_err()
+            .contains("in branch 'BASE_BRANCH' exceeds the cursor 2 of the branch latest link"));
+    }
+
+    #[tokio::test]
+    async fn invariants_allow_heartbeats_beyond_the_latest_link() -> Result<()> {
+        let (mut author, _, _) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"packet", b"").await?;
+        author.send_heartbeat(BASE_BRANCH).await?;
+        assert_eq!(
+            author.get_latest_link(&Topic::from(BASE_BRANCH)),
+            Some(packet.address().relative())
+        );
+        assert_eq!(author.check_invariants(), Ok(()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upgraded_subscribers_can_publish() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let mut reader = User::builder()
+            .with_identity(Ed25519::from_seed("reader"))
+            .with_transport(author.transport().clone())
+            .build();
+        reader.receive_message(announcement_address).await?;
+        for user in [&mut subscriber, &mut reader] {
+            let subscription = user.subscribe().await?;
+            author.receive_message(subscription.address()).await?;
         }
-        // Link message to latest message in branch
-        let link_to = self
-            .get_latest_link(&topic)
-            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
-        // Update own's cursor
-        let new_cursor = self.next_cursor(&topic)?;
-        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        subscriber.sync().await?;
+        reader.sync().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        let reader_id = reader.identifier().unwrap().clone();
+        assert!(matches!(
+            subscriber.send_signed_packet(BASE_BRANCH, b"public", b"masked").await,
+            Err(Error::NoCursor(_))
+        ));
 
-        // Prepare HDF and PCF
-        // Spongos must be copied because wrapping mutates it
-        let mut linked_msg_spongos = self
-            .state
-            .spongos_store
-            .get(&link_to)
-            .copied()
-            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
+        let read_write = Permissioned::ReadWrite(subscriber_id.clone(), PermissionDuration::Perpetual);
+        assert!(matches!(
+            author
+                .upgrade_permission(&base_branch, &subscriber_id, Permissioned::Read(subscriber_id.clone()))
+                .await,
+            Err(Error::Setup(_))
+        ));
+        let outsider_id = Identity::from(Ed25519::from_seed("outsider")).identifier().clone();
+        assert!(matches!(
+            author
+                .upgrade_permission(&base_branch, &outsider_id, Permissioned::Admin(outsider_id.clone()))
+                .await,
+            Err(Error::Setup(_))
+        ));
+        author
+            .upgrade_permission(&base_branch, &subscriber_id, read_write.clone())
+            .await?;
+        assert_eq!(
+            author.cursor_store().get_permission(&base_branch, &subscriber_id),
+            Some(&read_write)
+        );
+        assert!(matches!(
+            author
+                .upgrade_permission(&base_branch, &subscriber_id, read_write.clone())
+                .await,
+            Err(Error::Setup(_))
+        ));
 
-        let content = PCF::new_final_frame().with_content(signed_packet::Wrap::new(
-            &mut linked_msg_spongos,
-            &(*user_id),
-            public_payload.as_ref(),
-            masked_payload.as_ref(),
+        subscriber.sync().await?;
+        assert_eq!(subscriber.permission(&base_branch), Some(&read_write));
+        let packet = subscriber.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let message = author.receive_message(packet.address()).await?;
+        assert_eq!(message.public_payload(), Some(&b"public"[..]));
+
+        // The reader keeps its read permission, and can still read the branch
+        assert!(author.cursor_store().get_permission(&base_branch, &reader_id).is_none());
+        let messages = reader.fetch_next_messages().await?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].masked_payload(), Some(&b"masked"[..]));
+        assert!(reader.permission(&base_branch).is_none());
+        assert!(matches!(
+            reader.send_signed_packet(BASE_BRANCH, b"public", b"masked").await,
+            Err(Error::NoCursor(_))
         ));
-        let header = HDF::new(message_types::SIGNED_PACKET, new_cursor, identifier.clone(), &topic)
-            .with_linked_msg_address(link_to);
+        Ok(())
+    }
 
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
-            .await
-            .map_err(|e| Error::Wrapped("send signed packet", e))?;
+    #[tokio::test]
+    async fn spongos_states_are_accessible_by_link() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let spongos_count = subscriber.spongos_count();
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"").await?;
+        let link = packet.address().relative();
+        assert!(subscriber.get_spongos_for_address(&link).is_none());
+
+        subscriber.receive_message(packet.address()).await?;
+        assert_eq!(subscriber.spongos_count(), spongos_count + 1);
+        let spongos = subscriber
+            .get_spongos_for_address(&link)
+            .expect("spongos should be stored");
+        assert_eq!(Some(spongos), subscriber.state.spongos_store.get(&link));
+        assert_eq!(Some(spongos), author.get_spongos_for_address(&link));
+        assert!(subscriber
+            .get_spongos_for_address(&announcement_address.relative())
+            .is_some());
+        Ok(())
+    }
 
-        // Attempt to send message
-        let message_address = Address::new(stream_address.base(), rel_address);
-        if !self.transport.recv_message(message_address).await.is_err() {
-            return Err(Error::AddressUsed("signed packet", message_address));
+    #[tokio::test]
+    async fn announcement_acks_confirm_subscribers_in_the_keyload() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        assert!(matches!(
+            subscriber.send_announcement_ack(announcement_address).await,
+            Err(Error::Setup(_))
+        ));
+
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        subscriber.receive_message(keyload.address()).await?;
+        let ack = subscriber.send_announcement_ack(announcement_address).await?;
+        assert!(author.list_confirmed_subscribers(&Topic::from(BASE_BRANCH)).is_empty());
+        let message = author.receive_message(ack.address()).await?;
+        assert!(message.is_announcement_ack());
+        assert_eq!(
+            author.list_confirmed_subscribers(&Topic::from(BASE_BRANCH)),
+            vec![subscriber.identifier().unwrap().clone()]
+        );
+        assert!(author.list_confirmed_subscribers(&Topic::from("UNKNOWN")).is_empty());
+
+        let backup = author.backup("password").await?;
+        let restored = User::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(
+            restored.list_confirmed_subscribers(&Topic::from(BASE_BRANCH)),
+            author.list_confirmed_subscribers(&Topic::from(BASE_BRANCH))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn announcement_acks_with_a_bad_mac_fail_verification() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let mut outsider = User::builder()
+            .with_identity(Ed25519::from_seed("outsider"))
+            .with_transport(author.transport().clone())
+            .build();
+        outsider.receive_message(announcement_address).await?;
+
+        // The outsider is not part of the keyload, so it cannot derive its spongos state
+        let keyload = author.send_keyload_for_all(BASE_BRANCH).await?;
+        outsider.receive_message(keyload.address()).await?;
+        let ack = outsider.send_announcement_ack(announcement_address).await?;
+        let verified = author.verify_message(ack.address(), ack.response().clone()).await?;
+        assert!(!verified.is_valid());
+        assert!(matches!(
+            author.receive_message(ack.address()).await,
+            Err(Error::Unwrapping("announcement ack", _, _))
+        ));
+        assert!(author.list_confirmed_subscribers(&Topic::from(BASE_BRANCH)).is_empty());
+
+        // A valid ack verifies
+        subscriber.receive_message(keyload.address()).await?;
+        let ack = subscriber.send_announcement_ack(announcement_address).await?;
+        let verified = author.verify_message(ack.address(), ack.response().clone()).await?;
+        assert!(verified.is_valid());
+        Ok(())
+    }
+
+    #[cfg(feature = "cursor-csv")]
+    #[tokio::test]
+    async fn cursor_store_csv_round_trip_preserves_all_entries() -> Result<()> {
+        use crate::api::cursor_store::CursorStore;
+        use alloc::string::String;
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let branch = "sensors, \"room\" 1";
+        author.new_branch(BASE_BRANCH, branch).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        author
+            .send_keyload(
+                branch,
+                [Permissioned::ReadWrite(
+                    &subscriber_id,
+                    PermissionDuration::Unix(1_700_000_000),
+                )],
+                [],
+            )
+            .await?;
+        author.send_signed_packet(branch, b"public", b"masked").await?;
+
+        let mut csv = Vec::new();
+        author.export_cursor_store_csv(&mut csv)?;
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("topic,identifier_hex,permission_type,cursor,latest_link_hex\n"));
+        assert!(csv.contains("\"sensors, \"\"room\"\" 1\""));
+        assert!(csv.contains("read_write:unix:1700000000"));
+        let entries = author.cursor_store().to_vec();
+        assert_eq!(csv.lines().count(), entries.len() + 1);
+
+        // Import into a copy of the author whose cursors have been rolled back
+        let mut copy = User::restore_from_snapshot(author.snapshot(), author.transport().clone());
+        let rolled_back = entries
+            .iter()
+            .map(|(topic, permission, _)| (topic.clone(), permission.clone(), 0))
+            .collect();
+        copy.state.cursor_store = CursorStore::from_vec(rolled_back, copy.state.cursor_store.latest_links());
+        let before = copy.snapshot();
+        let unknown_permission = csv.replace("admin", "owner");
+        assert!(matches!(
+            copy.import_cursor_store_csv(&mut unknown_permission.as_bytes()),
+            Err(Error::InvalidCursorCsv(_, "unknown permission_type"))
+        ));
+        let changed_permission = csv.replace("read_write:unix:1700000000", "admin");
+        assert!(matches!(
+            copy.import_cursor_store_csv(&mut changed_permission.as_bytes()),
+            Err(Error::InvalidCursorCsv(_, "permission does not match the known one"))
+        ));
+        let stranger = Identity::from(Ed25519::from_seed("stranger")).identifier().to_string();
+        let unknown_identifier = csv.replace(&subscriber_id.to_string(), &stranger);
+        assert!(matches!(
+            copy.import_cursor_store_csv(&mut unknown_identifier.as_bytes()),
+            Err(Error::InvalidCursorCsv(_, "unknown identifier"))
+        ));
+        let latest_link = author.get_latest_link(&Topic::from(branch)).unwrap().to_string();
+        let unknown_link = csv.replace(&latest_link, &MsgId::from([7; 12]).to_string());
+        assert!(matches!(
+            copy.import_cursor_store_csv(&mut unknown_link.as_bytes()),
+            Err(Error::InvalidCursorCsv(_, "unknown latest_link_hex"))
+        ));
+        assert_eq!(copy.snapshot(), before);
+
+        let advanced = entries.iter().filter(|(_, _, cursor)| *cursor > 0).count();
+        assert_eq!(copy.import_cursor_store_csv(&mut csv.as_bytes())?, advanced);
+        assert_eq!(copy.cursor_store().to_vec(), entries);
+        let mut exported = Vec::new();
+        copy.export_cursor_store_csv(&mut exported)?;
+        assert_eq!(String::from_utf8(exported).unwrap(), csv);
+        assert_eq!(copy.import_cursor_store_csv(&mut csv.as_bytes())?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_to_multiple_topics_advances_each_branch_in_order() -> Result<()> {
+        use hashbrown::HashSet;
+
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let topics: Vec<Topic> = [BASE_BRANCH, "room1", "room2", "room3", "room4"]
+            .iter()
+            .map(|topic| Topic::from(*topic))
+            .collect();
+        for topic in &topics[1..] {
+            author.new_branch(BASE_BRANCH, topic.clone()).await?;
         }
-        let send_response = self
-            .transport
-            .send_message(message_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send signed packet", e))?;
+        // The base branch holds one more message of the author than the other branches
+        author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        let author_id = author.identifier().unwrap().clone();
+        let cursors: Vec<usize> = topics
+            .iter()
+            .map(|topic| author.get_subscriber_cursor(topic, &author_id).unwrap())
+            .collect();
 
-        // If message has been sent successfully, commit message to stores
-        self.state
-            .cursor_store
-            .insert_cursor(&topic, permission.clone(), new_cursor);
-        self.store_spongos(rel_address, spongos, link_to);
-        // Update Branch Links
-        self.set_latest_link(topic, message_address.relative());
-        Ok(SendResponse::new(message_address, send_response))
+        let before = author.snapshot();
+        assert!(matches!(
+            author
+                .send_to_multiple_topics((b"public", b"masked"), [BASE_BRANCH, "UNKNOWN"])
+                .await,
+            Err(Error::NoCursor(_))
+        ));
+        assert_eq!(author.snapshot(), before);
+
+        let responses = author
+            .send_to_multiple_topics((b"reading", b"21.5"), topics.clone())
+            .await?;
+        assert_eq!(responses.len(), 5);
+        for ((response, topic), cursor) in responses.iter().zip(&topics).zip(cursors) {
+            assert_eq!(author.get_subscriber_cursor(topic, &author_id), Some(cursor + 1));
+            assert_eq!(
+                Some(response.address()),
+                author.get_linked_message_address(cursor + 1, &author_id, topic)
+            );
+            assert_eq!(author.get_latest_message_address(topic), Some(response.address()));
+        }
+        let addresses: HashSet<Address> = responses.iter().map(|response| response.address()).collect();
+        assert_eq!(addresses.len(), 5);
+
+        let received: HashSet<Address> = subscriber
+            .fetch_next_messages()
+            .await?
+            .iter()
+            .filter(|message| message.public_payload() == Some(&b"reading"[..]))
+            .map(Message::address)
+            .collect();
+        assert_eq!(received, addresses);
+        Ok(())
     }
 
-    /// Create and send a new Tagged Packet message to the specified branch. The message will
-    /// contain a masked and an unmasked payload.
-    ///
-    /// # Arguments
-    /// * `topic`: The [`Topic`] of the branch to send the message to.
-    /// * `public_payload`: The unmasked payload of the message.
-    /// * `masked_payload`: The masked payload of the message.
-    pub async fn send_tagged_packet<P, M, Top>(
-        &mut self,
-        topic: Top,
-        public_payload: P,
-        masked_payload: M,
-    ) -> Result<SendResponse<TSR>>
-    where
-        M: AsRef<[u8]>,
-        P: AsRef<[u8]>,
-        Top: Into<Topic>,
-    {
-        // Check conditions
-        let stream_address = self.stream_address().ok_or(Error::Setup(
-            "before sending a tagged packet, the stream must be created",
-        ))?;
-        let user_id = self.identity().ok_or(Error::NoIdentity("send tagged packet"))?;
-        let identifier = user_id.identifier().clone();
-        // Check Topic
-        let topic = topic.into();
-        // Check Permission
-        let permission = self
-            .state
-            .cursor_store
-            .get_permission(&topic, &identifier)
-            .ok_or(Error::NoCursor(topic.clone()))?;
-        if permission.is_readonly() {
-            return Err(Error::WrongRole(
-                "ReadWrite",
-                permission.identifier().clone(),
-                "send a tagged packet",
-            ));
+    #[tokio::test]
+    async fn truncate_branch_history_keeps_the_checkpoint_state() -> Result<()> {
+        let (mut author, mut subscriber, announcement_address) = author_subscriber_fixture().await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        let mut packets = Vec::new();
+        for i in 0..20u8 {
+            packets.push(author.send_signed_packet(BASE_BRANCH, [i], b"masked").await?);
         }
-        // Link message to latest message in branch
-        let link_to = self
-            .get_latest_link(&topic)
-            .ok_or_else(|| Error::TopicNotFound(topic.clone()))?;
+        for packet in &packets[..15] {
+            subscriber.receive_message(packet.address()).await?;
+        }
+        let author_id = author.identifier().unwrap().clone();
+        assert_eq!(subscriber.get_subscriber_cursor(&base_branch, &author_id), Some(15));
+        assert!(matches!(
+            subscriber.truncate_branch_history(&Topic::from("UNKNOWN"), 15),
+            Err(Error::TopicNotFound(_))
+        ));
 
-        // Update own's cursor
-        let new_cursor = self.next_cursor(&topic)?;
-        let rel_address = MsgId::gen(stream_address.base(), &identifier, &topic, new_cursor);
+        let stored = subscriber.spongos_count();
+        assert_eq!(subscriber.truncate_branch_history(&base_branch, 15)?, 14);
+        assert_eq!(subscriber.spongos_count(), stored - 14);
+        assert!(subscriber
+            .get_spongos_for_address(&announcement_address.relative())
+            .is_some());
+        assert!(packets[..14].iter().all(|packet| subscriber
+            .get_spongos_for_address(&packet.address().relative())
+            .is_none()));
+        assert!(subscriber
+            .get_spongos_for_address(&packets[14].address().relative())
+            .is_some());
+        assert_eq!(subscriber.truncate_branch_history(&base_branch, 15)?, 0);
+
+        // The message at cursor 16 is linked to the one at cursor 15, whose state is kept
+        let message = subscriber.receive_message(packets[15].address()).await?;
+        assert!(message.is_signed_packet());
+        assert_eq!(message.public_payload(), Some(&[15][..]));
+        Ok(())
+    }
 
-        // Prepare HDF and PCF
-        // Spongos must be copied because wrapping mutates it
-        let mut linked_msg_spongos = self
+    #[tokio::test]
+    async fn stream_links_are_learnt_by_subscribers() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let mut remote_author = User::builder()
+            .with_identity(Ed25519::from_seed("remote author"))
+            .with_transport(author.transport().clone())
+            .build();
+        let remote_announcement = remote_author.create_stream("REMOTE").await?;
+        let remote_stream_address = remote_announcement.address();
+
+        let base_branch = Topic::from(BASE_BRANCH);
+        assert_eq!(author.get_linked_stream(&base_branch), None);
+        let link = author.connect_streams(&base_branch, remote_stream_address).await?;
+        assert_eq!(author.get_linked_stream(&base_branch), Some(remote_stream_address));
+        assert_eq!(author.get_latest_message_address(&base_branch), Some(link.address()));
+
+        let messages = subscriber.fetch_next_messages().await?;
+        let stream_link = messages.last().unwrap();
+        assert_eq!(stream_link.address(), link.address());
+        assert_eq!(
+            stream_link.as_stream_link().map(|link| link.remote_stream_address),
+            Some(remote_stream_address)
+        );
+        assert_eq!(subscriber.get_linked_stream(&base_branch), Some(remote_stream_address));
+        assert_eq!(subscriber.get_linked_stream(&Topic::from("UNKNOWN")), None);
+
+        // Subsequent messages are linked to the stream link
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        assert!(subscriber.receive_message(packet.address()).await?.is_signed_packet());
+
+        let backup = subscriber.backup("password").await?;
+        let restored = User::restore(backup, "password", subscriber.transport().clone()).await?;
+        assert_eq!(restored.get_linked_stream(&base_branch), Some(remote_stream_address));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn idempotent_signed_packets_are_sent_once() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let key = [7; 32];
+        let sent = author
+            .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &key)
+            .await?;
+        let message_count = author.transport().borrow().message_count();
+        let retried = author
+            .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &key)
+            .await?;
+        assert_eq!(retried, sent);
+        assert_eq!(author.transport().borrow().message_count(), message_count);
+
+        // The key is bound to the branch and payloads of the packet
+        assert!(matches!(
+            author
+                .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"other", &key)
+                .await,
+            Err(Error::IdempotencyKeyReused(address)) if address == sent.address()
+        ));
+        assert_eq!(author.transport().borrow().message_count(), message_count);
+
+        let other = author
+            .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &[8; 32])
+            .await?;
+        assert_ne!(other.address(), sent.address());
+        assert_eq!(author.transport().borrow().message_count(), message_count + 1);
+        let received: Vec<Address> = subscriber
+            .fetch_next_messages()
+            .await?
+            .iter()
+            .map(Message::address)
+            .collect();
+        assert_eq!(received, vec![sent.address(), other.address()]);
+
+        let backup = author.backup("password").await?;
+        let mut restored = User::restore(backup, "password", author.transport().clone()).await?;
+        // Transport responses are not backed up, but the packet is still not sent again
+        assert!(matches!(
+            restored
+                .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &key)
+                .await,
+            Err(Error::IdempotentResponseMissing(address)) if address == sent.address()
+        ));
+        assert_eq!(restored.transport().borrow().message_count(), message_count + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_the_latest_idempotency_keys_are_kept() -> Result<()> {
+        let (mut author, _, _) = author_subscriber_fixture().await?;
+        for n in 0..=IDEMPOTENCY_KEYS_KEPT {
+            let mut key = [0; 32];
+            key[..8].copy_from_slice(&(n as u64).to_be_bytes());
+            author
+                .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &key)
+                .await?;
+        }
+        assert_eq!(author.state.sent_idempotency_keys.len(), IDEMPOTENCY_KEYS_KEPT);
+
+        // The oldest key was pruned, so its packet is sent again
+        let message_count = author.transport().borrow().message_count();
+        author
+            .send_signed_packet_idempotent(BASE_BRANCH, b"public", b"masked", &[0; 32])
+            .await?;
+        assert_eq!(author.transport().borrow().message_count(), message_count + 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn branch_creation_times_follow_the_order_branches_were_created() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (branch_a, branch_b) = (Topic::from("A"), Topic::from("B"));
+        let before = unix_time_now().expect("system clock is available");
+        author.new_branch(BASE_BRANCH, branch_a.clone()).await?;
+        author.new_branch(BASE_BRANCH, branch_b.clone()).await?;
+        let after = unix_time_now().expect("system clock is available");
+        subscriber.sync().await?;
+
+        let created_a = author
+            .get_branch_creation_time(&branch_a)
+            .expect("branch A creation time");
+        let created_b = author
+            .get_branch_creation_time(&branch_b)
+            .expect("branch B creation time");
+        assert!(before <= created_a && created_a <= created_b && created_b <= after);
+        assert!(author.get_branch_creation_time(&Topic::from(BASE_BRANCH)).unwrap() <= created_a);
+        assert!(subscriber.get_branch_creation_time(&branch_b).is_some());
+        assert_eq!(author.get_branch_creation_time(&Topic::from("C")), None);
+
+        // Times are recorded once per branch, and kept in backups
+        author
             .state
-            .spongos_store
-            .get(&link_to)
-            .copied()
-            .ok_or(Error::MessageMissing(link_to, "spongos store"))?;
-        let content = PCF::new_final_frame().with_content(tagged_packet::Wrap::new(
-            &mut linked_msg_spongos,
-            public_payload.as_ref(),
-            masked_payload.as_ref(),
+            .branch_creation_times
+            .insert(branch_b.clone(), created_a + 60);
+        author.new_branch(BASE_BRANCH, Topic::from("C")).await?;
+        assert_eq!(author.get_branch_creation_time(&branch_b), Some(created_a + 60));
+        let backup = author.backup("password").await?;
+        let restored = User::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.get_branch_creation_time(&branch_a), Some(created_a));
+        assert_eq!(restored.get_branch_creation_time(&branch_b), Some(created_a + 60));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn data_producers_are_granted_write_permissions() -> Result<()> {
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let mut auditor = User::builder()
+            .with_identity(Ed25519::from_seed("auditor"))
+            .with_transport(author.transport().clone())
+            .build();
+        auditor.receive_message(announcement).await?;
+
+        let subscription = subscriber.subscribe().await?;
+        let auditor_subscription = auditor.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.receive_message(auditor_subscription.address()).await?;
+
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        let auditor_id = auditor.identifier().unwrap().clone();
+        assert_eq!(author.assign_capability(&subscriber_id, Capability::DataProducer), None);
+        assert_eq!(author.assign_capability(&auditor_id, Capability::Auditor), None);
+
+        author.send_keyload_for_capabilities(BASE_BRANCH).await?;
+        let base_branch = Topic::from(BASE_BRANCH);
+        assert!(matches!(
+            author.state.cursor_store.get_permission(&base_branch, &subscriber_id),
+            Some(Permissioned::ReadWrite(..))
         ));
-        let header = HDF::new(message_types::TAGGED_PACKET, new_cursor, identifier.clone(), &topic)
-            .with_linked_msg_address(link_to);
+        assert!(author
+            .state
+            .cursor_store
+            .get_permission(&base_branch, &auditor_id)
+            .is_none());
 
-        // Wrap message
-        let (transport_msg, spongos) = LetsMessage::new(header, content)
-            .wrap()
-            .await
-            .map_err(|e| Error::Wrapped("send tagged packet", e))?;
+        subscriber.sync().await?;
+        assert!(matches!(
+            subscriber.permission(&base_branch),
+            Some(Permissioned::ReadWrite(..))
+        ));
+        subscriber.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+        // Subscribers holding other capabilities are kept in the keyload
+        let messages = auditor.fetch_next_messages().await?;
+        assert_eq!(messages.last().unwrap().masked_payload(), Some(&b"masked"[..]));
+
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.capability(&subscriber_id), Some(Capability::DataProducer));
+        assert_eq!(restored.capability(&auditor_id), Some(Capability::Auditor));
+        Ok(())
+    }
 
-        // Attempt to send message
-        let message_address = Address::new(stream_address.base(), rel_address);
-        if !self.transport.recv_message(message_address).await.is_err() {
-            return Err(Error::AddressUsed("tagged packet", message_address));
-        }
-        let send_response = self
-            .transport
-            .send_message(message_address, transport_msg)
-            .await
-            .map_err(|e| Error::Transport(stream_address, "send tagged packet", e))?;
+    #[tokio::test]
+    async fn expired_ephemeral_subscribers_are_removed() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        assert!(matches!(subscriber.subscribe_ephemeral(0).await, Err(Error::Setup(_))));
+        let (subscription, token) = subscriber.subscribe_ephemeral(60).await?;
+        let subscriber_id = subscriber.identifier().unwrap().clone();
+        assert_eq!(token.subscriber(), &subscriber_id);
+
+        let message = author.receive_message(subscription.address()).await?;
+        assert_eq!(
+            message.header().message_type(),
+            message_types::SUBSCRIPTION_WITH_OPTIONS
+        );
+        assert_eq!(message.as_subscription().unwrap().ttl_seconds, Some(60));
+        assert!(!message.as_subscription().unwrap().readonly);
+        let expiring: Vec<_> = author.expiring_subscribers().collect();
+        assert_eq!(expiring.len(), 1);
+        assert_eq!(expiring[0].id, subscriber_id);
+        let expires_at = expiring[0].expires_at;
+
+        let backup = author.backup("password").await?;
+        let restored = User::<Transport>::restore(backup, "password", author.transport().clone()).await?;
+        assert_eq!(restored.expiring_subscribers().collect::<Vec<_>>(), expiring);
+
+        assert_eq!(author.expire_subscriptions(expires_at - 1), 0);
+        assert!(author.subscribers().any(|id| id == &subscriber_id));
+        assert_eq!(author.expire_subscriptions(expires_at), 1);
+        assert!(author.subscribers().next().is_none());
+        assert_eq!(author.expiring_subscribers().count(), 0);
+        Ok(())
+    }
 
-        // If message has been sent successfully, commit message to stores
-        self.state
-            .cursor_store
-            .insert_cursor(&topic, permission.clone(), new_cursor);
-        self.store_spongos(rel_address, spongos, link_to);
-        // Update Branch Links
-        self.set_latest_link(topic, rel_address);
-        Ok(SendResponse::new(message_address, send_response))
+    #[tokio::test]
+    async fn redeemed_unsubscribe_tokens_remove_the_ephemeral_subscriber() -> Result<()> {
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let mut other = User::builder()
+            .with_identity(Ed25519::from_seed("other"))
+            .with_transport(author.transport().clone())
+            .build();
+        other.receive_message(announcement).await?;
+        let (subscription, token) = subscriber.subscribe_ephemeral(60).await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all_rw(BASE_BRANCH).await?;
+        subscriber.sync().await?;
+
+        assert!(matches!(token.clone().redeem(&mut other).await, Err(Error::Setup(_))));
+        token.redeem(&mut subscriber).await?;
+        author.sync().await?;
+        assert!(author.subscribers().next().is_none());
+        assert_eq!(author.expiring_subscribers().count(), 0);
+        Ok(())
     }
-}
 
-#[async_trait(?Send)]
-impl ContentSizeof<State> for sizeof::Context {
-    async fn sizeof(&mut self, user_state: &State) -> SpongosResult<&mut Self> {
-        self.mask(Maybe::new(user_state.user_id.as_ref()))?
-            .mask(Maybe::new(user_state.stream_address.as_ref()))?
-            .mask(Maybe::new(user_state.author_identifier.as_ref()))?
-            .mask(&user_state.base_branch)?;
+    #[tokio::test]
+    async fn rekey_all_branches_continues_past_failing_branches() -> Result<()> {
+        use lets::transport::Transport as _;
+
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        let (branch_a, branch_b) = (Topic::from("A"), Topic::from("B"));
+        author.new_branch(BASE_BRANCH, branch_a.clone()).await?;
+        author.new_branch(BASE_BRANCH, branch_b.clone()).await?;
+        assert_eq!(author.rekey_all_branches().await?.len(), 3);
+
+        // Occupy the address of the next keyload of the second branch to rekey
+        let author_id = author.identifier().unwrap().clone();
+        let occupied = Address::new(
+            announcement.base(),
+            MsgId::gen(
+                announcement.base(),
+                &author_id,
+                &branch_b,
+                author.next_cursor(&branch_b)?,
+            ),
+        );
+        author
+            .transport_mut()
+            .send_message(occupied, TransportMessage::new(vec![0; 32]))
+            .await
+            .unwrap();
+
+        let error = match author.rekey_all_branches().await {
+            Err(Error::PartialRekey(error)) => error,
+            other => panic!(
+                "expected a partial rekey, got {:?}",
+                other.map(|responses| responses.len())
+            ),
+        };
+        let rekeyed: Vec<&Topic> = error.successes.iter().map(|(topic, _)| topic).collect();
+        assert_eq!(rekeyed, vec![&branch_a, &Topic::from(BASE_BRANCH)]);
+        assert_eq!(error.failures.len(), 1);
+        assert_eq!(error.failures[0].0, branch_b);
+        assert!(matches!(error.failures[0].1, Error::AddressUsed(..)));
+        Ok(())
+    }
 
-        let amount_spongos = user_state.spongos_store.len();
-        self.mask(Size::new(amount_spongos))?;
-        for (address, spongos) in &user_state.spongos_store {
-            self.mask(address)?.mask(spongos)?;
+    #[tokio::test]
+    async fn cloned_branches_are_bootstrapped_with_the_latest_packets() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (source, clone) = (Topic::from("SOURCE"), Topic::from("CLONE"));
+        author.new_branch(BASE_BRANCH, source.clone()).await?;
+        for i in 0..7u8 {
+            author.send_signed_packet(source.clone(), [i], [i; 8]).await?;
         }
+        let source_link = author.get_latest_link(&source);
 
-        // Only keep topics that exist in cursor store, any others serve no purpose
-        let topics = user_state
-            .topics
-            .iter()
-            .filter(|t| user_state.cursor_store.get_latest_link(t).is_some());
-        let amount_topics = topics.clone().count();
-        self.mask(Size::new(amount_topics))?;
+        author.clone_branch_to_topic(&source, &clone).await?;
+        assert_eq!(author.get_latest_link(&source), source_link);
+        assert!(author.topics().any(|topic| topic == &clone));
 
-        for topic in topics {
-            self.mask(topic)?;
-            let latest_link = user_state
-                .cursor_store
-                .get_latest_link(topic)
-                .ok_or(SpongosError::InvalidAction(
-                    "calculate sizeof for topic latest link",
-                    topic.to_string(),
-                    "No Cursor".to_owned(),
-                ))?;
-            self.mask(&latest_link)?;
+        let packets = subscriber.receive_signed_packets_only(clone.clone()).await?;
+        let payloads: Vec<(Vec<u8>, Vec<u8>)> = packets
+            .into_iter()
+            .map(|(_, public_payload, masked_payload)| (public_payload, masked_payload))
+            .collect();
+        let expected: Vec<(Vec<u8>, Vec<u8>)> = (2..7u8).map(|i| (vec![i], vec![i; 8])).collect();
+        assert_eq!(payloads, expected);
 
-            let cursors: Vec<(&Permissioned<Identifier>, &usize)> = user_state
-                .cursor_store
-                .cursors_by_topic(topic)
-                .ok_or(SpongosError::InvalidAction(
-                    "get cursor for topic",
-                    topic.to_string(),
-                    "No Cursor".to_owned(),
-                ))?
-                .collect();
-            let amount_cursors = cursors.len();
-            self.mask(Size::new(amount_cursors))?;
-            for (subscriber, cursor) in cursors {
-                self.mask(subscriber)?.mask(Size::new(*cursor))?;
+        let packet = author.send_signed_packet(clone.clone(), b"after", b"clone").await?;
+        let message = subscriber.receive_message(packet.address()).await?;
+        assert_eq!(message.masked_payload(), Some(&b"clone"[..]));
+        Ok(())
+    }
+
+    #[cfg(feature = "timestamped-payloads")]
+    #[tokio::test]
+    async fn messages_are_filtered_by_their_embedded_timestamp() -> Result<()> {
+        extern crate std;
+        use core::time::Duration;
+        use std::time::UNIX_EPOCH;
+
+        use crate::TimestampedPublicPayload;
+
+        let (mut author, mut subscriber, announcement) = author_subscriber_fixture().await?;
+        let mut strict_subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("strict subscriber"))
+            .with_transport(author.transport().clone())
+            .build();
+        strict_subscriber.receive_message(announcement).await?;
+
+        for timestamp_unix_secs in [100, 200, 300] {
+            let public_payload = TimestampedPublicPayload {
+                timestamp_unix_secs,
+                data: b"public".to_vec(),
             }
+            .encode()?;
+            author
+                .send_signed_packet(BASE_BRANCH, public_payload, b"masked")
+                .await?;
         }
+        author.send_signed_packet(BASE_BRANCH, b"", b"untimestamped").await?;
+
+        let since = UNIX_EPOCH + Duration::from_secs(200);
+        let timestamps = |messages: Vec<Message>| -> Vec<Option<u64>> {
+            messages
+                .iter()
+                .map(|message| message.decode_timestamp().ok().flatten())
+                .collect()
+        };
+        let messages = subscriber.fetch_messages_since(since).await?;
+        assert_eq!(timestamps(messages), vec![Some(200), Some(300), None]);
+        let messages = strict_subscriber.fetch_messages_since_strict(since).await?;
+        assert_eq!(timestamps(messages), vec![Some(200), Some(300)]);
+        Ok(())
+    }
 
-        let subs = &user_state.subscribers;
-        let amount_subs = subs.len();
-        self.mask(Size::new(amount_subs))?;
-        for subscriber in subs {
-            self.mask(subscriber)?;
+    #[cfg(feature = "transport-options")]
+    #[tokio::test]
+    async fn transport_options_time_out_slow_requests() -> Result<()> {
+        use alloc::boxed::Box;
+        use core::time::Duration;
+
+        use async_trait::async_trait;
+        use lets::{error::Result as LetsResult, transport::retry::RetryConfig};
+
+        use crate::api::transport_options::TransportOptions;
+
+        /// Transport taking a fixed time to answer every request
+        #[derive(Clone)]
+        struct SlowTransport {
+            inner: Transport,
+            delay: Duration,
         }
 
-        let psks = user_state.psk_store.iter();
-        let amount_psks = psks.len();
-        self.mask(Size::new(amount_psks))?;
-        for (pskid, psk) in psks {
-            self.mask(pskid)?.mask(psk)?;
+        #[async_trait(?Send)]
+        impl lets::transport::Transport<'_> for SlowTransport {
+            type Msg = TransportMessage;
+            type SendResponse = TransportMessage;
+
+            async fn send_message(&mut self, address: Address, msg: TransportMessage) -> LetsResult<TransportMessage>
+            where
+                Self::Msg: 'async_trait,
+            {
+                tokio::time::sleep(self.delay).await;
+                self.inner.send_message(address, msg).await
+            }
+
+            async fn recv_messages(&mut self, address: Address) -> LetsResult<Vec<TransportMessage>> {
+                tokio::time::sleep(self.delay).await;
+                self.inner.recv_messages(address).await
+            }
         }
 
-        let lean = if user_state.lean { 1 } else { 0 };
-        self.mask(Uint8::new(lean))?;
+        let (mut author, subscriber, _) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
 
-        self.commit()?.squeeze(Mac::new(32))
+        let mut subscriber = subscriber.transfer_to_transport(SlowTransport {
+            inner: author.transport().clone(),
+            delay: Duration::from_millis(500),
+        });
+        subscriber.set_transport_options(TransportOptions {
+            timeout: Some(Duration::from_millis(100)),
+            retry: Some(RetryConfig::new(1, Duration::from_millis(10))),
+            log_requests: true,
+        });
+        let result = subscriber.receive_message(packet.address()).await;
+        assert!(matches!(result, Err(Error::Timeout(address, _, timeout))
+            if address == packet.address() && timeout == Duration::from_millis(100)));
+        // Copies of the transport used to fetch branches concurrently time out as well
+        assert_eq!(subscriber.parallel_sync(2).await?, 0);
+
+        subscriber.set_transport_options(TransportOptions {
+            timeout: Some(Duration::from_secs(2)),
+            ..Default::default()
+        });
+        let message = subscriber.receive_message(packet.address()).await?;
+        assert_eq!(message.as_signed_packet().unwrap().public_payload, b"public");
+        Ok(())
     }
-}
 
-#[async_trait(?Send)]
-impl<'a> ContentWrap<State> for wrap::Context<&'a mut [u8]> {
-    async fn wrap(&mut self, user_state: &mut State) -> SpongosResult<&mut Self> {
-        self.mask(Maybe::new(user_state.user_id.as_ref()))?
-            .mask(Maybe::new(user_state.stream_address.as_ref()))?
-            .mask(Maybe::new(user_state.author_identifier.as_ref()))?
-            .mask(&user_state.base_branch)?;
+    #[tokio::test]
+    async fn psk_store_can_be_imported_and_exported() -> Result<()> {
+        let mut user = User::builder().with_transport(bucket::Client::new()).build();
+        let mut psks: Vec<(PskId, Psk)> = (0..10)
+            .map(|i| {
+                let psk = Psk::from_seed(i.to_string());
+                (psk.to_pskid(), psk)
+            })
+            .collect();
+        assert_eq!(user.import_psk_store(psks.clone())?, 10);
+        assert_eq!(user.import_psk_store(psks[..5].to_vec())?, 0);
 
-        let amount_spongos = user_state.spongos_store.len();
-        self.mask(Size::new(amount_spongos))?;
-        for (address, spongos) in &user_state.spongos_store {
-            self.mask(address)?.mask(spongos)?;
-        }
+        psks.sort_by_key(|(pskid, _)| *pskid);
+        assert!(user.export_psk_store() == psks);
 
-        // Only keep topics that exist in cursor store, any others serve no purpose
-        let topics = user_state
-            .topics
-            .iter()
-            .filter(|t| user_state.cursor_store.get_latest_link(t).is_some());
-        let amount_topics = topics.clone().count();
-        self.mask(Size::new(amount_topics))?;
+        let psk = Psk::from_seed("mismatched psk");
+        let result = user.import_psk_store(vec![(psks[0].0, psk)]);
+        assert!(matches!(result, Err(Error::PskIdMismatch(pskid)) if pskid == psks[0].0));
+        assert_eq!(user.export_psk_store().len(), 10);
+        Ok(())
+    }
 
-        for topic in topics {
-            self.mask(topic)?;
-            let latest_link = user_state
-                .cursor_store
-                .get_latest_link(topic)
-                .ok_or(SpongosError::InvalidAction(
-                    "get latest link topic for wrap",
-                    topic.to_string(),
-                    "No latest link".to_owned(),
-                ))?;
-            self.mask(&latest_link)?;
+    #[tokio::test]
+    async fn send_hooks_are_called_around_dispatch() -> Result<()> {
+        extern crate std;
+        use alloc::sync::Arc;
+        use std::sync::Mutex;
+
+        use lets::transport::Transport as _;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let pre_events = events.clone();
+        let post_events = events.clone();
+        let transport = Rc::new(RefCell::new(bucket::Client::new()));
+        let mut author = User::builder()
+            .with_identity(Ed25519::from_seed("author"))
+            .with_transport(transport.clone())
+            .with_pre_send_hook(Arc::new(move |address: &Address, msg: &TransportMessage| {
+                pre_events.lock().unwrap().push(("pre", *address, msg.clone()));
+            }))
+            .with_post_send_hook(Arc::new(
+                move |address: &Address, msg: &TransportMessage, response: &TransportMessage| {
+                    assert_eq!(msg, response);
+                    post_events.lock().unwrap().push(("post", *address, msg.clone()));
+                },
+            ))
+            .build();
+        let announcement = author.create_stream(BASE_BRANCH).await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let sent = transport.borrow_mut().recv_message(packet.address()).await.unwrap();
+        let events = events.lock().unwrap();
+        let kinds: Vec<(&str, Address)> = events.iter().map(|(kind, address, _)| (*kind, *address)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("pre", announcement.address()),
+                ("post", announcement.address()),
+                ("pre", packet.address()),
+                ("post", packet.address()),
+            ]
+        );
+        assert_eq!(events[2].2, sent);
+        Ok(())
+    }
 
-            let cursors: Vec<(&Permissioned<Identifier>, &usize)> = user_state
-                .cursor_store
-                .cursors_by_topic(topic)
-                .ok_or(SpongosError::InvalidAction(
-                    "get cursor for topic",
-                    topic.to_string(),
-                    "No cursor found".to_owned(),
-                ))?
-                .collect();
-            let amount_cursors = cursors.len();
-            self.mask(Size::new(amount_cursors))?;
-            for (subscriber, cursor) in cursors {
-                self.mask(subscriber)?.mask(Size::new(*cursor))?;
-            }
-        }
+    #[tokio::test]
+    async fn paused_user_can_be_resumed_from_state_bytes() -> Result<()> {
+        use crate::api::paused_user::PausedUser;
 
-        let subs = &user_state.subscribers;
-        let amount_subs = subs.len();
-        self.mask(Size::new(amount_subs))?;
-        for subscriber in subs {
-            self.mask(subscriber)?;
-        }
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        author.send_signed_packet(BASE_BRANCH, b"first", b"masked").await?;
+        let snapshot = author.snapshot();
 
-        let psks = user_state.psk_store.iter();
-        let amount_psks = psks.len();
-        self.mask(Size::new(amount_psks))?;
-        for (pskid, psk) in psks {
-            self.mask(pskid)?.mask(psk)?;
+        let paused = author.pause().await?;
+        let state_bytes: Vec<u8> = paused.state_bytes().to_vec();
+        let transport = paused.transport().clone();
+        drop(paused);
+
+        let mut author: User<Transport> = PausedUser::from_state_bytes(state_bytes, transport).resume().await?;
+        assert_eq!(author.snapshot(), snapshot);
+        let packet = author.send_signed_packet(BASE_BRANCH, b"second", b"masked").await?;
+
+        let messages = subscriber.fetch_next_messages().await?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].address(), packet.address());
+        assert_eq!(messages[1].as_signed_packet().unwrap().public_payload, b"second");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn receive_announcement_rejects_other_message_types() -> Result<()> {
+        let (mut author, _, announcement_address) = author_subscriber_fixture().await?;
+        let packet = author.send_signed_packet(BASE_BRANCH, b"public", b"masked").await?;
+
+        let mut subscriber = User::builder()
+            .with_identity(Ed25519::from_seed("late subscriber"))
+            .with_transport(author.transport().clone())
+            .build();
+        let result = subscriber.receive_announcement(packet.address()).await;
+        assert!(matches!(
+            result,
+            Err(Error::UnexpectedMessageType { expected, got })
+                if expected == message_types::ANNOUNCEMENT && got == message_types::SIGNED_PACKET
+        ));
+        assert_eq!(subscriber.stream_address(), None);
+
+        let announcement = subscriber.receive_announcement(announcement_address).await?;
+        assert!(announcement.is_announcement());
+        assert_eq!(subscriber.stream_address(), Some(announcement_address));
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        assert!(author.subscribers().any(|s| s == subscriber.identifier().unwrap()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn topics_are_sorted_by_depth_and_creation() -> Result<()> {
+        let (mut author, mut subscriber, _) = author_subscriber_fixture().await?;
+        let subscription = subscriber.subscribe().await?;
+        author.receive_message(subscription.address()).await?;
+        author.send_keyload_for_all(BASE_BRANCH).await?;
+        let (branch_a, branch_b, branch_c) = (Topic::from("A"), Topic::from("B"), Topic::from("C"));
+        author.new_branch(BASE_BRANCH, branch_c.clone()).await?;
+        author.new_branch(BASE_BRANCH, branch_a.clone()).await?;
+        author.new_branch(branch_c.clone(), branch_b.clone()).await?;
+        subscriber.sync().await?;
+
+        // Branches created within the same second are sorted lexicographically
+        let base_branch = Topic::from(BASE_BRANCH);
+        for (topic, creation_time) in [
+            (&base_branch, 100),
+            (&branch_c, 200),
+            (&branch_a, 300),
+            (&branch_b, 300),
+        ] {
+            author.state.branch_creation_times.insert(topic.clone(), creation_time);
         }
 
-        let lean = if user_state.lean { 1 } else { 0 };
-        self.mask(Uint8::new(lean))?;
+        let expected = vec![(0, &base_branch), (1, &branch_a), (1, &branch_c), (2, &branch_b)];
+        assert_eq!(author.topics_by_depth(), expected);
+        assert_eq!(subscriber.topics_by_depth(), expected);
 
-        self.commit()?.squeeze(Mac::new(32))
+        let sorted = author.topics_sorted_by_creation();
+        assert_eq!(sorted.len(), 4);
+        assert_eq!(sorted[0], &base_branch);
+        assert_eq!(sorted[1], &branch_c);
+        assert_eq!(sorted[2..], [&branch_a, &branch_b]);
+        Ok(())
     }
 }
 
-#[async_trait(?Send)]
-impl<'a> ContentUnwrap<State> for unwrap::Context<&'a [u8]> {
-    async fn unwrap(&mut self, user_state: &mut State) -> SpongosResult<&mut Self> {
-        self.mask(Maybe::new(&mut user_state.user_id))?
-            .mask(Maybe::new(&mut user_state.stream_address))?
-            .mask(Maybe::new(&mut user_state.author_identifier))?
-            .mask(&mut user_state.base_branch)?;
+#[cfg(all(test, feature = "proptest"))]
+mod proptests {
+    extern crate std;
 
-        let mut amount_spongos = Size::default();
-        self.mask(&mut amount_spongos)?;
-        for _ in 0..amount_spongos.inner() {
-            let mut address = MsgId::default();
-            let mut spongos = Spongos::default();
-            self.mask(&mut address)?.mask(&mut spongos)?;
-            user_state.spongos_store.insert(address, spongos);
-        }
+    use alloc::vec::Vec;
+    use core::{convert::TryInto, future::Future, marker::PhantomData};
 
-        let mut amount_topics = Size::default();
-        self.mask(&mut amount_topics)?;
+    use hashbrown::HashSet;
+    use lets::{
+        address::{Address, MsgId},
+        id::{Ed25519, Identifier, Identity, PermissionDuration, Permissioned, Psk, PskId},
+        message::Topic,
+    };
+    use proptest::{collection, option, prelude::*, sample::Index};
+    use spongos::Spongos;
 
-        for _ in 0..amount_topics.inner() {
-            let mut topic = Topic::default();
-            self.mask(&mut topic)?;
-            let mut latest_link = MsgId::default();
-            self.mask(&mut latest_link)?;
+    use crate::api::{identity_marker::NoIdentity, spongos_retention::SpongosRetention, user::User};
 
-            user_state.topics.insert(topic.clone());
-            user_state.cursor_store.set_latest_link(topic.clone(), latest_link);
+    use super::State;
 
-            let mut amount_cursors = Size::default();
-            self.mask(&mut amount_cursors)?;
-            for _ in 0..amount_cursors.inner() {
-                let mut subscriber = Permissioned::default();
-                let mut cursor = Size::default();
-                self.mask(&mut subscriber)?.mask(&mut cursor)?;
-                user_state
-                    .cursor_store
-                    .insert_cursor(&topic, subscriber, cursor.inner());
-            }
-        }
+    type Branch = (Topic, MsgId, Vec<(Permissioned<Identifier>, usize)>);
 
-        let mut amount_subs = Size::default();
-        self.mask(&mut amount_subs)?;
-        for _ in 0..amount_subs.inner() {
-            let mut subscriber = Identifier::default();
-            self.mask(&mut subscriber)?;
-            user_state.subscribers.insert(subscriber);
-        }
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
 
-        let mut amount_psks = Size::default();
-        self.mask(&mut amount_psks)?;
-        for _ in 0..amount_psks.inner() {
-            let mut pskid = PskId::default();
-            let mut psk = Psk::default();
-            self.mask(&mut pskid)?.mask(&mut psk)?;
-            user_state.psk_store.insert(pskid, psk);
-        }
+    fn identity() -> impl Strategy<Value = Identity> {
+        "[a-z0-9]{1,16}".prop_map(|seed| Ed25519::from_seed(seed).into())
+    }
 
-        let mut lean = Uint8::new(0);
-        self.mask(&mut lean)?;
-        user_state.lean = lean.inner() == 1;
+    fn identifier() -> impl Strategy<Value = Identifier> {
+        identity().prop_map(|identity| identity.identifier().clone())
+    }
 
-        self.commit()?.squeeze(Mac::new(32))
+    fn topic() -> impl Strategy<Value = Topic> {
+        "[a-zA-Z0-9_]{1,32}".prop_map(Topic::from)
     }
-}
 
-impl<T> Debug for User<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
-        write!(
-            f,
-            "\n* identifier: <{:?}>\n* topic: {}\n{:?}\n* PSKs: \n{}\n* messages:\n{}\n* lean: {}\n",
-            self.identifier(),
-            self.base_branch(),
-            self.state.cursor_store,
-            self.state
-                .psk_store
-                .keys()
-                .map(|pskid| format!("\t<{:?}>\n", pskid))
-                .collect::<String>(),
-            self.state
-                .spongos_store
-                .keys()
-                .map(|key| format!("\t<{}>\n", key))
-                .collect::<String>(),
-            self.state.lean
+    fn msgid() -> impl Strategy<Value = MsgId> {
+        any::<[u8; 12]>().prop_map(MsgId::new)
+    }
+
+    fn address() -> impl Strategy<Value = Address> {
+        (collection::vec(any::<u8>(), 40), any::<[u8; 12]>()).prop_map(|(appaddr, msgid)| {
+            let appaddr: [u8; 40] = appaddr.try_into().unwrap();
+            Address::new(appaddr, msgid)
+        })
+    }
+
+    fn spongos() -> impl Strategy<Value = Spongos> {
+        collection::vec(any::<u8>(), 0..64).prop_map(|bytes| {
+            let mut spongos = Spongos::init();
+            spongos.absorb(bytes);
+            spongos.commit();
+            spongos
+        })
+    }
+
+    fn permission() -> impl Strategy<Value = Permissioned<Identifier>> {
+        prop_oneof![
+            identifier().prop_map(Permissioned::Read),
+            identifier().prop_map(|id| Permissioned::ReadWrite(id, PermissionDuration::Perpetual)),
+            identifier().prop_map(Permissioned::Admin),
+        ]
+    }
+
+    fn branch() -> impl Strategy<Value = Branch> {
+        (
+            topic(),
+            msgid(),
+            collection::vec((permission(), 0..u32::MAX as usize), 0..4),
         )
     }
-}
 
-/// An streams user equality is determined by the equality of its state. The major consequence of
-/// this fact is that two users with the same identity but different transport configurations are
-/// considered equal
-impl<T> PartialEq for User<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.state == other.state
+    prop_compose! {
+        fn state()(
+            user_id in option::of(identity()),
+            stream_address in option::of(address()),
+            author_identifier in option::of(identifier()),
+            base_branch in topic(),
+            spongos_store in collection::vec((msgid(), spongos()), 0..8),
+            branches in collection::vec(branch(), 0..4),
+            subscribers in collection::vec(identifier(), 0..4),
+            psk_store in collection::vec((any::<[u8; 16]>(), any::<[u8; 32]>()), 0..4),
+            retention in prop_oneof![
+                Just(SpongosRetention::All),
+                Just(SpongosRetention::Lean),
+                (0..16usize).prop_map(SpongosRetention::LastN),
+            ],
+        ) -> State {
+            let mut state = State {
+                user_id,
+                stream_address,
+                author_identifier,
+                base_branch,
+                retention,
+                ..State::default()
+            };
+            state.spongos_store.extend(spongos_store);
+            // Changing the permission of a publisher moves its cursor, so each publisher is only
+            // given a single permission to keep the generated state stable across restores
+            let mut publishers = HashSet::new();
+            for (topic, latest_link, cursors) in branches {
+                state.cursor_store.set_latest_link(topic.clone(), latest_link);
+                for (permission, cursor) in cursors {
+                    if publishers.insert(permission.identifier().clone()) {
+                        state.cursor_store.insert_cursor(&topic, permission, cursor);
+                    }
+                }
+                state.topics.insert(topic);
+            }
+            state.subscribers.extend(subscribers);
+            state
+                .psk_store
+                .extend(psk_store.into_iter().map(|(pskid, psk)| (PskId::new(pskid), Psk::new(psk))));
+            state
+        }
     }
-}
 
-/// An streams user equality is determined by the equality of its state. The major consequence of
-/// this fact is that two users with the same identity but different transport configurations are
-/// considered equal
-impl<T> Eq for User<T> {}
+    fn user(state: State) -> User<(), NoIdentity> {
+        User {
+            transport: (),
+            state,
+            identity_marker: PhantomData,
+            send_hooks: Default::default(),
+            idempotent_responses: Default::default(),
+            #[cfg(feature = "transport-options")]
+            transport_options: Default::default(),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn restore_recovers_backed_up_state(state in state(), pwd in "\\PC{0,32}") {
+            let mut user = user(state);
+            let backup = block_on(user.backup(&pwd)).unwrap();
+            let restored = block_on(User::<(), NoIdentity>::restore(&backup, &pwd, ())).unwrap();
+            prop_assert_eq!(restored, user);
+        }
+
+        #[test]
+        fn restore_rejects_corrupted_backup(state in state(), position in any::<Index>(), flip in 1..=u8::MAX) {
+            let mut user = user(state);
+            let mut backup = block_on(user.backup("password")).unwrap();
+            let position = position.index(backup.len());
+            backup[position] ^= flip;
+            prop_assert!(block_on(User::<(), NoIdentity>::restore(&backup, "password", ())).is_err());
+        }
+    }
+}
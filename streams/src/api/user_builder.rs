@@ -1,12 +1,16 @@
 // Rust
 use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+// 3rd-party
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 // IOTA
 
 // Streams
 use lets::{
     address::Address,
-    id::{Identity, Psk, PskId},
+    id::{Ed25519, Identity, Psk, PskId},
     message::TransportMessage,
     transport::Transport,
 };
@@ -15,10 +19,21 @@ use lets::{
 use lets::transport::utangle;
 
 // Local
-use crate::{api::user::User, Result};
+use crate::{
+    api::{
+        identity_marker::{HasIdentity, NoIdentity},
+        send_hook::{PostSendHook, PreSendHook, SendHooks},
+        spongos_retention::SpongosRetention,
+        user::User,
+    },
+    Result,
+};
 
 /// Builder instance for a Streams [`User`].
-pub struct UserBuilder<T> {
+///
+/// The `I` [marker](`crate::IdentityMarker`) tracks whether an [`Identity`] has been provided, and is
+/// carried over to the built [`User`].
+pub struct UserBuilder<T, I = NoIdentity> {
     /// Base [`Identity`] that will be used to identify a Streams [`User`]
     id: Option<Identity>,
     /// [`Transport`] Client instance.
@@ -26,7 +41,11 @@ pub struct UserBuilder<T> {
     /// Pre Shared Keys.
     psks: Vec<(PskId, Psk)>,
     /// Spongos Storage Type.
-    lean: bool,
+    retention: SpongosRetention,
+    /// Hooks called around the dispatch of sent messages.
+    send_hooks: SendHooks<T>,
+    /// Marker of the [`Identity`] availability.
+    identity_marker: PhantomData<I>,
 }
 
 impl Default for UserBuilder<()> {
@@ -35,7 +54,9 @@ impl Default for UserBuilder<()> {
             id: None,
             transport: (),
             psks: Default::default(),
-            lean: false,
+            retention: SpongosRetention::All,
+            send_hooks: Default::default(),
+            identity_marker: PhantomData,
         }
     }
 }
@@ -45,24 +66,96 @@ impl UserBuilder<()> {
     pub(crate) fn new() -> Self {
         Self::default()
     }
+
+    /// Create a new User Builder instance with an [`Ed25519`] [`Identity`] deterministically
+    /// generated from a seed. Builders created from the same seed produce users with the same
+    /// identifier.
+    ///
+    /// # Examples
+    /// ## User from Seed
+    /// ```
+    /// use streams::{transport::bucket, UserBuilder};
+    ///
+    /// let user = UserBuilder::from_seed("cryptographically-secure-random-user-seed")
+    ///     .with_transport(bucket::Client::new())
+    ///     .lean()
+    ///     .build();
+    /// ```
+    ///
+    /// # Arguments
+    /// * `seed` - Seed the [`Ed25519`] keypair is generated from
+    pub fn from_seed(seed: &str) -> UserBuilder<(), HasIdentity> {
+        Self::new().with_identity(Ed25519::from_seed(seed))
+    }
+
+    /// Create a new User Builder instance with a fresh random [`Ed25519`] [`Identity`]
+    pub fn from_entropy() -> UserBuilder<(), HasIdentity> {
+        let seed: [u8; 32] = StdRng::from_entropy().gen();
+        Self::new().with_identity(Ed25519::from_seed(seed))
+    }
 }
 
-impl<T> UserBuilder<T> {
-    /// Inject Base [`Identity`] into the [`User`] Builder.
+impl<T, I> UserBuilder<T, I> {
+    /// Inject Base [`Identity`] into the [`User`] Builder. The built [`User`] will be able to
+    /// publish messages.
     ///
     /// # Arguments
     /// * `id` - [`Identity`] to be used for base identification of the Streams User
-    pub fn with_identity<I>(mut self, id: I) -> Self
+    pub fn with_identity<Id>(self, id: Id) -> UserBuilder<T, HasIdentity>
     where
-        I: Into<Identity>,
+        Id: Into<Identity>,
     {
-        self.id = Some(id.into());
-        self
+        UserBuilder {
+            id: Some(id.into()),
+            transport: self.transport,
+            psks: self.psks,
+            retention: self.retention,
+            send_hooks: self.send_hooks,
+            identity_marker: PhantomData,
+        }
+    }
+
+    /// Remove any [`Identity`] from the [`User`] Builder. The built [`User`] will only be able to
+    /// read messages.
+    ///
+    /// # Examples
+    /// ## Users without Identity cannot publish
+    /// ```compile_fail
+    /// # use anyhow::Result;
+    /// use streams::{transport::bucket, User};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let mut reader = User::builder()
+    ///     .with_no_identity()
+    ///     .with_transport(bucket::Client::new())
+    ///     .build();
+    /// reader.send_signed_packet("BASE_BRANCH", b"public", b"masked").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_no_identity(self) -> UserBuilder<T, NoIdentity> {
+        UserBuilder {
+            id: None,
+            transport: self.transport,
+            psks: self.psks,
+            retention: self.retention,
+            send_hooks: self.send_hooks,
+            identity_marker: PhantomData,
+        }
     }
 
     /// Set the User Builder lean state to true
     pub fn lean(mut self) -> Self {
-        self.lean = true;
+        self.retention = SpongosRetention::Lean;
+        self
+    }
+
+    /// Keep only the states of the last `n` messages, besides the stream announcement
+    ///
+    /// # Arguments
+    /// * `n` - Number of message states to keep
+    pub fn lean_threshold(mut self, n: usize) -> Self {
+        self.retention = SpongosRetention::LastN(n);
         self
     }
 
@@ -70,7 +163,7 @@ impl<T> UserBuilder<T> {
     ///
     /// # Arguments
     /// * `transport` - Transport Client to be used by the Streams User
-    pub fn with_transport<NewTransport>(self, transport: NewTransport) -> UserBuilder<NewTransport>
+    pub fn with_transport<NewTransport>(self, transport: NewTransport) -> UserBuilder<NewTransport, I>
     where
         NewTransport: for<'a> Transport<'a>,
     {
@@ -78,7 +171,9 @@ impl<T> UserBuilder<T> {
             transport,
             id: self.id,
             psks: self.psks,
-            lean: self.lean,
+            retention: self.retention,
+            send_hooks: self.send_hooks.into_transport(),
+            identity_marker: PhantomData,
         }
     }
 
@@ -109,9 +204,37 @@ impl<T> UserBuilder<T> {
         self.psks.push((pskid, psk));
         self
     }
+
+    /// Inject a hook called with the [`Address`] and the wire-format [`TransportMessage`] of every
+    /// message the [`User`] sends, right before it is dispatched to the transport. Useful to
+    /// forward sent messages to a secondary system, such as an audit database or a local cache.
+    ///
+    /// # Arguments
+    /// * `hook` - Hook called before each message is dispatched
+    pub fn with_pre_send_hook(mut self, hook: PreSendHook) -> Self {
+        self.send_hooks.set_pre_send(hook);
+        self
+    }
+
+    /// Inject a hook called with the [`Address`], the wire-format [`TransportMessage`] and the
+    /// response of the injected [`Transport`] for every message the [`User`] sends, once it has been
+    /// dispatched. The hook is not called for messages the transport failed to send. As it is typed
+    /// by the responses of the current [`Transport`], the hook is dropped if another transport is
+    /// injected afterwards, or if the [`User`] is moved to another transport.
+    ///
+    /// # Arguments
+    /// * `hook` - Hook called after each message is dispatched
+    pub fn with_post_send_hook<TSR>(mut self, hook: PostSendHook<TSR>) -> Self
+    where
+        T: for<'a> Transport<'a, SendResponse = TSR>,
+        TSR: 'static,
+    {
+        self.send_hooks.set_post_send(hook);
+        self
+    }
 }
 
-impl<T> UserBuilder<T> {
+impl<T, I> UserBuilder<T, I> {
     /// Build a [`User`] instance using the Builder parameters.
     ///
     /// If a [`Transport`] is not provided, the builder will use a default client.
@@ -135,12 +258,18 @@ impl<T> UserBuilder<T> {
     /// # }
     /// ```
 
-    pub fn build<Trans>(self) -> User<Trans>
+    pub fn build<Trans>(self) -> User<Trans, I>
     where
         T: IntoTransport<Trans>,
         Trans: for<'a> Transport<'a>,
     {
-        User::new(self.id, self.psks, self.transport.into(), self.lean)
+        User::new(
+            self.id,
+            self.psks,
+            self.transport.into(),
+            self.retention,
+            <T as IntoTransport<Trans>>::into_send_hooks(self.send_hooks),
+        )
     }
 
     /// Recover a user instance from the builder parameters.
@@ -191,7 +320,7 @@ impl<T> UserBuilder<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn recover<Trans>(self, announcement: Address) -> Result<User<Trans>>
+    pub async fn recover<Trans>(self, announcement: Address) -> Result<User<Trans, I>>
     where
         T: IntoTransport<Trans>,
         Trans: for<'a> Transport<'a, Msg = TransportMessage>,
@@ -208,6 +337,19 @@ where
     T: for<'a> Transport<'a>,
 {
     fn into(self) -> T;
+
+    /// Converts the hooks configured for this transport into hooks for the converted transport.
+    /// The post-send hook is typed by the responses of this transport, so by default only the
+    /// pre-send hook is kept.
+    ///
+    /// # Arguments
+    /// * `send_hooks` - The hooks configured for this transport
+    fn into_send_hooks(send_hooks: SendHooks<Self>) -> SendHooks<T>
+    where
+        Self: Sized,
+    {
+        send_hooks.into_transport()
+    }
 }
 
 #[cfg(feature = "utangle-client")]
@@ -224,4 +366,8 @@ where
     fn into(self) -> T {
         self
     }
+
+    fn into_send_hooks(send_hooks: SendHooks<T>) -> SendHooks<T> {
+        send_hooks
+    }
 }
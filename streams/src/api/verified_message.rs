@@ -0,0 +1,53 @@
+use lets::{address::Address, id::Identifier};
+
+/// The outcome of verifying a raw message against a [`User`](`crate::User`) state, without
+/// processing it
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub struct VerifiedMessage {
+    /// [`Address`] of the message that was verified
+    pub address: Address,
+    /// Message type identifier found in the message header
+    pub message_type: u8,
+    /// [`Identifier`] of the publisher found in the message header
+    pub publisher: Identifier,
+    /// Whether the message content could be unwrapped and authenticated
+    pub is_valid: bool,
+}
+
+impl VerifiedMessage {
+    /// Creates a new [`VerifiedMessage`]
+    ///
+    /// # Arguments
+    /// * `address`: The [`Address`] of the message that was verified
+    /// * `message_type`: The message type identifier from the header
+    /// * `publisher`: The [`Identifier`] of the publisher from the header
+    /// * `is_valid`: Whether the message content was successfully unwrapped
+    pub(crate) fn new(address: Address, message_type: u8, publisher: Identifier, is_valid: bool) -> Self {
+        Self {
+            address,
+            message_type,
+            publisher,
+            is_valid,
+        }
+    }
+
+    /// Returns the [`Address`] of the message
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the message type identifier of the message
+    pub fn message_type(&self) -> u8 {
+        self.message_type
+    }
+
+    /// Returns a reference to the publisher [`Identifier`] of the message
+    pub fn publisher(&self) -> &Identifier {
+        &self.publisher
+    }
+
+    /// Returns true if the message content was successfully unwrapped and authenticated
+    pub fn is_valid(&self) -> bool {
+        self.is_valid
+    }
+}
@@ -0,0 +1,42 @@
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// Local
+use crate::error::Result;
+
+/// Approval gate of the payloads sent with
+/// [`User::send_signed_packet_verified`](`crate::User::send_signed_packet_verified`), such as a
+/// hardware signing device confirming the payload before it is published
+#[async_trait(?Send)]
+pub trait Verify {
+    /// Approves or rejects a payload. Returning an `Err` aborts the send.
+    ///
+    /// # Arguments
+    /// * `payload`: The unmasked payload about to be sent
+    async fn verify(&self, payload: &[u8]) -> Result<()>;
+}
+
+/// A [`Verify`] implementation approving every payload
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct NoopVerify;
+
+#[async_trait(?Send)]
+impl Verify for NoopVerify {
+    async fn verify(&self, _payload: &[u8]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A [`Verify`] implementation delegating the approval to a function
+#[derive(Clone, Copy)]
+pub struct CallbackVerify(pub fn(&[u8]) -> Result<()>);
+
+#[async_trait(?Send)]
+impl Verify for CallbackVerify {
+    async fn verify(&self, payload: &[u8]) -> Result<()> {
+        (self.0)(payload)
+    }
+}
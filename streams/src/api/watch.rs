@@ -0,0 +1,74 @@
+// Rust
+use alloc::rc::Rc;
+use core::{cell::Cell, time::Duration};
+
+// 3rd-party
+use tokio::{sync::Notify, task::JoinHandle};
+
+// Local
+use crate::{
+    api::{identity_marker::HasIdentity, user::User},
+    error::{Error, Result},
+};
+
+/// Handle to a background task watching a branch, as returned by
+/// [`User::watch_branch`](`crate::User::watch_branch`)
+///
+/// Dropping the handle cancels the task, along with the [`User`] it owns. Use
+/// [`WatchHandle::stop`] to stop watching and get the [`User`] back.
+pub struct WatchHandle<T, I = HasIdentity> {
+    /// Time waited between two polls of the stream, shared with the task
+    interval: Rc<Cell<Duration>>,
+    /// Signal asking the task to stop after its current poll
+    stop: Rc<Notify>,
+    /// The background task, yielding the [`User`] once stopped
+    task: Option<JoinHandle<User<T, I>>>,
+}
+
+impl<T, I> WatchHandle<T, I> {
+    /// Creates a new [`WatchHandle`] for a task spawned by
+    /// [`User::watch_branch`](`crate::User::watch_branch`)
+    ///
+    /// # Arguments
+    /// * `interval`: Time waited between two polls of the stream, shared with the task
+    /// * `stop`: Signal asking the task to stop, shared with the task
+    /// * `task`: The background task
+    pub(crate) fn new(interval: Rc<Cell<Duration>>, stop: Rc<Notify>, task: JoinHandle<User<T, I>>) -> Self {
+        Self {
+            interval,
+            stop,
+            task: Some(task),
+        }
+    }
+
+    /// Returns the time waited between two polls of the stream
+    pub fn interval(&self) -> Duration {
+        self.interval.get()
+    }
+
+    /// Adjusts the time waited between two polls of the stream. The new interval applies from the
+    /// next wait onwards.
+    ///
+    /// # Arguments
+    /// * `interval`: The new time waited between two polls of the stream
+    pub fn set_interval(&self, interval: Duration) {
+        self.interval.set(interval);
+    }
+
+    /// Stops watching the branch, waiting for the poll in progress, if any, to complete. Returns
+    /// the [`User`], with its state synced up to the last poll.
+    pub async fn stop(mut self) -> Result<User<T, I>> {
+        self.stop.notify_one();
+        let task = self.task.take().expect("watch task is only taken when stopping");
+        task.await
+            .map_err(|e| Error::External(anyhow::anyhow!("watch task did not complete: {}", e)))
+    }
+}
+
+impl<T, I> Drop for WatchHandle<T, I> {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
@@ -1,7 +1,11 @@
 //! Stream Errors
 
 // Rust
-use core::{array::TryFromSliceError, fmt::Debug};
+use alloc::{string::String, vec::Vec};
+use core::{
+    array::TryFromSliceError,
+    fmt::{Debug, Display, Formatter, Result as FormatResult},
+};
 
 // 3rd-party
 use thiserror_no_std::Error;
@@ -31,12 +35,27 @@ pub enum Error {
     )]
     AddressUsed(&'static str, Address),
 
+    #[error("Cannot reset cursor in branch '{0}' to {1}, as it is ahead of the current cursor {2}")]
+    InvalidCursor(Topic, usize, usize),
+
+    #[error(
+        "Topic '{0}' is not valid. Topics must be at most {} bytes long and must not contain null bytes",
+        Topic::MAX_LENGTH
+    )]
+    InvalidTopic(Topic),
+
     #[error("Unexpected message type {0}")]
     MessageTypeUnknown(u8),
 
+    #[error("Expected a message of type {expected}, but got a message of type {got}")]
+    UnexpectedMessageType { expected: u8, got: u8 },
+
     #[error("Message  '{0}' not found in {1}")]
     MessageMissing(MsgId, &'static str),
 
+    #[error("Message not found at address '{0}' before the timeout expired")]
+    MessageNotFound(Address),
+
     #[error("Failed to get messages. Error: {0}")]
     Messages(anyhow::Error),
 
@@ -62,9 +81,18 @@ Any {0} message must be linked to a previous message by including the address of
     )]
     NotLinked(&'static str, Address),
 
+    #[error("No default topic is set. Call `User::set_default_topic` before sending to the default branch")]
+    NoDefaultTopic,
+
+    #[error("The message at address '{0}' is not a keyload the user is included in")]
+    NotInKeyload(Address),
+
     #[error("A payload must be specified in order to send a message")]
     PayloadEmpty,
 
+    #[error("Payload does not match the schema: {0}")]
+    SchemaValidationFailed(String),
+
     #[error("Setup error: {0}")]
     Setup(&'static str),
 
@@ -74,15 +102,80 @@ Any {0} message must be linked to a previous message by including the address of
     #[error("Transport error while trying to {1} for address {0}; Error: {2}")]
     Transport(Address, &'static str, LetsError),
 
+    #[cfg(feature = "transport-options")]
+    #[error("Transport request to {1} for address {0} did not complete within {2:?}")]
+    Timeout(Address, &'static str, core::time::Duration),
+
+    #[error("Group '{0}' is not known")]
+    UnknownGroup(String),
+
     #[error("PSK by id {0} is not known")]
     UnknownPsk(PskId),
 
+    #[error("PSK id {0} does not match the id derived from its key")]
+    PskIdMismatch(PskId),
+
     #[error("Topic by hash {0} is not known")]
     UnknownTopic(TopicHash),
 
     #[error("Error unwrapping the message {0}. The message at address '{1:#?}' could not be unwrapped: {2}")]
     Unwrapping(&'static str, Address, LetsError),
 
+    #[cfg(feature = "typed-payloads")]
+    #[error("Failed to {0} the typed payload: {1}")]
+    TypedPayload(&'static str, serde_json::Error),
+
+    #[cfg(feature = "timestamped-payloads")]
+    #[error("Failed to {0} the timestamped payload: {1}")]
+    TimestampedPayload(&'static str, bincode::Error),
+
+    #[cfg(feature = "timestamped-payloads")]
+    #[error("System time is set before the Unix epoch: {0}")]
+    SystemTime(std::time::SystemTimeError),
+
+    #[cfg(feature = "compressed-payloads")]
+    #[error("Failed to {0} the compressed payload: {1}")]
+    CompressedPayload(&'static str, std::io::Error),
+
+    #[cfg(feature = "json-log")]
+    #[error("Failed to write the JSON log: {0}")]
+    JsonLog(std::io::Error),
+
+    #[cfg(feature = "cursor-csv")]
+    #[error("Failed to {0} the cursor store CSV: {1}")]
+    CursorCsv(&'static str, std::io::Error),
+
+    #[cfg(feature = "cursor-csv")]
+    #[error("Invalid record {0} of the cursor store CSV: {1}")]
+    InvalidCursorCsv(usize, &'static str),
+
+    #[cfg(feature = "invite-links")]
+    #[error("Invalid invite link '{0}': {1}")]
+    InvalidInviteLink(String, &'static str),
+
+    #[error(
+        "Invalid subscription request. The subscription request at address '{0}' is not located at the address derived from its requester"
+    )]
+    InvalidSubscriptionRequest(Address),
+
+    #[error(
+        "Invalid subscription approval. The subscription approval at address '{0}' is not signed by the author of the stream"
+    )]
+    InvalidSubscriptionApproval(Address),
+
+    #[error(
+        "Idempotency key reused. A packet with a different branch or payloads was already sent with the same key, at address '{0}'"
+    )]
+    IdempotencyKeyReused(Address),
+
+    #[error(
+        "The transport response of the packet already sent with the same idempotency key, at address '{0}', is not held by the user"
+    )]
+    IdempotentResponseMissing(Address),
+
+    #[error("{0}")]
+    PartialRekey(PartialRekeyError),
+
     #[error("Missing role {0} for {1:?} in order to {2}")]
     WrongRole(&'static str, Identifier, &'static str),
 
@@ -97,6 +190,43 @@ Any {0} message must be linked to a previous message by including the address of
     Wrapped(&'static str, lets::error::Error),
 }
 
+/// Outcome of a [`User::rekey_all_branches`](`crate::User::rekey_all_branches`) that could not
+/// send a keyload to some of the branches
+#[derive(Debug)]
+pub struct PartialRekeyError {
+    /// Branches rekeyed, along with the [`Address`] of their new keyload
+    pub successes: Vec<(Topic, Address)>,
+    /// Branches that could not be rekeyed, along with the error raised
+    pub failures: Vec<(Topic, Error)>,
+}
+
+impl Display for PartialRekeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "Failed to rekey {} of {} branches:",
+            self.failures.len(),
+            self.successes.len() + self.failures.len()
+        )?;
+        for (topic, error) in &self.failures {
+            write!(f, " '{}' ({})", topic, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+    /// Wraps an error raised by the transport while trying to perform `action` for `address`.
+    /// Requests that timed out are reported as [`Error::Timeout`].
+    pub(crate) fn transport(address: Address, action: &'static str, error: LetsError) -> Self {
+        match error {
+            #[cfg(feature = "transport-options")]
+            LetsError::Timeout(timeout) => Self::Timeout(address, action, timeout),
+            error => Self::Transport(address, action, error),
+        }
+    }
+}
+
 impl From<SpongosError> for Error {
     fn from(error: SpongosError) -> Self {
         Self::Spongos(error)
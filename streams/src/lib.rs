@@ -34,6 +34,9 @@
 // #[macro_use]
 // extern crate std;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[macro_use]
 extern crate alloc;
 
@@ -44,17 +47,51 @@ mod message;
 mod api;
 
 pub use api::{
-    message::{Message, MessageContent},
+    batch::BatchItem,
+    branch_audit::BranchAudit,
+    capability::Capability,
+    cursor_diff::CursorDiff,
+    cursor_store::CursorStore,
+    ephemeral_subscription::{ExpiringSubscriber, UnsubscribeToken},
+    identity_marker::{HasIdentity, IdentityMarker, NoIdentity},
+    message::{Message, MessageContent, StreamMetadata},
     message_builder::MessageBuilder,
+    message_log::MessageLogEntry,
     messages::Messages,
+    paused_user::PausedUser,
+    schema::{NoopValidator, SchemaValidator},
     selector::Selector,
+    send_hook::{PostSendHook, PreSendHook},
     send_response::SendResponse,
+    spongos_retention::SpongosRetention,
+    stream_diff::StreamDiff,
+    stream_health::StreamHealth,
     user::User,
     user_builder::UserBuilder,
+    verified_message::VerifiedMessage,
+    verify::{CallbackVerify, NoopVerify, Verify},
 };
 
+#[cfg(feature = "blocking")]
+pub use api::messages::SyncMessages;
+
+#[cfg(feature = "timestamped-payloads")]
+pub use api::timestamped_payload::TimestampedPublicPayload;
+
+#[cfg(feature = "invite-links")]
+pub use api::invite::InviteInfo;
+
+#[cfg(feature = "watch-branch")]
+pub use api::watch::WatchHandle;
+
+#[cfg(feature = "json-schema")]
+pub use api::schema::JsonSchemaValidator;
+
+#[cfg(feature = "transport-options")]
+pub use api::transport_options::TransportOptions;
+
 /// Errors for Streams
 mod error;
-pub use error::{Error, Result};
+pub use error::{Error, PartialRekeyError, Result};
 
 pub use lets::{address::Address, id, message::TransportMessage, transport};
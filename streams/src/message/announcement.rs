@@ -14,6 +14,20 @@
 //!     ed25519(hash)           sig;
 //! }
 //! ```
+//!
+//! When the stream is the child of another stream, the announcement is published as a
+//! `ChildAnnouncement` message, carrying the address of the parent stream announcement as well.
+//!
+//! ```ddml
+//! message ChildAnnouncement {
+//!     mask             u8     identifier;
+//!     mask             u8     topic;
+//!     mask             u8     parent_address;
+//!     commit;
+//!     squeeze          u8     hash[64];
+//!     ed25519(hash)           sig;
+//! }
+//! ```
 
 // Rust
 use alloc::boxed::Box;
@@ -24,7 +38,9 @@ use async_trait::async_trait;
 // IOTA
 
 // Streams
+use crate::message::message_types;
 use lets::{
+    address::Address,
     id::{Identifier, Identity},
     message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap, Topic},
 };
@@ -45,6 +61,8 @@ pub(crate) struct Wrap<'a> {
     user_id: &'a Identity,
     /// The [`Topic`] of the base branch of the stream
     topic: &'a Topic,
+    /// The [`Address`] of the parent stream announcement, if the stream is a child stream
+    parent_address: Option<&'a Address>,
 }
 
 impl<'a> Wrap<'a> {
@@ -53,19 +71,32 @@ impl<'a> Wrap<'a> {
     /// # Arguments
     /// * `user_id`: The [`Identity`] of the sender
     /// * `topic`: The base branch [`Topic`] for the stream
-    pub(crate) fn new(user_id: &'a Identity, topic: &'a Topic) -> Self {
-        Self { user_id, topic }
+    /// * `parent_address`: The [`Address`] of the parent stream announcement, if any
+    pub(crate) fn new(user_id: &'a Identity, topic: &'a Topic, parent_address: Option<&'a Address>) -> Self {
+        Self {
+            user_id,
+            topic,
+            parent_address,
+        }
+    }
+
+    /// Returns the message type the announcement must be published with
+    pub(crate) fn message_type(&self) -> u8 {
+        match self.parent_address {
+            Some(_) => message_types::CHILD_ANNOUNCEMENT,
+            None => message_types::ANNOUNCEMENT,
+        }
     }
 }
 
 #[async_trait(?Send)]
 impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
     async fn sizeof(&mut self, announcement: &Wrap<'a>) -> Result<&mut Self> {
-        self.mask(announcement.user_id.identifier())?
-            .mask(announcement.topic)?
-            .sign_sizeof(announcement.user_id)
-            .await?
-            .commit()?;
+        self.mask(announcement.user_id.identifier())?.mask(announcement.topic)?;
+        if let Some(parent_address) = announcement.parent_address {
+            self.mask(parent_address)?;
+        }
+        self.sign_sizeof(announcement.user_id).await?.commit()?;
         Ok(self)
     }
 }
@@ -76,11 +107,11 @@ where
     OS: io::OStream,
 {
     async fn wrap(&mut self, announcement: &mut Wrap<'a>) -> Result<&mut Self> {
-        self.mask(announcement.user_id.identifier())?
-            .mask(announcement.topic)?
-            .sign(announcement.user_id)
-            .await?
-            .commit()?;
+        self.mask(announcement.user_id.identifier())?.mask(announcement.topic)?;
+        if let Some(parent_address) = announcement.parent_address {
+            self.mask(parent_address)?;
+        }
+        self.sign(announcement.user_id).await?.commit()?;
         Ok(self)
     }
 }
@@ -92,17 +123,38 @@ pub(crate) struct Unwrap {
     author_id: Identifier,
     /// The base branch [`Topic`] of the stream
     topic: Topic,
+    /// The [`Address`] of the parent stream announcement, if the stream is a child stream
+    parent_address: Option<Address>,
+    /// Whether the message is a [`message_types::CHILD_ANNOUNCEMENT`] message
+    is_child: bool,
 }
 
 impl Default for Unwrap {
     fn default() -> Self {
         let author_id = Default::default();
         let topic = Default::default();
-        Self { author_id, topic }
+        let parent_address = Default::default();
+        Self {
+            author_id,
+            topic,
+            parent_address,
+            is_child: false,
+        }
     }
 }
 
 impl Unwrap {
+    /// Creates a new [`Unwrap`] struct for an announcement message
+    ///
+    /// # Arguments
+    /// * `message_type`: The message type of the announcement
+    pub(crate) fn new(message_type: u8) -> Self {
+        Self {
+            is_child: message_type == message_types::CHILD_ANNOUNCEMENT,
+            ..Default::default()
+        }
+    }
+
     /// Returns a reference to the [`Identifier`] of the author.
     pub(crate) fn author_id(&self) -> &Identifier {
         &self.author_id
@@ -111,6 +163,10 @@ impl Unwrap {
     pub(crate) fn topic(&self) -> &Topic {
         &self.topic
     }
+    /// Returns a reference to the [`Address`] of the parent stream announcement, if any.
+    pub(crate) fn parent_address(&self) -> Option<&Address> {
+        self.parent_address.as_ref()
+    }
     /// Consumes the [`Unwrap`], returning the [`Identifier`] of the author.
     pub(crate) fn into_author_id(self) -> Identifier {
         self.author_id
@@ -124,11 +180,13 @@ where
     IS: io::IStream,
 {
     async fn unwrap(&mut self, announcement: &mut Unwrap) -> Result<&mut Self> {
-        self.mask(&mut announcement.author_id)?
-            .mask(&mut announcement.topic)?
-            .verify(&announcement.author_id)
-            .await?
-            .commit()?;
+        self.mask(&mut announcement.author_id)?.mask(&mut announcement.topic)?;
+        if announcement.is_child {
+            let mut parent_address = Address::default();
+            self.mask(&mut parent_address)?;
+            announcement.parent_address = Some(parent_address);
+        }
+        self.verify(&announcement.author_id).await?.commit()?;
         Ok(self)
     }
 }
@@ -0,0 +1,144 @@
+//! `AnnouncementAck` message _wrapping_ and _unwrapping_.
+//!
+//! `AnnouncementAck` messages are sent by a subscriber once it has processed a keyload, to confirm
+//! to the stream admins that it can read the branch. The message is joined to the keyload
+//! [`Spongos`], so the MAC can only be produced and verified by the users that hold the keyload
+//! key, and is signed by the subscriber.
+//!
+//! An `AnnouncementAck` is linked to the keyload it acknowledges, but no other message links to it.
+//!
+//! ```ddml
+//! message AnnouncementAck {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     commit;
+//!     squeeze                 u8      mac[32];
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask, Squeeze},
+        io,
+        types::Mac,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+/// [`Mac`] proving the knowledge of the keyload [`Spongos`]
+const MAC: Mac = Mac::new(32);
+
+/// A struct that holds references needed for announcement ack message encoding
+pub(crate) struct Wrap<'a> {
+    /// The [`Spongos`] state of the acknowledged keyload that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the subscriber
+    user_id: &'a Identity,
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for an announcement ack message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The [`Spongos`] state of the acknowledged keyload
+    /// * `user_id`: The [`Identity`] of the subscriber.
+    pub(crate) fn new(initial_state: &'a mut Spongos, user_id: &'a Identity) -> Self {
+        Self { initial_state, user_id }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, announcement_ack: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(announcement_ack.user_id.identifier())?
+            .commit()?
+            .squeeze(&MAC)?
+            .commit()?
+            .sign_sizeof(announcement_ack.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, announcement_ack: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(announcement_ack.initial_state)?
+            .mask(announcement_ack.user_id.identifier())?
+            .commit()?
+            .squeeze(&MAC)?
+            .commit()?
+            .sign(announcement_ack.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for announcement ack message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The [`Spongos`] state of the acknowledged keyload that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the subscriber
+    subscriber_id: Identifier,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for an announcement ack message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The [`Spongos`] state of the acknowledged keyload
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            subscriber_id: Identifier::default(),
+        }
+    }
+
+    /// Returns a reference to the [`Identifier`] of the subscriber
+    pub(crate) fn subscriber_identifier(&self) -> &Identifier {
+        &self.subscriber_id
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the subscriber
+    pub(crate) fn into_subscriber_identifier(self) -> Identifier {
+        self.subscriber_id
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, announcement_ack: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.join(announcement_ack.initial_state)?
+            .mask(&mut announcement_ack.subscriber_id)?
+            .commit()?
+            .squeeze(&MAC)?
+            .commit()?
+            .verify(&announcement_ack.subscriber_id)
+            .await?;
+        Ok(self)
+    }
+}
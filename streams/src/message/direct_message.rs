@@ -0,0 +1,206 @@
+//! `DirectMessage` message _wrapping_ and _unwrapping_.
+//!
+//! `DirectMessage` messages carry a payload addressed to a single recipient. The payload is masked
+//! with a random key that is encapsulated for the recipient using the X25519 public key derived
+//! from its [`Identifier`], so no other participant of the stream can read it.
+//!
+//! The identifiers of the sender and the recipient are masked with the announcement spongos, so
+//! any participant can tell who the message is addressed to. Users other than the recipient stop
+//! the unwrapping right after the recipient identifier.
+//!
+//! ```ddml
+//! message DirectMessage {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    u8      recipient;
+//!     x25519(pub/priv_key)    u8      x25519_pubkey[32];
+//!     absorb external         u8      key[32];
+//!     commit;
+//!     mask                    bytes   payload;
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{
+        ContentDecrypt, ContentEncrypt, ContentEncryptSizeOf, ContentSign, ContentSignSizeof, ContentSizeof,
+        ContentUnwrap, ContentVerify, ContentWrap,
+    },
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Absorb, Commit, Join, Mask},
+        io,
+        modifiers::External,
+        types::{Bytes, NBytes},
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+const KEY_SIZE: usize = 32;
+
+/// A struct that holds references needed for direct message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the sender
+    user_id: &'a Identity,
+    /// The [`Identifier`] of the recipient
+    recipient: &'a Identifier,
+    /// The key used to mask the payload, encapsulated for the recipient
+    key: [u8; KEY_SIZE],
+    /// Payload slice that will be masked
+    payload: &'a [u8],
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a direct message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the sender.
+    /// * `recipient`: The [`Identifier`] of the recipient.
+    /// * `key`: A random key used to mask the payload.
+    /// * `payload`: The payload that will be masked for the recipient.
+    pub(crate) fn new(
+        initial_state: &'a mut Spongos,
+        user_id: &'a Identity,
+        recipient: &'a Identifier,
+        key: [u8; KEY_SIZE],
+        payload: &'a [u8],
+    ) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            recipient,
+            key,
+            payload,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, direct_message: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(direct_message.user_id.identifier())?
+            .mask(direct_message.recipient)?
+            .encrypt_sizeof(direct_message.recipient, &direct_message.key)
+            .await?
+            .absorb(External::new(&NBytes::new(&direct_message.key)))?
+            .commit()?
+            .mask(Bytes::new(direct_message.payload))?
+            .commit()?
+            .sign_sizeof(direct_message.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, direct_message: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(direct_message.initial_state)?
+            .mask(direct_message.user_id.identifier())?
+            .mask(direct_message.recipient)?
+            .encrypt(direct_message.recipient, &direct_message.key)
+            .await?
+            .absorb(External::new(&NBytes::new(&direct_message.key)))?
+            .commit()?
+            .mask(Bytes::new(direct_message.payload))?
+            .commit()?
+            .sign(direct_message.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for direct message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the reader
+    user_id: Option<&'a Identity>,
+    /// The [`Identifier`] of the sender
+    sender_id: Identifier,
+    /// The [`Identifier`] of the recipient
+    recipient_id: Identifier,
+    /// The decrypted payload, if the reader is the recipient
+    payload: Option<Vec<u8>>,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a direct message
+    ///
+    /// # Arguments
+    /// * `initial_state`: The base [`Spongos`] state that the message will be joined to
+    /// * `user_id`: The optional [`Identity`] of the reading user
+    pub(crate) fn new(initial_state: &'a mut Spongos, user_id: Option<&'a Identity>) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            sender_id: Identifier::default(),
+            recipient_id: Identifier::default(),
+            payload: None,
+        }
+    }
+
+    /// Returns true if the reading user is the recipient of the message and its payload has been
+    /// decrypted
+    pub(crate) fn is_decrypted(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`]s of the sender and the recipient, and
+    /// the decrypted payload (empty if the reading user is not the recipient)
+    pub(crate) fn into_parts(self) -> (Identifier, Identifier, Vec<u8>) {
+        (self.sender_id, self.recipient_id, self.payload.unwrap_or_default())
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, direct_message: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.join(direct_message.initial_state)?
+            .mask(&mut direct_message.sender_id)?
+            .mask(&mut direct_message.recipient_id)?;
+
+        let user_id = match direct_message.user_id {
+            Some(user_id) if user_id.identifier() == &direct_message.recipient_id => user_id,
+            // The message is addressed to someone else, the payload cannot be decrypted
+            _ => return Ok(self),
+        };
+
+        let mut key = [0u8; KEY_SIZE];
+        let mut payload = Vec::new();
+        self.decrypt(user_id, &mut key)
+            .await?
+            .absorb(External::new(&NBytes::new(&key)))?
+            .commit()?
+            .mask(Bytes::new(&mut payload))?
+            .commit()?
+            .verify(&direct_message.sender_id)
+            .await?;
+        direct_message.payload = Some(payload);
+        Ok(self)
+    }
+}
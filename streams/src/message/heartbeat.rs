@@ -0,0 +1,125 @@
+//! `Heartbeat` message _wrapping_ and _unwrapping_.
+//!
+//! `Heartbeat` messages carry no payload. They are published to signal that the publisher is still
+//! active in a branch, and are signed by the sender.
+//!
+//! A `Heartbeat` is linked to the latest message of the branch, but no other message links to it.
+//!
+//! ```ddml
+//! message Heartbeat {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask},
+        io,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+/// A struct that holds references needed for heartbeat message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a heartbeat message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the publisher.
+    pub(crate) fn new(initial_state: &'a mut Spongos, user_id: &'a Identity) -> Self {
+        Self { initial_state, user_id }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, heartbeat: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(heartbeat.user_id.identifier())?
+            .commit()?
+            .sign_sizeof(heartbeat.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, heartbeat: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(heartbeat.initial_state)?
+            .mask(heartbeat.user_id.identifier())?
+            .commit()?
+            .sign(heartbeat.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for heartbeat message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a heartbeat message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            publisher_id: Identifier::default(),
+        }
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher
+    pub(crate) fn into_publisher_identifier(self) -> Identifier {
+        self.publisher_id
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, heartbeat: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.join(heartbeat.initial_state)?
+            .mask(&mut heartbeat.publisher_id)?
+            .commit()?
+            .verify(&heartbeat.publisher_id)
+            .await?;
+        Ok(self)
+    }
+}
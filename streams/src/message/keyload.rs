@@ -210,6 +210,10 @@ pub(crate) struct Unwrap<'a> {
     author_id: &'a Identifier,
     /// The [`Identity`] of the reader
     user_id: Option<&'a Identity>,
+    /// The nonce of the keyload
+    nonce: [u8; NONCE_SIZE],
+    /// The key shared with the subscribers, if the reader could unmask it
+    key: Option<[u8; KEY_SIZE]>,
 }
 
 impl<'a> Unwrap<'a> {
@@ -232,6 +236,29 @@ impl<'a> Unwrap<'a> {
             psk_store,
             author_id,
             user_id,
+            nonce: [0; NONCE_SIZE],
+            key: None,
+        }
+    }
+
+    /// Creates a new [`Unwrap`] struct for a keyload message whose key is already known, so that
+    /// none of its key slots is unmasked
+    ///
+    /// # Arguments
+    /// * `initial_state`: The base [`Spongos`] state that the message will be joined to
+    /// * `author_id`: The [`Identifier`] of the author of the stream
+    /// * `psk_store`: The [`PskId`] to [`Psk`] mapping of the reader
+    /// * `key`: The key shared by the keyload
+    #[cfg(test)]
+    pub(crate) fn with_key(
+        initial_state: &'a mut Spongos,
+        author_id: &'a Identifier,
+        psk_store: &'a HashMap<PskId, Psk>,
+        key: [u8; KEY_SIZE],
+    ) -> Self {
+        Self {
+            key: Some(key),
+            ..Self::new(initial_state, None, author_id, psk_store)
         }
     }
 
@@ -239,6 +266,16 @@ impl<'a> Unwrap<'a> {
     pub(crate) fn subscribers(&self) -> &[Permissioned<Identifier>] {
         &self.subscribers
     }
+
+    /// Returns the nonce of the keyload
+    pub(crate) fn nonce(&self) -> [u8; NONCE_SIZE] {
+        self.nonce
+    }
+
+    /// Returns the key shared with the subscribers, if the reader is one of them
+    pub(crate) fn key(&self) -> Option<[u8; KEY_SIZE]> {
+        self.key
+    }
 }
 
 #[async_trait(?Send)]
@@ -248,7 +285,7 @@ where
 {
     async fn unwrap(&mut self, keyload: &mut Unwrap<'a>) -> Result<&mut Self> {
         let mut nonce = [0u8; NONCE_SIZE];
-        let mut key: Option<[u8; KEY_SIZE]> = None;
+        let mut key: Option<[u8; KEY_SIZE]> = keyload.key;
         let mut n_subscribers = Size::default();
         let mut n_psks = Size::default();
         self.join(keyload.initial_state)?
@@ -305,6 +342,8 @@ where
                 .await?;
         }
         self.commit()?;
+        keyload.nonce = nonce;
+        keyload.key = key;
         Ok(self)
     }
 }
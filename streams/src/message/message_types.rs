@@ -12,3 +12,27 @@ pub(crate) const TAGGED_PACKET: u8 = 4;
 pub(crate) const SUBSCRIPTION: u8 = 5;
 /// Unsubscribe Message Type
 pub(crate) const UNSUBSCRIPTION: u8 = 6;
+/// Heartbeat Message Type
+pub(crate) const HEARTBEAT: u8 = 7;
+/// Stream Catalog Message Type
+pub(crate) const STREAM_CATALOG: u8 = 8;
+/// Direct Message Type
+pub(crate) const DIRECT_MESSAGE: u8 = 9;
+/// Stream Metadata Message Type
+pub(crate) const STREAM_METADATA: u8 = 10;
+/// Proof of Inclusion Message Type
+pub(crate) const PROOF_OF_INCLUSION: u8 = 11;
+/// Multipart Manifest Message Type
+pub(crate) const MULTIPART_MANIFEST: u8 = 12;
+/// Subscription Request Message Type
+pub(crate) const SUBSCRIPTION_REQUEST: u8 = 13;
+/// Stream Metadata Update Message Type
+pub(crate) const STREAM_METADATA_UPDATE: u8 = 14;
+/// Announcement Ack Message Type
+pub(crate) const ANNOUNCEMENT_ACK: u8 = 15;
+/// Stream Link Message Type
+pub(crate) const STREAM_LINK: u8 = 16;
+/// Subscribe With Options Message Type
+pub(crate) const SUBSCRIPTION_WITH_OPTIONS: u8 = 17;
+/// Child Announcement Message Type
+pub(crate) const CHILD_ANNOUNCEMENT: u8 = 18;
@@ -23,3 +23,33 @@ pub(crate) mod message_types;
 
 /// BranchAnnouncement message.
 pub(crate) mod branch_announcement;
+
+/// Heartbeat message.
+pub(crate) mod heartbeat;
+
+/// StreamCatalog message.
+pub(crate) mod stream_catalog;
+
+/// DirectMessage message.
+pub(crate) mod direct_message;
+
+/// StreamMetadata message.
+pub(crate) mod stream_metadata;
+
+/// ProofOfInclusion message.
+pub(crate) mod proof_of_inclusion;
+
+/// MultipartManifest message.
+pub(crate) mod multipart_manifest;
+
+/// SubscriptionRequest message.
+pub(crate) mod subscription_request;
+
+/// StreamMetadataUpdate message.
+pub(crate) mod stream_metadata_update;
+
+/// AnnouncementAck message.
+pub(crate) mod announcement_ack;
+
+/// StreamLink message.
+pub(crate) mod stream_link;
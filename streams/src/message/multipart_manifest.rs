@@ -0,0 +1,163 @@
+//! `MultipartManifest` message _wrapping_ and _unwrapping_.
+//!
+//! The `MultipartManifest` message advertises a document split across several signed packets. It
+//! carries the number of parts and the [`Address`] of each of them, in the order they must be
+//! concatenated. As the addresses of the parts are only known once they have been published, the
+//! manifest is published right after its parts, and is linked to the last of them. Its content is
+//! signed by the publisher.
+//!
+//! A `MultipartManifest` is linked to the latest message of the branch, but no other message links
+//! to it.
+//!
+//! ```ddml
+//! message MultipartManifest {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    u8      total_parts[4];
+//!     repeated(total_parts):
+//!         mask                u8      part_address[52];
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    address::Address,
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask},
+        io,
+        types::NBytes,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+/// A struct that holds references needed for multipart manifest message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+    /// The [`Address`]es of the parts, in order
+    part_addresses: &'a [Address],
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a multipart manifest message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the publisher.
+    /// * `part_addresses`: The [`Address`]es of the parts, in order.
+    pub(crate) fn new(initial_state: &'a mut Spongos, user_id: &'a Identity, part_addresses: &'a [Address]) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            part_addresses,
+        }
+    }
+
+    /// Number of parts listed in the manifest, as encoded in the message
+    fn total_parts(&self) -> u32 {
+        self.part_addresses.len() as u32
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, manifest: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(manifest.user_id.identifier())?
+            .mask(NBytes::new(manifest.total_parts().to_be_bytes()))?;
+        for address in manifest.part_addresses {
+            self.mask(address)?;
+        }
+        self.commit()?.sign_sizeof(manifest.user_id).await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, manifest: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(manifest.initial_state)?
+            .mask(manifest.user_id.identifier())?
+            .mask(NBytes::new(manifest.total_parts().to_be_bytes()))?;
+        for address in manifest.part_addresses {
+            self.mask(address)?;
+        }
+        self.commit()?.sign(manifest.user_id).await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for multipart manifest message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+    /// The number of parts listed in the manifest
+    total_parts: u32,
+    /// The [`Address`]es of the parts, in order
+    part_addresses: Vec<Address>,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a multipart manifest message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            publisher_id: Identifier::default(),
+            total_parts: 0,
+            part_addresses: Vec::new(),
+        }
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher, the number of parts
+    /// and their [`Address`]es
+    pub(crate) fn into_parts(self) -> (Identifier, u32, Vec<Address>) {
+        (self.publisher_id, self.total_parts, self.part_addresses)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, manifest: &mut Unwrap<'a>) -> Result<&mut Self> {
+        let mut total_parts = [0u8; 4];
+        self.join(manifest.initial_state)?
+            .mask(&mut manifest.publisher_id)?
+            .mask(NBytes::new(&mut total_parts))?;
+        manifest.total_parts = u32::from_be_bytes(total_parts);
+        for _ in 0..manifest.total_parts {
+            let mut address = Address::default();
+            self.mask(&mut address)?;
+            manifest.part_addresses.push(address);
+        }
+        self.commit()?.verify(&manifest.publisher_id).await?;
+        Ok(self)
+    }
+}
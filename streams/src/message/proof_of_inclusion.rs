@@ -0,0 +1,176 @@
+//! `ProofOfInclusion` message _wrapping_ and _unwrapping_.
+//!
+//! The `ProofOfInclusion` message references a target message of the stream by its [`Address`] and
+//! carries a digest squeezed out of the [`Spongos`] state of that message. Any user holding the
+//! state of the target message can re-squeeze the digest and compare it with the one in the proof,
+//! confirming the target message was part of the stream when the proof was published. The proof is
+//! signed by the publisher.
+//!
+//! A `ProofOfInclusion` is linked to the latest message of the branch, but no other message links
+//! to it.
+//!
+//! ```ddml
+//! message ProofOfInclusion {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    u8      target_address[52];
+//!     mask                    u8      digest[32];
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    address::Address,
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask},
+        io,
+        types::NBytes,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+/// Size of the digest of the target message [`Spongos`] state
+pub(crate) const DIGEST_SIZE: usize = 32;
+
+/// Squeezes the digest of the [`Spongos`] state of a message, bound to the message [`Address`].
+/// The state is copied, so the provided [`Spongos`] is left untouched.
+///
+/// # Arguments
+/// * `spongos`: The [`Spongos`] state of the target message
+/// * `address`: The [`Address`] of the target message
+pub(crate) fn digest(spongos: &Spongos, address: Address) -> [u8; DIGEST_SIZE] {
+    let mut spongos = *spongos;
+    spongos.sponge(address.relative())
+}
+
+/// A struct that holds references needed for proof of inclusion message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+    /// The [`Address`] of the target message
+    target_address: Address,
+    /// The digest of the target message [`Spongos`] state
+    digest: [u8; DIGEST_SIZE],
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a proof of inclusion message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the publisher.
+    /// * `target_address`: The [`Address`] of the target message.
+    /// * `digest`: The digest of the target message [`Spongos`] state.
+    pub(crate) fn new(
+        initial_state: &'a mut Spongos,
+        user_id: &'a Identity,
+        target_address: Address,
+        digest: [u8; DIGEST_SIZE],
+    ) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            target_address,
+            digest,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, proof: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(proof.user_id.identifier())?
+            .mask(&proof.target_address)?
+            .mask(NBytes::new(proof.digest))?
+            .commit()?
+            .sign_sizeof(proof.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, proof: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(proof.initial_state)?
+            .mask(proof.user_id.identifier())?
+            .mask(&proof.target_address)?
+            .mask(NBytes::new(proof.digest))?
+            .commit()?
+            .sign(proof.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for proof of inclusion message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+    /// The [`Address`] of the target message
+    target_address: Address,
+    /// The digest of the target message [`Spongos`] state
+    digest: [u8; DIGEST_SIZE],
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a proof of inclusion message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            publisher_id: Identifier::default(),
+            target_address: Address::default(),
+            digest: [0; DIGEST_SIZE],
+        }
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher, the [`Address`] of
+    /// the target message and the digest of its [`Spongos`] state
+    pub(crate) fn into_parts(self) -> (Identifier, Address, [u8; DIGEST_SIZE]) {
+        (self.publisher_id, self.target_address, self.digest)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, proof: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.join(proof.initial_state)?
+            .mask(&mut proof.publisher_id)?
+            .mask(&mut proof.target_address)?
+            .mask(NBytes::new(&mut proof.digest))?
+            .commit()?
+            .verify(&proof.publisher_id)
+            .await?;
+        Ok(self)
+    }
+}
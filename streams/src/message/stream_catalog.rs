@@ -0,0 +1,130 @@
+//! `StreamCatalog` message _wrapping_ and _unwrapping_.
+//!
+//! The `StreamCatalog` message publishes a list of known stream [`Address`]es on a discovery
+//! branch, so that discoverers can enumerate active streams without out-of-band knowledge.
+//!
+//! Like the `Announcement`, the `StreamCatalog` is not joined to any previous message, so it can be
+//! read from its address alone, without any prior state of the stream. Its content is signed by
+//! the publisher.
+//!
+//! ```ddml
+//! message StreamCatalog {
+//!     mask                    u8      identifier;
+//!     mask                    size_t  n_streams;
+//!     repeated(n_streams):
+//!         mask                u8      address[52];
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+
+// Rust
+use alloc::{boxed::Box, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    address::Address,
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Mask},
+        io,
+        types::Size,
+    },
+    error::Result,
+    PRP,
+};
+
+// Local
+
+/// A struct that holds references needed for stream catalog message encoding
+pub(crate) struct Wrap<'a> {
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+    /// The [`Address`]es of the streams listed in the catalog
+    streams: &'a [Address],
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a stream catalog message
+    ///
+    /// # Arguments
+    /// * `user_id`: The [`Identity`] of the publisher
+    /// * `streams`: The [`Address`]es of the known streams to be listed
+    pub(crate) fn new(user_id: &'a Identity, streams: &'a [Address]) -> Self {
+        Self { user_id, streams }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, catalog: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(catalog.user_id.identifier())?
+            .mask(Size::new(catalog.streams.len()))?;
+        for address in catalog.streams {
+            self.mask(address)?;
+        }
+        self.commit()?.sign_sizeof(catalog.user_id).await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, catalog: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.mask(catalog.user_id.identifier())?
+            .mask(Size::new(catalog.streams.len()))?;
+        for address in catalog.streams {
+            self.mask(address)?;
+        }
+        self.commit()?.sign(catalog.user_id).await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for stream catalog message decoding
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) struct Unwrap {
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+    /// The [`Address`]es of the streams listed in the catalog
+    streams: Vec<Address>,
+}
+
+impl Unwrap {
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher and the listed
+    /// stream [`Address`]es
+    pub(crate) fn into_parts(self) -> (Identifier, Vec<Address>) {
+        (self.publisher_id, self.streams)
+    }
+}
+
+#[async_trait(?Send)]
+impl<IS, F> ContentUnwrap<Unwrap> for unwrap::Context<IS, F>
+where
+    F: PRP,
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, catalog: &mut Unwrap) -> Result<&mut Self> {
+        let mut n_streams = Size::default();
+        self.mask(&mut catalog.publisher_id)?.mask(&mut n_streams)?;
+        for _ in 0..n_streams.inner() {
+            let mut address = Address::default();
+            self.mask(&mut address)?;
+            catalog.streams.push(address);
+        }
+        self.commit()?.verify(&catalog.publisher_id).await?;
+        Ok(self)
+    }
+}
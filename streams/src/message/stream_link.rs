@@ -0,0 +1,149 @@
+//! `StreamLink` message _wrapping_ and _unwrapping_.
+//!
+//! `StreamLink` messages reference another stream from a branch, so that the readers of the branch
+//! learn the [`Address`] of the remote stream without out-of-band knowledge. The address is masked
+//! and the message is signed by the publisher.
+//!
+//! ```ddml
+//! message StreamLink {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    u8      remote_stream_address[52];
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    address::Address,
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask},
+        io,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+
+/// A struct that holds references needed for stream link message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+    /// The [`Address`] of the remote stream
+    remote_stream_address: &'a Address,
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a stream link message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the publisher.
+    /// * `remote_stream_address`: The [`Address`] of the remote stream.
+    pub(crate) fn new(
+        initial_state: &'a mut Spongos,
+        user_id: &'a Identity,
+        remote_stream_address: &'a Address,
+    ) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            remote_stream_address,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, stream_link: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(stream_link.user_id.identifier())?
+            .mask(stream_link.remote_stream_address)?
+            .commit()?
+            .sign_sizeof(stream_link.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, stream_link: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(stream_link.initial_state)?
+            .mask(stream_link.user_id.identifier())?
+            .mask(stream_link.remote_stream_address)?
+            .commit()?
+            .sign(stream_link.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for stream link message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+    /// The [`Address`] of the remote stream
+    remote_stream_address: Address,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a stream link message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            publisher_id: Identifier::default(),
+            remote_stream_address: Address::default(),
+        }
+    }
+
+    /// Returns the [`Address`] of the remote stream
+    pub(crate) fn remote_stream_address(&self) -> Address {
+        self.remote_stream_address
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher and the [`Address`]
+    /// of the remote stream
+    pub(crate) fn into_parts(self) -> (Identifier, Address) {
+        (self.publisher_id, self.remote_stream_address)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, stream_link: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.join(stream_link.initial_state)?
+            .mask(&mut stream_link.publisher_id)?
+            .mask(&mut stream_link.remote_stream_address)?
+            .commit()?
+            .verify(&stream_link.publisher_id)
+            .await?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,212 @@
+//! `StreamMetadata` message _wrapping_ and _unwrapping_.
+//!
+//! The `StreamMetadata` message attaches human-readable metadata to a stream: a name, a
+//! description, the version of the schema of the stream payloads and a list of tags. It can only be
+//! published by an admin of the base branch.
+//!
+//! The metadata is joined to the stream announcement, so any user attached to the stream can read
+//! it, and is signed by the publisher.
+//!
+//! ```ddml
+//! message StreamMetadata {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    bytes   name;
+//!     mask                    bytes   description;
+//!     mask                    u8      schema_version[4];
+//!     mask                    size_t  n_tags;
+//!     repeated(n_tags):
+//!         mask                bytes   tag;
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Join, Mask},
+        io,
+        types::{Bytes, NBytes, Size},
+    },
+    error::{Error as SpongosError, Result},
+    Spongos,
+};
+
+// Local
+
+/// A struct that holds references needed for stream metadata message encoding
+pub(crate) struct Wrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identity`] of the publisher
+    user_id: &'a Identity,
+    /// Human-readable name of the stream
+    name: &'a str,
+    /// Human-readable description of the stream
+    description: &'a str,
+    /// Version of the schema of the stream payloads
+    schema_version: u32,
+    /// Tags describing the stream
+    tags: &'a [String],
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a stream metadata message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
+    /// * `user_id`: The [`Identity`] of the publisher.
+    /// * `name`: The name of the stream.
+    /// * `description`: The description of the stream.
+    /// * `schema_version`: The version of the schema of the stream payloads.
+    /// * `tags`: The tags describing the stream.
+    pub(crate) fn new(
+        initial_state: &'a mut Spongos,
+        user_id: &'a Identity,
+        name: &'a str,
+        description: &'a str,
+        schema_version: u32,
+        tags: &'a [String],
+    ) -> Self {
+        Self {
+            initial_state,
+            user_id,
+            name,
+            description,
+            schema_version,
+            tags,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, metadata: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(metadata.user_id.identifier())?
+            .mask(Bytes::new(metadata.name.as_bytes()))?
+            .mask(Bytes::new(metadata.description.as_bytes()))?
+            .mask(NBytes::new(metadata.schema_version.to_be_bytes()))?
+            .mask(Size::new(metadata.tags.len()))?;
+        for tag in metadata.tags {
+            self.mask(Bytes::new(tag.as_bytes()))?;
+        }
+        self.commit()?.sign_sizeof(metadata.user_id).await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, metadata: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.join(metadata.initial_state)?
+            .mask(metadata.user_id.identifier())?
+            .mask(Bytes::new(metadata.name.as_bytes()))?
+            .mask(Bytes::new(metadata.description.as_bytes()))?
+            .mask(NBytes::new(metadata.schema_version.to_be_bytes()))?
+            .mask(Size::new(metadata.tags.len()))?;
+        for tag in metadata.tags {
+            self.mask(Bytes::new(tag.as_bytes()))?;
+        }
+        self.commit()?.sign(metadata.user_id).await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for stream metadata message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The base [`Spongos`] state that the message will be joined to
+    initial_state: &'a mut Spongos,
+    /// The [`Identifier`] of the publisher
+    publisher_id: Identifier,
+    /// Human-readable name of the stream
+    name: String,
+    /// Human-readable description of the stream
+    description: String,
+    /// Version of the schema of the stream payloads
+    schema_version: u32,
+    /// Tags describing the stream
+    tags: Vec<String>,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a stream metadata message
+    ///
+    /// # Arguments
+    /// * `initial_state`: The base [`Spongos`] state that the message will be joined to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            initial_state,
+            publisher_id: Identifier::default(),
+            name: String::new(),
+            description: String::new(),
+            schema_version: 0,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher, and the name,
+    /// description, schema version and tags of the stream
+    pub(crate) fn into_parts(self) -> (Identifier, String, String, u32, Vec<String>) {
+        (
+            self.publisher_id,
+            self.name,
+            self.description,
+            self.schema_version,
+            self.tags,
+        )
+    }
+}
+
+/// Converts unmasked bytes into a [`String`], failing if they are not valid UTF-8
+fn into_string(bytes: Vec<u8>) -> Result<String> {
+    String::from_utf8(bytes).map_err(|e| SpongosError::Context("StreamMetadata", e.to_string()))
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, metadata: &mut Unwrap<'a>) -> Result<&mut Self> {
+        let mut name = Vec::new();
+        let mut description = Vec::new();
+        let mut schema_version = [0u8; 4];
+        let mut n_tags = Size::default();
+        self.join(metadata.initial_state)?
+            .mask(&mut metadata.publisher_id)?
+            .mask(Bytes::new(&mut name))?
+            .mask(Bytes::new(&mut description))?
+            .mask(NBytes::new(&mut schema_version))?
+            .mask(&mut n_tags)?;
+        for _ in 0..n_tags.inner() {
+            let mut tag = Vec::new();
+            self.mask(Bytes::new(&mut tag))?;
+            metadata.tags.push(into_string(tag)?);
+        }
+        self.commit()?.verify(&metadata.publisher_id).await?;
+        metadata.name = into_string(name)?;
+        metadata.description = into_string(description)?;
+        metadata.schema_version = u32::from_be_bytes(schema_version);
+        Ok(self)
+    }
+}
@@ -0,0 +1,130 @@
+//! `StreamMetadataUpdate` message _wrapping_ and _unwrapping_.
+//!
+//! The `StreamMetadataUpdate` message replaces the human-readable metadata of a stream after it has
+//! been created. It carries the same fields as the `StreamMetadata` message and can only be
+//! published by an admin of the base branch.
+//!
+//! Unlike the `StreamMetadata` message, it is linked to the latest message of the base branch, so
+//! that readers apply successive updates in the order they were published. No other message links
+//! to it. Its content is signed by the publisher.
+//!
+//! ```ddml
+//! message StreamMetadataUpdate {
+//!     join(spongos);
+//!     mask                    u8      identifier;
+//!     mask                    bytes   name;
+//!     mask                    bytes   description;
+//!     mask                    u8      schema_version[4];
+//!     mask                    size_t  n_tags;
+//!     repeated(n_tags):
+//!         mask                bytes   tag;
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+// Rust
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{ContentSizeof, ContentUnwrap, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap},
+        io,
+    },
+    error::Result,
+    Spongos,
+};
+
+// Local
+use crate::message::stream_metadata;
+
+/// A struct that holds references needed for stream metadata update message encoding
+pub(crate) struct Wrap<'a> {
+    /// The encoding of the updated metadata, shared with the stream metadata message
+    metadata: stream_metadata::Wrap<'a>,
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a stream metadata update message
+    ///
+    /// # Arguments:
+    /// * `initial_state`: The [`Spongos`] state of the latest message of the base branch
+    /// * `user_id`: The [`Identity`] of the publisher.
+    /// * `name`: The updated name of the stream.
+    /// * `description`: The updated description of the stream.
+    /// * `schema_version`: The updated version of the schema of the stream payloads.
+    /// * `tags`: The updated tags describing the stream.
+    pub(crate) fn new(
+        initial_state: &'a mut Spongos,
+        user_id: &'a Identity,
+        name: &'a str,
+        description: &'a str,
+        schema_version: u32,
+        tags: &'a [String],
+    ) -> Self {
+        Self {
+            metadata: stream_metadata::Wrap::new(initial_state, user_id, name, description, schema_version, tags),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, update: &Wrap<'a>) -> Result<&mut Self> {
+        self.sizeof(&update.metadata).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, update: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.wrap(&mut update.metadata).await
+    }
+}
+
+/// A struct that holds the placeholders needed for stream metadata update message decoding
+pub(crate) struct Unwrap<'a> {
+    /// The decoding of the updated metadata, shared with the stream metadata message
+    metadata: stream_metadata::Unwrap<'a>,
+}
+
+impl<'a> Unwrap<'a> {
+    /// Creates a new [`Unwrap`] struct for a stream metadata update message
+    ///
+    /// # Arguments
+    /// * `initial_state`: The [`Spongos`] state of the message the update is linked to
+    pub(crate) fn new(initial_state: &'a mut Spongos) -> Self {
+        Self {
+            metadata: stream_metadata::Unwrap::new(initial_state),
+        }
+    }
+
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the publisher, and the updated name,
+    /// description, schema version and tags of the stream
+    pub(crate) fn into_parts(self) -> (Identifier, String, String, u32, Vec<String>) {
+        self.metadata.into_parts()
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, IS> ContentUnwrap<Unwrap<'a>> for unwrap::Context<IS>
+where
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, update: &mut Unwrap<'a>) -> Result<&mut Self> {
+        self.unwrap(&mut update.metadata).await
+    }
+}
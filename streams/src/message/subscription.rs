@@ -11,7 +11,6 @@
 //! Subscriber must trust channel owner's Ed25519 public key in order to
 //! maintain privacy.
 //!
-//!
 //! ```ddml
 //! message Subscribe {
 //!     join(spongos);
@@ -23,6 +22,29 @@
 //!     ed25519(hash)           u8      signature[64];
 //! }
 //! ```
+//!
+//! Subscriptions with options are published with their own message type, so the layout of plain
+//! subscriptions stays readable by authors unaware of the options. The `options` flags are masked
+//! after the identifier: the `readonly` flag signals that the subscriber only intends to read the
+//! stream, and must never be granted write permissions. The `ephemeral` flag signals a
+//! subscription the author can expire once `ttl_seconds` elapsed since it processed the
+//! subscription; `ttl_seconds` is only present if the flag is set.
+//!
+//! ```ddml
+//! message SubscribeWithOptions {
+//!     join(spongos);
+//!     x25519(pub/priv_key)    u8      x25519_auth_pubkey[32];
+//!     commit;
+//!     mask                    u8      identifier;
+//!     mask                    u8      options;
+//!     if options & ephemeral {
+//!         mask                u64     ttl_seconds;
+//!     }
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
 // Rust
 use alloc::boxed::Box;
 
@@ -33,6 +55,7 @@ use async_trait::async_trait;
 use crypto::keys::x25519;
 
 // Streams
+use crate::message::message_types;
 use lets::{
     id::{Identifier, Identity},
     message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
@@ -41,12 +64,17 @@ use spongos::{
     ddml::{
         commands::{sizeof, unwrap, wrap, Join, Mask, X25519},
         io,
-        types::NBytes,
+        types::{NBytes, Uint64, Uint8},
     },
     error::Result,
     Spongos,
 };
 
+/// Option flag of a subscriber that must never be granted write permissions
+const READONLY: u8 = 0b01;
+/// Option flag of a subscription that expires, followed by its time to live
+const EPHEMERAL: u8 = 0b10;
+
 /// A struct that holds references needed for subscription message encoding
 pub(crate) struct Wrap<'a> {
     /// The base [`Spongos`] state that the message will be joined to
@@ -57,9 +85,40 @@ pub(crate) struct Wrap<'a> {
     subscriber_id: &'a Identity,
     /// The authors [`x25519::PublicKey`]
     author_ke_pk: &'a x25519::PublicKey,
+    /// Whether the subscriber must never be granted write permissions
+    readonly: bool,
+    /// Seconds the subscription lasts for, or 0 if it does not expire
+    ttl_seconds: u64,
 }
 
 impl<'a> Wrap<'a> {
+    /// Returns true if the subscription carries options, and must be published as a
+    /// [`message_types::SUBSCRIPTION_WITH_OPTIONS`] message
+    fn has_options(&self) -> bool {
+        self.readonly || self.ttl_seconds > 0
+    }
+
+    /// Returns the message type the subscription must be published with
+    pub(crate) fn message_type(&self) -> u8 {
+        if self.has_options() {
+            message_types::SUBSCRIPTION_WITH_OPTIONS
+        } else {
+            message_types::SUBSCRIPTION
+        }
+    }
+
+    /// Returns the option flags of the subscription
+    fn options(&self) -> u8 {
+        let mut options = 0;
+        if self.readonly {
+            options |= READONLY;
+        }
+        if self.ttl_seconds > 0 {
+            options |= EPHEMERAL;
+        }
+        options
+    }
+
     /// Creates a new [`Wrap`] struct for a subscription message
     ///
     /// # Arguments:
@@ -67,17 +126,23 @@ impl<'a> Wrap<'a> {
     /// * `unsubscribe_key`: A unique key for unsubscribing later.
     /// * `subscriber_id`: The [`Identity`] of the subscriber.
     /// * `author_ke_pk`: The author's public exchange key
+    /// * `readonly`: Whether the subscriber must never be granted write permissions
+    /// * `ttl_seconds`: Seconds the subscription lasts for, or 0 if it does not expire
     pub(crate) fn new(
         initial_state: &'a mut Spongos,
         unsubscribe_key: [u8; 32],
         subscriber_id: &'a Identity,
         author_ke_pk: &'a x25519::PublicKey,
+        readonly: bool,
+        ttl_seconds: u64,
     ) -> Self {
         Self {
             initial_state,
             unsubscribe_key,
             subscriber_id,
             author_ke_pk,
+            readonly,
+            ttl_seconds,
         }
     }
 }
@@ -86,9 +151,14 @@ impl<'a> Wrap<'a> {
 impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
     async fn sizeof(&mut self, subscription: &Wrap<'a>) -> Result<&mut Self> {
         self.x25519(subscription.author_ke_pk, NBytes::new(subscription.unsubscribe_key))?
-            .mask(subscription.subscriber_id.identifier())?
-            .sign_sizeof(subscription.subscriber_id)
-            .await?;
+            .mask(subscription.subscriber_id.identifier())?;
+        if subscription.has_options() {
+            self.mask(Uint8::new(subscription.options()))?;
+        }
+        if subscription.ttl_seconds > 0 {
+            self.mask(Uint64::new(subscription.ttl_seconds))?;
+        }
+        self.sign_sizeof(subscription.subscriber_id).await?;
         Ok(self)
     }
 }
@@ -101,9 +171,14 @@ where
     async fn wrap(&mut self, subscription: &mut Wrap<'a>) -> Result<&mut Self> {
         self.join(subscription.initial_state)?
             .x25519(subscription.author_ke_pk, NBytes::new(subscription.unsubscribe_key))?
-            .mask(subscription.subscriber_id.identifier())?
-            .sign(subscription.subscriber_id)
-            .await?;
+            .mask(subscription.subscriber_id.identifier())?;
+        if subscription.has_options() {
+            self.mask(Uint8::new(subscription.options()))?;
+        }
+        if subscription.ttl_seconds > 0 {
+            self.mask(Uint64::new(subscription.ttl_seconds))?;
+        }
+        self.sign(subscription.subscriber_id).await?;
         Ok(self)
     }
 }
@@ -118,6 +193,12 @@ pub(crate) struct Unwrap<'a> {
     subscriber_identifier: Identifier,
     /// The author's [x25519::SecretKey`]
     author_ke_sk: &'a x25519::SecretKey,
+    /// Whether the subscriber must never be granted write permissions
+    readonly: bool,
+    /// Seconds the subscription lasts for, or 0 if it does not expire
+    ttl_seconds: u64,
+    /// Whether the message is a [`message_types::SUBSCRIPTION_WITH_OPTIONS`] message
+    with_options: bool,
 }
 
 impl<'a> Unwrap<'a> {
@@ -126,12 +207,16 @@ impl<'a> Unwrap<'a> {
     /// # Arguments:
     /// * `initial_state`: The initial [`Spongos`] state the message will be joined to
     /// * `author_ke_sk`: The author's secret exchange key
-    pub(crate) fn new(initial_state: &'a mut Spongos, author_ke_sk: &'a x25519::SecretKey) -> Self {
+    /// * `message_type`: The message type of the subscription
+    pub(crate) fn new(initial_state: &'a mut Spongos, author_ke_sk: &'a x25519::SecretKey, message_type: u8) -> Self {
         Self {
             initial_state,
             unsubscribe_key: Default::default(),
             subscriber_identifier: Default::default(),
             author_ke_sk,
+            readonly: false,
+            ttl_seconds: 0,
+            with_options: message_type == message_types::SUBSCRIPTION_WITH_OPTIONS,
         }
     }
 
@@ -140,6 +225,16 @@ impl<'a> Unwrap<'a> {
         &self.subscriber_identifier
     }
 
+    /// Returns true if the subscriber must never be granted write permissions
+    pub(crate) fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Returns the seconds the subscription lasts for, or `None` if it does not expire
+    pub(crate) fn ttl_seconds(&self) -> Option<u64> {
+        Some(self.ttl_seconds).filter(|ttl_seconds| *ttl_seconds > 0)
+    }
+
     /// Consumes the [`Unwrap`], returning the [`Identifier`] of the subscriber
     pub(crate) fn into_subscriber_identifier(self) -> Identifier {
         self.subscriber_identifier
@@ -152,14 +247,23 @@ where
     IS: io::IStream,
 {
     async fn unwrap(&mut self, subscription: &mut Unwrap<'a>) -> Result<&mut Self> {
+        let mut options = Uint8::default();
+        let mut ttl_seconds = Uint64::default();
         self.join(subscription.initial_state)?
             .x25519(
                 subscription.author_ke_sk,
                 NBytes::new(&mut subscription.unsubscribe_key),
             )?
-            .mask(&mut subscription.subscriber_identifier)?
-            .verify(&subscription.subscriber_identifier)
-            .await?;
+            .mask(&mut subscription.subscriber_identifier)?;
+        if subscription.with_options {
+            self.mask(&mut options)?;
+        }
+        if options.inner() & EPHEMERAL != 0 {
+            self.mask(&mut ttl_seconds)?;
+        }
+        self.verify(&subscription.subscriber_identifier).await?;
+        subscription.readonly = options.inner() & READONLY != 0;
+        subscription.ttl_seconds = ttl_seconds.inner();
         Ok(self)
     }
 }
@@ -0,0 +1,109 @@
+//! `SubscriptionRequest` message _wrapping_ and _unwrapping_.
+//!
+//! The `SubscriptionRequest` message asks the author of a stream to be accepted as a subscriber,
+//! without knowing the address of the stream. It is published outside of any stream, in an inbox
+//! derived from the [`Identifier`] of the author, so it is not joined to any previous message and
+//! can be read from its address alone. Its content is signed by the requester.
+//!
+//! ```ddml
+//! message SubscriptionRequest {
+//!     mask                    u8      identifier;
+//!     commit;
+//!     squeeze external        u8      hash[64];
+//!     ed25519(hash)           u8      signature[64];
+//! }
+//! ```
+
+// Rust
+use alloc::boxed::Box;
+
+// 3rd-party
+use async_trait::async_trait;
+
+// IOTA
+
+// Streams
+use lets::{
+    id::{Identifier, Identity},
+    message::{ContentSign, ContentSignSizeof, ContentSizeof, ContentUnwrap, ContentVerify, ContentWrap},
+};
+use spongos::{
+    ddml::{
+        commands::{sizeof, unwrap, wrap, Commit, Mask},
+        io,
+    },
+    error::Result,
+    PRP,
+};
+
+// Local
+
+/// A struct that holds references needed for subscription request message encoding
+pub(crate) struct Wrap<'a> {
+    /// The [`Identity`] of the requester
+    user_id: &'a Identity,
+}
+
+impl<'a> Wrap<'a> {
+    /// Creates a new [`Wrap`] struct for a subscription request message
+    ///
+    /// # Arguments
+    /// * `user_id`: The [`Identity`] of the requester
+    pub(crate) fn new(user_id: &'a Identity) -> Self {
+        Self { user_id }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a> ContentSizeof<Wrap<'a>> for sizeof::Context {
+    async fn sizeof(&mut self, request: &Wrap<'a>) -> Result<&mut Self> {
+        self.mask(request.user_id.identifier())?
+            .commit()?
+            .sign_sizeof(request.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, OS> ContentWrap<Wrap<'a>> for wrap::Context<OS>
+where
+    OS: io::OStream,
+{
+    async fn wrap(&mut self, request: &mut Wrap<'a>) -> Result<&mut Self> {
+        self.mask(request.user_id.identifier())?
+            .commit()?
+            .sign(request.user_id)
+            .await?;
+        Ok(self)
+    }
+}
+
+/// A struct that holds the placeholders needed for subscription request message decoding
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) struct Unwrap {
+    /// The [`Identifier`] of the requester
+    requester_id: Identifier,
+}
+
+impl Unwrap {
+    /// Consumes the [`Unwrap`], returning the [`Identifier`] of the requester
+    pub(crate) fn into_requester_id(self) -> Identifier {
+        self.requester_id
+    }
+}
+
+#[async_trait(?Send)]
+impl<IS, F> ContentUnwrap<Unwrap> for unwrap::Context<IS, F>
+where
+    F: PRP,
+    IS: io::IStream,
+{
+    async fn unwrap(&mut self, request: &mut Unwrap) -> Result<&mut Self> {
+        self.mask(&mut request.requester_id)?
+            .commit()?
+            .verify(&request.requester_id)
+            .await?;
+        Ok(self)
+    }
+}